@@ -0,0 +1,68 @@
+//! Per-source failure alerting (`alert`): counts consecutive failed ticks per source and POSTs one JSON
+//! alert to the webhook when the streak reaches `threshold_consecutive_failures`. A successful tick resets it.
+
+use crate::config::AlertConfig;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+/// Consecutive failed ticks per source, alongside the last error kept for health.
+static CONSECUTIVE_FAILURES: LazyLock<Mutex<HashMap<String, u32>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reset the failure streak after a successful tick.
+pub fn record_success(source_id: &str) {
+    CONSECUTIVE_FAILURES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(source_id);
+}
+
+/// Count a failed tick; when the streak reaches the threshold, POST the alert. Webhook errors are logged only.
+pub async fn record_failure(
+    source_id: &str,
+    cfg: Option<&AlertConfig>,
+    error: &str,
+    clock: &dyn crate::clock::Clock,
+) {
+    let failures = {
+        let mut counts = CONSECUTIVE_FAILURES
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let n = counts.entry(source_id.to_string()).or_default();
+        *n = n.saturating_add(1);
+        *n
+    };
+    let Some(cfg) = cfg else {
+        return;
+    };
+    if failures != cfg.threshold_consecutive_failures.max(1) {
+        return;
+    }
+    let body = serde_json::json!({
+        "source": source_id,
+        "consecutive_failures": failures,
+        "threshold": cfg.threshold_consecutive_failures,
+        "error": error,
+        "ts": clock.now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+    });
+    tracing::warn!(source = %source_id, consecutive_failures = failures, "failure threshold reached, sending alert");
+    if let Err(e) = send(&cfg.webhook_url, &body).await {
+        tracing::warn!(source = %source_id, error = %e, "alert webhook failed");
+    }
+}
+
+async fn send(url: &str, body: &serde_json::Value) -> anyhow::Result<()> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .timeout(WEBHOOK_TIMEOUT)
+        .json(body)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        anyhow::bail!("alert webhook returned {}", response.status());
+    }
+    Ok(())
+}
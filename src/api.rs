@@ -4,19 +4,25 @@
 //! Base path: /api/v1. Endpoints: GET /api/v1/sources, GET /api/v1/sources/:id,
 //! GET /api/v1/sources/:id/state, GET /api/v1/sources/:id/config, GET /api/v1/config,
 //! POST /api/v1/sources/:id/poll, POST /api/v1/reload.
+//! Admin: POST /drain pauses scheduled polling and flushes the output; POST /resume continues.
+//! POST /circuit/:id?state=open|closed forces a source's circuit breaker open or resets it.
+//! Debug: GET /tail?n= returns the most recent emitted lines as NDJSON (global.debug.tail_capacity).
 
 use crate::audit;
+use crate::circuit;
 use crate::config::Config;
 use crate::health::{self, HealthState, SourceStatusDto};
 use crate::poll;
 use axum::Json;
 use axum::extract::Path;
+use axum::extract::Query;
 use axum::extract::State;
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::Ordering;
 
 /// Response for GET /api/v1/sources: list of sources with status.
 #[derive(Debug, Serialize)]
@@ -50,6 +56,15 @@ pub struct ReloadResponse {
     pub error: Option<String>,
 }
 
+/// Response for POST /drain and POST /resume.
+#[derive(Debug, Serialize)]
+pub struct DrainResponse {
+    pub draining: bool,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 /// GET /api/v1/sources — list all sources and their status (circuit, last_error).
 pub async fn list_sources_handler(State(state): State<Arc<HealthState>>) -> impl IntoResponse {
     let body = health::build_health_body(state.as_ref()).await;
@@ -237,8 +252,11 @@ pub async fn trigger_poll_handler(
         None,
         state.last_errors.clone(),
         poll_deps.global_sources_semaphore.clone(),
+        poll_deps.poll_caches.clone(),
         None,
         skip_priority_below,
+        false,
+        Arc::new(crate::clock::SystemClock),
     )
     .await;
 
@@ -293,11 +311,21 @@ pub async fn reload_handler(State(state): State<Arc<HealthState>>) -> impl IntoR
                 *guard = new_config;
             }
             if restart {
-                state.circuit_store.write().await.clear();
-                if let Some(d) = &state.poll_deps {
-                    d.token_cache.write().await.clear();
+                match &state.poll_deps {
+                    Some(d) => {
+                        crate::poll::reset_source_caches(
+                            &state.circuit_store,
+                            &d.token_cache,
+                            d.dpop_key_cache.as_ref(),
+                            &d.poll_caches,
+                        )
+                        .await
+                    }
+                    None => state.circuit_store.write().await.clear(),
                 }
-                tracing::info!("config reloaded via API, circuit breaker and token cache cleared");
+                tracing::info!(
+                    "config reloaded via API, circuit breaker, token and key caches cleared"
+                );
             } else {
                 tracing::info!("config reloaded via API");
             }
@@ -323,6 +351,167 @@ pub async fn reload_handler(State(state): State<Arc<HealthState>>) -> impl IntoR
     }
 }
 
+/// POST /drain — stop scheduled polling (an in-flight tick finishes) and flush the output sink.
+pub async fn drain_handler(State(state): State<Arc<HealthState>>) -> impl IntoResponse {
+    state.draining.store(true, Ordering::SeqCst);
+    tracing::info!("drain requested via API, polling paused");
+    let flushed = match &state.poll_deps {
+        Some(d) => {
+            let sink = d.event_sink.clone();
+            tokio::task::spawn_blocking(move || sink.flush())
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|r| r)
+        }
+        None => Ok(()),
+    };
+    match flushed {
+        Ok(()) => (
+            StatusCode::OK,
+            Json(DrainResponse {
+                draining: true,
+                ok: true,
+                error: None,
+            }),
+        ),
+        Err(e) => {
+            let msg = e.to_string();
+            tracing::warn!(error = %msg, "API drain: flush output failed");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(DrainResponse {
+                    draining: true,
+                    ok: false,
+                    error: Some(msg),
+                }),
+            )
+        }
+    }
+}
+
+/// POST /resume — continue scheduled polling after a drain.
+pub async fn resume_handler(State(state): State<Arc<HealthState>>) -> impl IntoResponse {
+    let was_draining = state.draining.swap(false, Ordering::SeqCst);
+    if was_draining {
+        tracing::info!("resume requested via API, polling continues");
+    }
+    (
+        StatusCode::OK,
+        Json(DrainResponse {
+            draining: false,
+            ok: true,
+            error: None,
+        }),
+    )
+}
+
+/// Query for POST /circuit/:id: `state=open` or `state=closed`.
+#[derive(Debug, Deserialize)]
+pub struct CircuitQuery {
+    pub state: String,
+}
+
+/// Response for POST /circuit/:id.
+#[derive(Debug, Serialize)]
+pub struct CircuitResponse {
+    pub source_id: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// POST /circuit/:id?state=open|closed — manually trip or reset a source's circuit breaker.
+/// 404 for an unknown source, 400 for another state or a source without circuit_breaker enabled.
+pub async fn force_circuit_handler(
+    State(state): State<Arc<HealthState>>,
+    Path(source_id): Path<String>,
+    Query(query): Query<CircuitQuery>,
+) -> impl IntoResponse {
+    let error = |status: StatusCode, source_id: String, msg: &str| {
+        (
+            status,
+            Json(CircuitResponse {
+                source_id,
+                ok: false,
+                state: None,
+                error: Some(msg.to_string()),
+            }),
+        )
+    };
+    let enabled = {
+        let config = state.config.read().await;
+        let Some(source) = config.sources.get(&source_id) else {
+            return error(StatusCode::NOT_FOUND, source_id, "source not found");
+        };
+        source
+            .resilience
+            .as_ref()
+            .and_then(|r| r.circuit_breaker.as_ref())
+            .is_some_and(|cb| cb.enabled)
+    };
+    let open = match query.state.as_str() {
+        "open" => true,
+        "closed" => false,
+        _ => {
+            return error(
+                StatusCode::BAD_REQUEST,
+                source_id,
+                "state must be open or closed",
+            );
+        }
+    };
+    if !enabled {
+        return error(
+            StatusCode::BAD_REQUEST,
+            source_id,
+            "circuit_breaker not enabled for source",
+        );
+    }
+    circuit::force_state(&state.circuit_store, &source_id, open).await;
+    (
+        StatusCode::OK,
+        Json(CircuitResponse {
+            source_id,
+            ok: true,
+            state: Some(query.state),
+            error: None,
+        }),
+    )
+}
+
+/// Query for GET /tail: `n` most recent lines (default: all retained).
+#[derive(Debug, Deserialize)]
+pub struct TailQuery {
+    pub n: Option<usize>,
+}
+
+/// GET /tail — last emitted NDJSON lines, oldest first. 404 when global.debug.tail_capacity is unset.
+pub async fn tail_handler(
+    State(state): State<Arc<HealthState>>,
+    Query(query): Query<TailQuery>,
+) -> axum::response::Response {
+    let Some(tail) = &state.tail else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({ "error": "tail disabled (set global.debug.tail_capacity)" })),
+        )
+            .into_response();
+    };
+    let mut body = String::new();
+    for line in tail.tail(query.n) {
+        body.push_str(&line);
+        body.push('\n');
+    }
+    (
+        StatusCode::OK,
+        [(axum::http::header::CONTENT_TYPE, "application/x-ndjson")],
+        body,
+    )
+        .into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -368,6 +557,8 @@ sources:
             state_store_fallback_active: false,
             config_path: None,
             poll_deps: None,
+            draining: Default::default(),
+            tail: None,
         })
     }
 
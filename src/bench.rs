@@ -0,0 +1,141 @@
+//! `helr bench`: replay recordings in a loop into a null sink and report throughput and line-write latency,
+//! for sizing backpressure and queue settings without a live API or downstream.
+
+use crate::config::Config;
+use crate::output::{BackpressureSink, EventSink};
+use crate::state::MemoryStateStore;
+use crate::{circuit, dedupe, oauth2, poll, replay};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// Bench result, printed as one JSON line.
+#[derive(Debug, Serialize)]
+pub struct BenchReport {
+    pub ticks: u64,
+    pub events: u64,
+    pub elapsed_secs: f64,
+    pub events_per_sec: f64,
+    pub write_latency_p50_ns: u64,
+    pub write_latency_p99_ns: u64,
+}
+
+/// Discards every line.
+struct NullSink;
+
+impl EventSink for NullSink {
+    fn write_line(&self, _line: &str) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Times each write into `inner` (the null sink, behind backpressure when configured).
+struct LatencySink {
+    inner: Arc<dyn EventSink>,
+    samples_ns: Mutex<Vec<u64>>,
+}
+
+impl EventSink for LatencySink {
+    fn write_line(&self, line: &str) -> anyhow::Result<()> {
+        self.write_line_from_source(None, line)
+    }
+
+    fn write_line_from_source(&self, source: Option<&str>, line: &str) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let result = self.inner.write_line_from_source(source, line);
+        let ns = start.elapsed().as_nanos().min(u64::MAX as u128) as u64;
+        self.samples_ns
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(ns);
+        result
+    }
+
+    fn flush(&self) -> anyhow::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Poll every source against a looping replay of `replay_dir` until `duration` elapses. Each tick starts
+/// from fresh state and dedupe so recordings are re-emitted rather than skipped.
+pub async fn run_bench(
+    config: &Config,
+    replay_dir: &Path,
+    duration: Duration,
+) -> anyhow::Result<BenchReport> {
+    let recordings = replay::load_recordings(replay_dir)?;
+    if recordings.is_empty() {
+        anyhow::bail!("replay dir has no recordings: {}", replay_dir.display());
+    }
+    let (addr, _join) = replay::start_replay_server(recordings, 0, true).await?;
+    let config = replay::rewrite_config_for_replay(config, &format!("http://{}", addr));
+
+    let null: Arc<dyn EventSink> = Arc::new(NullSink);
+    let inner: Arc<dyn EventSink> = match config.global.backpressure.as_ref() {
+        Some(bp) if bp.enabled => Arc::new(BackpressureSink::new(null, bp, None)?),
+        _ => null,
+    };
+    let sink = Arc::new(LatencySink {
+        inner,
+        samples_ns: Mutex::new(Vec::new()),
+    });
+
+    let circuit_store = circuit::new_circuit_store();
+    let token_cache = oauth2::new_oauth2_token_cache();
+    let poll_caches = poll::new_poll_caches();
+    let last_errors: poll::LastErrorStore = Arc::new(RwLock::new(Default::default()));
+    let start = Instant::now();
+    let mut ticks = 0u64;
+    while start.elapsed() < duration {
+        poll::run_one_tick(
+            &config,
+            Arc::new(MemoryStateStore::new()),
+            None,
+            circuit_store.clone(),
+            token_cache.clone(),
+            None,
+            dedupe::new_dedupe_store(),
+            sink.clone(),
+            None,
+            last_errors.clone(),
+            None,
+            poll_caches.clone(),
+            None,
+            None,
+            false,
+            Arc::new(crate::clock::SystemClock),
+        )
+        .await?;
+        ticks += 1;
+    }
+    sink.flush()?;
+    let elapsed = start.elapsed().as_secs_f64();
+
+    let mut samples =
+        std::mem::take(&mut *sink.samples_ns.lock().unwrap_or_else(|e| e.into_inner()));
+    samples.sort_unstable();
+    let events = samples.len() as u64;
+    Ok(BenchReport {
+        ticks,
+        events,
+        elapsed_secs: elapsed,
+        events_per_sec: if elapsed > 0.0 {
+            events as f64 / elapsed
+        } else {
+            0.0
+        },
+        write_latency_p50_ns: percentile(&samples, 50),
+        write_latency_p99_ns: percentile(&samples, 99),
+    })
+}
+
+/// Nearest-rank percentile of sorted samples; 0 when empty.
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
@@ -3,18 +3,20 @@
 
 use crate::config::CircuitBreakerConfig;
 use crate::metrics::{self, CircuitStateValue};
-use std::collections::HashMap;
-use std::sync::Arc;
-use std::time::Instant;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
 #[derive(Debug, Clone)]
 pub enum CircuitState {
     /// Closed: normal operation. failures/requests used for count and rate thresholds.
+    /// `recent` holds (time, success) outcomes when failure_rate_window_secs is set.
     Closed {
         failures: u32,
         requests: u32,
+        recent: VecDeque<(Instant, bool)>,
     },
     Open {
         open_until: Instant,
@@ -32,6 +34,49 @@ pub fn new_circuit_store() -> CircuitStore {
     Arc::new(RwLock::new(HashMap::new()))
 }
 
+/// Callback invoked on a state transition: (source_id, previous_state, new_state).
+pub type CircuitTransitionHook = Arc<dyn Fn(&str, &'static str, &'static str) + Send + Sync>;
+
+/// Per-source transition hooks (circuit_breaker.emit_circuit_events).
+static TRANSITION_HOOKS: LazyLock<std::sync::RwLock<HashMap<String, CircuitTransitionHook>>> =
+    LazyLock::new(|| std::sync::RwLock::new(HashMap::new()));
+
+/// Register (or clear, with None) the transition hook for a source. Replaces any previous hook.
+pub fn set_transition_hook(source_id: &str, hook: Option<CircuitTransitionHook>) {
+    let mut g = TRANSITION_HOOKS.write().unwrap_or_else(|e| e.into_inner());
+    match hook {
+        Some(h) => {
+            g.insert(source_id.to_string(), h);
+        }
+        None => {
+            g.remove(source_id);
+        }
+    }
+}
+
+fn notify_transition(source_id: &str, from: &'static str, to: &'static str) {
+    if from == to {
+        return;
+    }
+    let hook = TRANSITION_HOOKS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(source_id)
+        .cloned();
+    if let Some(h) = hook {
+        h(source_id, from, to);
+    }
+}
+
+/// State name as used in metrics labels and circuit envelopes.
+pub fn state_name(s: &CircuitState) -> &'static str {
+    match s {
+        CircuitState::Closed { .. } => "closed",
+        CircuitState::Open { .. } => "open",
+        CircuitState::HalfOpen { .. } => "half_open",
+    }
+}
+
 #[derive(Debug)]
 pub struct CircuitOpenError {
     pub open_until: Instant,
@@ -63,6 +108,7 @@ pub async fn allow_request(
             Some(CircuitState::Closed {
                 failures: 0,
                 requests: 0,
+                recent: VecDeque::new(),
             }),
         ),
         Some(CircuitState::Closed { .. }) => (true, None),
@@ -77,10 +123,16 @@ pub async fn allow_request(
         }
         Some(CircuitState::HalfOpen { successes: _ }) => (true, None),
     };
+    let previous = state.as_ref().map(state_name).unwrap_or("closed");
+    let transition = new_state.as_ref().map(|s| (previous, state_name(s)));
     if let Some(s) = new_state {
         g.insert(source_id.to_string(), s.clone());
         metrics::set_circuit_state(source_id, circuit_state_to_value(&s));
     }
+    drop(g);
+    if let Some((from, to)) = transition {
+        notify_transition(source_id, from, to);
+    }
     if !allowed && let Some(CircuitState::Open { open_until }) = state {
         warn!(source = %source_id, "request rejected: circuit open");
         return Err(CircuitOpenError { open_until });
@@ -102,24 +154,45 @@ pub async fn record_result(
     let state = g.get(source_id).cloned().unwrap_or(CircuitState::Closed {
         failures: 0,
         requests: 0,
+        recent: VecDeque::new(),
     });
+    let previous = state_name(&state);
     let now = Instant::now();
     let open_duration_secs = config
         .reset_timeout_secs
         .map(|r| std::cmp::min(config.half_open_timeout_secs, r))
         .unwrap_or(config.half_open_timeout_secs);
     let new_state = match state {
-        CircuitState::Closed { failures, requests } => {
+        CircuitState::Closed {
+            failures,
+            requests,
+            mut recent,
+        } => {
             let requests = requests + 1;
             let failures = if success { 0 } else { failures + 1 };
             let open_on_count = failures >= config.failure_threshold;
+            let (rate_failures, rate_requests) = match config.failure_rate_window_secs {
+                Some(window_secs) => {
+                    recent.push_back((now, success));
+                    let window = Duration::from_secs(window_secs);
+                    while recent
+                        .front()
+                        .is_some_and(|(t, _)| now.duration_since(*t) > window)
+                    {
+                        recent.pop_front();
+                    }
+                    let window_failures = recent.iter().filter(|(_, ok)| !ok).count() as u32;
+                    (window_failures, recent.len() as u32)
+                }
+                None => (failures, requests),
+            };
             let open_on_rate = config.minimum_requests.is_some()
                 && config.failure_rate_threshold.is_some()
-                && requests >= config.minimum_requests.unwrap_or(0)
-                && (failures as f64 / requests as f64)
+                && rate_requests >= config.minimum_requests.unwrap_or(0)
+                && (rate_failures as f64 / rate_requests as f64)
                     >= config.failure_rate_threshold.unwrap_or(0.0);
             if !success && (open_on_count || open_on_rate) {
-                let open_until = now + std::time::Duration::from_secs(open_duration_secs);
+                let open_until = now + Duration::from_secs(open_duration_secs);
                 warn!(
                     source = %source_id,
                     failures,
@@ -129,7 +202,11 @@ pub async fn record_result(
                 );
                 CircuitState::Open { open_until }
             } else {
-                CircuitState::Closed { failures, requests }
+                CircuitState::Closed {
+                    failures,
+                    requests,
+                    recent,
+                }
             }
         }
         CircuitState::Open { open_until } => CircuitState::Open { open_until },
@@ -141,12 +218,13 @@ pub async fn record_result(
                     CircuitState::Closed {
                         failures: 0,
                         requests: 0,
+                        recent: VecDeque::new(),
                     }
                 } else {
                     CircuitState::HalfOpen { successes: s }
                 }
             } else {
-                let open_until = now + std::time::Duration::from_secs(open_duration_secs);
+                let open_until = now + Duration::from_secs(open_duration_secs);
                 warn!(source = %source_id, "circuit re-opened from half-open");
                 CircuitState::Open { open_until }
             }
@@ -154,6 +232,34 @@ pub async fn record_result(
     };
     g.insert(source_id.to_string(), new_state.clone());
     metrics::set_circuit_state(source_id, circuit_state_to_value(&new_state));
+    drop(g);
+    notify_transition(source_id, previous, state_name(&new_state));
+}
+
+/// How long a manually opened circuit stays open; in practice until it is closed again.
+const FORCED_OPEN: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// Manually trip (`open: true`) or reset a source's circuit, e.g. from the admin API during an incident.
+/// A forced-open circuit does not move to half-open on its own; a reset starts closed with zero failures.
+pub async fn force_state(store: &CircuitStore, source_id: &str, open: bool) {
+    let new_state = if open {
+        CircuitState::Open {
+            open_until: Instant::now() + FORCED_OPEN,
+        }
+    } else {
+        CircuitState::Closed {
+            failures: 0,
+            requests: 0,
+            recent: VecDeque::new(),
+        }
+    };
+    let mut g = store.write().await;
+    let previous = g.get(source_id).map(state_name).unwrap_or("closed");
+    g.insert(source_id.to_string(), new_state.clone());
+    metrics::set_circuit_state(source_id, circuit_state_to_value(&new_state));
+    drop(g);
+    warn!(source = %source_id, state = state_name(&new_state), "circuit state forced");
+    notify_transition(source_id, previous, state_name(&new_state));
 }
 
 fn circuit_state_to_value(s: &CircuitState) -> CircuitStateValue {
@@ -185,6 +291,8 @@ mod tests {
             reset_timeout_secs: None,
             failure_rate_threshold: None,
             minimum_requests: None,
+            failure_rate_window_secs: None,
+            emit_circuit_events: false,
         };
         for _ in 0..3 {
             allow_request(&store, "s1", &config).await.unwrap();
@@ -205,6 +313,8 @@ mod tests {
             reset_timeout_secs: None,
             failure_rate_threshold: None,
             minimum_requests: None,
+            failure_rate_window_secs: None,
+            emit_circuit_events: false,
         };
         allow_request(&store, "s1", &config).await.unwrap();
         record_result(&store, "s1", &config, false).await;
@@ -227,6 +337,8 @@ mod tests {
             reset_timeout_secs: Some(1),
             failure_rate_threshold: None,
             minimum_requests: None,
+            failure_rate_window_secs: None,
+            emit_circuit_events: false,
         };
         for _ in 0..2 {
             allow_request(&store, "s1", &config).await.unwrap();
@@ -255,6 +367,8 @@ mod tests {
             reset_timeout_secs: None,
             failure_rate_threshold: Some(0.5),
             minimum_requests: Some(10),
+            failure_rate_window_secs: None,
+            emit_circuit_events: false,
         };
         for i in 0..10 {
             allow_request(&store, "s1", &config).await.unwrap();
@@ -275,6 +389,8 @@ mod tests {
             reset_timeout_secs: None,
             failure_rate_threshold: Some(0.5),
             minimum_requests: Some(20),
+            failure_rate_window_secs: None,
+            emit_circuit_events: false,
         };
         for _ in 0..10 {
             allow_request(&store, "s1", &config).await.unwrap();
@@ -282,4 +398,56 @@ mod tests {
         }
         allow_request(&store, "s1", &config).await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_circuit_failure_rate_window_discards_old_failures() {
+        let store = new_circuit_store();
+        let config = CircuitBreakerConfig {
+            enabled: true,
+            failure_threshold: 100,
+            success_threshold: 1,
+            half_open_timeout_secs: 60,
+            reset_timeout_secs: None,
+            failure_rate_threshold: Some(0.5),
+            minimum_requests: Some(4),
+            failure_rate_window_secs: Some(1),
+            emit_circuit_events: false,
+        };
+        for _ in 0..3 {
+            allow_request(&store, "s1", &config).await.unwrap();
+            record_result(&store, "s1", &config, false).await;
+        }
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        // Without the window this 4th failure would trip (4/4 >= 0.5); the first 3 have aged out.
+        allow_request(&store, "s1", &config).await.unwrap();
+        record_result(&store, "s1", &config, false).await;
+        allow_request(&store, "s1", &config).await.unwrap();
+
+        // Failures inside the window still trip the breaker.
+        for _ in 0..3 {
+            record_result(&store, "s1", &config, false).await;
+        }
+        assert!(allow_request(&store, "s1", &config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_circuit_open_sets_state_gauge() {
+        let _ = metrics::init();
+        let store = new_circuit_store();
+        let config = CircuitBreakerConfig {
+            enabled: true,
+            failure_threshold: 1,
+            success_threshold: 1,
+            half_open_timeout_secs: 60,
+            reset_timeout_secs: None,
+            failure_rate_threshold: None,
+            minimum_requests: None,
+            failure_rate_window_secs: None,
+            emit_circuit_events: false,
+        };
+        allow_request(&store, "gauge-src", &config).await.unwrap();
+        assert!(metrics::encode().contains("helr_circuit_state{source=\"gauge-src\"} 0"));
+        record_result(&store, "gauge-src", &config, false).await;
+        assert!(metrics::encode().contains("helr_circuit_state{source=\"gauge-src\"} 2"));
+    }
 }
@@ -2,12 +2,16 @@
 //! Single GET request; pagination is handled by the caller.
 //! Auth secrets can come from env vars or files (config parity).
 
-use crate::config::{self, AuthConfig, ResilienceConfig, SourceConfig, TlsConfig};
+use crate::config::{
+    self, ApiKeyLocation, AuthConfig, HttpVersionConfig, ResilienceConfig, SourceConfig, TlsConfig,
+};
 use anyhow::Context;
 use base64::Engine;
 use reqwest::Client;
 use reqwest::header::{AUTHORIZATION, HeaderName, HeaderValue};
 use reqwest::tls::{Certificate, Identity, Version};
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
 use std::time::Duration;
 
 /// Effective timeout values: from split timeouts when set, else from legacy timeout_secs.
@@ -24,10 +28,18 @@ fn effective_timeouts(
     let connect = Duration::from_secs(connect_secs);
     let request = Duration::from_secs(request_secs);
     let read = t.and_then(|t| t.read_secs).map(Duration::from_secs);
-    let idle = t.and_then(|t| t.idle_secs).map(Duration::from_secs);
+    let idle = resilience
+        .and_then(|r| r.pool_idle_timeout_secs)
+        .or_else(|| t.and_then(|t| t.idle_secs))
+        .map(Duration::from_secs);
     (connect, request, read, idle)
 }
 
+/// Per-request timeout the client is built with (timeouts.request_secs, else timeout_secs).
+pub(crate) fn request_timeout(resilience: Option<&ResilienceConfig>) -> Duration {
+    effective_timeouts(resilience).1
+}
+
 /// Load CA cert(s) from PEM (single or bundle) and return certs for reqwest.
 fn load_ca_certs(tls: &TlsConfig) -> anyhow::Result<Vec<Certificate>> {
     let pem = config::read_secret(tls.ca_file.as_deref(), tls.ca_env.as_deref().unwrap_or(""))?;
@@ -104,9 +116,135 @@ fn apply_tls(
     Ok(builder)
 }
 
-/// Build a reqwest client with timeouts and optional TLS from resilience config.
+/// Parse one `resolve` entry: key "host:port" or "host", value "ip:port" (or bare "ip", taking the key's port).
+/// Returns the host, the key's port (None for a bare host key) and the address.
+fn parse_resolve_override<'a>(
+    host_port: &'a str,
+    addr: &str,
+) -> anyhow::Result<(&'a str, Option<u16>, SocketAddr)> {
+    let (host, key_port) = match host_port.rsplit_once(':') {
+        Some((h, p)) if !h.is_empty() && p.parse::<u16>().is_ok() => (h, p.parse::<u16>().ok()),
+        _ => (host_port, None),
+    };
+    let socket_addr = match addr.parse::<SocketAddr>() {
+        Ok(a) => a,
+        Err(_) => {
+            let ip: IpAddr = addr
+                .parse()
+                .with_context(|| format!("resolve {:?}: invalid address {:?}", host_port, addr))?;
+            SocketAddr::new(ip, key_port.unwrap_or(0))
+        }
+    };
+    Ok((host, key_port, socket_addr))
+}
+
+/// The source's resilience.resolve overrides, one address per host (reqwest overrides by host name).
+/// For the source URL's host, a "host:port" key applies only on the URL's port and wins over a bare "host" key.
+/// Other hosts take their bare key, or their only entry; several port keys for one of them are ambiguous.
+fn resolve_overrides(source: &SourceConfig) -> anyhow::Result<Vec<(String, SocketAddr)>> {
+    let Some(resolve) = source.resilience.as_ref().and_then(|r| r.resolve.as_ref()) else {
+        return Ok(Vec::new());
+    };
+    let url = reqwest::Url::parse(&source.url).ok();
+    let url_host = url.as_ref().and_then(|u| u.host_str());
+    let url_port = url.as_ref().and_then(|u| u.port_or_known_default());
+    let mut by_host: BTreeMap<&str, Vec<(Option<u16>, SocketAddr)>> = BTreeMap::new();
+    for (host_port, addr) in resolve {
+        let (host, key_port, addr) = parse_resolve_override(host_port, addr)?;
+        by_host.entry(host).or_default().push((key_port, addr));
+    }
+    let mut overrides = Vec::with_capacity(by_host.len());
+    for (host, entries) in by_host {
+        let bare = entries.iter().find(|(p, _)| p.is_none());
+        let chosen = if Some(host) == url_host {
+            entries
+                .iter()
+                .find(|(p, _)| p.is_some() && *p == url_port)
+                .or(bare)
+        } else if entries.len() == 1 {
+            entries.first()
+        } else {
+            Some(bare.with_context(|| {
+                format!(
+                    "resolve: {:?} has entries for several ports; add a bare {:?} key",
+                    host, host
+                )
+            })?)
+        };
+        if let Some((_, addr)) = chosen {
+            overrides.push((host.to_string(), *addr));
+        }
+    }
+    Ok(overrides)
+}
+
+/// With tls.sni_hostname: the hostname to present and the address (the source URL's IP and port) to connect to.
+/// Fails when the URL host is not an IP address or the hostname is empty or itself an IP.
+pub(crate) fn sni_override(source: &SourceConfig) -> anyhow::Result<Option<(String, SocketAddr)>> {
+    let Some(sni) = source
+        .resilience
+        .as_ref()
+        .and_then(|r| r.tls.as_ref())
+        .and_then(|t| t.sni_hostname.as_deref())
+    else {
+        return Ok(None);
+    };
+    if sni.is_empty() || sni.parse::<IpAddr>().is_ok() {
+        anyhow::bail!("sni_hostname must be a DNS name, got {:?}", sni);
+    }
+    let url =
+        reqwest::Url::parse(&source.url).with_context(|| format!("parse url {:?}", source.url))?;
+    let host = url.host_str().unwrap_or_default();
+    let ip: IpAddr = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .map_err(|_| {
+            anyhow::anyhow!(
+                "sni_hostname {:?} requires an IP address as the url host, got {:?}",
+                sni,
+                host
+            )
+        })?;
+    let port = url.port_or_known_default().unwrap_or(443);
+    Ok(Some((sni.to_string(), SocketAddr::new(ip, port))))
+}
+
+/// Request URL with the source's IP host replaced by tls.sni_hostname (the client resolves that name back to
+/// the IP), so TLS SNI and the Host header carry the hostname. Other URLs (e.g. absolute next links) pass through.
+fn sni_request_url(source: &SourceConfig, url: &str) -> anyhow::Result<String> {
+    let Some((sni, addr)) = sni_override(source)? else {
+        return Ok(url.to_string());
+    };
+    let mut u = reqwest::Url::parse(url).with_context(|| format!("parse url {:?}", url))?;
+    let is_source_ip = u
+        .host_str()
+        .map(|h| h.trim_start_matches('[').trim_end_matches(']'))
+        .and_then(|h| h.parse::<IpAddr>().ok())
+        .is_some_and(|ip| ip == addr.ip());
+    if is_source_ip {
+        u.set_host(Some(&sni))
+            .with_context(|| format!("set sni host {:?}", sni))?;
+    }
+    Ok(u.to_string())
+}
+
+/// Build the client for a source from its resilience config and resolve overrides plus, with tls.sni_hostname, a
+/// resolve override sending that hostname to the URL's IP.
+pub fn build_source_client(source: &SourceConfig) -> anyhow::Result<Client> {
+    let mut builder = client_builder(source.resilience.as_ref())?;
+    for (host, addr) in resolve_overrides(source)? {
+        builder = builder.resolve(&host, addr);
+    }
+    if let Some((sni, addr)) = sni_override(source)? {
+        builder = builder.resolve(&sni, addr);
+    }
+    builder.build().context("build reqwest client")
+}
+
+/// Reqwest client builder with timeouts and optional TLS from resilience config.
 /// Uses split timeouts (connect, request, read, idle) when set; otherwise timeout_secs for request and min(10, timeout_secs) for connect.
-pub fn build_client(resilience: Option<&ResilienceConfig>) -> anyhow::Result<Client> {
+fn client_builder(resilience: Option<&ResilienceConfig>) -> anyhow::Result<reqwest::ClientBuilder> {
     let (connect, request, read, idle) = effective_timeouts(resilience);
     let mut builder = Client::builder().connect_timeout(connect).timeout(request);
     if let Some(d) = read {
@@ -115,11 +253,44 @@ pub fn build_client(resilience: Option<&ResilienceConfig>) -> anyhow::Result<Cli
     if let Some(d) = idle {
         builder = builder.pool_idle_timeout(d);
     }
+    if let Some(secs) = resilience.and_then(|r| r.tcp_keepalive_secs) {
+        builder = builder.tcp_keepalive(Duration::from_secs(secs));
+    }
     if let Some(tls) = resilience.and_then(|r| r.tls.as_ref()) {
         builder = apply_tls(builder, tls)?;
     }
-    let client = builder.build().context("build reqwest client")?;
-    Ok(client)
+    if let Some(n) = resilience.and_then(|r| r.max_connections) {
+        builder = builder.pool_max_idle_per_host(n);
+    }
+    match resilience.map(|r| r.http_version).unwrap_or_default() {
+        // rustls offers h2 and http/1.1 via ALPN by default; validation keeps http2 sources on https.
+        HttpVersionConfig::Auto | HttpVersionConfig::Http2 => {}
+        HttpVersionConfig::Http1 => builder = builder.http1_only(),
+        HttpVersionConfig::Http2PriorKnowledge => builder = builder.http2_prior_knowledge(),
+    }
+    Ok(builder)
+}
+
+/// Flatten a JSON object into form fields: scalars as strings, arrays repeat the key, nested objects as JSON.
+fn form_pairs(body: &serde_json::Value) -> anyhow::Result<Vec<(String, String)>> {
+    let obj = body
+        .as_object()
+        .ok_or_else(|| anyhow::anyhow!("form POST body must be an object"))?;
+    let scalar = |v: &serde_json::Value| match v {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    };
+    let mut pairs = Vec::with_capacity(obj.len());
+    for (k, v) in obj {
+        match v {
+            serde_json::Value::Array(items) => {
+                pairs.extend(items.iter().map(|item| (k.clone(), scalar(item))));
+            }
+            _ => pairs.push((k.clone(), scalar(v))),
+        }
+    }
+    Ok(pairs)
 }
 
 /// Context passed to build_request: overrides and audit for auth.
@@ -139,17 +310,25 @@ pub fn build_request(
     url: &str,
     ctx: &BuildRequestContext<'_>,
 ) -> anyhow::Result<reqwest::Request> {
-    use crate::config::HttpMethod;
+    use crate::config::{BodyContentType, HttpMethod};
+    let url = sni_request_url(source, url)?;
+    let url = url.as_str();
     let mut req = match source.method {
         HttpMethod::Get => client.get(url),
         HttpMethod::Post => {
             let empty: serde_json::Value = serde_json::Value::Object(serde_json::Map::new());
             let body_value = ctx.body_override.or(source.body.as_ref()).unwrap_or(&empty);
-            let body_bytes = serde_json::to_vec(body_value).context("serialize POST body")?;
-            client
-                .post(url)
-                .header("Content-Type", "application/json")
-                .body(body_bytes)
+            match source.body_content_type {
+                BodyContentType::Json => {
+                    let body_bytes =
+                        serde_json::to_vec(body_value).context("serialize POST body")?;
+                    client
+                        .post(url)
+                        .header("Content-Type", "application/json")
+                        .body(body_bytes)
+                }
+                BodyContentType::Form => client.post(url).form(&form_pairs(body_value)?),
+            }
         }
     };
     if let Some(token) = ctx.bearer_override {
@@ -180,7 +359,97 @@ pub fn build_request(
             req = req.header(name, value);
         }
     }
-    req.build().context("build request")
+    let mut request = req.build().context("build request")?;
+    if ctx.bearer_override.is_none()
+        && let Some(AuthConfig::ApiKey {
+            key_env,
+            key_file,
+            location: ApiKeyLocation::Query,
+            ..
+        }) = &source.auth
+        && let Some(param) = api_key_query_param(source)
+    {
+        let key = config::read_secret(key_file.as_deref(), key_env)?;
+        crate::audit::log_credential_access(ctx.audit, ctx.source_id, "api_key");
+        set_query_param(request.url_mut(), param, &key);
+    }
+    if source.request_gzip {
+        gzip_request_body(&mut request)?;
+    }
+    if let Some(AuthConfig::AwsSigV4 {
+        access_key_env,
+        secret_key_env,
+        session_token_env,
+        region,
+        service,
+    }) = &source.auth
+    {
+        let creds = crate::sigv4::Credentials {
+            access_key: config::read_secret(None, access_key_env)?,
+            secret_key: config::read_secret(None, secret_key_env)?,
+            session_token: session_token_env
+                .as_deref()
+                .map(|env| config::read_secret(None, env))
+                .transpose()?,
+        };
+        crate::audit::log_credential_access(ctx.audit, ctx.source_id, "aws_secret_key");
+        crate::sigv4::sign_request(&mut request, &creds, region, service, chrono::Utc::now())
+            .context("aws sigv4 signing")?;
+    }
+    Ok(request)
+}
+
+/// Replace a buffered request body with its gzip encoding and set `Content-Encoding: gzip`. No-op without a body.
+fn gzip_request_body(request: &mut reqwest::Request) -> anyhow::Result<()> {
+    use std::io::Write;
+    let Some(bytes) = request.body().and_then(|b| b.as_bytes()) else {
+        return Ok(());
+    };
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(bytes).context("gzip request body")?;
+    let compressed = encoder.finish().context("gzip request body")?;
+    *request.body_mut() = Some(compressed.into());
+    request.headers_mut().insert(
+        reqwest::header::CONTENT_ENCODING,
+        HeaderValue::from_static("gzip"),
+    );
+    Ok(())
+}
+
+/// Query param carrying the API key when auth is api_key with location query; its value is redacted in logs and audit.
+pub(crate) fn api_key_query_param(source: &SourceConfig) -> Option<&str> {
+    match &source.auth {
+        Some(AuthConfig::ApiKey {
+            location: ApiKeyLocation::Query,
+            param,
+            ..
+        }) => param.as_deref(),
+        _ => None,
+    }
+}
+
+/// Set `name` to `value` in the URL query, replacing any existing occurrences (e.g. echoed in a next-page link).
+fn set_query_param(url: &mut reqwest::Url, name: &str, value: &str) {
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| k != name)
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair(name, value);
+}
+
+/// Redact the URL carried by a reqwest error (its Display includes it) so query-param secrets don't reach logs.
+pub(crate) fn redact_reqwest_error(mut e: reqwest::Error, source: &SourceConfig) -> reqwest::Error {
+    if let Some(url) = e.url_mut() {
+        let redacted = crate::audit::redact_url_with(url.as_str(), api_key_query_param(source));
+        if let Ok(u) = reqwest::Url::parse(&redacted) {
+            *url = u;
+        }
+    }
+    e
 }
 
 fn add_auth(
@@ -205,14 +474,24 @@ fn add_auth(
             header,
             key_env,
             key_file,
+            location: ApiKeyLocation::Header,
+            ..
         } => {
+            let header = header
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("api_key auth with location header needs header"))?;
             let key = config::read_secret(key_file.as_deref(), key_env)?;
             crate::audit::log_credential_access(audit, source_id, "api_key");
-            let name = HeaderName::try_from(header.as_str())
+            let name = HeaderName::try_from(header)
                 .with_context(|| format!("invalid api key header name: {:?}", header))?;
             let hv = HeaderValue::try_from(key.as_str()).context("invalid api key value")?;
             req.header(name, hv)
         }
+        // Query-param keys are set on the built URL in build_request.
+        AuthConfig::ApiKey {
+            location: ApiKeyLocation::Query,
+            ..
+        } => req,
         AuthConfig::Basic {
             user_env,
             user_file,
@@ -235,6 +514,8 @@ fn add_auth(
         AuthConfig::GoogleServiceAccount { .. } => {
             unreachable!("GoogleServiceAccount auth must use bearer_override in build_request")
         }
+        // Signed over the built request in build_request.
+        AuthConfig::AwsSigV4 { .. } => req,
     };
     Ok(req)
 }
@@ -253,6 +534,11 @@ mod tests {
             circuit_breaker: None,
             rate_limit: None,
             bulkhead: None,
+            resolve: None,
+            max_connections: None,
+            http_version: HttpVersionConfig::Auto,
+            pool_idle_timeout_secs: None,
+            tcp_keepalive_secs: None,
             tls: Some(TlsConfig {
                 ca_file: None,
                 ca_env: None,
@@ -262,9 +548,279 @@ mod tests {
                 client_key_file: None,
                 client_key_env: None,
                 min_version: Some("1.3".to_string()),
+                sni_hostname: None,
+            }),
+        };
+        let client = client_builder(Some(&resilience)).unwrap().build().unwrap();
+        drop(client);
+    }
+
+    #[test]
+    fn build_client_with_resolve_override() {
+        let source: SourceConfig = serde_yaml_ng::from_str(
+            r#"
+url: "https://api.example.com/logs"
+resilience:
+  resolve:
+    "api.example.com:443": "10.0.0.5:8443"
+    "api.example.com:8443": "10.0.0.6"
+    "api.example.com": "10.0.0.7"
+    "logs.internal": "127.0.0.1"
+"#,
+        )
+        .unwrap();
+        let overrides = resolve_overrides(&source).unwrap();
+        assert_eq!(
+            overrides,
+            vec![
+                (
+                    "api.example.com".to_string(),
+                    "10.0.0.5:8443".parse::<SocketAddr>().unwrap()
+                ),
+                (
+                    "logs.internal".to_string(),
+                    "127.0.0.1:0".parse::<SocketAddr>().unwrap()
+                ),
+            ],
+            "the URL's port picks the host:port entry"
+        );
+        build_source_client(&source).unwrap();
+
+        let other_port: SourceConfig = serde_yaml_ng::from_str(
+            r#"
+url: "https://api.example.com:9443/logs"
+resilience:
+  resolve:
+    "api.example.com:443": "10.0.0.5"
+"#,
+        )
+        .unwrap();
+        assert!(
+            resolve_overrides(&other_port).unwrap().is_empty(),
+            "a host:port entry does not apply on another port"
+        );
+
+        let ambiguous: SourceConfig = serde_yaml_ng::from_str(
+            r#"
+url: "https://api.example.com/logs"
+resilience:
+  resolve:
+    "auth.example.com:443": "10.0.0.5"
+    "auth.example.com:8443": "10.0.0.6"
+"#,
+        )
+        .unwrap();
+        assert!(resolve_overrides(&ambiguous).is_err());
+
+        let (host, key_port, addr) =
+            parse_resolve_override("api.example.com:443", "10.0.0.5").unwrap();
+        assert_eq!(host, "api.example.com");
+        assert_eq!(key_port, Some(443));
+        assert_eq!(addr, "10.0.0.5:443".parse::<SocketAddr>().unwrap());
+        assert!(parse_resolve_override("api.example.com", "not-an-ip").is_err());
+    }
+
+    #[tokio::test]
+    async fn build_source_client_with_sni_hostname_connects_to_ip() {
+        use wiremock::matchers::{header, method};
+        let server = wiremock::MockServer::start().await;
+        let port = server.address().port();
+        wiremock::Mock::given(method("GET"))
+            .and(header(
+                "host",
+                format!("logs.example.test:{}", port).as_str(),
+            ))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_string("[]"))
+            .mount(&server)
+            .await;
+        let source: SourceConfig = serde_yaml_ng::from_str(&format!(
+            r#"
+url: "http://127.0.0.1:{}/logs"
+resilience:
+  tls:
+    sni_hostname: logs.example.test
+"#,
+            port
+        ))
+        .unwrap();
+        let (sni, addr) = sni_override(&source).unwrap().unwrap();
+        assert_eq!(sni, "logs.example.test");
+        assert_eq!(addr, SocketAddr::from(([127, 0, 0, 1], port)));
+
+        let client = build_source_client(&source).unwrap();
+        let req = build_request(&client, &source, &source.url, &Default::default()).unwrap();
+        assert_eq!(req.url().host_str(), Some("logs.example.test"));
+        let resp = client.execute(req).await.unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn build_request_gzips_post_body() {
+        use std::io::Read;
+        use wiremock::matchers::{header, method};
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(method("POST"))
+            .and(header("content-encoding", "gzip"))
+            .and(header("content-type", "application/json"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_string("[]"))
+            .mount(&server)
+            .await;
+        let source: SourceConfig = serde_yaml_ng::from_str(&format!(
+            r#"
+url: "{}/search"
+method: post
+request_gzip: true
+body:
+  query: "status:failed"
+  fields: ["actor", "action"]
+"#,
+            server.uri()
+        ))
+        .unwrap();
+        let client = Client::new();
+        let req = build_request(&client, &source, &source.url, &Default::default()).unwrap();
+        let resp = client.execute(req).await.unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+
+        let received = server.received_requests().await.unwrap();
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(received[0].body.as_slice())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_str(&decoded).unwrap();
+        assert_eq!(&body, source.body.as_ref().unwrap());
+    }
+
+    #[tokio::test]
+    async fn build_client_with_pool_idle_timeout_and_tcp_keepalive() {
+        let resilience = ResilienceConfig {
+            timeout_secs: 30,
+            timeouts: Some(crate::config::TimeoutsConfig {
+                idle_secs: Some(5),
+                ..Default::default()
             }),
+            pool_idle_timeout_secs: Some(300),
+            tcp_keepalive_secs: Some(30),
+            ..Default::default()
+        };
+        assert_eq!(
+            effective_timeouts(Some(&resilience)).3,
+            Some(Duration::from_secs(300)),
+            "pool_idle_timeout_secs overrides timeouts.idle_secs"
+        );
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        let client = client_builder(Some(&resilience)).unwrap().build().unwrap();
+        for _ in 0..2 {
+            let resp = client.get(server.uri()).send().await.unwrap();
+            assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        }
+    }
+
+    #[test]
+    fn build_client_with_max_connections() {
+        let resilience = ResilienceConfig {
+            max_connections: Some(4),
+            ..Default::default()
         };
-        let client = build_client(Some(&resilience)).unwrap();
+        let client = client_builder(Some(&resilience)).unwrap().build().unwrap();
         drop(client);
     }
+
+    #[test]
+    fn build_client_with_each_http_version() {
+        for http_version in [
+            HttpVersionConfig::Auto,
+            HttpVersionConfig::Http1,
+            HttpVersionConfig::Http2,
+            HttpVersionConfig::Http2PriorKnowledge,
+        ] {
+            let resilience = ResilienceConfig {
+                http_version,
+                ..Default::default()
+            };
+            let client = client_builder(Some(&resilience)).unwrap().build().unwrap();
+            drop(client);
+        }
+    }
+
+    #[test]
+    fn build_request_api_key_in_query() {
+        unsafe {
+            std::env::set_var("HELR_TEST_API_KEY_QUERY", "s3cr3t");
+        }
+        let source: SourceConfig = serde_yaml_ng::from_str(
+            r#"
+url: "https://legacy.example.com/events"
+auth:
+  type: api_key
+  location: query
+  param: api_key
+  key_env: HELR_TEST_API_KEY_QUERY
+"#,
+        )
+        .unwrap();
+        let client = Client::new();
+        let ctx = BuildRequestContext {
+            source_id: "legacy",
+            ..Default::default()
+        };
+        // An echoed key in a next-page URL is replaced, not duplicated.
+        let req = build_request(
+            &client,
+            &source,
+            "https://legacy.example.com/events?page=2&api_key=old",
+            &ctx,
+        )
+        .unwrap();
+        assert_eq!(
+            req.url().as_str(),
+            "https://legacy.example.com/events?page=2&api_key=s3cr3t"
+        );
+        assert!(req.headers().get(AUTHORIZATION).is_none());
+        assert_eq!(api_key_query_param(&source), Some("api_key"));
+        assert_eq!(
+            crate::audit::redact_url_with(req.url().as_str(), api_key_query_param(&source)),
+            "https://legacy.example.com/events?page=2&api_key=REDACTED"
+        );
+        unsafe {
+            std::env::remove_var("HELR_TEST_API_KEY_QUERY");
+        }
+    }
+
+    #[test]
+    fn build_request_includes_header_profile_headers() {
+        let mut config: crate::config::Config = serde_yaml_ng::from_str(
+            r#"
+global:
+  header_profiles:
+    github:
+      Accept: "application/vnd.github+json"
+      X-GitHub-Api-Version: "2022-11-28"
+sources:
+  gh:
+    url: "https://api.github.com/orgs/acme/audit-log"
+    header_profile: github
+    headers:
+      Accept: "application/json"
+"#,
+        )
+        .unwrap();
+        crate::config::apply_header_profiles(&mut config).unwrap();
+        let source = &config.sources["gh"];
+        let client = Client::new();
+        let ctx = BuildRequestContext {
+            source_id: "gh",
+            ..Default::default()
+        };
+        let req = build_request(&client, source, &source.url, &ctx).unwrap();
+        assert_eq!(req.headers().get("Accept").unwrap(), "application/json");
+        assert_eq!(
+            req.headers().get("X-GitHub-Api-Version").unwrap(),
+            "2022-11-28"
+        );
+    }
 }
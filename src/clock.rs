@@ -0,0 +1,52 @@
+//! Clock source for time-based poll decisions and timestamps (cron due checks, start_mode seeding, rate-limit waits,
+//! envelope ts fallback and ingested_at, last-success and alert times).
+//! `run_one_tick` takes the clock and passes it down; production code uses [`SystemClock`], tests pass a
+//! [`MockClock`] to control time without sleeping.
+
+use chrono::{DateTime, Utc};
+
+/// Source of the current wall-clock time.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Real clock (`Utc::now()`).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Manually driven clock for tests: starts at a fixed time and only moves on `advance` / `set`.
+#[cfg(test)]
+#[derive(Debug)]
+pub struct MockClock {
+    now: std::sync::Mutex<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+impl MockClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self {
+            now: std::sync::Mutex::new(start),
+        }
+    }
+
+    pub fn advance(&self, by: chrono::Duration) {
+        *self.now.lock().unwrap() += by;
+    }
+
+    pub fn set(&self, to: DateTime<Utc>) {
+        *self.now.lock().unwrap() = to;
+    }
+}
+
+#[cfg(test)]
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
 pub(crate) fn default_bearer_prefix() -> String {
@@ -9,6 +10,7 @@ pub(crate) fn default_bearer_prefix() -> String {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[serde(deny_unknown_fields)]
+#[allow(clippy::large_enum_variant)] // config value, built once per load
 pub enum AuthConfig {
     Bearer {
         token_env: String,
@@ -19,10 +21,18 @@ pub enum AuthConfig {
         prefix: String,
     },
     ApiKey {
-        header: String,
+        /// Header name (required when location is header).
+        #[serde(default)]
+        header: Option<String>,
         key_env: String,
         #[serde(default)]
         key_file: Option<String>,
+        /// Where to send the key: header (default) or query.
+        #[serde(default)]
+        location: ApiKeyLocation,
+        /// Query param name (required when location is query), e.g. "api_key".
+        #[serde(default)]
+        param: Option<String>,
     },
     Basic {
         user_env: String,
@@ -57,6 +67,18 @@ pub enum AuthConfig {
         /// When true, send DPoP (Demonstrating Proof-of-Possession) header on token and API requests (e.g. Okta).
         #[serde(default)]
         dpop: bool,
+        /// Optional `audience` form field on the token request (e.g. Auth0 API identifier).
+        #[serde(default)]
+        audience: Option<String>,
+        /// Extra form fields sent on the token request (e.g. `resource`, `tenant`). Do not override grant fields.
+        #[serde(default)]
+        extra_params: HashMap<String, String>,
+        /// JSON keys to read from the token response when the provider doesn't use `access_token` / `expires_in`.
+        #[serde(default)]
+        token_response_fields: Option<TokenResponseFields>,
+        /// Sources with the same key share one cached token (and one refresh) instead of fetching their own. They must use the same token_url, client id, scopes and audience. Not with dpop.
+        #[serde(default)]
+        token_cache_key: Option<String>,
     },
     /// Google Service Account (JWT bearer grant). For GWS Admin SDK use domain-wide delegation: set subject to admin user email.
     #[serde(rename = "google_service_account")]
@@ -75,6 +97,59 @@ pub enum AuthConfig {
         subject_file: Option<String>,
         scopes: Vec<String>,
     },
+    /// AWS Signature Version 4 (e.g. CloudWatch Logs): each request is signed with the access key pair.
+    #[serde(rename = "aws_sigv4")]
+    AwsSigV4 {
+        access_key_env: String,
+        secret_key_env: String,
+        /// Env var holding a session token for temporary credentials (sent as x-amz-security-token).
+        #[serde(default)]
+        session_token_env: Option<String>,
+        /// AWS region of the endpoint (e.g. "us-east-1").
+        region: String,
+        /// Signing service name (e.g. "logs").
+        service: String,
+    },
+}
+
+/// Where an API key is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeyLocation {
+    /// Request header named by `header`.
+    #[default]
+    Header,
+    /// URL query param named by `param` (legacy APIs); redacted in logs and audit.
+    Query,
+}
+
+/// Token response key names (dotted paths allowed). Defaults are the RFC 6749 names.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenResponseFields {
+    /// Key holding the access token (default "access_token").
+    #[serde(default = "default_access_token_field")]
+    pub access_token: String,
+    /// Key holding the lifetime in seconds, as a number or numeric string (default "expires_in").
+    #[serde(default = "default_expires_in_field")]
+    pub expires_in: String,
+}
+
+impl Default for TokenResponseFields {
+    fn default() -> Self {
+        Self {
+            access_token: default_access_token_field(),
+            expires_in: default_expires_in_field(),
+        }
+    }
+}
+
+fn default_access_token_field() -> String {
+    "access_token".to_string()
+}
+
+fn default_expires_in_field() -> String {
+    "expires_in".to_string()
 }
 
 /// Resolve a secret from file path (if set) or environment variable. File takes precedence.
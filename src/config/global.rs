@@ -30,6 +30,14 @@ pub struct GlobalConfig {
     #[serde(default)]
     pub metrics: Option<MetricsConfig>,
 
+    /// Health endpoint semantics (e.g. readyz warmup: require a first successful poll).
+    #[serde(default)]
+    pub health: Option<HealthConfig>,
+
+    /// OpenTelemetry trace export (OTLP/HTTP) of poll spans. Requires the `otel` feature.
+    #[serde(default)]
+    pub otel: Option<OtelConfig>,
+
     /// Backpressure: detection (queue depth, memory) and strategy when downstream can't keep up (block, disk_buffer, drop).
     #[serde(default)]
     pub backpressure: Option<BackpressureConfig>,
@@ -65,6 +73,90 @@ pub struct GlobalConfig {
     /// Output sink tuning (HTTP batch size, NATS subject override, etc.).
     #[serde(default)]
     pub output: Option<OutputConfig>,
+
+    /// Append responses that fail to parse (source, url, status, error, body) to a separate NDJSON file.
+    #[serde(default)]
+    pub dead_letter: Option<DeadLetterConfig>,
+
+    /// Debugging aids: keep the last `tail_capacity` emitted lines in memory, served on GET /tail (api server).
+    #[serde(default)]
+    pub debug: Option<DebugConfig>,
+
+    /// After each source poll, emit one envelope with `meta.summary: true` carrying pages, events, bytes and duration_ms.
+    #[serde(default)]
+    pub emit_tick_summary: bool,
+
+    /// Exit cleanly (after flushing output) when no source has emitted an event for this many seconds. For ephemeral runs.
+    #[serde(default)]
+    pub idle_shutdown_secs: Option<u64>,
+
+    /// Identifier for this collector replica in meta.instance_id (default: hostname). Emitted only with include_instance_id.
+    #[serde(default)]
+    pub instance_id: Option<String>,
+
+    /// Add meta.instance_id and meta.collector_host to every envelope (attribute records when many replicas share a stream).
+    #[serde(default)]
+    pub include_instance_id: bool,
+
+    /// Envelope schema version stamped into every envelope's meta.schema_version (e.g. "2").
+    #[serde(default)]
+    pub schema_version: Option<String>,
+
+    /// Directory of source fragments (e.g. "conf.d"), relative to the config file. Each `*.yaml` / `*.yml` file maps
+    /// source ids to source configs and is merged into `sources`; a source id defined twice fails the load.
+    #[serde(default)]
+    pub sources_dir: Option<String>,
+
+    /// Rename the envelope's top-level keys (e.g. ts → timestamp, event → payload) for downstream schemas.
+    #[serde(default)]
+    pub envelope_keys: Option<EnvelopeKeysConfig>,
+
+    /// How hook request ids (hook context requestId and meta.request_id on hook-emitted events) are generated:
+    /// monotonic (default), uuid, ulid.
+    #[serde(default)]
+    pub request_id_scheme: RequestIdScheme,
+
+    /// Seed for schedule and retry jitter so delays are reproducible (e.g. in tests). Unset: entropy-based randomness.
+    #[serde(default)]
+    pub jitter_seed: Option<u64>,
+
+    /// Named header sets that sources pull in via `header_profile` (e.g. shared GitHub Accept / API-version headers).
+    #[serde(default)]
+    pub header_profiles: HashMap<String, HashMap<String, String>>,
+}
+
+/// Envelope top-level key names; unset keys keep their defaults (ts, source, endpoint, event, meta).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnvelopeKeysConfig {
+    /// Key for the event timestamp (default "ts").
+    #[serde(default)]
+    pub ts: Option<String>,
+    /// Key for the producer label (default "source"); source_label_key (global or per source) takes precedence.
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Key for the request path (default "endpoint").
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// Key for the event payload (default "event").
+    #[serde(default)]
+    pub event: Option<String>,
+    /// Key for the meta object (default "meta").
+    #[serde(default)]
+    pub meta: Option<String>,
+}
+
+/// Request id generator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestIdScheme {
+    /// `helr-<nanos>`, strictly increasing within the process.
+    #[default]
+    Monotonic,
+    /// Random UUID v4.
+    Uuid,
+    /// ULID: 48-bit millisecond timestamp + 80 random bits, Crockford base32 (sortable).
+    Ulid,
 }
 
 /// Audit config: log credential access and config changes. Credential-access events never include secret values.
@@ -80,6 +172,9 @@ pub struct AuditConfig {
     /// Log when config is loaded or reloaded (e.g. on SIGHUP).
     #[serde(default = "default_true")]
     pub log_config_changes: bool,
+    /// Log every outbound source request (method, URL with secrets redacted, status, bytes). Off by default (one line per request).
+    #[serde(default)]
+    pub log_http_requests: bool,
 }
 
 fn default_true() -> bool {
@@ -154,13 +249,17 @@ pub struct BulkheadConfig {
     /// Max concurrent HTTP requests per source (default no limit). Overridable per source in resilience.bulkhead.
     #[serde(default)]
     pub max_concurrent_requests: Option<u32>,
+
+    /// Max concurrent HTTP requests across all sources (one shared semaphore; default no limit). Per-source caps apply on top.
+    #[serde(default)]
+    pub max_concurrent_requests_total: Option<u32>,
 }
 
 /// Config reload on SIGHUP.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct ReloadConfig {
-    /// When true, on SIGHUP also clear circuit breaker and OAuth2 token cache so sources re-establish on next tick.
+    /// When true, on SIGHUP also clear circuit breaker, OAuth2 token, DPoP key and hook auth caches so sources re-establish (and pick up rotated secrets) on next tick.
     #[serde(default)]
     pub restart_sources_on_sighup: bool,
 }
@@ -276,6 +375,14 @@ pub struct BackpressureDetectionConfig {
     /// RSS memory limit in MB; when exceeded we apply backpressure (optional; not implemented on all platforms).
     #[serde(default)]
     pub memory_threshold_mb: Option<u64>,
+
+    /// Queue depth at which load shedding engages. Default: event_queue_size (queue full).
+    #[serde(default)]
+    pub high_watermark: Option<usize>,
+
+    /// Queue depth below which load shedding disengages. Default: 75% of event_queue_size.
+    #[serde(default)]
+    pub low_watermark: Option<usize>,
 }
 
 fn default_stdout_buffer_size() -> u64 {
@@ -341,6 +448,15 @@ fn default_api_port() -> u16 {
     8080
 }
 
+/// Health endpoint options.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HealthConfig {
+    /// /readyz stays not-ready until at least one source has completed a successful poll.
+    #[serde(default)]
+    pub ready_requires_first_success: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct MetricsConfig {
@@ -352,6 +468,21 @@ pub struct MetricsConfig {
     pub port: u16,
 }
 
+/// OTLP trace exporter: collector endpoint and the service.name resource attribute.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OtelConfig {
+    /// OTLP/HTTP traces endpoint (e.g. "http://localhost:4318/v1/traces").
+    pub endpoint: String,
+    /// service.name reported on every span (default "helr").
+    #[serde(default = "default_otel_service_name")]
+    pub service_name: String,
+}
+
+fn default_otel_service_name() -> String {
+    "helr".to_string()
+}
+
 fn default_metrics_address() -> String {
     "0.0.0.0".to_string()
 }
@@ -360,7 +491,7 @@ fn default_metrics_port() -> u16 {
 }
 
 /// Output sink configuration (optional; CLI `--output` sets the destination,
-/// this section provides tuning for HTTP and NATS sinks).
+/// this section provides tuning for HTTP and NATS sinks and configures the Loki sink).
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct OutputConfig {
@@ -369,6 +500,65 @@ pub struct OutputConfig {
 
     #[serde(default)]
     pub nats: Option<NatsOutputConfig>,
+
+    /// Push to Grafana Loki instead of stdout (used when `--output` is not given).
+    #[serde(default)]
+    pub loki: Option<LokiOutputConfig>,
+
+    /// Write events to the systemd journal (used when `--output` is not given; Linux, `journald` feature).
+    #[serde(default)]
+    pub journald: Option<JournaldOutputConfig>,
+
+    /// Export events as OTLP log records over gRPC (used when `--output` is not given; `otlp-logs` feature).
+    #[serde(default)]
+    pub otlp: Option<OtlpOutputConfig>,
+
+    /// Write events as CSV rows (header once, then one row per line) to whichever sink is selected.
+    #[serde(default)]
+    pub csv: Option<CsvOutputConfig>,
+
+    /// Write events as JSON arrays of up to `batch_size` envelopes (one array per line) instead of one envelope per line.
+    #[serde(default)]
+    pub batch_array: Option<BatchArrayOutputConfig>,
+}
+
+/// JSON array batching: buffered envelopes are written as `[{...},{...}]` once `batch_size` is reached, once the
+/// oldest buffered envelope is `flush_interval_secs` old, or on flush.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchArrayOutputConfig {
+    #[serde(default = "default_batch_array_size")]
+    pub batch_size: usize,
+
+    /// Max seconds a partial batch waits before it is written (0: only when full or on flush).
+    #[serde(default = "default_batch_array_flush_interval_secs")]
+    pub flush_interval_secs: u64,
+}
+
+fn default_batch_array_size() -> usize {
+    100
+}
+
+fn default_batch_array_flush_interval_secs() -> u64 {
+    5
+}
+
+/// CSV output: one column per dotted path into the NDJSON envelope (e.g. "ts", "source", "event.actor.id").
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CsvOutputConfig {
+    pub columns: Vec<String>,
+}
+
+/// OTLP logs output: gRPC collector endpoint and the service.name resource attribute.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OtlpOutputConfig {
+    /// OTLP/gRPC endpoint (e.g. "http://localhost:4317").
+    pub endpoint: String,
+    /// service.name reported on every log record (default "helr").
+    #[serde(default = "default_otel_service_name")]
+    pub service_name: String,
 }
 
 /// Tuning for the HTTP POST output sink.
@@ -422,11 +612,112 @@ fn default_http_max_retries() -> u32 {
     3
 }
 
+/// Grafana Loki push sink: endpoint, static stream labels and batching.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LokiOutputConfig {
+    /// Push URL, e.g. `http://loki:3100/loki/api/v1/push`.
+    pub endpoint: String,
+
+    /// Static stream labels added to every stream (e.g. `env: prod`). `source` is always set from the event.
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+
+    /// Max lines per push request (default 100).
+    #[serde(default = "default_http_batch_size")]
+    pub batch_size: usize,
+
+    /// Max milliseconds to wait for a full batch before pushing early (default 500).
+    #[serde(default = "default_http_batch_timeout_ms")]
+    pub batch_timeout_ms: u64,
+
+    /// Extra HTTP headers (e.g. `X-Scope-OrgID`, Authorization).
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+
+    /// Request timeout in seconds (default 30).
+    #[serde(default = "default_http_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Max retries on transient failure (5xx, timeout) with exponential backoff (default 3).
+    #[serde(default = "default_http_max_retries")]
+    pub max_retries: u32,
+}
+
+/// systemd journal sink: each event becomes one journal entry with SOURCE, ENDPOINT, EVENT_TS and MESSAGE fields.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JournaldOutputConfig {
+    /// SYSLOG_IDENTIFIER on every entry (default "helr"); filter with `journalctl -t`.
+    #[serde(default = "default_journald_identifier")]
+    pub syslog_identifier: String,
+}
+
+fn default_journald_identifier() -> String {
+    "helr".to_string()
+}
+
+/// Dead-letter file for unparseable responses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeadLetterConfig {
+    /// NDJSON file the records are appended to (created if missing).
+    pub path: String,
+}
+
+/// Debug options.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DebugConfig {
+    /// Number of most recent emitted lines retained for GET /tail (0 = disabled).
+    #[serde(default)]
+    pub tail_capacity: usize,
+}
+
 /// Tuning for the NATS output sink.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct NatsOutputConfig {
-    /// Override the NATS subject parsed from the `--output nats://` URL.
+    /// Server URL (e.g. `nats://nats:4222`). When set and `--output` is omitted, events are published to NATS.
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// Override the NATS subject parsed from the `--output nats://` URL (default `helr.events`).
     #[serde(default)]
     pub subject: Option<String>,
+
+    /// Publish each envelope to `<subject_prefix>.<source>` instead of one subject.
+    #[serde(default)]
+    pub subject_prefix: Option<String>,
+
+    /// Publish through JetStream; acks are awaited on flush so the stream has every line before exit.
+    #[serde(default)]
+    pub jetstream: bool,
+
+    /// NATS credentials file (`.creds`: user JWT + NKey seed).
+    #[serde(default)]
+    pub credentials_file: Option<String>,
+
+    /// Env var holding a NATS auth token.
+    #[serde(default)]
+    pub token_env: Option<String>,
+
+    /// Username for user/password auth (password from `password_env`).
+    #[serde(default)]
+    pub user: Option<String>,
+
+    /// Env var holding the password for `user`.
+    #[serde(default)]
+    pub password_env: Option<String>,
+}
+
+/// Reject a NATS output `user` without `password_env` (it would connect with an empty password).
+pub fn validate_nats_output(config: &super::Config) -> anyhow::Result<()> {
+    let Some(nats) = config.global.output.as_ref().and_then(|o| o.nats.as_ref()) else {
+        return Ok(());
+    };
+    if nats.user.is_some() && nats.password_env.as_deref().is_none_or(str::is_empty) {
+        anyhow::bail!("global.output.nats: user requires password_env");
+    }
+    Ok(())
 }
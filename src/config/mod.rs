@@ -1,7 +1,7 @@
 //! Configuration schema for Helr (v0.1).
 //!
-//! YAML config: sources, schedule, auth, pagination, resilience.
-//! Env overrides: HELRR_*.
+//! YAML config (or TOML / JSON by file extension): sources, schedule, auth, pagination, resilience.
+//! Env overrides: HELR_* (log settings, per-source interval).
 
 #![allow(dead_code)] // fields used when implementing poll loop
 
@@ -28,26 +28,233 @@ pub struct Config {
     #[serde(default)]
     pub global: GlobalConfig,
 
+    #[serde(default)]
     pub sources: HashMap<String, SourceConfig>,
 }
 
 impl Config {
-    /// Load and parse config from path. Expands env vars; fails if any placeholder is unset (no default).
+    /// Load and parse config from path (`-` reads YAML from stdin). Expands env vars; fails if any placeholder is unset (no default).
     pub fn load(path: &Path) -> anyhow::Result<Self> {
-        let s = std::fs::read_to_string(path)
-            .map_err(|e| anyhow::anyhow!("read config {:?}: {}", path, e))?;
+        let s = if is_stdin_path(path) {
+            std::io::read_to_string(std::io::stdin())
+                .map_err(|e| anyhow::anyhow!("read config from stdin: {}", e))?
+        } else {
+            std::fs::read_to_string(path)
+                .map_err(|e| anyhow::anyhow!("read config {:?}: {}", path, e))?
+        };
         let expanded = expand_env_vars_strict(&s)?;
-        let config: Config = serde_yaml_ng::from_str(&expanded)
-            .map_err(|e| anyhow::anyhow!("parse config: {}", e))?;
+        let format = ConfigFormat::from_path(path);
+        let mut config = match parse_config_value(&expanded, format)? {
+            value if value.get("include").is_some() => {
+                let merged = resolve_includes(path, value, 0)?;
+                serde_yaml_ng::from_value(merged)
+                    .map_err(|e| anyhow::anyhow!("parse config: {}", e))?
+            }
+            _ => parse_config_str(&expanded, format)?,
+        };
+        load_sources_dir(path, &mut config)?;
+        resolve_schema_files(path, &mut config);
+        expand_for_each(&mut config)?;
         if config.sources.is_empty() {
             anyhow::bail!("config must have at least one source");
         }
+        apply_header_profiles(&mut config)?;
         validate_auth_secrets(&config)?;
+        validate_token_cache_keys(&config)?;
         validate_tls(&config)?;
+        validate_http_version(&config)?;
+        validate_schedules(&config)?;
+        validate_redact(&config)?;
+        validate_rotating_params(&config)?;
+        validate_jq(&config)?;
+        validate_schema_files(&config)?;
+        validate_streaming(&config)?;
+        validate_nats_output(&config)?;
+        if let Some(otel) = &config.global.otel {
+            crate::otel::check(otel)?;
+        }
+        validate_retry_body_match(&config)?;
+        apply_interval_env_overrides(&mut config)?;
         Ok(config)
     }
 }
 
+/// True when the config path is `-` (read from stdin). Relative includes and `sources_dir` then resolve against the working directory.
+pub fn is_stdin_path(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// On-disk config format, chosen by file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Yaml,
+    Toml,
+    Json,
+}
+
+impl ConfigFormat {
+    /// `.toml` → TOML, `.json` → JSON; `.yaml`, `.yml` and anything else → YAML.
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("toml") => ConfigFormat::Toml,
+            Some("json") => ConfigFormat::Json,
+            _ => ConfigFormat::Yaml,
+        }
+    }
+}
+
+/// Parse an (already env-expanded) config string in the given format.
+fn parse_config_str(s: &str, format: ConfigFormat) -> anyhow::Result<Config> {
+    match format {
+        ConfigFormat::Yaml => {
+            serde_yaml_ng::from_str(s).map_err(|e| anyhow::anyhow!("parse config: {}", e))
+        }
+        ConfigFormat::Toml => {
+            toml::from_str(s).map_err(|e| anyhow::anyhow!("parse config (toml): {}", e))
+        }
+        ConfigFormat::Json => {
+            serde_json::from_str(s).map_err(|e| anyhow::anyhow!("parse config (json): {}", e))
+        }
+    }
+}
+
+/// Parse an (already env-expanded) config string into a generic value, for include/overlay merging.
+fn parse_config_value(s: &str, format: ConfigFormat) -> anyhow::Result<serde_yaml_ng::Value> {
+    match format {
+        ConfigFormat::Yaml => {
+            serde_yaml_ng::from_str(s).map_err(|e| anyhow::anyhow!("parse config: {}", e))
+        }
+        ConfigFormat::Toml => {
+            toml::from_str(s).map_err(|e| anyhow::anyhow!("parse config (toml): {}", e))
+        }
+        ConfigFormat::Json => {
+            serde_json::from_str(s).map_err(|e| anyhow::anyhow!("parse config (json): {}", e))
+        }
+    }
+}
+
+/// Merge the source fragments under global.sources_dir (relative to `path`'s directory) into config.sources,
+/// in file-name order. Fails on a source id already defined in the config or an earlier fragment.
+fn load_sources_dir(path: &Path, config: &mut Config) -> anyhow::Result<()> {
+    let Some(dir) = config.global.sources_dir.as_deref() else {
+        return Ok(());
+    };
+    let dir = path.parent().unwrap_or_else(|| Path::new(".")).join(dir);
+    let mut files: Vec<std::path::PathBuf> = std::fs::read_dir(&dir)
+        .map_err(|e| anyhow::anyhow!("read sources_dir {:?}: {}", dir, e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.is_file()
+                && p.extension().and_then(|e| e.to_str()).is_some_and(|e| {
+                    e.eq_ignore_ascii_case("yaml") || e.eq_ignore_ascii_case("yml")
+                })
+        })
+        .collect();
+    files.sort();
+    for file in files {
+        let s = std::fs::read_to_string(&file)
+            .map_err(|e| anyhow::anyhow!("read source fragment {:?}: {}", file, e))?;
+        let expanded = expand_env_vars_strict(&s)?;
+        let fragment: HashMap<String, SourceConfig> = serde_yaml_ng::from_str(&expanded)
+            .map_err(|e| anyhow::anyhow!("parse source fragment {:?}: {}", file, e))?;
+        for (source_id, source) in fragment {
+            if config.sources.contains_key(&source_id) {
+                anyhow::bail!(
+                    "source fragment {:?}: duplicate source {:?} (already defined)",
+                    file,
+                    source_id
+                );
+            }
+            config.sources.insert(source_id, source);
+        }
+    }
+    Ok(())
+}
+
+/// Resolve relative `schema_file` paths against `path`'s directory, like `include` and `sources_dir`.
+fn resolve_schema_files(path: &Path, config: &mut Config) {
+    if is_stdin_path(path) {
+        return;
+    }
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    for source in config.sources.values_mut() {
+        if let Some(file) = source.schema_file.as_mut()
+            && Path::new(file.as_str()).is_relative()
+        {
+            *file = base_dir.join(&*file).to_string_lossy().into_owned();
+        }
+    }
+}
+
+/// Max nesting of `include:` (guards against include cycles).
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Resolve a top-level `include: [paths]` in `value` (loaded from `path`): each included file (relative to
+/// `path`'s directory, itself env-expanded and include-resolved) is deep-merged in order, then `value`
+/// is merged over the result, so later includes win over earlier ones and the including file wins over all.
+fn resolve_includes(
+    path: &Path,
+    mut value: serde_yaml_ng::Value,
+    depth: usize,
+) -> anyhow::Result<serde_yaml_ng::Value> {
+    let Some(map) = value.as_mapping_mut() else {
+        return Ok(value);
+    };
+    let Some(include) = map.remove("include") else {
+        return Ok(value);
+    };
+    if depth >= MAX_INCLUDE_DEPTH {
+        anyhow::bail!(
+            "config include nested more than {} levels at {:?} (include cycle?)",
+            MAX_INCLUDE_DEPTH,
+            path
+        );
+    }
+    let includes: Vec<String> = serde_yaml_ng::from_value(include).map_err(|e| {
+        anyhow::anyhow!(
+            "config include in {:?}: expected a list of paths: {}",
+            path,
+            e
+        )
+    })?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut merged = serde_yaml_ng::Value::Mapping(Default::default());
+    for inc in includes {
+        let inc_path = base_dir.join(&inc);
+        let s = std::fs::read_to_string(&inc_path)
+            .map_err(|e| anyhow::anyhow!("read config include {:?}: {}", inc_path, e))?;
+        let expanded = expand_env_vars_strict(&s)?;
+        let inc_value = parse_config_value(&expanded, ConfigFormat::from_path(&inc_path))
+            .map_err(|e| anyhow::anyhow!("config include {:?}: {}", inc_path, e))?;
+        let inc_value = resolve_includes(&inc_path, inc_value, depth + 1)?;
+        deep_merge(&mut merged, inc_value);
+    }
+    deep_merge(&mut merged, value);
+    Ok(merged)
+}
+
+/// Merge `overlay` into `base`: mappings merge key by key (recursively); any other value replaces.
+fn deep_merge(base: &mut serde_yaml_ng::Value, overlay: serde_yaml_ng::Value) {
+    match (base, overlay) {
+        (serde_yaml_ng::Value::Mapping(base_map), serde_yaml_ng::Value::Mapping(overlay_map)) => {
+            for (k, v) in overlay_map {
+                match base_map.get_mut(&k) {
+                    Some(existing) => deep_merge(existing, v),
+                    None => {
+                        base_map.insert(k, v);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
 /// Expand env vars in config: `$VAR`, `${VAR}`, `${VAR:-default}`. Fails if any var is unset (no default).
 /// Lines that are comments (after trim, empty or starting with #) are not expanded, so placeholders in comments are left as-is.
 fn expand_env_vars_strict(s: &str) -> anyhow::Result<String> {
@@ -182,6 +389,133 @@ sources:
         let _ = std::fs::remove_file(&path);
     }
 
+    #[test]
+    fn config_load_include_overlay_wins() {
+        let dir = std::env::temp_dir().join("helr_config_include");
+        let _ = std::fs::create_dir_all(&dir);
+        let base = dir.join("base.yaml");
+        let overlay = dir.join("prod.yaml");
+        std::fs::write(
+            &base,
+            r#"
+global:
+  log_level: info
+sources:
+  okta:
+    url: "https://example.okta.com/api/v1/logs"
+    schedule:
+      interval_secs: 60
+      jitter_secs: 5
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            &overlay,
+            r#"
+include: ["base.yaml"]
+global:
+  log_level: warn
+sources:
+  okta:
+    schedule:
+      interval_secs: 15
+"#,
+        )
+        .unwrap();
+        let config = Config::load(&overlay).unwrap();
+        assert_eq!(config.global.log_level, "warn");
+        let okta = &config.sources["okta"];
+        assert_eq!(okta.url, "https://example.okta.com/api/v1/logs");
+        assert_eq!(okta.schedule.interval_secs, 15);
+        assert_eq!(okta.schedule.jitter_secs, Some(5));
+        let _ = std::fs::remove_file(&base);
+        let _ = std::fs::remove_file(&overlay);
+    }
+
+    #[test]
+    fn config_load_sources_dir_merges_fragments() {
+        let dir = std::env::temp_dir().join("helr_config_sources_dir");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("conf.d")).unwrap();
+        let path = dir.join("helr.yaml");
+        std::fs::write(&path, "global:\n  sources_dir: conf.d\n").unwrap();
+        std::fs::write(
+            dir.join("conf.d/okta.yaml"),
+            "okta:\n  url: \"https://example.okta.com/api/v1/logs\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("conf.d/github.yml"),
+            "github:\n  url: \"https://api.github.com/audit-log\"\n  schedule:\n    interval_secs: 30\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("conf.d/README.txt"), "not a fragment").unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.sources.len(), 2);
+        assert_eq!(
+            config.sources["okta"].url,
+            "https://example.okta.com/api/v1/logs"
+        );
+        assert_eq!(config.sources["github"].schedule.interval_secs, 30);
+
+        std::fs::write(
+            dir.join("conf.d/okta-copy.yaml"),
+            "okta:\n  url: \"https://other.okta.com/api/v1/logs\"\n",
+        )
+        .unwrap();
+        let err = Config::load(&path).unwrap_err().to_string();
+        assert!(err.contains("duplicate source \"okta\""), "{}", err);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn config_load_resolves_schema_file_against_config_dir() {
+        let dir = std::env::temp_dir().join("helr_config_schema_file");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("schemas")).unwrap();
+        std::fs::write(dir.join("schemas/okta.json"), r#"{"type": "object"}"#).unwrap();
+        let path = dir.join("helr.yaml");
+        std::fs::write(
+            &path,
+            "sources:\n  okta:\n    url: \"https://example.okta.com/api/v1/logs\"\n    schema_file: schemas/okta.json\n",
+        )
+        .unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(
+            config.sources["okta"].schema_file.as_deref(),
+            Some(dir.join("schemas/okta.json").to_str().unwrap())
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn config_load_tls_sni_hostname_requires_ip_url() {
+        let dir = std::env::temp_dir().join("helr_config_sni");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("helr.yaml");
+        let yaml = |url: &str| {
+            format!(
+                "sources:\n  lb:\n    url: \"{}\"\n    resilience:\n      tls:\n        sni_hostname: api.example.com\n",
+                url
+            )
+        };
+        std::fs::write(&path, yaml("https://10.0.0.5:8443/logs")).unwrap();
+        let config = Config::load(&path).unwrap();
+        let tls = config.sources["lb"]
+            .resilience
+            .as_ref()
+            .and_then(|r| r.tls.as_ref())
+            .unwrap();
+        assert_eq!(tls.sni_hostname.as_deref(), Some("api.example.com"));
+
+        std::fs::write(&path, yaml("https://api.example.com/logs")).unwrap();
+        let err = format!("{:#}", Config::load(&path).unwrap_err());
+        assert!(err.contains("requires an IP address"), "{}", err);
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[test]
     fn config_load_backpressure() {
         let dir = std::env::temp_dir().join("helr_config_load_test");
@@ -300,6 +634,7 @@ global:
   bulkhead:
     max_concurrent_sources: 4
     max_concurrent_requests: 2
+    max_concurrent_requests_total: 8
 sources:
   s1:
     url: "https://example.com/"
@@ -315,6 +650,7 @@ sources:
         let b = config.global.bulkhead.as_ref().unwrap();
         assert_eq!(b.max_concurrent_sources, Some(4));
         assert_eq!(b.max_concurrent_requests, Some(2));
+        assert_eq!(b.max_concurrent_requests_total, Some(8));
         let s1 = config.sources.get("s1").unwrap();
         let sb = s1.resilience.as_ref().unwrap().bulkhead.as_ref().unwrap();
         assert_eq!(sb.max_concurrent_requests, Some(1));
@@ -353,6 +689,468 @@ sources:
         let _ = std::fs::remove_file(&path);
     }
 
+    #[test]
+    fn config_load_resolve_override() {
+        let dir = std::env::temp_dir().join("helr_config_resolve");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("helr.yaml");
+        let yaml = r#"
+sources:
+  pinned:
+    url: "https://api.example.com/logs"
+    resilience:
+      resolve:
+        "api.example.com:443": "10.0.0.5:443"
+"#;
+        std::fs::write(&path, yaml).unwrap();
+        let config = Config::load(&path).unwrap();
+        let resolve = config.sources["pinned"]
+            .resilience
+            .as_ref()
+            .unwrap()
+            .resolve
+            .as_ref()
+            .unwrap();
+        assert_eq!(
+            resolve.get("api.example.com:443").map(String::as_str),
+            Some("10.0.0.5:443")
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn config_load_max_connections() {
+        let dir = std::env::temp_dir().join("helr_config_max_connections");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("helr.yaml");
+        let yaml = r#"
+sources:
+  capped:
+    url: "https://example.com/logs"
+    resilience:
+      max_connections: 2
+"#;
+        std::fs::write(&path, yaml).unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(
+            config.sources["capped"]
+                .resilience
+                .as_ref()
+                .unwrap()
+                .max_connections,
+            Some(2)
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn config_load_pool_idle_timeout_and_tcp_keepalive() {
+        let dir = std::env::temp_dir().join("helr_config_keepalive");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("helr.yaml");
+        let yaml = r#"
+sources:
+  bursty:
+    url: "https://example.com/logs"
+    resilience:
+      pool_idle_timeout_secs: 300
+      tcp_keepalive_secs: 30
+"#;
+        std::fs::write(&path, yaml).unwrap();
+        let config = Config::load(&path).unwrap();
+        let r = config.sources["bursty"].resilience.as_ref().unwrap();
+        assert_eq!(r.pool_idle_timeout_secs, Some(300));
+        assert_eq!(r.tcp_keepalive_secs, Some(30));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn config_load_http_version_variants() {
+        let dir = std::env::temp_dir().join("helr_config_http_version");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("helr.yaml");
+        let yaml = r#"
+sources:
+  default:
+    url: "https://example.com/logs"
+  auto:
+    url: "https://example.com/logs"
+    resilience:
+      http_version: auto
+  h1:
+    url: "https://example.com/logs"
+    resilience:
+      http_version: http1
+  h2:
+    url: "https://example.com/logs"
+    resilience:
+      http_version: http2
+  h2c:
+    url: "http://grpc-gateway:8080/logs"
+    resilience:
+      http_version: http2_prior_knowledge
+"#;
+        std::fs::write(&path, yaml).unwrap();
+        let config = Config::load(&path).unwrap();
+        let version = |id: &str| {
+            config.sources[id]
+                .resilience
+                .as_ref()
+                .map(|r| r.http_version)
+                .unwrap_or_default()
+        };
+        assert_eq!(version("default"), HttpVersionConfig::Auto);
+        assert_eq!(version("auto"), HttpVersionConfig::Auto);
+        assert_eq!(version("h1"), HttpVersionConfig::Http1);
+        assert_eq!(version("h2"), HttpVersionConfig::Http2);
+        assert_eq!(version("h2c"), HttpVersionConfig::Http2PriorKnowledge);
+
+        let yaml = r#"
+sources:
+  cleartext:
+    url: "http://example.com/logs"
+    resilience:
+      http_version: http2
+"#;
+        std::fs::write(&path, yaml).unwrap();
+        let err = Config::load(&path).unwrap_err();
+        assert!(err.to_string().contains("http2_prior_knowledge"), "{}", err);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn config_load_rejects_invalid_cron() {
+        let dir = std::env::temp_dir().join("helr_config_cron");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("helr.yaml");
+        let yaml = r#"
+sources:
+  weekday:
+    url: "https://example.com/logs"
+    schedule:
+      cron: "0 9 * * Mon-Fri"
+  broken:
+    url: "https://example.com/logs"
+    schedule:
+      cron: "not a cron"
+"#;
+        std::fs::write(&path, yaml).unwrap();
+        let err = Config::load(&path).unwrap_err();
+        assert!(err.to_string().contains("broken"), "{}", err);
+        assert!(err.to_string().contains("schedule.cron"), "{}", err);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn config_load_rejects_token_cache_key_shared_across_different_token_requests() {
+        unsafe {
+            std::env::set_var("HELR_TEST_SHARED_CLIENT_ID", "client");
+            std::env::set_var("HELR_TEST_SHARED_CLIENT_SECRET", "secret");
+        }
+        let dir = std::env::temp_dir().join("helr_config_token_cache_key");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("helr.yaml");
+        let yaml = |audit_scopes: &str| {
+            format!(
+                r#"
+sources:
+  audit:
+    url: "https://example.com/audit"
+    auth:
+      type: oauth2
+      token_url: "https://idp.example.com/token"
+      client_id_env: HELR_TEST_SHARED_CLIENT_ID
+      client_secret_env: HELR_TEST_SHARED_CLIENT_SECRET
+      scopes: [{audit_scopes}]
+      token_cache_key: acme
+  users:
+    url: "https://example.com/users"
+    auth:
+      type: oauth2
+      token_url: "https://idp.example.com/token"
+      client_id_env: HELR_TEST_SHARED_CLIENT_ID
+      client_secret_env: HELR_TEST_SHARED_CLIENT_SECRET
+      scopes: [logs.read]
+      token_cache_key: acme
+"#
+            )
+        };
+        std::fs::write(&path, yaml("logs.read")).unwrap();
+        Config::load(&path).unwrap();
+
+        std::fs::write(&path, yaml("admin")).unwrap();
+        let err = Config::load(&path).unwrap_err().to_string();
+        assert!(err.contains("token_cache_key"), "{}", err);
+        assert!(err.contains("scopes"), "{}", err);
+        let _ = std::fs::remove_file(&path);
+        unsafe {
+            std::env::remove_var("HELR_TEST_SHARED_CLIENT_ID");
+            std::env::remove_var("HELR_TEST_SHARED_CLIENT_SECRET");
+        }
+    }
+
+    #[test]
+    fn config_load_rejects_nats_user_without_password_env() {
+        let dir = std::env::temp_dir().join("helr_config_nats_user");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("helr.yaml");
+        let yaml = r#"
+global:
+  output:
+    nats:
+      url: "nats://localhost:4222"
+      user: "helr"
+sources:
+  s:
+    url: "https://example.com/logs"
+"#;
+        std::fs::write(&path, yaml).unwrap();
+        let err = Config::load(&path).unwrap_err();
+        assert!(err.to_string().contains("password_env"), "{}", err);
+
+        std::fs::write(
+            &path,
+            yaml.replace(
+                "user: \"helr\"",
+                "user: \"helr\"\n      password_env: HELR_NATS_PASSWORD",
+            ),
+        )
+        .unwrap();
+        Config::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn config_load_yaml_toml_json_equivalent() {
+        let dir = std::env::temp_dir().join("helr_config_formats");
+        let _ = std::fs::create_dir_all(&dir);
+        unsafe {
+            std::env::set_var("HELR_TEST_FORMAT_HOST", "example.com");
+        }
+        let yaml = r#"
+global:
+  log_level: warn
+  state:
+    backend: memory
+sources:
+  okta:
+    url: "https://${HELR_TEST_FORMAT_HOST}/api/v1/logs"
+    schedule:
+      interval_secs: 120
+    pagination:
+      strategy: link_header
+      rel: next
+      max_pages: 5
+    resilience:
+      timeout_secs: 10
+      retries:
+        max_attempts: 3
+"#;
+        let toml = r#"
+# comment lines are not env-expanded: ${HELR_TEST_FORMAT_UNSET}
+[global]
+log_level = "warn"
+
+[global.state]
+backend = "memory"
+
+[sources.okta]
+url = "https://${HELR_TEST_FORMAT_HOST}/api/v1/logs"
+
+[sources.okta.schedule]
+interval_secs = 120
+
+[sources.okta.pagination]
+strategy = "link_header"
+rel = "next"
+max_pages = 5
+
+[sources.okta.resilience]
+timeout_secs = 10
+
+[sources.okta.resilience.retries]
+max_attempts = 3
+"#;
+        let json = r#"{
+  "global": { "log_level": "warn", "state": { "backend": "memory" } },
+  "sources": {
+    "okta": {
+      "url": "https://${HELR_TEST_FORMAT_HOST}/api/v1/logs",
+      "schedule": { "interval_secs": 120 },
+      "pagination": { "strategy": "link_header", "rel": "next", "max_pages": 5 },
+      "resilience": { "timeout_secs": 10, "retries": { "max_attempts": 3 } }
+    }
+  }
+}"#;
+        let mut loaded = Vec::new();
+        for (name, content) in [
+            ("helr.yaml", yaml),
+            ("helr.toml", toml),
+            ("helr.json", json),
+        ] {
+            let path = dir.join(name);
+            std::fs::write(&path, content).unwrap();
+            let config = Config::load(&path).unwrap_or_else(|e| panic!("{}: {}", name, e));
+            loaded.push(serde_json::to_value(&config).unwrap());
+        }
+        unsafe {
+            std::env::remove_var("HELR_TEST_FORMAT_HOST");
+        }
+        assert_eq!(
+            loaded[0]["sources"]["okta"]["url"],
+            "https://example.com/api/v1/logs"
+        );
+        assert_eq!(loaded[0], loaded[1], "yaml vs toml");
+        assert_eq!(loaded[0], loaded[2], "yaml vs json");
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("x.YML")),
+            ConfigFormat::Yaml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("x.Toml")),
+            ConfigFormat::Toml
+        );
+    }
+
+    #[test]
+    fn config_load_header_profile_merges_with_source_headers() {
+        let dir = std::env::temp_dir().join("helr_config_header_profiles");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("helr.yaml");
+        let yaml = r#"
+global:
+  header_profiles:
+    github:
+      Accept: "application/vnd.github+json"
+      X-GitHub-Api-Version: "2022-11-28"
+sources:
+  gh-audit:
+    url: "https://api.github.com/orgs/acme/audit-log"
+    header_profile: github
+    headers:
+      X-GitHub-Api-Version: "2024-01-01"
+      X-Extra: "1"
+  gh-lower:
+    url: "https://api.github.com/orgs/acme/hooks"
+    header_profile: github
+    headers:
+      accept: "application/json"
+  gh-plain:
+    url: "https://api.github.com/orgs/acme/events"
+    header_profile: github
+"#;
+        std::fs::write(&path, yaml).unwrap();
+        let config = Config::load(&path).unwrap();
+        let audit = config.sources["gh-audit"].headers.as_ref().unwrap();
+        assert_eq!(audit["Accept"], "application/vnd.github+json");
+        assert_eq!(audit["X-GitHub-Api-Version"], "2024-01-01");
+        assert_eq!(audit["X-Extra"], "1");
+        let lower = config.sources["gh-lower"].headers.as_ref().unwrap();
+        assert_eq!(lower.len(), 2);
+        assert_eq!(lower["accept"], "application/json");
+        assert!(!lower.contains_key("Accept"));
+        let plain = config.sources["gh-plain"].headers.as_ref().unwrap();
+        assert_eq!(plain.len(), 2);
+        assert_eq!(plain["X-GitHub-Api-Version"], "2022-11-28");
+
+        std::fs::write(
+            &path,
+            yaml.replace(
+                "header_profile: github\n    headers",
+                "header_profile: gitlab\n    headers",
+            ),
+        )
+        .unwrap();
+        let err = Config::load(&path).unwrap_err();
+        assert!(err.to_string().contains("gitlab"), "{}", err);
+    }
+
+    #[test]
+    fn config_load_for_each_expands_one_source_per_value() {
+        let dir = std::env::temp_dir().join("helr_config_for_each");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("helr.yaml");
+        let yaml = r#"
+sources:
+  tenant:
+    url: "https://api.example.com/tenants/{item}/audit"
+    headers:
+      X-Tenant: "{item}"
+    state:
+      watermark_field: "ts"
+      watermark_param: "since"
+      state_key: "wm-{item}"
+    for_each:
+      values: ["acme", "globex"]
+"#;
+        std::fs::write(&path, yaml).unwrap();
+        let config = Config::load(&path).unwrap();
+        let mut ids: Vec<&str> = config.sources.keys().map(String::as_str).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["tenant-acme", "tenant-globex"]);
+        let acme = &config.sources["tenant-acme"];
+        assert_eq!(acme.url, "https://api.example.com/tenants/acme/audit");
+        assert_eq!(acme.headers.as_ref().unwrap()["X-Tenant"], "acme");
+        assert_eq!(
+            acme.state.as_ref().unwrap().state_key.as_deref(),
+            Some("wm-acme")
+        );
+        assert!(acme.for_each.is_none());
+        assert_eq!(
+            config.sources["tenant-globex"].url,
+            "https://api.example.com/tenants/globex/audit"
+        );
+
+        std::fs::write(
+            &path,
+            yaml.replace(r#"values: ["acme", "globex"]"#, "values: []"),
+        )
+        .unwrap();
+        let err = Config::load(&path).unwrap_err();
+        assert!(err.to_string().contains("no values"), "{}", err);
+    }
+
+    #[test]
+    fn config_load_interval_env_override() {
+        let dir = std::env::temp_dir().join("helr_config_interval_env");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("helr.yaml");
+        let yaml = r#"
+sources:
+  staging-audit.v2:
+    url: "https://example.com/logs"
+    schedule:
+      interval_secs: 60
+  untouched:
+    url: "https://example.com/logs"
+    schedule:
+      interval_secs: 60
+"#;
+        std::fs::write(&path, yaml).unwrap();
+        assert_eq!(
+            interval_env_var("staging-audit.v2"),
+            "HELR_SOURCE_STAGING_AUDIT_V2_INTERVAL_SECS"
+        );
+        unsafe {
+            std::env::set_var("HELR_SOURCE_STAGING_AUDIT_V2_INTERVAL_SECS", "300");
+        }
+        let config = Config::load(&path).unwrap();
+        assert_eq!(
+            config.sources["staging-audit.v2"].schedule.interval_secs,
+            300
+        );
+        assert_eq!(config.sources["untouched"].schedule.interval_secs, 60);
+        unsafe {
+            std::env::set_var("HELR_SOURCE_STAGING_AUDIT_V2_INTERVAL_SECS", "0");
+        }
+        let err = Config::load(&path).unwrap_err();
+        assert!(err.to_string().contains("positive integer"), "{}", err);
+        unsafe {
+            std::env::remove_var("HELR_SOURCE_STAGING_AUDIT_V2_INTERVAL_SECS");
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
     #[test]
     fn config_load_rate_limit() {
         let dir = std::env::temp_dir().join("helr_config_rate_limit");
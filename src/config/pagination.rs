@@ -16,6 +16,30 @@ impl QueryParamValue {
     }
 }
 
+/// How `query_params` values are encoded when appended to the first request URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryEncoding {
+    /// RFC 3986 percent-encoding: everything but unreserved characters is escaped (space → %20).
+    Standard,
+    /// application/x-www-form-urlencoded (space → +), as produced by `query_pairs_mut`.
+    #[default]
+    Form,
+    /// Append as written; for values that are already percent-encoded (avoids double-encoding).
+    Raw,
+}
+
+/// Which matching link wins when a response has more than one Link entry with the wanted `rel`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateRelSelection {
+    /// First match in header order (across multiple Link headers, then within each).
+    #[default]
+    PreferFirst,
+    /// Last match in header order.
+    PreferLast,
+}
+
 /// HTTP method for the source request.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
@@ -25,6 +49,16 @@ pub enum HttpMethod {
     Post,
 }
 
+impl HttpMethod {
+    /// Upper-case method name (e.g. for logs and DPoP `htm`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Post => "POST",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "strategy", rename_all = "snake_case")]
 #[serde(deny_unknown_fields)]
@@ -34,12 +68,18 @@ pub enum PaginationConfig {
         rel: String,
         #[serde(default)]
         max_pages: Option<u32>,
+        /// Which link to follow when several Link entries carry `rel` (e.g. next + preload hint).
+        #[serde(default)]
+        duplicate_rel: DuplicateRelSelection,
     },
     Cursor {
         cursor_param: String,
         cursor_path: String, // JSONPath or simple key
         #[serde(default)]
         max_pages: Option<u32>,
+        /// Dotted path to a "has more" boolean (e.g. "has_more"). When it resolves to false, stop even if a cursor is present.
+        #[serde(default)]
+        has_more_path: Option<String>,
     },
     PageOffset {
         page_param: String,
@@ -47,6 +87,15 @@ pub enum PaginationConfig {
         limit: u32,
         #[serde(default)]
         max_pages: Option<u32>,
+        /// Store the last (partial) page in state and start there next tick instead of page 1. For append-only feeds.
+        #[serde(default)]
+        offset_resume: bool,
+        /// Dotted path to the total result count (e.g. "total", "meta.total"); stop once the pages fetched cover it.
+        #[serde(default)]
+        stop_on_total_path: Option<String>,
+        /// Stop after this many consecutive empty pages; isolated empty pages before that are skipped over.
+        #[serde(default = "default_stop_after_empty_pages")]
+        stop_after_empty_pages: u32,
     },
     Offset {
         offset_param: String,
@@ -54,9 +103,22 @@ pub enum PaginationConfig {
         limit: u32,
         #[serde(default)]
         max_pages: Option<u32>,
+        /// Store the offset after the last event in state and start there next tick instead of 0. For append-only feeds.
+        #[serde(default)]
+        offset_resume: bool,
+        /// Dotted path to the total result count (e.g. "total", "meta.total"); stop once the pages fetched cover it.
+        #[serde(default)]
+        stop_on_total_path: Option<String>,
+        /// Stop after this many consecutive empty pages; isolated empty pages before that are skipped over.
+        #[serde(default = "default_stop_after_empty_pages")]
+        stop_after_empty_pages: u32,
     },
 }
 
+fn default_stop_after_empty_pages() -> u32 {
+    1
+}
+
 fn default_rel() -> String {
     "next".to_string()
 }
@@ -1,5 +1,6 @@
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
 use super::{AuthConfig, Config, read_secret};
@@ -54,6 +55,10 @@ pub struct TlsConfig {
     /// Minimum TLS version: "1.2" or "1.3".
     #[serde(default)]
     pub min_version: Option<String>,
+    /// Hostname to present in TLS SNI (and the Host header) when the source URL addresses an IP directly,
+    /// e.g. an internal load balancer. Requires an IP address as the URL host.
+    #[serde(default)]
+    pub sni_hostname: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -75,6 +80,37 @@ pub struct ResilienceConfig {
     /// Per-source bulkhead: max concurrent requests for this source. Overrides global bulkhead.max_concurrent_requests when set.
     #[serde(default)]
     pub bulkhead: Option<SourceBulkheadConfig>,
+    /// Static DNS overrides: "host:port" (or "host") -> "ip:port". Pins a hostname to an address without touching /etc/hosts.
+    #[serde(default)]
+    pub resolve: Option<HashMap<String, String>>,
+    /// Max connections to this source: caps in-flight requests across overlapping polls and idle pooled connections
+    /// per host (the pool is kept across ticks).
+    #[serde(default)]
+    pub max_connections: Option<usize>,
+    /// HTTP protocol version: auto (ALPN, HTTP/1.1 over cleartext), http1, http2 (TLS only), http2_prior_knowledge.
+    #[serde(default)]
+    pub http_version: HttpVersionConfig,
+    /// Keep idle pooled connections (and their TLS sessions) this long between ticks. Overrides timeouts.idle_secs.
+    #[serde(default)]
+    pub pool_idle_timeout_secs: Option<u64>,
+    /// TCP keepalive probe interval (seconds) on connections to this source.
+    #[serde(default)]
+    pub tcp_keepalive_secs: Option<u64>,
+}
+
+/// Protocol version the client speaks to a source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HttpVersionConfig {
+    /// Negotiate via ALPN over TLS (h2 or HTTP/1.1); HTTP/1.1 over cleartext.
+    #[default]
+    Auto,
+    /// HTTP/1.1 only.
+    Http1,
+    /// HTTP/2 negotiated via ALPN (the server may still pick HTTP/1.1); requires an https URL.
+    Http2,
+    /// HTTP/2 without negotiation (h2c over cleartext, or h2 over TLS without HTTP/1.1 fallback).
+    Http2PriorKnowledge,
 }
 
 /// Per-source bulkhead (overrides global when set).
@@ -99,6 +135,10 @@ pub struct RateLimitHeaderMapping {
     /// Header for window reset time, Unix timestamp (e.g. "X-RateLimit-Reset").
     #[serde(default)]
     pub reset_header: Option<String>,
+    /// Header naming the rate-limit bucket a response counts against (e.g. GitHub's "X-RateLimit-Resource").
+    /// When set, adaptive throttling tracks remaining/reset per bucket instead of one window per source.
+    #[serde(default)]
+    pub resource_header: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -153,6 +193,12 @@ pub struct CircuitBreakerConfig {
     /// Minimum requests before evaluating failure_rate_threshold.
     #[serde(default)]
     pub minimum_requests: Option<u32>,
+    /// Sliding window (seconds) for failure_rate_threshold: outcomes older than this are discarded before computing the rate. When unset, the rate uses a flat count since the circuit last closed.
+    #[serde(default)]
+    pub failure_rate_window_secs: Option<u64>,
+    /// When true, state transitions (closed→open, open→half_open, half_open→closed) emit an envelope with `meta.circuit_state`.
+    #[serde(default)]
+    pub emit_circuit_events: bool,
 }
 
 fn default_cb_enabled() -> bool {
@@ -189,6 +235,92 @@ pub struct RetryConfig {
     /// HTTP status codes to retry. When unset, default: 408, 429, 5xx.
     #[serde(default)]
     pub retryable_status_codes: Option<Vec<u16>>,
+    /// Multiply the per-request timeout by this factor on each retry (attempt n uses timeout × factor^n), capped at max_backoff_secs when set but never below the base timeout.
+    #[serde(default)]
+    pub timeout_multiplier: Option<f64>,
+    /// Treat a 2xx response whose body matches this rule as retryable (e.g. 200 with `{"error": "rate_limited"}`). The body is buffered when set.
+    #[serde(default)]
+    pub retry_on_body_match: Option<RetryBodyMatchConfig>,
+    /// Which failure classes are retried: connection errors, timeouts, retryable HTTP statuses. All on by default.
+    #[serde(default)]
+    pub retry_on: RetryOnConfig,
+}
+
+/// Per-class switches for retries.retry_on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetryOnConfig {
+    /// Transport errors other than timeouts (connection refused or reset, DNS, TLS).
+    #[serde(default = "default_true")]
+    pub connect: bool,
+    /// Request timeouts (the request or per-attempt timeout elapsed).
+    #[serde(default = "default_true")]
+    pub timeout: bool,
+    /// Retryable HTTP status codes (retryable_status_codes, or 408, 429, 5xx).
+    #[serde(default = "default_true")]
+    pub status: bool,
+}
+
+impl Default for RetryOnConfig {
+    fn default() -> Self {
+        Self {
+            connect: true,
+            timeout: true,
+            status: true,
+        }
+    }
+}
+
+/// Body condition for retries.retry_on_body_match: `path` + `equals`, and/or a `pattern` regex.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RetryBodyMatchConfig {
+    /// Dotted JSON path into the body (e.g. "error" or "meta.status"). Required with `equals`.
+    #[serde(default)]
+    pub path: Option<String>,
+    /// Retry when the value at `path` equals this JSON value.
+    #[serde(default)]
+    pub equals: Option<serde_json::Value>,
+    /// Retry when this regex matches the string at `path` (or the whole body when `path` is unset).
+    #[serde(default)]
+    pub pattern: Option<String>,
+}
+
+/// Validate retries.retry_on_body_match: needs `equals` or `pattern`, `equals` needs `path`, and `pattern` must compile.
+pub fn validate_retry_body_match(config: &Config) -> anyhow::Result<()> {
+    for (source_id, source) in &config.sources {
+        let Some(rule) = source
+            .resilience
+            .as_ref()
+            .and_then(|r| r.retries.as_ref())
+            .and_then(|r| r.retry_on_body_match.as_ref())
+        else {
+            continue;
+        };
+        if rule.equals.is_none() && rule.pattern.is_none() {
+            anyhow::bail!(
+                "source {}: retries.retry_on_body_match requires equals or pattern",
+                source_id
+            );
+        }
+        if rule.equals.is_some() && rule.path.is_none() {
+            anyhow::bail!(
+                "source {}: retries.retry_on_body_match.equals requires path",
+                source_id
+            );
+        }
+        if let Some(pattern) = &rule.pattern {
+            regex::Regex::new(pattern).map_err(|e| {
+                anyhow::anyhow!(
+                    "source {}: retries.retry_on_body_match pattern {:?}: {}",
+                    source_id,
+                    pattern,
+                    e
+                )
+            })?;
+        }
+    }
+    Ok(())
 }
 
 fn default_max_attempts() -> u32 {
@@ -200,6 +332,27 @@ fn default_initial_backoff_secs() -> u64 {
 fn default_multiplier() -> f64 {
     2.0
 }
+fn default_true() -> bool {
+    true
+}
+
+/// Reject `http_version: http2` on cleartext URLs (no upgrade support; use http2_prior_knowledge for h2c).
+pub fn validate_http_version(config: &Config) -> anyhow::Result<()> {
+    for (source_id, source) in &config.sources {
+        let version = source
+            .resilience
+            .as_ref()
+            .map(|r| r.http_version)
+            .unwrap_or_default();
+        if version == HttpVersionConfig::Http2 && source.url.starts_with("http://") {
+            anyhow::bail!(
+                "source {}: http_version http2 requires an https url; use http2_prior_knowledge for cleartext HTTP/2",
+                source_id
+            );
+        }
+    }
+    Ok(())
+}
 
 /// Validate TLS config: client cert and key both set when either is set; resolve CA/cert/key so startup fails if missing.
 pub fn validate_tls(config: &Config) -> anyhow::Result<()> {
@@ -258,10 +411,90 @@ pub fn validate_tls(config: &Config) -> anyhow::Result<()> {
                 v
             );
         }
+        if tls.sni_hostname.is_some() {
+            crate::client::sni_override(source)
+                .with_context(|| format!("source {}: tls sni_hostname", source_id))?;
+        }
     }
     Ok(())
 }
 
+/// Sources sharing an oauth2 `token_cache_key` share one token, so they must request it identically:
+/// same `token_url`, client id (env/file), `scopes` and `audience`.
+pub fn validate_token_cache_keys(config: &Config) -> anyhow::Result<()> {
+    let mut first_by_key: HashMap<&str, (&str, &AuthConfig)> = HashMap::new();
+    let mut ids: Vec<&String> = config.sources.keys().collect();
+    ids.sort();
+    for source_id in ids {
+        let Some(auth) = config.sources[source_id].auth.as_ref() else {
+            continue;
+        };
+        let Some(key) = token_cache_key_of(auth) else {
+            continue;
+        };
+        let Some(&(first_id, first_auth)) = first_by_key.get(key) else {
+            first_by_key.insert(key, (source_id, auth));
+            continue;
+        };
+        if let Some(field) = token_request_mismatch(first_auth, auth) {
+            anyhow::bail!(
+                "sources {} and {} share oauth2 token_cache_key {:?} but differ in {}",
+                first_id,
+                source_id,
+                key,
+                field
+            );
+        }
+    }
+    Ok(())
+}
+
+fn token_cache_key_of(auth: &AuthConfig) -> Option<&str> {
+    match auth {
+        AuthConfig::OAuth2 {
+            token_cache_key: Some(key),
+            ..
+        } => Some(key),
+        _ => None,
+    }
+}
+
+/// First token request field that differs between two oauth2 auth configs, if any.
+fn token_request_mismatch(a: &AuthConfig, b: &AuthConfig) -> Option<&'static str> {
+    let (
+        AuthConfig::OAuth2 {
+            token_url: a_url,
+            client_id_env: a_id_env,
+            client_id_file: a_id_file,
+            scopes: a_scopes,
+            audience: a_audience,
+            ..
+        },
+        AuthConfig::OAuth2 {
+            token_url: b_url,
+            client_id_env: b_id_env,
+            client_id_file: b_id_file,
+            scopes: b_scopes,
+            audience: b_audience,
+            ..
+        },
+    ) = (a, b)
+    else {
+        return Some("auth type");
+    };
+    if a_url != b_url {
+        Some("token_url")
+    } else if a_id_env != b_id_env || a_id_file != b_id_file {
+        Some("client_id")
+    } else if a_scopes != b_scopes {
+        Some("scopes")
+    } else if a_audience != b_audience {
+        Some("audience")
+    } else {
+        None
+    }
+}
+
 /// Validate that all auth secrets (env or file) can be resolved. Fail at startup so health reflects "not ready".
 pub fn validate_auth_secrets(config: &Config) -> anyhow::Result<()> {
     let audit = config.global.audit.as_ref();
@@ -278,8 +511,23 @@ pub fn validate_auth_secrets(config: &Config) -> anyhow::Result<()> {
                     crate::audit::log_credential_access(audit, source_id, "bearer_token");
                 }
                 AuthConfig::ApiKey {
-                    key_env, key_file, ..
+                    header,
+                    key_env,
+                    key_file,
+                    location,
+                    param,
                 } => {
+                    match location {
+                        super::ApiKeyLocation::Header if header.is_none() => anyhow::bail!(
+                            "source {}: api_key auth with location header requires header",
+                            source_id
+                        ),
+                        super::ApiKeyLocation::Query if param.is_none() => anyhow::bail!(
+                            "source {}: api_key auth with location query requires param",
+                            source_id
+                        ),
+                        _ => {}
+                    }
                     read_secret(key_file.as_deref(), key_env)
                         .with_context(|| format!("source {}: api key", source_id))?;
                     crate::audit::log_credential_access(audit, source_id, "api_key");
@@ -306,8 +554,16 @@ pub fn validate_auth_secrets(config: &Config) -> anyhow::Result<()> {
                     client_private_key_file,
                     refresh_token_env,
                     refresh_token_file,
+                    dpop,
+                    token_cache_key,
                     ..
                 } => {
+                    if *dpop && token_cache_key.is_some() {
+                        anyhow::bail!(
+                            "source {}: oauth2 token_cache_key cannot be used with dpop (tokens are bound to each source's key)",
+                            source_id
+                        );
+                    }
                     read_secret(client_id_file.as_deref(), client_id_env)
                         .with_context(|| format!("source {}: oauth2 client_id", source_id))?;
                     crate::audit::log_credential_access(audit, source_id, "oauth2_client_id");
@@ -432,6 +688,32 @@ pub fn validate_auth_secrets(config: &Config) -> anyhow::Result<()> {
                         );
                     }
                 }
+                AuthConfig::AwsSigV4 {
+                    access_key_env,
+                    secret_key_env,
+                    session_token_env,
+                    region,
+                    service,
+                } => {
+                    if region.is_empty() || service.is_empty() {
+                        anyhow::bail!(
+                            "source {}: aws_sigv4 requires non-empty region and service",
+                            source_id
+                        );
+                    }
+                    read_secret(None, access_key_env)
+                        .with_context(|| format!("source {}: aws_sigv4 access key", source_id))?;
+                    crate::audit::log_credential_access(audit, source_id, "aws_access_key");
+                    read_secret(None, secret_key_env)
+                        .with_context(|| format!("source {}: aws_sigv4 secret key", source_id))?;
+                    crate::audit::log_credential_access(audit, source_id, "aws_secret_key");
+                    if let Some(env) = session_token_env.as_deref() {
+                        read_secret(None, env).with_context(|| {
+                            format!("source {}: aws_sigv4 session token", source_id)
+                        })?;
+                        crate::audit::log_credential_access(audit, source_id, "aws_session_token");
+                    }
+                }
             }
         }
     }
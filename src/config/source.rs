@@ -1,8 +1,13 @@
+use chrono::{DateTime, Utc};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
 
 use super::{
-    AuthConfig, HttpMethod, PaginationConfig, QueryParamValue, ResilienceConfig, SourceHooksConfig,
+    AuthConfig, HttpMethod, PaginationConfig, QueryEncoding, QueryParamValue, ResilienceConfig,
+    SourceHooksConfig,
 };
 
 /// Per-source config (one entry under sources:).
@@ -19,6 +24,14 @@ pub struct SourceConfig {
     #[serde(default)]
     pub body: Option<serde_json::Value>,
 
+    /// How `body` is serialized for POST: "json" (default) or "form" (application/x-www-form-urlencoded; body must be an object).
+    #[serde(default)]
+    pub body_content_type: BodyContentType,
+
+    /// Gzip the POST body and send `Content-Encoding: gzip` (for APIs accepting compressed filter payloads).
+    #[serde(default)]
+    pub request_gzip: bool,
+
     /// Key for the producer label in emitted NDJSON (default from global.source_label_key). Use to align with downstream (e.g. "service", "origin").
     #[serde(default)]
     pub source_label_key: Option<String>,
@@ -42,6 +55,14 @@ pub struct SourceConfig {
     #[serde(default)]
     pub headers: Option<HashMap<String, String>>,
 
+    /// Name of a global.header_profiles entry merged into `headers` at load (per-source headers win on conflict).
+    #[serde(default)]
+    pub header_profile: Option<String>,
+
+    /// Expand this entry at load into one source per value (`<id>-<value>`), substituting `{item}` in url, headers and state keys.
+    #[serde(default)]
+    pub for_each: Option<ForEachConfig>,
+
     /// Optional safety limit: stop pagination when total response body bytes exceed this (per poll).
     #[serde(default)]
     pub max_bytes: Option<u64>,
@@ -58,6 +79,36 @@ pub struct SourceConfig {
     #[serde(default)]
     pub response_event_object_path: Option<String>,
 
+    /// Collect events from several arrays in one response (e.g. alerts + incidents), tagging each with meta.kind. Takes precedence over response_events_path and disables response_streaming.
+    #[serde(default)]
+    pub event_sources: Option<Vec<EventSourceConfig>>,
+
+    /// Emit one envelope per response with the whole parsed body as `event` and meta.raw true, skipping event extraction. Disables response_streaming.
+    #[serde(default)]
+    pub raw_passthrough: bool,
+
+    /// Stamp meta.ingested_at with the collection time (RFC3339, now) on each envelope; `ts` stays the event timestamp.
+    #[serde(default)]
+    pub include_ingested_at: bool,
+
+    /// Response header names (case-insensitive) copied into meta.headers on each envelope from that response, e.g. X-Request-Id or rate-limit headers.
+    #[serde(default)]
+    pub capture_headers: Vec<String>,
+
+    /// Static labels (e.g. env: prod, team: secops) added as meta.tags on every event from this source, for downstream routing.
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+
+    /// jq expression applied to the parsed response to produce the events (requires the `jq` feature). Replaces response_events_path / event_sources and disables response_streaming.
+    #[serde(default)]
+    pub jq: Option<String>,
+
+    /// JSON Schema file each event is validated against before emit (requires the `schema` feature); relative paths
+    /// resolve against the config file's directory. Events that fail are dead-lettered (when global.dead_letter is set)
+    /// and dropped (helr_events_dropped_total reason "schema_violation").
+    #[serde(default)]
+    pub schema_file: Option<String>,
+
     /// Optional transform: which raw-event fields map to envelope ts and meta.id.
     #[serde(default)]
     pub transform: Option<TransformConfig>,
@@ -66,6 +117,15 @@ pub struct SourceConfig {
     #[serde(default)]
     pub on_cursor_error: Option<CursorExpiredBehavior>,
 
+    /// Dotted path to a structured error field in a 4xx body (e.g. "error.code"). When set, cursor expiry is
+    /// detected from this field instead of matching "expired"/"invalid cursor" anywhere in the body.
+    #[serde(default)]
+    pub cursor_error_path: Option<String>,
+
+    /// Values at cursor_error_path that mean the cursor expired (e.g. ["cursor_expired"]). Empty: any string or number there counts.
+    #[serde(default)]
+    pub cursor_error_values: Vec<String>,
+
     /// Start of range for first request (e.g. ISO timestamp). Sent as query param named by from_param.
     #[serde(default)]
     pub from: Option<String>,
@@ -74,10 +134,31 @@ pub struct SourceConfig {
     #[serde(default)]
     pub from_param: Option<String>,
 
+    /// Where to start when there is no saved state: "earliest" (default; from/full history) or "latest" (seed watermark/incremental state to now so only new events are collected).
+    /// Without state or incremental_from, from takes precedence and the seed is kept under the `start_seed` state key.
+    #[serde(default)]
+    pub start_mode: StartMode,
+
+    /// Env var whose value seeds the starting cursor when no cursor is saved (e.g. disaster recovery). Cursor pagination only.
+    #[serde(default)]
+    pub initial_cursor_env: Option<String>,
+
+    /// Env var whose value seeds the starting watermark / incremental_from value when none is saved. Takes precedence over start_mode.
+    #[serde(default)]
+    pub initial_watermark_env: Option<String>,
+
     /// Query params added only to the first request (when no saved cursor/next_url). Reusable across APIs (limit, until, filter, q, sortOrder, etc.). Values can be strings or numbers in YAML.
     #[serde(default)]
     pub query_params: Option<HashMap<String, QueryParamValue>>,
 
+    /// How query_params are encoded: standard (RFC 3986, space → %20), form (space → +; default), or raw (already encoded, appended as-is).
+    #[serde(default)]
+    pub query_encoding: QueryEncoding,
+
+    /// Query param whose value rotates through a list, one value per tick (shard polling). Added with query_params.
+    #[serde(default)]
+    pub rotating_params: Option<RotatingParamsConfig>,
+
     /// Time-based incremental: read param from state on first request, store max event timestamp after each poll (e.g. Slack oldest from date_create).
     #[serde(default)]
     pub incremental_from: Option<IncrementalFromConfig>,
@@ -90,6 +171,29 @@ pub struct SourceConfig {
     #[serde(default)]
     pub on_parse_error: Option<OnParseErrorBehavior>,
 
+    /// Response body format: "json" (default; one JSON document) or "ndjson" (one event per line; trailing `\r` from CRLF line endings is stripped and blank lines skipped). NDJSON disables response_streaming.
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+
+    /// Pick the parser from the response Content-Type (application/json → json, application/x-ndjson → ndjson);
+    /// other content types fall back to response_format. Disables response_streaming.
+    #[serde(default)]
+    pub auto_detect_format: bool,
+
+    /// Long-lived streaming API: hold one connection open per tick and emit each NDJSON body line as it arrives,
+    /// until the server closes it, the request timeout fires, or timeouts.poll_tick_secs passes. Reconnects next tick.
+    /// Cannot be combined with pagination.
+    #[serde(default)]
+    pub streaming: bool,
+
+    /// Trim leading/trailing whitespace (including stray `\r`) from every string value in each event before emitting.
+    #[serde(default)]
+    pub trim_whitespace: bool,
+
+    /// Keep the source in config but don't poll it; /healthz reports it as `disabled`.
+    #[serde(default)]
+    pub disabled: bool,
+
     /// Load-shedding priority (0–10, higher = higher priority). When load_shedding.skip_priority_below is set and under load, sources with priority below that threshold are not polled. Default 10 when unset.
     #[serde(default)]
     pub priority: Option<u32>,
@@ -98,6 +202,14 @@ pub struct SourceConfig {
     #[serde(default)]
     pub max_response_bytes: Option<u64>,
 
+    /// Extra HTTP status codes treated as success besides 2xx (e.g. 304). An empty body (e.g. 204) yields zero events.
+    #[serde(default)]
+    pub accept_status_codes: Vec<u16>,
+
+    /// POST a JSON alert to a webhook when this source fails this many ticks in a row.
+    #[serde(default)]
+    pub alert: Option<AlertConfig>,
+
     /// When response body is not valid UTF-8: "replace" (U+FFFD), "escape", or "fail".
     #[serde(default)]
     pub on_invalid_utf8: Option<InvalidUtf8Behavior>,
@@ -126,6 +238,126 @@ pub struct SourceConfig {
     /// or "full" (stream download + stream parse). Requires `streaming` Cargo feature.
     #[serde(default)]
     pub response_streaming: Option<StreamingMode>,
+
+    /// Decrypt successful response bodies before parsing (e.g. AES-256-GCM event blobs). Forces buffered reads when set.
+    #[serde(default)]
+    pub body_decrypt: Option<BodyDecryptConfig>,
+
+    /// Limits for decompressing gzip/deflate (Content-Encoding) response bodies; decompression aborts when exceeded (zip-bomb guard).
+    #[serde(default)]
+    pub compression: Option<CompressionConfig>,
+
+    /// Per-event detail fetch (e.g. list of incident IDs → incident detail); merged into the event before emit.
+    #[serde(default)]
+    pub subrequest: Option<SubrequestConfig>,
+}
+
+/// POST body encoding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyContentType {
+    /// application/json (default).
+    #[default]
+    Json,
+    /// application/x-www-form-urlencoded: top-level keys become fields; arrays repeat the key; nested objects are sent as JSON strings.
+    Form,
+}
+
+/// Response body decryption: algorithm, key from env, nonce from a response header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BodyDecryptConfig {
+    /// Cipher used for the body (currently only "aes_256_gcm").
+    pub algorithm: BodyDecryptAlgorithm,
+    /// Env var holding the base64-encoded key (32 bytes for AES-256).
+    pub key_env: String,
+    /// Response header carrying the base64-encoded nonce (12 bytes for AES-GCM).
+    pub nonce_header: String,
+}
+
+/// Decompression guard: abort when the decompressed body outgrows the compressed one by `max_ratio` or exceeds `max_decompressed_bytes`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CompressionConfig {
+    /// Max decompressed-to-compressed size ratio (e.g. 100).
+    #[serde(default)]
+    pub max_ratio: Option<f64>,
+    /// Max decompressed body size in bytes.
+    #[serde(default)]
+    pub max_decompressed_bytes: Option<u64>,
+}
+
+/// One events array within a combined response and the kind label for its events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EventSourceConfig {
+    /// Dotted path to the events array (e.g. "alerts"); a missing path yields no events.
+    pub path: String,
+    /// Label emitted as meta.kind on each event from this path.
+    pub kind: String,
+}
+
+/// Child detail fetch for each parent event: GET a URL template and merge the JSON response into the event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SubrequestConfig {
+    /// URL template; each `{path}` is replaced with the (URL-encoded) value at that dotted path in the parent event.
+    pub url: String,
+    /// Key under which the detail JSON is merged into the parent event (default "detail").
+    #[serde(default = "default_subrequest_merge_key")]
+    pub merge_key: String,
+    /// Optional dotted path into the detail response to merge instead of the whole body (e.g. "incident").
+    #[serde(default)]
+    pub response_path: Option<String>,
+}
+
+fn default_subrequest_merge_key() -> String {
+    "detail".to_string()
+}
+
+/// Supported body decryption algorithms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyDecryptAlgorithm {
+    /// AES-256-GCM; body is ciphertext followed by the 16-byte tag.
+    Aes256Gcm,
+}
+
+/// Per-source failure alert: fires once per failure streak, when consecutive failed ticks reach the threshold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertConfig {
+    /// URL the alert JSON (source, consecutive_failures, error, ts) is POSTed to.
+    pub webhook_url: String,
+    /// Consecutive failed ticks before alerting (default 3).
+    #[serde(default = "default_alert_threshold")]
+    pub threshold_consecutive_failures: u32,
+}
+
+fn default_alert_threshold() -> u32 {
+    3
+}
+
+/// Multi-tenant templating: the values a `for_each` source expands over.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ForEachConfig {
+    /// Values substituted for `{item}`, one source each.
+    #[serde(default)]
+    pub values: Vec<String>,
+    /// Env var holding more values, comma-separated (appended to `values`).
+    #[serde(default)]
+    pub values_env: Option<String>,
+}
+
+/// Rotating first-request query param: tick N sends `param=values[N % len]`; the tick counter is kept in state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RotatingParamsConfig {
+    /// Query param name (e.g. "shard").
+    pub param: String,
+    /// Values cycled in order, one per tick; must be non-empty.
+    pub values: Vec<String>,
 }
 
 /// Config for time-based incremental ingestion: use state for "from" param and store latest event timestamp after each poll.
@@ -138,6 +370,9 @@ pub struct IncrementalFromConfig {
     pub event_timestamp_path: String,
     /// Query param name for the state value on first request (e.g. "oldest").
     pub param_name: String,
+    /// Seconds subtracted from the stored value (RFC3339 or epoch) on first request, to re-fetch late arrivals.
+    #[serde(default)]
+    pub lookback_secs: Option<u64>,
 }
 
 /// Per-source state: which event field to use as watermark and which API param receives it (e.g. GWS startTime).
@@ -151,6 +386,12 @@ pub struct SourceStateConfig {
     /// State key to read/write the watermark. Default "watermark" when omitted.
     #[serde(default)]
     pub state_key: Option<String>,
+    /// Compare watermark values numerically (epoch seconds/millis, sequence numbers) instead of as strings.
+    #[serde(default)]
+    pub watermark_numeric: bool,
+    /// Seconds subtracted from the stored watermark (RFC3339 or epoch) on first request, to re-fetch late arrivals.
+    #[serde(default)]
+    pub lookback_secs: Option<u64>,
 }
 
 /// Behavior when state store write fails (e.g. disk full).
@@ -187,6 +428,17 @@ pub enum MaxEventBytesBehavior {
     Fail,
 }
 
+/// Where a source starts on its very first poll (no saved state).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartMode {
+    /// Collect from the beginning (or from `from` when set).
+    #[default]
+    Earliest,
+    /// Skip history: seed the start state to the current time.
+    Latest,
+}
+
 /// When to persist state (cursor/next_url).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -207,6 +459,17 @@ pub enum CursorExpiredBehavior {
     Fail,
 }
 
+/// Response body format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseFormat {
+    /// One JSON document (array or object with an events array).
+    #[default]
+    Json,
+    /// Newline-delimited JSON: each non-blank line is one event (LF or CRLF line endings).
+    Ndjson,
+}
+
 /// Behavior when parsing response or extracting events fails.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -225,6 +488,9 @@ pub struct DedupeConfig {
     /// Max number of event IDs to keep (LRU eviction).
     #[serde(default = "default_dedupe_capacity")]
     pub capacity: u64,
+    /// Add the computed id to each emitted event as `meta.dedupe_id` (for debugging dedupe).
+    #[serde(default)]
+    pub include_dedupe_id: bool,
 }
 
 fn default_dedupe_capacity() -> u64 {
@@ -241,6 +507,30 @@ pub struct TransformConfig {
     /// Dotted path to the event unique ID (e.g. "uuid", "id"). When set, value is included in envelope `meta.id`.
     #[serde(default)]
     pub id_field: Option<String>,
+    /// Rename top-level raw event keys (old -> new), e.g. `eventType: action`. Applied in config order after
+    /// timestamp_field/id_field lookup, so chained renames (a -> b, b -> c) are deterministic.
+    #[serde(default)]
+    pub rename_fields: IndexMap<String, String>,
+    /// Regex redaction of string leaves at dotted paths (e.g. emails in `displayMessage`). Paths use the raw field names.
+    #[serde(default)]
+    pub redact: Vec<RedactConfig>,
+}
+
+/// One redaction rule: every match of `pattern` in string leaves at `field` is replaced with `replacement`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RedactConfig {
+    /// Dotted path to the field; when it holds an object or array, all strings beneath it are redacted.
+    pub field: String,
+    /// Regular expression (Rust `regex` syntax).
+    pub pattern: String,
+    /// Replacement text; may reference capture groups (`$1`, `${name}`). Default "[REDACTED]".
+    #[serde(default = "default_redact_replacement")]
+    pub replacement: String,
+}
+
+fn default_redact_replacement() -> String {
+    "[REDACTED]".to_string()
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -250,6 +540,241 @@ pub struct ScheduleConfig {
     pub interval_secs: u64,
     #[serde(default)]
     pub jitter_secs: Option<u64>,
+    /// Delay this source's first poll after startup by a random amount in [0, startup_jitter_secs] to avoid synchronized
+    /// polling. The delayed poll runs on its own; the startup tick and other sources don't wait for it.
+    #[serde(default)]
+    pub startup_jitter_secs: Option<u64>,
+    /// Cron expression (UTC) used instead of interval_secs, e.g. "0 9 * * Mon-Fri". 5-field (minute-first) or 6/7-field (seconds-first).
+    #[serde(default)]
+    pub cron: Option<String>,
+    /// With cron: also poll once at startup even when the expression doesn't match then.
+    #[serde(default)]
+    pub cron_poll_on_startup: bool,
+    /// Skip a tick for this source while its previous poll is still running instead of starting an overlapping one.
+    #[serde(default)]
+    pub skip_if_running: bool,
+}
+
+impl ScheduleConfig {
+    /// Parsed `cron` expression; 5-field expressions get a leading "0" seconds field.
+    pub fn cron_schedule(&self) -> anyhow::Result<Option<cron::Schedule>> {
+        let Some(expr) = self.cron.as_deref() else {
+            return Ok(None);
+        };
+        let expr = expr.trim();
+        let normalized = if expr.split_whitespace().count() == 5 {
+            format!("0 {}", expr)
+        } else {
+            expr.to_string()
+        };
+        cron::Schedule::from_str(&normalized)
+            .map(Some)
+            .map_err(|e| anyhow::anyhow!("schedule.cron {:?}: {}", expr, e))
+    }
+
+    /// Delay from `now` until the next cron match, or None when no cron is set (or it never fires again).
+    pub fn next_cron_delay(&self, now: DateTime<Utc>) -> Option<Duration> {
+        let schedule = self.cron_schedule().ok()??;
+        let next = schedule.after(&now).next()?;
+        (next - now).to_std().ok()
+    }
+}
+
+/// Fail on any source whose schedule.cron does not parse.
+pub fn validate_schedules(config: &super::Config) -> anyhow::Result<()> {
+    for (source_id, source) in &config.sources {
+        source
+            .schedule
+            .cron_schedule()
+            .map_err(|e| anyhow::anyhow!("source {:?}: {}", source_id, e))?;
+    }
+    Ok(())
+}
+
+/// Env var that overrides a source's `schedule.interval_secs`: `HELR_SOURCE_<NAME>_INTERVAL_SECS`,
+/// where `<NAME>` is the source id uppercased with non-alphanumerics replaced by `_`.
+pub fn interval_env_var(source_id: &str) -> String {
+    let name: String = source_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("HELR_SOURCE_{}_INTERVAL_SECS", name)
+}
+
+/// Placeholder replaced by the current value in `for_each` sources.
+const FOR_EACH_ITEM: &str = "{item}";
+
+/// Replace each `for_each` source with one source per value, id `<id>-<value>`, with `{item}` substituted in
+/// url, headers, state.state_key and incremental_from.state_key. Fails on an empty value list or an id collision.
+pub fn expand_for_each(config: &mut super::Config) -> anyhow::Result<()> {
+    let templated: Vec<String> = config
+        .sources
+        .iter()
+        .filter(|(_, s)| s.for_each.is_some())
+        .map(|(id, _)| id.clone())
+        .collect();
+    for source_id in templated {
+        let mut template = config.sources.remove(&source_id).expect("listed above");
+        let for_each = template.for_each.take().expect("filtered above");
+        let mut values = for_each.values;
+        if let Some(var) = &for_each.values_env {
+            let raw = std::env::var(var).map_err(|_| {
+                anyhow::anyhow!(
+                    "source {:?}: for_each.values_env {:?} is not set",
+                    source_id,
+                    var
+                )
+            })?;
+            values.extend(
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(String::from),
+            );
+        }
+        if values.is_empty() {
+            anyhow::bail!("source {:?}: for_each has no values", source_id);
+        }
+        for item in values {
+            let id = format!("{}-{}", source_id, item);
+            let mut source = template.clone();
+            source.url = source.url.replace(FOR_EACH_ITEM, &item);
+            for value in source.headers.iter_mut().flat_map(|h| h.values_mut()) {
+                *value = value.replace(FOR_EACH_ITEM, &item);
+            }
+            if let Some(key) = source.state.as_mut().and_then(|s| s.state_key.as_mut()) {
+                *key = key.replace(FOR_EACH_ITEM, &item);
+            }
+            if let Some(inc) = source.incremental_from.as_mut() {
+                inc.state_key = inc.state_key.replace(FOR_EACH_ITEM, &item);
+            }
+            if config.sources.insert(id.clone(), source).is_some() {
+                anyhow::bail!(
+                    "source {:?}: for_each expands to {:?}, which is already defined",
+                    source_id,
+                    id
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Merge each source's `header_profile` into its `headers` (source entries win on conflict; header names compare
+/// case-insensitively). Fails on an unknown profile.
+pub fn apply_header_profiles(config: &mut super::Config) -> anyhow::Result<()> {
+    for (source_id, source) in config.sources.iter_mut() {
+        let Some(name) = &source.header_profile else {
+            continue;
+        };
+        let profile = config.global.header_profiles.get(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "source {:?}: header_profile {:?} not found in global.header_profiles",
+                source_id,
+                name
+            )
+        })?;
+        let own = source.headers.take().unwrap_or_default();
+        let mut merged: HashMap<String, String> = profile
+            .iter()
+            .filter(|(k, _)| !own.keys().any(|o| o.eq_ignore_ascii_case(k)))
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        merged.extend(own);
+        source.headers = Some(merged);
+    }
+    Ok(())
+}
+
+/// Apply `HELR_SOURCE_<NAME>_INTERVAL_SECS` overrides (set, non-empty). Fails on a non-positive or non-numeric value.
+pub fn apply_interval_env_overrides(config: &mut super::Config) -> anyhow::Result<()> {
+    for (source_id, source) in config.sources.iter_mut() {
+        let var = interval_env_var(source_id);
+        let Ok(raw) = std::env::var(&var) else {
+            continue;
+        };
+        let raw = raw.trim();
+        if raw.is_empty() {
+            continue;
+        }
+        let secs: u64 = raw.parse().ok().filter(|&n| n > 0).ok_or_else(|| {
+            anyhow::anyhow!("{}: expected a positive integer, got {:?}", var, raw)
+        })?;
+        source.schedule.interval_secs = secs;
+    }
+    Ok(())
+}
+
+/// Fail on any transform.redact pattern that does not compile.
+pub fn validate_redact(config: &super::Config) -> anyhow::Result<()> {
+    for (source_id, source) in &config.sources {
+        for rule in source.transform.iter().flat_map(|t| &t.redact) {
+            regex::Regex::new(&rule.pattern).map_err(|e| {
+                anyhow::anyhow!(
+                    "source {:?}: transform.redact pattern {:?}: {}",
+                    source_id,
+                    rule.pattern,
+                    e
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Reject rotating_params with an empty values list.
+pub fn validate_rotating_params(config: &super::Config) -> anyhow::Result<()> {
+    for (source_id, source) in &config.sources {
+        if let Some(rp) = &source.rotating_params
+            && rp.values.is_empty()
+        {
+            anyhow::bail!(
+                "source {:?}: rotating_params.values must not be empty",
+                source_id
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Reject `streaming: true` combined with pagination (a stream is one open response, not pages).
+pub fn validate_streaming(config: &super::Config) -> anyhow::Result<()> {
+    for (source_id, source) in &config.sources {
+        if source.streaming && source.pagination.is_some() {
+            anyhow::bail!(
+                "source {:?}: streaming cannot be combined with pagination",
+                source_id
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Reject sources whose `schema_file` cannot be read or compiled (or when built without the `schema` feature).
+pub fn validate_schema_files(config: &super::Config) -> anyhow::Result<()> {
+    for (source_id, source) in &config.sources {
+        if let Some(path) = &source.schema_file {
+            crate::schema::check(path)
+                .map_err(|e| anyhow::anyhow!("source {:?}: {:#}", source_id, e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Reject sources whose `jq` expression does not compile (or when built without the `jq` feature).
+pub fn validate_jq(config: &super::Config) -> anyhow::Result<()> {
+    for (source_id, source) in &config.sources {
+        if let Some(expr) = &source.jq {
+            crate::jq::check(expr).map_err(|e| anyhow::anyhow!("source {:?}: {}", source_id, e))?;
+        }
+    }
+    Ok(())
 }
 
 /// Streaming JSON parse mode (requires `streaming` Cargo feature).
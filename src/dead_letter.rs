@@ -0,0 +1,43 @@
+//! Dead-letter output (global `dead_letter`): responses that failed to parse (and events failing `schema_file`, with
+//! status 0), one JSON line each with source, url, status, error and body, appended to a separate file for later inspection or replay.
+
+use crate::config::DeadLetterConfig;
+use std::io::Write;
+use std::sync::Mutex;
+
+/// Serializes appends so concurrent sources never interleave a line.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Append one dead-letter record; failures to write are logged, never propagated (the poll outcome stands).
+pub fn record(
+    cfg: Option<&DeadLetterConfig>,
+    source_id: &str,
+    url: &str,
+    status: u16,
+    error: &anyhow::Error,
+    body: Option<&[u8]>,
+) {
+    let Some(cfg) = cfg else {
+        return;
+    };
+    let line = serde_json::json!({
+        "ts": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        "source": source_id,
+        "url": url,
+        "status": status,
+        "error": format!("{:#}", error),
+        "body": body.map(|b| String::from_utf8_lossy(b).into_owned()),
+    });
+    if let Err(e) = append(&cfg.path, &line.to_string()) {
+        tracing::warn!(source = %source_id, path = %cfg.path, error = %e, "dead_letter write failed");
+    }
+}
+
+fn append(path: &str, line: &str) -> std::io::Result<()> {
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    file.write_all(format!("{}\n", line).as_bytes())
+}
@@ -0,0 +1,130 @@
+//! Response body decompression (gzip/deflate `Content-Encoding`) with the per-source `compression` guard:
+//! output is checked while inflating, so a zip bomb is aborted before it is fully expanded.
+
+use crate::config::CompressionConfig;
+use anyhow::Context;
+use reqwest::header::{CONTENT_ENCODING, HeaderMap};
+use std::io::Read;
+
+/// Non-identity Content-Encoding of the response, lowercased; None when absent or identity.
+pub fn content_encoding(headers: &HeaderMap) -> Option<String> {
+    let enc = headers
+        .get(CONTENT_ENCODING)?
+        .to_str()
+        .ok()?
+        .trim()
+        .to_ascii_lowercase();
+    (!enc.is_empty() && enc != "identity").then_some(enc)
+}
+
+/// Decompress `body` per its Content-Encoding (gzip, x-gzip, deflate); unencoded bodies are returned unchanged
+/// and other encodings are an error.
+/// Fails once the output exceeds `cfg.max_decompressed_bytes` or `cfg.max_ratio` × the compressed size.
+pub fn decompress_body(
+    cfg: Option<&CompressionConfig>,
+    headers: &HeaderMap,
+    body: Vec<u8>,
+) -> anyhow::Result<Vec<u8>> {
+    let Some(encoding) = content_encoding(headers) else {
+        return Ok(body);
+    };
+    match encoding.as_str() {
+        "gzip" | "x-gzip" => inflate(
+            flate2::read::MultiGzDecoder::new(body.as_slice()),
+            body.len(),
+            cfg,
+        ),
+        "deflate" => inflate(
+            flate2::read::ZlibDecoder::new(body.as_slice()),
+            body.len(),
+            cfg,
+        ),
+        other => anyhow::bail!("unsupported Content-Encoding {:?}", other),
+    }
+}
+
+/// Read `decoder` to the end in chunks, aborting as soon as the output passes the configured limit.
+fn inflate<R: Read>(
+    mut decoder: R,
+    compressed_len: usize,
+    cfg: Option<&CompressionConfig>,
+) -> anyhow::Result<Vec<u8>> {
+    let max_bytes = cfg.and_then(|c| c.max_decompressed_bytes);
+    let max_ratio_bytes = cfg
+        .and_then(|c| c.max_ratio)
+        .map(|r| (r * compressed_len.max(1) as f64) as u64);
+    let limit = match (max_bytes, max_ratio_bytes) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+    let mut out = Vec::with_capacity(compressed_len.saturating_mul(4));
+    let mut chunk = [0u8; 64 * 1024];
+    loop {
+        let n = decoder
+            .read(&mut chunk)
+            .context("decompress response body")?;
+        if n == 0 {
+            return Ok(out);
+        }
+        let total = out.len() as u64 + n as u64;
+        if let Some(limit) = limit
+            && total > limit
+        {
+            anyhow::bail!(
+                "decompressed response body exceeds limit ({} bytes from {} compressed; max_decompressed_bytes {:?}, max_ratio {:?})",
+                total,
+                compressed_len,
+                max_bytes,
+                cfg.and_then(|c| c.max_ratio)
+            );
+        }
+        out.extend_from_slice(&chunk[..n]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut enc = GzEncoder::new(Vec::new(), Compression::best());
+        enc.write_all(data).unwrap();
+        enc.finish().unwrap()
+    }
+
+    fn gzip_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_ENCODING, "gzip".parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn decompress_gzip_and_abort_on_ratio_or_size() {
+        let json = br#"[{"id":"1"},{"id":"2"}]"#;
+        let out = decompress_body(None, &gzip_headers(), gzip(json)).unwrap();
+        assert_eq!(out, json);
+        assert_eq!(
+            decompress_body(None, &HeaderMap::new(), json.to_vec()).unwrap(),
+            json
+        );
+
+        // 8 MiB of zeros compresses ~1000:1.
+        let bomb = gzip(&vec![0u8; 8 * 1024 * 1024]);
+        let ratio = CompressionConfig {
+            max_ratio: Some(10.0),
+            max_decompressed_bytes: None,
+        };
+        let err = decompress_body(Some(&ratio), &gzip_headers(), bomb.clone()).unwrap_err();
+        assert!(err.to_string().contains("exceeds limit"), "{}", err);
+
+        let size = CompressionConfig {
+            max_ratio: None,
+            max_decompressed_bytes: Some(1024 * 1024),
+        };
+        let err = decompress_body(Some(&size), &gzip_headers(), bomb).unwrap_err();
+        assert!(err.to_string().contains("exceeds limit"), "{}", err);
+    }
+}
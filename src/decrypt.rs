@@ -0,0 +1,102 @@
+//! Response body decryption (`body_decrypt`): applied to successful bodies before parsing.
+
+use crate::config::{BodyDecryptAlgorithm, BodyDecryptConfig, read_secret};
+use anyhow::Context;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as B64;
+use reqwest::header::HeaderMap;
+use ring::aead::{AES_256_GCM, Aad, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+
+/// Decrypt `body` per `cfg`: key from `key_env`, nonce from the `nonce_header` response header.
+pub fn decrypt_body(
+    cfg: &BodyDecryptConfig,
+    headers: &HeaderMap,
+    body: &[u8],
+) -> anyhow::Result<Vec<u8>> {
+    let key_b64 = read_secret(None, &cfg.key_env).context("body_decrypt key")?;
+    let key = B64
+        .decode(key_b64.trim())
+        .context("body_decrypt key: invalid base64")?;
+    let nonce_b64 = headers
+        .get(cfg.nonce_header.as_str())
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| {
+            anyhow::anyhow!("body_decrypt: missing nonce header {:?}", cfg.nonce_header)
+        })?;
+    let nonce = B64
+        .decode(nonce_b64.trim())
+        .context("body_decrypt nonce: invalid base64")?;
+    match cfg.algorithm {
+        BodyDecryptAlgorithm::Aes256Gcm => aes_256_gcm_decrypt(&key, &nonce, body),
+    }
+}
+
+/// AES-256-GCM open: `ciphertext` is the encrypted body followed by the 16-byte tag.
+fn aes_256_gcm_decrypt(key: &[u8], nonce: &[u8], ciphertext: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let unbound = UnboundKey::new(&AES_256_GCM, key)
+        .map_err(|_| anyhow::anyhow!("body_decrypt: AES-256-GCM key must be 32 bytes"))?;
+    if nonce.len() != NONCE_LEN {
+        anyhow::bail!("body_decrypt: AES-GCM nonce must be {} bytes", NONCE_LEN);
+    }
+    let nonce = Nonce::try_assume_unique_for_key(nonce)
+        .map_err(|_| anyhow::anyhow!("body_decrypt: invalid nonce"))?;
+    let key = LessSafeKey::new(unbound);
+    let mut in_out = ciphertext.to_vec();
+    let plaintext = key
+        .open_in_place(nonce, Aad::empty(), &mut in_out)
+        .map_err(|_| anyhow::anyhow!("body_decrypt: AES-256-GCM authentication failed"))?;
+    let len = plaintext.len();
+    in_out.truncate(len);
+    Ok(in_out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [7u8; 32];
+    const NONCE: [u8; 12] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+
+    fn encrypt(plaintext: &[u8]) -> Vec<u8> {
+        let key = LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &KEY).unwrap());
+        let mut in_out = plaintext.to_vec();
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(NONCE),
+            Aad::empty(),
+            &mut in_out,
+        )
+        .unwrap();
+        in_out
+    }
+
+    fn config() -> BodyDecryptConfig {
+        BodyDecryptConfig {
+            algorithm: BodyDecryptAlgorithm::Aes256Gcm,
+            key_env: "HELR_TEST_BODY_DECRYPT_KEY".into(),
+            nonce_header: "X-Nonce".into(),
+        }
+    }
+
+    #[test]
+    fn decrypt_body_aes_256_gcm_round_trip() {
+        unsafe {
+            std::env::set_var("HELR_TEST_BODY_DECRYPT_KEY", B64.encode(KEY));
+        }
+        let body = br#"[{"id":"1"},{"id":"2"}]"#;
+        let mut headers = HeaderMap::new();
+        headers.insert("x-nonce", B64.encode(NONCE).parse().unwrap());
+
+        let out = decrypt_body(&config(), &headers, &encrypt(body)).unwrap();
+        assert_eq!(out, body);
+
+        let mut tampered = encrypt(body);
+        tampered[0] ^= 0xff;
+        assert!(decrypt_body(&config(), &headers, &tampered).is_err());
+
+        let err = decrypt_body(&config(), &HeaderMap::new(), &encrypt(body)).unwrap_err();
+        assert!(err.to_string().contains("missing nonce header"));
+        unsafe {
+            std::env::remove_var("HELR_TEST_BODY_DECRYPT_KEY");
+        }
+    }
+}
@@ -37,6 +37,13 @@ impl LruDedupe {
         }
         false // new
     }
+
+    /// Drop `id` so it counts as new again (e.g. its event failed to emit and will be refetched).
+    pub fn forget(&mut self, id: &str) {
+        if self.seen.remove(id) {
+            self.order.retain(|o| o != id);
+        }
+    }
 }
 
 /// Store of per-source LRU dedupes. Shared across poll ticks.
@@ -57,6 +64,13 @@ pub async fn seen_and_add(store: &DedupeStore, source_id: &str, id: String, capa
     dedupe.seen_and_add(id)
 }
 
+/// Un-record `id` for this source after seen_and_add, when the event it admitted was not emitted.
+pub async fn forget(store: &DedupeStore, source_id: &str, id: &str) {
+    if let Some(dedupe) = store.write().await.get_mut(source_id) {
+        dedupe.forget(id);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,6 +82,15 @@ mod tests {
         assert!(!d.seen_and_add("id2".to_string()));
     }
 
+    #[test]
+    fn lru_dedupe_forget_makes_id_new_again() {
+        let mut d = LruDedupe::new(10);
+        assert!(!d.seen_and_add("id1".to_string()));
+        d.forget("id1");
+        assert!(!d.seen_and_add("id1".to_string()));
+        assert_eq!(d.order.len(), 1);
+    }
+
     #[test]
     fn lru_dedupe_duplicate_returns_true() {
         let mut d = LruDedupe::new(10);
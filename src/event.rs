@@ -4,8 +4,10 @@
 
 #![allow(dead_code)] // used when implementing poll loop
 
+use crate::config::{EnvelopeKeysConfig, GlobalConfig};
 use anyhow::anyhow;
 use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// One log event emitted to stdout (NDJSON line).
 #[derive(Debug, Clone, Serialize)]
@@ -21,10 +23,44 @@ pub struct EmittedEvent {
 pub struct EventMeta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cursor: Option<String>,
+    /// Request id of the hook poll that emitted the event (hook sources only; global.request_id_scheme).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub request_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
+    /// True for the per-poll summary record (global.emit_tick_summary).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<bool>,
+    /// Which event_sources path the event came from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    /// Collector instance (global.instance_id or hostname) when global.include_instance_id is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_id: Option<String>,
+    /// Hostname of the collector when global.include_instance_id is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collector_host: Option<String>,
+    /// Envelope schema version (global.schema_version).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<String>,
+    /// True when `event` is a whole response body (raw_passthrough).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw: Option<bool>,
+    /// New circuit breaker state on a circuit transition envelope (circuit_breaker.emit_circuit_events).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub circuit_state: Option<String>,
+    /// When the collector received the event (source include_ingested_at); `ts` remains the event time.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ingested_at: Option<String>,
+    /// Response headers listed in the source's capture_headers (lowercased names).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<BTreeMap<String, String>>,
+    /// Id the dedupe filter computed for this event (dedupe.include_dedupe_id).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dedupe_id: Option<String>,
+    /// Static labels from the source's `tags`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<BTreeMap<String, String>>,
 }
 
 impl EmittedEvent {
@@ -38,6 +74,17 @@ impl EmittedEvent {
                 cursor: None,
                 request_id: None,
                 id: None,
+                summary: None,
+                kind: None,
+                instance_id: None,
+                collector_host: None,
+                schema_version: None,
+                raw: None,
+                circuit_state: None,
+                ingested_at: None,
+                headers: None,
+                dedupe_id: None,
+                tags: None,
             },
         }
     }
@@ -57,9 +104,69 @@ impl EmittedEvent {
         self
     }
 
+    pub fn with_kind(mut self, kind: String) -> Self {
+        self.meta.kind = Some(kind);
+        self
+    }
+
+    pub fn with_instance(mut self, instance_id: String, collector_host: String) -> Self {
+        self.meta.instance_id = Some(instance_id);
+        self.meta.collector_host = Some(collector_host);
+        self
+    }
+
+    pub fn with_schema_version(mut self, schema_version: String) -> Self {
+        self.meta.schema_version = Some(schema_version);
+        self
+    }
+
+    pub fn with_ingested_at(mut self, ingested_at: String) -> Self {
+        self.meta.ingested_at = Some(ingested_at);
+        self
+    }
+
+    pub fn with_headers(mut self, headers: BTreeMap<String, String>) -> Self {
+        self.meta.headers = Some(headers);
+        self
+    }
+
+    pub fn with_tags(mut self, tags: BTreeMap<String, String>) -> Self {
+        self.meta.tags = Some(tags);
+        self
+    }
+
+    pub fn with_dedupe_id(mut self, id: String) -> Self {
+        self.meta.dedupe_id = Some(id);
+        self
+    }
+
+    pub fn with_raw(mut self) -> Self {
+        self.meta.raw = Some(true);
+        self
+    }
+
+    pub fn with_circuit_state(mut self, state: String) -> Self {
+        self.meta.circuit_state = Some(state);
+        self
+    }
+
+    pub fn with_summary(mut self) -> Self {
+        self.meta.summary = Some(true);
+        self
+    }
+
     /// Serialize to one NDJSON line (no trailing newline; caller adds).
     /// `label_key` is the key for the producer field (default "source"; configurable via global/source source_label_key).
     pub fn to_ndjson_line_with_label_key(&self, label_key: &str) -> anyhow::Result<String> {
+        self.to_ndjson_line_with_keys(label_key, None)
+    }
+
+    /// Like `to_ndjson_line_with_label_key`, renaming ts/endpoint/event/meta per global.envelope_keys.
+    pub fn to_ndjson_line_with_keys(
+        &self,
+        label_key: &str,
+        keys: Option<&EnvelopeKeysConfig>,
+    ) -> anyhow::Result<String> {
         let mut val = serde_json::to_value(self).map_err(anyhow::Error::from)?;
         let obj = val
             .as_object_mut()
@@ -67,6 +174,21 @@ impl EmittedEvent {
         let source_val = obj
             .remove("source")
             .unwrap_or(serde_json::Value::String(String::new()));
+        if let Some(keys) = keys {
+            for (default, key) in [
+                ("ts", &keys.ts),
+                ("endpoint", &keys.endpoint),
+                ("event", &keys.event),
+                ("meta", &keys.meta),
+            ] {
+                if let Some(key) = key.as_deref()
+                    && key != default
+                    && let Some(v) = obj.remove(default)
+                {
+                    obj.insert(key.to_string(), v);
+                }
+            }
+        }
         obj.insert(label_key.to_string(), source_val);
         serde_json::to_string(&val).map_err(anyhow::Error::from)
     }
@@ -77,6 +199,49 @@ impl EmittedEvent {
     }
 }
 
+/// Envelope top-level key names as written, for sinks that read envelope fields back out of each line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvelopeKeys {
+    pub ts: String,
+    pub source: String,
+    pub endpoint: String,
+    pub event: String,
+    pub meta: String,
+}
+
+impl Default for EnvelopeKeys {
+    fn default() -> Self {
+        Self {
+            ts: "ts".to_string(),
+            source: "source".to_string(),
+            endpoint: "endpoint".to_string(),
+            event: "event".to_string(),
+            meta: "meta".to_string(),
+        }
+    }
+}
+
+impl EnvelopeKeys {
+    /// Keys from global.envelope_keys; the producer label follows global.source_label_key first.
+    pub fn from_global(global: &GlobalConfig) -> Self {
+        let defaults = Self::default();
+        let keys = global.envelope_keys.as_ref();
+        let key = |configured: Option<&Option<String>>, default: String| {
+            configured.and_then(|k| k.clone()).unwrap_or(default)
+        };
+        Self {
+            ts: key(keys.map(|k| &k.ts), defaults.ts),
+            source: global
+                .source_label_key
+                .clone()
+                .unwrap_or_else(|| key(keys.map(|k| &k.source), defaults.source)),
+            endpoint: key(keys.map(|k| &k.endpoint), defaults.endpoint),
+            event: key(keys.map(|k| &k.event), defaults.event),
+            meta: key(keys.map(|k| &k.meta), defaults.meta),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -108,6 +273,24 @@ mod tests {
         assert!(line.contains("\"meta\":{}"));
     }
 
+    #[test]
+    fn emitted_event_with_renamed_envelope_keys() {
+        let e = EmittedEvent::new(
+            "2024-01-15T12:00:00Z".to_string(),
+            "test-source".to_string(),
+            "/logs".to_string(),
+            serde_json::json!({"id": 1}),
+        )
+        .with_id("evt-1".to_string());
+        let keys = EnvelopeKeysConfig {
+            ts: Some("timestamp".to_string()),
+            event: Some("payload".to_string()),
+            ..Default::default()
+        };
+        let line = e.to_ndjson_line_with_keys("type", Some(&keys)).unwrap();
+        insta::assert_snapshot!(line, @r#"{"endpoint":"/logs","meta":{"id":"evt-1"},"payload":{"id":1},"timestamp":"2024-01-15T12:00:00Z","type":"test-source"}"#);
+    }
+
     #[test]
     fn emitted_event_with_cursor_and_request_id() {
         let e = EmittedEvent::new(
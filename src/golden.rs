@@ -0,0 +1,90 @@
+//! Golden-file comparison for `helr test --golden`: capture emitted NDJSON and diff it against a committed file.
+
+use crate::output::EventSink;
+use similar::TextDiff;
+use std::sync::Mutex;
+
+/// Sink that keeps emitted lines in memory instead of writing them.
+#[derive(Default)]
+pub struct CaptureSink {
+    lines: Mutex<Vec<String>>,
+}
+
+impl CaptureSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lines captured so far, in emit order.
+    pub fn lines(&self) -> Vec<String> {
+        self.lines.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+impl EventSink for CaptureSink {
+    fn write_line(&self, line: &str) -> anyhow::Result<()> {
+        self.lines
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(line.to_string());
+        Ok(())
+    }
+}
+
+/// Compare captured lines with the golden file contents, ignoring the volatile top-level `ts`.
+/// Returns a unified diff (golden → actual) on mismatch, None when equal.
+pub fn diff_against_golden(actual: &[String], golden: &str) -> Option<String> {
+    let expected: String = golden
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| normalize_line(l) + "\n")
+        .collect();
+    let actual: String = actual.iter().map(|l| normalize_line(l) + "\n").collect();
+    if expected == actual {
+        return None;
+    }
+    Some(
+        TextDiff::from_lines(&expected, &actual)
+            .unified_diff()
+            .header("golden", "actual")
+            .to_string(),
+    )
+}
+
+/// Drop top-level `ts` and re-serialize (sorted keys) so key order and timestamps don't cause diffs.
+fn normalize_line(line: &str) -> String {
+    match serde_json::from_str::<serde_json::Value>(line) {
+        Ok(mut v) => {
+            if let Some(obj) = v.as_object_mut() {
+                obj.remove("ts");
+            }
+            v.to_string()
+        }
+        Err(_) => line.trim_end().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn golden_match_ignores_ts_and_key_order() {
+        let actual =
+            vec![r#"{"ts":"2026-01-01T00:00:00Z","source":"s","event":{"id":"1"}}"#.to_string()];
+        let golden =
+            "{\"event\":{\"id\":\"1\"},\"source\":\"s\",\"ts\":\"2020-05-05T00:00:00Z\"}\n\n";
+        assert!(diff_against_golden(&actual, golden).is_none());
+    }
+
+    #[test]
+    fn golden_mismatch_returns_unified_diff() {
+        let actual = vec![r#"{"source":"s","event":{"id":"2"}}"#.to_string()];
+        let golden = r#"{"source":"s","event":{"id":"1"}}"#;
+        let diff = diff_against_golden(&actual, golden).unwrap();
+        assert!(diff.contains("--- golden"));
+        assert!(diff.contains("+++ actual"));
+        assert!(diff.contains(r#"-{"event":{"id":"1"},"source":"s"}"#));
+        assert!(diff.contains(r#"+{"event":{"id":"2"},"source":"s"}"#));
+    }
+}
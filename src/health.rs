@@ -3,14 +3,16 @@
 //!
 //! **Readyz semantics:** Ready when (1) output path is writable (or stdout), (2) state store is
 //! connected, and (3) at least one source is healthy (circuit not open). All three are reported
-//! in the JSON body.
+//! in the JSON body. With `health.ready_requires_first_success`, (4) some source must also have
+//! completed a successful poll.
 
 use crate::circuit::{CircuitState, CircuitStore};
 use crate::config::Config;
 use crate::dedupe::DedupeStore;
 use crate::dpop::DPoPKeyCache;
 use crate::oauth2::OAuth2TokenCache;
-use crate::output::EventSink;
+use crate::output::{EventSink, RingBufferSink};
+use crate::poll::PollCaches;
 use crate::state::StateStore;
 use axum::extract::State;
 use axum::http::StatusCode;
@@ -19,6 +21,7 @@ use serde::Serialize;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use std::time::Instant;
 use tokio::sync::{RwLock, Semaphore};
 
@@ -30,6 +33,7 @@ pub struct PollDeps {
     pub dpop_key_cache: Option<DPoPKeyCache>,
     pub dedupe_store: DedupeStore,
     pub global_sources_semaphore: Option<Arc<Semaphore>>,
+    pub poll_caches: Arc<PollCaches>,
 }
 
 /// Shared state for health and API handlers (config, circuits, last errors, start time, output path, state store).
@@ -48,6 +52,10 @@ pub struct HealthState {
     pub config_path: Option<PathBuf>,
     /// Dependencies for trigger poll. None when running with --once or replay. Also used for reload (clear token_cache on restart_sources_on_sighup).
     pub poll_deps: Option<Arc<PollDeps>>,
+    /// Set by POST /drain, cleared by POST /resume; the scheduler skips ticks while set.
+    pub draining: Arc<AtomicBool>,
+    /// Recent emitted lines for GET /tail (global.debug.tail_capacity). None when disabled.
+    pub tail: Option<Arc<RingBufferSink>>,
 }
 
 /// Circuit state as JSON: "closed" | "open" | "half_open" plus optional detail.
@@ -91,6 +99,9 @@ pub struct ReadyBody {
     pub output_writable: Option<bool>,
     pub state_store_connected: bool,
     pub at_least_one_source_healthy: bool,
+    /// Set only with health.ready_requires_first_success: whether any source has completed a successful poll.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_success: Option<bool>,
     #[serde(skip_serializing_if = "std::ops::Not::not")]
     pub state_store_fallback_active: bool,
 }
@@ -151,7 +162,7 @@ async fn build_sources(
     let circuits = circuit_store.read().await;
     let errors = last_errors.read().await;
     let mut sources = HashMap::new();
-    for source_id in config.sources.keys() {
+    for (source_id, source) in &config.sources {
         let circuit_state = circuits
             .get(source_id)
             .map(circuit_state_to_dto)
@@ -162,7 +173,9 @@ async fn build_sources(
                 successes: None,
             });
         let last_error = errors.get(source_id).cloned();
-        let status = if circuit_state.state == "open" {
+        let status = if source.disabled {
+            "disabled"
+        } else if circuit_state.state == "open" {
             "unhealthy"
         } else if last_error.is_some() {
             "degraded"
@@ -195,7 +208,7 @@ pub async fn build_health_body(state: &HealthState) -> HealthBody {
 }
 
 /// Build ready JSON body: ready flag and per-condition flags (no per-source detail; use /healthz).
-/// Ready when: output writable (or stdout), state store connected, and ≥1 source healthy.
+/// Ready when: output writable (or stdout), state store connected, ≥1 source healthy, and (when required) a first successful poll.
 pub async fn build_ready_body(state: &HealthState) -> ReadyBody {
     let uptime_secs = state.started_at.elapsed().as_secs_f64();
     let config = state.config.read().await;
@@ -209,8 +222,24 @@ pub async fn build_ready_body(state: &HealthState) -> ReadyBody {
         Some(store) => store.list_sources().await.is_ok(),
         None => true,
     };
-    let at_least_one_source_healthy = sources.values().any(|s| s.status != "unhealthy");
-    let ready = output_ok && state_store_connected && at_least_one_source_healthy;
+    let at_least_one_source_healthy = sources
+        .values()
+        .any(|s| s.status != "unhealthy" && s.status != "disabled");
+    let first_success = config
+        .global
+        .health
+        .as_ref()
+        .filter(|h| h.ready_requires_first_success)
+        .map(|_| {
+            config
+                .sources
+                .keys()
+                .any(|id| crate::poll::last_success(id).is_some())
+        });
+    let ready = output_ok
+        && state_store_connected
+        && at_least_one_source_healthy
+        && first_success.unwrap_or(true);
     ReadyBody {
         version: version(),
         uptime_secs,
@@ -218,6 +247,7 @@ pub async fn build_ready_body(state: &HealthState) -> ReadyBody {
         output_writable,
         state_store_connected,
         at_least_one_source_healthy,
+        first_success,
         state_store_fallback_active: state.state_store_fallback_active,
     }
 }
@@ -316,6 +346,8 @@ sources:
             state_store_fallback_active: false,
             config_path: None,
             poll_deps: None,
+            draining: Default::default(),
+            tail: None,
         }
     }
 
@@ -360,6 +392,8 @@ sources:
             state_store_fallback_active: false,
             config_path: None,
             poll_deps: None,
+            draining: Default::default(),
+            tail: None,
         };
         let body = build_health_body(&state).await;
         let s1 = body.sources.get("s1").unwrap();
@@ -391,6 +425,8 @@ sources:
             state_store_fallback_active: false,
             config_path: None,
             poll_deps: None,
+            draining: Default::default(),
+            tail: None,
         };
         let body = build_health_body(&state).await;
         let s1 = body.sources.get("s1").unwrap();
@@ -417,6 +453,8 @@ sources:
             state_store_fallback_active: false,
             config_path: None,
             poll_deps: None,
+            draining: Default::default(),
+            tail: None,
         };
         let body = build_health_body(&state).await;
         let s1 = body.sources.get("s1").unwrap();
@@ -481,6 +519,8 @@ sources:
             state_store_fallback_active: false,
             config_path: None,
             poll_deps: None,
+            draining: Default::default(),
+            tail: None,
         };
         let body = build_health_body(&state).await;
         let s1 = body.sources.get("s1").unwrap();
@@ -518,6 +558,8 @@ sources:
             state_store_fallback_active: false,
             config_path: None,
             poll_deps: None,
+            draining: Default::default(),
+            tail: None,
         };
         let body = build_ready_body(&state).await;
         assert!(!body.at_least_one_source_healthy);
@@ -540,6 +582,8 @@ sources:
             state_store_fallback_active: false,
             config_path: None,
             poll_deps: None,
+            draining: Default::default(),
+            tail: None,
         };
         let body = build_ready_body(&state).await;
         assert!(body.state_store_connected);
@@ -570,4 +614,51 @@ sources:
             "readyz body should report state_store_fallback_active when true"
         );
     }
+
+    #[tokio::test]
+    async fn test_disabled_source_not_polled_and_reported_disabled() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_string(r#"[{"id":"1"}]"#))
+            .mount(&server)
+            .await;
+        let state = health_state_with(None, Instant::now());
+        {
+            let mut config = state.config.write().await;
+            config.sources.remove("s2");
+            let s1 = config.sources.get_mut("s1").unwrap();
+            s1.url = format!("{}/logs", server.uri());
+            s1.pagination = None;
+            s1.disabled = true;
+        }
+        let sink = Arc::new(crate::golden::CaptureSink::new());
+        let config = state.config.read().await.clone();
+        crate::poll::run_one_tick(
+            &config,
+            Arc::new(crate::state::MemoryStateStore::new()),
+            None,
+            state.circuit_store.clone(),
+            crate::oauth2::new_oauth2_token_cache(),
+            None,
+            crate::dedupe::new_dedupe_store(),
+            sink.clone(),
+            None,
+            state.last_errors.clone(),
+            None,
+            crate::poll::new_poll_caches(),
+            None,
+            None,
+            false,
+            std::sync::Arc::new(crate::clock::SystemClock),
+        )
+        .await
+        .unwrap();
+        assert!(sink.lines().is_empty());
+        assert!(server.received_requests().await.unwrap().is_empty());
+
+        let body = build_health_body(&state).await;
+        assert_eq!(body.sources["s1"].status, "disabled");
+        let ready = build_ready_body(&state).await;
+        assert!(!ready.at_least_one_source_healthy);
+    }
 }
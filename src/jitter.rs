@@ -0,0 +1,56 @@
+//! Random jitter for poll scheduling and retry backoff.
+//!
+//! With `global.jitter_seed`, draws come from seeded RNGs so the delay sequence is reproducible (tests,
+//! incident replays); otherwise from the thread-local entropy RNG. Each [`Stream`] has its own seeded RNG,
+//! so retries don't shift the schedule sequence.
+
+use rand::distr::uniform::{SampleRange, SampleUniform};
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+
+/// What a draw is for; each stream is seeded independently from the global seed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stream {
+    /// Interval jitter between ticks (schedule.jitter_secs).
+    Schedule,
+    /// Retry backoff jitter (retries.jitter).
+    Retry,
+    /// First-poll delay after startup (schedule.startup_jitter_secs).
+    Startup,
+}
+
+struct Seeded {
+    seed: u64,
+    streams: HashMap<Stream, StdRng>,
+}
+
+static SEEDED: LazyLock<Mutex<Option<Seeded>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Seed the jitter RNGs (None: entropy-based). Restarts every stream's sequence.
+pub fn set_seed(seed: Option<u64>) {
+    *SEEDED.lock().unwrap_or_else(|e| e.into_inner()) = seed.map(|seed| Seeded {
+        seed,
+        streams: HashMap::new(),
+    });
+}
+
+/// Uniform draw from `range` on the given stream.
+pub fn random_range<T, R>(stream: Stream, range: R) -> T
+where
+    T: SampleUniform,
+    R: SampleRange<T>,
+{
+    let mut guard = SEEDED.lock().unwrap_or_else(|e| e.into_inner());
+    match guard.as_mut() {
+        Some(s) => {
+            let seed = s.seed;
+            s.streams
+                .entry(stream)
+                .or_insert_with(|| StdRng::seed_from_u64(seed ^ stream as u64))
+                .random_range(range)
+        }
+        None => rand::rng().random_range(range),
+    }
+}
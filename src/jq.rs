@@ -0,0 +1,98 @@
+//! jq transforms (source `jq`): run a jq expression over the parsed response to produce the events.
+//! Requires the `jq` Cargo feature (jaq); without it, sources with `jq` are rejected at load.
+
+/// Check that `expr` compiles (config validation).
+#[cfg(feature = "jq")]
+pub fn check(expr: &str) -> anyhow::Result<()> {
+    compile(expr).map(|_| ())
+}
+
+#[cfg(not(feature = "jq"))]
+pub fn check(_expr: &str) -> anyhow::Result<()> {
+    anyhow::bail!("jq requires building with --features jq")
+}
+
+/// Run `expr` over the response body. A single array output becomes the events; otherwise each output is one event.
+#[cfg(feature = "jq")]
+pub fn events_from_jq(
+    expr: &str,
+    value: serde_json::Value,
+) -> anyhow::Result<Vec<serde_json::Value>> {
+    use jaq_core::{Ctx, RcIter};
+    use jaq_json::Val;
+
+    let filter = compile(expr)?;
+    let inputs = RcIter::new(core::iter::empty());
+    let mut outputs = Vec::new();
+    for out in filter.run((Ctx::new([], &inputs), Val::from(value))) {
+        let v = out.map_err(|e| anyhow::anyhow!("jq {:?}: {}", expr, e))?;
+        outputs.push(serde_json::Value::from(v));
+    }
+    if outputs.len() == 1
+        && outputs[0].is_array()
+        && let Some(serde_json::Value::Array(arr)) = outputs.pop()
+    {
+        return Ok(arr);
+    }
+    Ok(outputs)
+}
+
+#[cfg(not(feature = "jq"))]
+pub fn events_from_jq(
+    _expr: &str,
+    _value: serde_json::Value,
+) -> anyhow::Result<Vec<serde_json::Value>> {
+    anyhow::bail!("jq requires building with --features jq")
+}
+
+#[cfg(feature = "jq")]
+fn compile(expr: &str) -> anyhow::Result<jaq_core::Filter<jaq_core::Native<jaq_json::Val>>> {
+    use jaq_core::load::{Arena, File, Loader};
+
+    let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+    let arena = Arena::default();
+    let modules = loader
+        .load(
+            &arena,
+            File {
+                code: expr,
+                path: (),
+            },
+        )
+        .map_err(|errs| anyhow::anyhow!("jq {:?}: parse error: {:?}", expr, errs_summary(&errs)))?;
+    jaq_core::Compiler::default()
+        .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+        .compile(modules)
+        .map_err(|errs| anyhow::anyhow!("jq {:?}: compile error: {:?}", expr, errs_summary(&errs)))
+}
+
+#[cfg(feature = "jq")]
+fn errs_summary<F, E: std::fmt::Debug>(errs: &[(F, E)]) -> Vec<&E> {
+    errs.iter().map(|(_, e)| e).collect()
+}
+
+#[cfg(all(test, feature = "jq"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn jq_extracts_and_reshapes_events() {
+        let body = json!({
+            "meta": {"count": 2},
+            "results": [
+                {"uuid": "a", "actor": {"name": "alice"}, "severity": 3},
+                {"uuid": "b", "actor": {"name": "bob"}, "severity": 7},
+            ]
+        });
+        let expr = ".results[] | select(.severity > 5) | {id: .uuid, user: .actor.name}";
+        let events = events_from_jq(expr, body.clone()).unwrap();
+        assert_eq!(events, vec![json!({"id": "b", "user": "bob"})]);
+
+        // A single array output is flattened into the events.
+        let events = events_from_jq("[.results[] | .uuid]", body).unwrap();
+        assert_eq!(events, vec![json!("a"), json!("b")]);
+
+        assert!(check(".results[").is_err());
+    }
+}
@@ -7,22 +7,34 @@ use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
 
+mod alert;
 mod api;
 mod audit;
+mod bench;
 mod circuit;
 mod client;
+mod clock;
 mod config;
+mod dead_letter;
+mod decompress;
+mod decrypt;
 mod dedupe;
 mod dpop;
 mod event;
+mod golden;
 mod health;
+mod jitter;
+mod jq;
 mod metrics;
 mod oauth2;
+mod otel;
 mod output;
 mod pagination;
 mod poll;
 mod replay;
 mod retry;
+mod schema;
+mod sigv4;
 mod state;
 
 #[cfg(feature = "hooks")]
@@ -36,7 +48,8 @@ use config::{Config, DumpOnSigusr1Config};
 use dpop::new_dpop_key_cache;
 use oauth2::new_oauth2_token_cache;
 use output::{
-    BackpressureSink, EventSink, FileSink, HttpSink, RotationPolicy, StdoutSink, parse_rotation,
+    BackpressureSink, BatchArraySink, CsvSink, EventSink, FileSink, HttpSink, LokiSink,
+    RingBufferSink, RotationPolicy, StdoutSink, parse_rotation,
 };
 #[cfg(feature = "nats")]
 use output::{NatsSink, parse_nats_url};
@@ -70,7 +83,7 @@ struct Cli {
 enum Commands {
     /// Start the collector (default)
     Run {
-        /// Config file path (sources, schedule, auth, etc.)
+        /// Config file path (sources, schedule, auth, etc.); `-` reads YAML from stdin
         #[arg(short, long, default_value = "helr.yaml", value_name = "PATH")]
         config: PathBuf,
 
@@ -90,6 +103,22 @@ enum Commands {
         #[arg(long, value_name = "POLICY", requires = "output")]
         output_rotate: Option<String>,
 
+        /// Gzip rotated output files (`<name>.gz`); the active file stays uncompressed
+        #[arg(long, requires = "output_rotate")]
+        output_compress_on_rotate: bool,
+
+        /// Write `<rotated>.sha256` and `<rotated>.meta.json` (lines, bytes, first/last ts) for each rotated file
+        #[arg(long, requires = "output_rotate")]
+        output_manifest: bool,
+
+        /// fsync the output file after each line for durable writes (see --output-fsync-every)
+        #[arg(long, requires = "output")]
+        output_fsync: bool,
+
+        /// With --output-fsync, fsync after every N lines instead of each line (also on flush and rotation)
+        #[arg(long, value_name = "N", requires = "output_fsync")]
+        output_fsync_every: Option<u64>,
+
         /// Record HTTP responses to directory (for later replay)
         #[arg(long, value_name = "PATH")]
         record_dir: Option<PathBuf>,
@@ -104,6 +133,10 @@ enum Commands {
         /// Config file path
         #[arg(short, long, default_value = "helr.yaml", value_name = "PATH")]
         config: PathBuf,
+
+        /// Also build each source's client and make one authenticated request per source (no events emitted); exit non-zero if any is unreachable or rejects auth
+        #[arg(long)]
+        strict: bool,
     },
 
     /// Test a source configuration (one poll tick for the given source)
@@ -117,6 +150,25 @@ enum Commands {
 
         #[arg(long, help = "Run one poll cycle (default for test)")]
         once: bool,
+
+        /// Compare emitted NDJSON with this golden file (ignoring `ts`) instead of printing it; exit non-zero with a unified diff on mismatch
+        #[arg(long, value_name = "PATH")]
+        golden: Option<PathBuf>,
+    },
+
+    /// Replay recordings in a loop against a null sink and report events/sec and line-write latency
+    Bench {
+        /// Config file path (sources, backpressure settings)
+        #[arg(short, long, default_value = "helr.yaml", value_name = "PATH")]
+        config: PathBuf,
+
+        /// Recordings directory (as written by `run --record-dir`)
+        #[arg(long, value_name = "PATH")]
+        replay_dir: PathBuf,
+
+        /// How long to run, in seconds
+        #[arg(long, value_name = "SECS", default_value_t = 10)]
+        duration: u64,
     },
 
     /// Inspect or manage state store
@@ -128,6 +180,22 @@ enum Commands {
         #[command(subcommand)]
         subcommand: Option<StateSubcommand>,
     },
+
+    /// Inspect configured sources
+    Sources {
+        /// Config file path
+        #[arg(short, long, default_value = "helr.yaml", value_name = "PATH")]
+        config: PathBuf,
+
+        #[command(subcommand)]
+        subcommand: Option<SourcesSubcommand>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SourcesSubcommand {
+    /// Print a table of sources: name, URL (secrets redacted), pagination, interval, auth type
+    List,
 }
 
 #[derive(Subcommand)]
@@ -144,9 +212,16 @@ enum StateSubcommand {
         key: String,
         value: String,
     },
-    Export,
-    /// Import state from JSON (same format as export). Reads from stdin.
-    Import,
+    /// Export state as JSON to stdout; optionally only one source.
+    Export {
+        source: Option<String>,
+    },
+    /// Import state from JSON (same format as export). Reads from stdin; optionally only one source.
+    Import {
+        source: Option<String>,
+    },
+    /// Compact the state store (SQLite: VACUUM + PRAGMA optimize; no-op for other backends).
+    Vacuum,
 }
 
 /// Path to helr config (sources, state, etc.) for commands that use it. Default "helr.yaml" when no subcommand (implicit run).
@@ -154,9 +229,11 @@ fn helr_config_path(cli: &Cli) -> PathBuf {
     match &cli.command {
         None => PathBuf::from("helr.yaml"),
         Some(Commands::Run { config, .. }) => config.clone(),
-        Some(Commands::Validate { config }) => config.clone(),
+        Some(Commands::Validate { config, .. }) => config.clone(),
         Some(Commands::Test { config, .. }) => config.clone(),
+        Some(Commands::Bench { config, .. }) => config.clone(),
         Some(Commands::State { config, .. }) => config.clone(),
+        Some(Commands::Sources { config, .. }) => config.clone(),
     }
 }
 
@@ -187,22 +264,31 @@ async fn main() -> anyhow::Result<()> {
     }
 
     match &cli.command {
-        Some(Commands::Validate { config }) => {
+        Some(Commands::Validate { config, strict }) => {
             init_logging(None, &cli);
-            run_validate(config)
+            if *strict {
+                run_validate_strict(config).await
+            } else {
+                run_validate(config)
+            }
         }
         other => {
             let config_path = helr_config_path(&cli);
             let config = Config::load(&config_path)?;
             audit::log_config_change(config.global.audit.as_ref(), &config_path, false);
+            jitter::set_seed(config.global.jitter_seed);
             init_logging(Some(&config), &cli);
-            match other {
+            let result = match other {
                 Some(Commands::Run {
                     config: run_config_path,
                     once,
                     source,
                     output,
                     output_rotate,
+                    output_compress_on_rotate,
+                    output_manifest,
+                    output_fsync,
+                    output_fsync_every,
                     record_dir,
                     replay_dir,
                 }) => {
@@ -237,21 +323,9 @@ async fn main() -> anyhow::Result<()> {
                                     }
                                     #[cfg(feature = "nats")]
                                     {
-                                        let (server, mut subject) = parse_nats_url(&path_str);
-                                        if let Some(s) = config
-                                            .global
-                                            .output
-                                            .as_ref()
-                                            .and_then(|o| o.nats.as_ref())
-                                            .and_then(|n| n.subject.as_deref())
-                                        {
-                                            subject = s.to_string();
-                                        }
-                                        tracing::info!(
-                                            %server, %subject,
-                                            "output: NATS sink",
-                                        );
-                                        let sink = NatsSink::connect(&server, &subject).await?;
+                                        let (server, subject) = parse_nats_url(&path_str);
+                                        let sink =
+                                            connect_nats_sink(&config, &server, subject).await?;
                                         (Arc::new(sink), None)
                                     }
                                 } else {
@@ -261,11 +335,84 @@ async fn main() -> anyhow::Result<()> {
                                         .transpose()?
                                         .unwrap_or(RotationPolicy::None);
                                     let path_clone = path.clone();
-                                    (Arc::new(FileSink::new(path, rotation)?), Some(path_clone))
+                                    (
+                                        Arc::new(
+                                            FileSink::new(path, rotation)?
+                                                .with_compress_on_rotate(*output_compress_on_rotate)
+                                                .with_manifest(*output_manifest)
+                                                .with_envelope_keys(
+                                                    &event::EnvelopeKeys::from_global(
+                                                        &config.global,
+                                                    ),
+                                                )
+                                                .with_fsync(
+                                                    output_fsync
+                                                        .then(|| output_fsync_every.unwrap_or(1)),
+                                                ),
+                                        ),
+                                        Some(path_clone),
+                                    )
+                                }
+                            }
+                            None => {
+                                match config.global.output.as_ref().and_then(|o| o.loki.as_ref()) {
+                                    Some(loki_cfg) => {
+                                        tracing::info!(endpoint = %loki_cfg.endpoint, "output: Loki sink");
+                                        (
+                                            Arc::new(LokiSink::new(
+                                                loki_cfg,
+                                                event::EnvelopeKeys::from_global(&config.global),
+                                            )?),
+                                            None,
+                                        )
+                                    }
+                                    None => match config
+                                        .global
+                                        .output
+                                        .as_ref()
+                                        .and_then(|o| o.nats.as_ref())
+                                        .and_then(|n| n.url.as_deref())
+                                    {
+                                        #[cfg(feature = "nats")]
+                                        Some(server) => {
+                                            let (server, subject) = parse_nats_url(server);
+                                            let sink = connect_nats_sink(&config, &server, subject)
+                                                .await?;
+                                            (Arc::new(sink), None)
+                                        }
+                                        #[cfg(not(feature = "nats"))]
+                                        Some(_) => anyhow::bail!(
+                                            "global.output.nats requires the 'nats' feature: \
+                                             rebuild with --features nats"
+                                        ),
+                                        None => match otlp_logs_sink(&config)? {
+                                            Some(sink) => (sink, None),
+                                            None => (journald_or_stdout_sink(&config)?, None),
+                                        },
+                                    },
                                 }
                             }
-                            None => (Arc::new(StdoutSink), None),
                         };
+                    let output_cfg = config.global.output.as_ref();
+                    let event_sink: Arc<dyn EventSink> = match (
+                        output_cfg.and_then(|o| o.csv.as_ref()),
+                        output_cfg.and_then(|o| o.batch_array.as_ref()),
+                    ) {
+                        (Some(_), Some(_)) => anyhow::bail!(
+                            "global.output.csv and global.output.batch_array cannot both be set"
+                        ),
+                        (Some(csv_cfg), None) => {
+                            Arc::new(CsvSink::new(event_sink, csv_cfg.columns.clone())?)
+                        }
+                        (None, Some(batch_cfg)) => Arc::new(BatchArraySink::new(
+                            event_sink,
+                            batch_cfg.batch_size,
+                            Some(std::time::Duration::from_secs(
+                                batch_cfg.flush_interval_secs,
+                            )),
+                        )?),
+                        (None, None) => event_sink,
+                    };
                     let record_state = if let Some(dir) = record_dir {
                         Some(Arc::new(replay::RecordState::new(dir)?))
                     } else {
@@ -276,7 +423,8 @@ async fn main() -> anyhow::Result<()> {
                         if recordings.is_empty() {
                             anyhow::bail!("replay dir has no recordings: {}", dir.display());
                         }
-                        let (addr, _join) = replay::start_replay_server(recordings, 0).await?;
+                        let (addr, _join) =
+                            replay::start_replay_server(recordings, 0, false).await?;
                         let base = format!("http://{}", addr);
                         tracing::info!(%base, "replay server started");
                         let rewritten = replay::rewrite_config_for_replay(&config, &base);
@@ -306,7 +454,11 @@ async fn main() -> anyhow::Result<()> {
                     } else {
                         (event_sink, None)
                     };
-                    let reload_path = if *once || replay_dir.is_some() {
+                    // Config read from stdin cannot be re-read on SIGHUP or /reload.
+                    let reload_path = if *once
+                        || replay_dir.is_some()
+                        || config::is_stdin_path(run_config_path)
+                    {
                         None
                     } else {
                         Some(run_config_path.as_path())
@@ -329,12 +481,31 @@ async fn main() -> anyhow::Result<()> {
                     )
                     .await
                 }
+                Some(Commands::Test {
+                    source,
+                    golden: Some(golden),
+                    ..
+                }) => run_test_golden(&config, source, golden).await,
                 Some(Commands::Test { source, .. }) => {
                     run_test(&config, source, Arc::new(StdoutSink)).await
                 }
+                Some(Commands::Bench {
+                    replay_dir,
+                    duration,
+                    ..
+                }) => {
+                    let report =
+                        bench::run_bench(&config, replay_dir, Duration::from_secs(*duration))
+                            .await?;
+                    println!("{}", serde_json::to_string(&report)?);
+                    Ok(())
+                }
                 Some(Commands::State { subcommand, .. }) => {
                     run_state(&config, subcommand.as_ref()).await
                 }
+                Some(Commands::Sources { subcommand, .. }) => {
+                    run_sources(&config, subcommand.as_ref())
+                }
                 None => {
                     let path = helr_config_path(&cli);
                     let skip_priority_below = config
@@ -356,11 +527,87 @@ async fn main() -> anyhow::Result<()> {
                     .await
                 }
                 _ => unreachable!(),
-            }
+            };
+            let _ = tokio::task::spawn_blocking(otel::shutdown).await;
+            result
         }
     }
 }
 
+/// OTLP logs sink when `global.output.otlp` is set.
+fn otlp_logs_sink(config: &Config) -> anyhow::Result<Option<Arc<dyn EventSink>>> {
+    let Some(otlp_cfg) = config.global.output.as_ref().and_then(|o| o.otlp.as_ref()) else {
+        return Ok(None);
+    };
+    #[cfg(feature = "otlp-logs")]
+    {
+        tracing::info!(endpoint = %otlp_cfg.endpoint, "output: OTLP logs sink");
+        Ok(Some(Arc::new(output::OtlpLogsSink::new(
+            otlp_cfg,
+            event::EnvelopeKeys::from_global(&config.global),
+        )?)))
+    }
+    #[cfg(not(feature = "otlp-logs"))]
+    {
+        let _ = otlp_cfg;
+        anyhow::bail!(
+            "global.output.otlp requires the 'otlp-logs' feature: \
+             rebuild with --features otlp-logs"
+        )
+    }
+}
+
+/// Journal sink when `global.output.journald` is set, else stdout.
+fn journald_or_stdout_sink(config: &Config) -> anyhow::Result<Arc<dyn EventSink>> {
+    let Some(journald_cfg) = config
+        .global
+        .output
+        .as_ref()
+        .and_then(|o| o.journald.as_ref())
+    else {
+        return Ok(Arc::new(StdoutSink));
+    };
+    #[cfg(all(feature = "journald", target_os = "linux"))]
+    {
+        tracing::info!(syslog_identifier = %journald_cfg.syslog_identifier, "output: systemd journal sink");
+        Ok(Arc::new(output::JournaldSink::new(
+            journald_cfg,
+            event::EnvelopeKeys::from_global(&config.global),
+        )))
+    }
+    #[cfg(not(all(feature = "journald", target_os = "linux")))]
+    {
+        let _ = journald_cfg;
+        anyhow::bail!(
+            "global.output.journald requires Linux and the 'journald' feature: \
+             rebuild with --features journald"
+        )
+    }
+}
+
+/// Connect the NATS sink; `global.output.nats.subject` overrides the subject taken from the URL.
+#[cfg(feature = "nats")]
+async fn connect_nats_sink(
+    config: &Config,
+    server: &str,
+    subject: String,
+) -> anyhow::Result<NatsSink> {
+    let nats_cfg = config
+        .global
+        .output
+        .as_ref()
+        .and_then(|o| o.nats.clone())
+        .unwrap_or_default();
+    let subject = nats_cfg.subject.clone().unwrap_or(subject);
+    tracing::info!(
+        %server, %subject,
+        subject_prefix = ?nats_cfg.subject_prefix,
+        jetstream = nats_cfg.jetstream,
+        "output: NATS sink",
+    );
+    NatsSink::connect(server, &subject, &nats_cfg).await
+}
+
 /// Key/value for the producer label in Helr's JSON log lines (set in init_logging).
 static HELR_LOG_LABEL_KEY: std::sync::OnceLock<String> = std::sync::OnceLock::new();
 static HELR_LOG_LABEL_VALUE: std::sync::OnceLock<String> = std::sync::OnceLock::new();
@@ -516,6 +763,11 @@ fn init_logging(config: Option<&Config>, cli: &Cli) {
             EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(filter_str))
         }
     };
+    // OTLP span export; the exporter failing to build must not stop the collector, so only warn.
+    let (otel_layer, otel_err) = match otel::layer(config.and_then(|c| c.global.otel.as_ref())) {
+        Ok(layer) => (layer, None),
+        Err(e) => (None, Some(e)),
+    };
     if use_json {
         // Omit current_span and span_list so we don't parse span fields as JSON (they're key=value, not JSON).
         // Use HelJsonStderr so each line gets "source":"helr" for consistent labeling with NDJSON events (stdout).
@@ -524,6 +776,7 @@ fn init_logging(config: Option<&Config>, cli: &Cli) {
             .with_current_span(false)
             .with_span_list(false);
         tracing_subscriber::registry()
+            .with(otel_layer)
             .with(
                 tracing_subscriber::fmt::layer()
                     .with_writer(HelJsonStderr)
@@ -535,6 +788,7 @@ fn init_logging(config: Option<&Config>, cli: &Cli) {
             .init();
     } else {
         tracing_subscriber::registry()
+            .with(otel_layer)
             .with(
                 tracing_subscriber::fmt::layer()
                     .with_writer(std::io::stderr)
@@ -543,6 +797,9 @@ fn init_logging(config: Option<&Config>, cli: &Cli) {
             .with(filter)
             .init();
     }
+    if let Some(e) = otel_err {
+        tracing::warn!("otel trace export disabled: {}", e);
+    }
 }
 
 fn run_validate(config_path: &std::path::Path) -> anyhow::Result<()> {
@@ -558,6 +815,67 @@ fn run_validate(config_path: &std::path::Path) -> anyhow::Result<()> {
     }
 }
 
+/// Strict validation: load config (resolving secrets), then send each source's configured request (method, body, auth) once, without retries.
+/// Prints one line per source; exits 1 if any source is unreachable or rejects auth (401/403).
+async fn run_validate_strict(config_path: &std::path::Path) -> anyhow::Result<()> {
+    let config = match Config::load(config_path) {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::error!("config invalid: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let token_cache = new_oauth2_token_cache();
+    let dpop_key_cache = new_dpop_key_cache();
+    let mut names: Vec<&String> = config.sources.keys().collect();
+    names.sort();
+    let mut failed = false;
+    for name in names {
+        let source = &config.sources[name];
+        let result = async {
+            let client = client::build_source_client(source)?;
+            retry::execute_once(
+                &client,
+                source,
+                name,
+                &source.url,
+                source.body.as_ref(),
+                Some(&token_cache),
+                Some(&dpop_key_cache),
+                config.global.audit.as_ref(),
+            )
+            .await
+        }
+        .await;
+        match result {
+            Ok(resp) if matches!(resp.status().as_u16(), 401 | 403) => {
+                println!("{}: auth failed (http {})", name, resp.status().as_u16());
+                failed = true;
+            }
+            Ok(resp)
+                if resp.status().is_success()
+                    || source.accept_status_codes.contains(&resp.status().as_u16()) =>
+            {
+                println!("{}: ok (http {})", name, resp.status().as_u16())
+            }
+            Ok(resp) => {
+                println!("{}: failed (http {})", name, resp.status().as_u16());
+                failed = true;
+            }
+            Err(e) => {
+                println!("{}: unreachable: {:#}", name, e);
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        tracing::error!("strict validation failed");
+        std::process::exit(1);
+    }
+    tracing::info!("config valid");
+    std::process::exit(0);
+}
+
 /// Open state store from config. On primary failure, falls back to memory when `degradation.state_store_fallback: memory`.
 /// Returns (store, state_store_fallback_active).
 async fn open_store_with_fallback(config: &Config) -> anyhow::Result<(Arc<dyn StateStore>, bool)> {
@@ -667,6 +985,12 @@ async fn run_test(
         anyhow::bail!("source {:?} not found in config", source_name);
     }
     tracing::info!("testing source {:?} (one poll tick)", source_name);
+    // A test poll runs now even when the source's cron doesn't match.
+    let mut config = config.clone();
+    if let Some(source) = config.sources.get_mut(source_name) {
+        source.schedule.cron_poll_on_startup = true;
+    }
+    let config = &config;
     let store = open_store(config).await?;
     let circuit_store = new_circuit_store();
     let token_cache = new_oauth2_token_cache();
@@ -692,13 +1016,43 @@ async fn run_test(
         None,
         last_errors,
         global_sources_semaphore,
+        poll::new_poll_caches(),
         None,
         None,
+        false,
+        Arc::new(clock::SystemClock),
     )
     .await
 }
 
-/// State subcommands: show, reset, export.
+/// Run one poll tick for the source, capturing output, and diff it against the golden NDJSON file.
+async fn run_test_golden(
+    config: &Config,
+    source_name: &str,
+    golden_path: &Path,
+) -> anyhow::Result<()> {
+    let golden = std::fs::read_to_string(golden_path)
+        .map_err(|e| anyhow::anyhow!("read golden file {}: {}", golden_path.display(), e))?;
+    let capture = Arc::new(golden::CaptureSink::new());
+    run_test(config, source_name, capture.clone()).await?;
+    let lines = capture.lines();
+    match golden::diff_against_golden(&lines, &golden) {
+        None => {
+            tracing::info!(
+                lines = lines.len(),
+                "output matches golden {:?}",
+                golden_path
+            );
+            Ok(())
+        }
+        Some(diff) => {
+            print!("{}", diff);
+            anyhow::bail!("output differs from golden {}", golden_path.display())
+        }
+    }
+}
+
+/// State subcommands: show, reset, set, export, import, vacuum.
 async fn run_state(config: &Config, subcommand: Option<&StateSubcommand>) -> anyhow::Result<()> {
     let store = open_store(config).await?;
     match subcommand {
@@ -707,15 +1061,23 @@ async fn run_state(config: &Config, subcommand: Option<&StateSubcommand>) -> any
         Some(StateSubcommand::Set { source, key, value }) => {
             state_set(store.as_ref(), source, key, value).await
         }
-        Some(StateSubcommand::Export) => state_export(store.as_ref()).await,
-        Some(StateSubcommand::Import) => state_import(store.as_ref()).await,
+        Some(StateSubcommand::Export { source }) => {
+            state_export(store.as_ref(), source.as_deref()).await
+        }
+        Some(StateSubcommand::Import { source }) => {
+            state_import(store.as_ref(), source.as_deref()).await
+        }
+        Some(StateSubcommand::Vacuum) => state_vacuum(store.as_ref()).await,
         None => {
-            eprintln!("usage: helr state {{show,reset,set,export,import}}");
+            eprintln!("usage: helr state {{show,reset,set,export,import,vacuum}}");
             eprintln!("  show <source>   show state keys and values for a source");
             eprintln!("  reset <source>  clear all state for a source");
             eprintln!("  set <source> <key> <value>  set a single state key");
-            eprintln!("  export         write all state as JSON to stdout");
-            eprintln!("  import         read state from JSON on stdin (same format as export)");
+            eprintln!("  export [source]  write all state (or one source) as JSON to stdout");
+            eprintln!(
+                "  import [source]  read state from JSON on stdin (same format as export; or only one source)"
+            );
+            eprintln!("  vacuum          compact the state store (sqlite only)");
             Ok(())
         }
     }
@@ -752,31 +1114,35 @@ async fn state_set(
     Ok(())
 }
 
-async fn state_export(store: &dyn StateStore) -> anyhow::Result<()> {
-    let sources = store.list_sources().await?;
-    let mut out = serde_json::Map::new();
-    for source_id in sources {
-        let keys = store.list_keys(&source_id).await?;
-        let mut m = serde_json::Map::new();
-        for key in keys {
-            if let Some(v) = store.get(&source_id, &key).await? {
-                m.insert(key, serde_json::Value::String(v));
-            }
-        }
-        out.insert(source_id, serde_json::Value::Object(m));
+async fn state_vacuum(store: &dyn StateStore) -> anyhow::Result<()> {
+    match store.vacuum().await? {
+        Some((before, after)) => println!(
+            "vacuumed state store: {} -> {} bytes ({} reclaimed)",
+            before,
+            after,
+            before.saturating_sub(after)
+        ),
+        None => println!("state backend has nothing to vacuum"),
     }
-    println!(
-        "{}",
-        serde_json::to_string_pretty(&serde_json::Value::Object(out))?
-    );
     Ok(())
 }
 
-/// Export state to a JSON string (same shape as state export).
-async fn state_export_to_string(store: &dyn StateStore) -> anyhow::Result<String> {
+async fn state_export(store: &dyn StateStore, source_filter: Option<&str>) -> anyhow::Result<()> {
+    println!("{}", state_export_to_string(store, source_filter).await?);
+    Ok(())
+}
+
+/// Export state to a JSON string (same shape as state export). When `source_filter` is set, only that source.
+async fn state_export_to_string(
+    store: &dyn StateStore,
+    source_filter: Option<&str>,
+) -> anyhow::Result<String> {
     let sources = store.list_sources().await?;
     let mut out = serde_json::Map::new();
     for source_id in sources {
+        if source_filter.is_some_and(|f| f != source_id) {
+            continue;
+        }
         let keys = store.list_keys(&source_id).await?;
         let mut m = serde_json::Map::new();
         for key in keys {
@@ -794,7 +1160,7 @@ async fn dump_state_and_metrics(
     store: &dyn StateStore,
     cfg: &DumpOnSigusr1Config,
 ) -> anyhow::Result<()> {
-    let state_json = state_export_to_string(store)
+    let state_json = state_export_to_string(store, None)
         .await
         .unwrap_or_else(|e| format!("(state export failed: {})", e));
     let metrics_text = metrics::encode();
@@ -821,13 +1187,17 @@ async fn dump_state_and_metrics(
 }
 
 /// Import state from JSON on stdin (same shape as export: { "source_id": { "key": "value", ... }, ... }).
-async fn state_import(store: &dyn StateStore) -> anyhow::Result<()> {
+/// When `source_filter` is set, other sources in the input are ignored.
+async fn state_import(store: &dyn StateStore, source_filter: Option<&str>) -> anyhow::Result<()> {
     let stdin = std::io::stdin();
     let mut input = String::new();
     std::io::Read::read_to_string(&mut stdin.lock(), &mut input)?;
     let root: serde_json::Map<String, serde_json::Value> =
         serde_json::from_str(&input).map_err(|e| anyhow::anyhow!("invalid JSON: {}", e))?;
     for (source_id, val) in root {
+        if source_filter.is_some_and(|f| f != source_id) {
+            continue;
+        }
         let obj = val
             .as_object()
             .ok_or_else(|| anyhow::anyhow!("source {:?} value must be an object", source_id))?;
@@ -842,6 +1212,75 @@ async fn state_import(store: &dyn StateStore) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Run a `helr sources` subcommand; without one, print usage.
+fn run_sources(config: &Config, subcommand: Option<&SourcesSubcommand>) -> anyhow::Result<()> {
+    match subcommand {
+        Some(SourcesSubcommand::List) => {
+            print!("{}", sources_list_to_string(config));
+            Ok(())
+        }
+        None => {
+            eprintln!("usage: helr sources {{list}}");
+            eprintln!("  list  print configured sources and their effective settings");
+            Ok(())
+        }
+    }
+}
+
+/// Render configured sources as an aligned table (sorted by name). Reads the parsed config only.
+fn sources_list_to_string(config: &Config) -> String {
+    let header = ["NAME", "URL", "PAGINATION", "INTERVAL", "AUTH"].map(String::from);
+    let mut names: Vec<&String> = config.sources.keys().collect();
+    names.sort();
+    let mut rows = vec![header];
+    for name in names {
+        let source = &config.sources[name];
+        let pagination = match &source.pagination {
+            Some(config::PaginationConfig::LinkHeader { .. }) => "link_header",
+            Some(config::PaginationConfig::Cursor { .. }) => "cursor",
+            Some(config::PaginationConfig::PageOffset { .. }) => "page_offset",
+            Some(config::PaginationConfig::Offset { .. }) => "offset",
+            None => "none",
+        };
+        let auth = match &source.auth {
+            Some(config::AuthConfig::Bearer { .. }) => "bearer",
+            Some(config::AuthConfig::ApiKey { .. }) => "api_key",
+            Some(config::AuthConfig::Basic { .. }) => "basic",
+            Some(config::AuthConfig::OAuth2 { .. }) => "oauth2",
+            Some(config::AuthConfig::GoogleServiceAccount { .. }) => "google_service_account",
+            Some(config::AuthConfig::AwsSigV4 { .. }) => "aws_sigv4",
+            None => "none",
+        };
+        rows.push([
+            name.clone(),
+            audit::redact_url(&source.url),
+            pagination.to_string(),
+            match &source.schedule.cron {
+                Some(expr) => format!("cron {:?}", expr),
+                None => format!("{}s", source.schedule.interval_secs),
+            },
+            auth.to_string(),
+        ]);
+    }
+    let mut widths = [0usize; 5];
+    for row in &rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.len());
+        }
+    }
+    let mut out = String::new();
+    for row in &rows {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, w)| format!("{:<w$}", cell, w = w))
+            .collect();
+        out.push_str(line.join("  ").trim_end());
+        out.push('\n');
+    }
+    out
+}
+
 /// Log each incoming HTTP request at debug level (method, path). Enable with HELR_LOG_LEVEL=debug or RUST_LOG=helr=trace for more detail.
 async fn log_incoming_http_request(
     request: Request<axum::body::Body>,
@@ -867,6 +1306,19 @@ async fn run_collector(
 ) -> anyhow::Result<()> {
     tracing::info!("loaded config");
 
+    // Retain the last global.debug.tail_capacity lines for GET /tail.
+    let tail_sink = config
+        .global
+        .debug
+        .as_ref()
+        .map(|d| d.tail_capacity)
+        .filter(|&n| n > 0)
+        .map(|n| Arc::new(RingBufferSink::new(event_sink.clone(), n)));
+    let event_sink: Arc<dyn EventSink> = match &tail_sink {
+        Some(t) => t.clone(),
+        None => event_sink,
+    };
+
     let (store, state_store_fallback_active) = open_store_with_fallback(config).await?;
 
     let circuit_store = new_circuit_store();
@@ -881,6 +1333,7 @@ async fn run_collector(
         .and_then(|b| b.max_concurrent_sources)
         .filter(|&n| n > 0)
         .map(|n| Arc::new(Semaphore::new(n as usize)));
+    let poll_caches = poll::new_poll_caches();
 
     let config_arc = Arc::new(RwLock::new(config.clone()));
     let config_path_for_reload = config_path.map(std::path::Path::to_path_buf);
@@ -924,6 +1377,9 @@ async fn run_collector(
         }
     }
 
+    // Pause flag for POST /drain and POST /resume; checked by the scheduler before each tick.
+    let draining = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
     // API and health server: bind only when enabled and running continuously
     if let Some(api_cfg) = &config.global.api
         && api_cfg.enabled
@@ -940,6 +1396,7 @@ async fn run_collector(
                 dpop_key_cache: dpop_key_cache.clone(),
                 dedupe_store: dedupe_store.clone(),
                 global_sources_semaphore: global_sources_semaphore.clone(),
+                poll_caches: poll_caches.clone(),
             }))
         } else {
             None
@@ -954,13 +1411,19 @@ async fn run_collector(
             state_store_fallback_active,
             config_path: config_path_for_reload.clone(),
             poll_deps,
+            draining: draining.clone(),
+            tail: tail_sink.clone(),
         });
-        tracing::info!(%addr, "health server listening on GET /healthz, /readyz, /startupz and REST API /api/v1/sources, /api/v1/sources/{{id}}/state, /api/v1/sources/{{id}}/config, GET /api/v1/config, POST /api/v1/sources/{{id}}/poll, POST /api/v1/reload");
+        tracing::info!(%addr, "health server listening on GET /healthz, /readyz, /startupz and REST API /api/v1/sources, /api/v1/sources/{{id}}/state, /api/v1/sources/{{id}}/config, GET /api/v1/config, POST /api/v1/sources/{{id}}/poll, POST /api/v1/reload, POST /drain, POST /resume, POST /circuit/{{id}}, GET /tail");
         tokio::spawn(async move {
             let app = axum::Router::new()
                 .route("/healthz", get(health::healthz_handler))
                 .route("/readyz", get(health::readyz_handler))
                 .route("/startupz", get(health::startupz_handler))
+                .route("/drain", post(api::drain_handler))
+                .route("/resume", post(api::resume_handler))
+                .route("/circuit/{id}", post(api::force_circuit_handler))
+                .route("/tail", get(api::tail_handler))
                 .nest(
                     "/api/v1",
                     axum::Router::new()
@@ -980,6 +1443,8 @@ async fn run_collector(
         });
     }
 
+    let mut idle_since = Instant::now();
+    let mut events_seen = poll::events_emitted_total();
     poll::run_one_tick(
         config,
         store.clone(),
@@ -992,26 +1457,48 @@ async fn run_collector(
         record_state.clone(),
         last_errors.clone(),
         global_sources_semaphore.clone(),
+        poll_caches.clone(),
         under_load_flag.clone(),
         skip_priority_below,
+        true,
+        Arc::new(clock::SystemClock),
     )
     .await?;
 
     if once {
-        return Ok(());
+        poll::wait_startup_polls().await;
+        // Write out anything a wrapper sink still buffers (e.g. a partial batch_array batch). Off the runtime
+        // threads: some sinks block in flush (OTLP force_flush).
+        let sink = event_sink.clone();
+        return tokio::task::spawn_blocking(move || sink.flush()).await?;
     }
 
     const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
 
     let mut tick = 0u64;
     'run: loop {
-        let (delay, dump_enabled) = {
+        let (delay, dump_enabled, idle_shutdown_secs) = {
             let g = config_arc.read().await;
             (
                 next_delay(&g, state_store_fallback_active),
                 g.global.dump_on_sigusr1.is_some(),
+                g.global.idle_shutdown_secs,
             )
         };
+        let events_total = poll::events_emitted_total();
+        if events_total != events_seen {
+            events_seen = events_total;
+            idle_since = Instant::now();
+        }
+        let idle_remaining = idle_shutdown_secs
+            .map(|secs| Duration::from_secs(secs).saturating_sub(idle_since.elapsed()));
+        if idle_remaining == Some(Duration::ZERO) {
+            tracing::info!(
+                idle_secs = idle_since.elapsed().as_secs(),
+                "no events within idle_shutdown_secs, shutting down"
+            );
+            break 'run;
+        }
         tick += 1;
         tracing::debug!(tick, delay_secs = delay.as_secs(), "scheduling next tick");
 
@@ -1034,9 +1521,14 @@ async fn run_collector(
                             let restart = config_arc.read().await.global.reload.as_ref()
                                 .is_some_and(|r| r.restart_sources_on_sighup);
                             if restart {
-                                circuit_store.write().await.clear();
-                                token_cache.write().await.clear();
-                                tracing::info!("config reloaded on SIGHUP, circuit breaker and token cache cleared");
+                                poll::reset_source_caches(
+                                    &circuit_store,
+                                    &token_cache,
+                                    dpop_key_cache.as_ref(),
+                                    &poll_caches,
+                                )
+                                .await;
+                                tracing::info!("config reloaded on SIGHUP, circuit breaker, token and key caches cleared");
                             } else {
                                 tracing::info!("config reloaded on SIGHUP");
                             }
@@ -1056,6 +1548,20 @@ async fn run_collector(
                 continue 'run;
             }
             _ = tokio::time::sleep(delay) => {}
+            _ = idle_deadline(idle_remaining) => {
+                tracing::info!(
+                    idle_secs = idle_since.elapsed().as_secs(),
+                    "no events within idle_shutdown_secs, shutting down"
+                );
+                break 'run;
+            }
+        }
+
+        if draining.load(std::sync::atomic::Ordering::SeqCst) {
+            tracing::debug!(tick, "draining: skipping scheduled tick");
+            // A paused process is not idle: don't let idle_shutdown_secs fire during maintenance.
+            idle_since = Instant::now();
+            continue 'run;
         }
 
         let config_guard = config_arc.read().await;
@@ -1086,8 +1592,11 @@ async fn run_collector(
             record_state_ref.cloned(),
             last_errors_ref,
             global_sources_semaphore.clone(),
+            poll_caches.clone(),
             under_load_flag.clone(),
             skip_priority_below_tick,
+            false,
+            Arc::new(clock::SystemClock),
         ));
 
         tokio::select! {
@@ -1112,13 +1621,41 @@ async fn run_collector(
         }
     }
 
-    if let Err(e) = event_sink.flush() {
-        tracing::warn!("flush output: {}", e);
+    // Flush on a plain thread so a stalled consumer can't hold up exit; on timeout, persist what is
+    // still queued (backpressure disk buffer) for replay on next start.
+    let (flush_tx, flush_rx) = tokio::sync::oneshot::channel();
+    let flush_sink = event_sink.clone();
+    std::thread::spawn(move || {
+        let _ = flush_tx.send(flush_sink.flush());
+    });
+    match tokio::time::timeout(SHUTDOWN_TIMEOUT, flush_rx).await {
+        Ok(Ok(Ok(()))) => {}
+        Ok(Ok(Err(e))) => tracing::warn!("flush output: {}", e),
+        Ok(Err(_)) => tracing::warn!("flush output: flush thread exited"),
+        Err(_) => {
+            tracing::warn!("output flush did not finish within shutdown timeout");
+            match event_sink.persist_pending() {
+                Ok(0) => {}
+                Ok(n) => tracing::info!(
+                    lines = n,
+                    "persisted queued events to disk buffer for replay on next start"
+                ),
+                Err(e) => tracing::warn!("persist queued events: {}", e),
+            }
+        }
     }
     tracing::info!("graceful shutdown complete");
     Ok(())
 }
 
+/// Completes after `remaining` (idle shutdown deadline); never completes when None.
+async fn idle_deadline(remaining: Option<Duration>) {
+    match remaining {
+        Some(d) => tokio::time::sleep(d).await,
+        None => std::future::pending().await,
+    }
+}
+
 /// Future that completes when SIGUSR1 is received (Unix only). When listen is false, never completes.
 async fn sigusr1_fut_optional(listen: bool) {
     if listen {
@@ -1183,10 +1720,18 @@ async fn shutdown_signal() {
 
 /// Interval + jitter: min interval across sources, max jitter; delay = interval ± jitter, at least 1s.
 /// When state_store_fallback_active and degradation.reduced_frequency_multiplier is set, multiplies delay by that factor.
+/// Sources with schedule.cron don't contribute an interval; the delay is capped at the earliest next cron match.
 fn next_delay(config: &Config, state_store_fallback_active: bool) -> Duration {
+    let now = chrono::Utc::now();
+    let cron_delay = config
+        .sources
+        .values()
+        .filter_map(|s| s.schedule.next_cron_delay(now))
+        .min();
     let interval_secs = config
         .sources
         .values()
+        .filter(|s| s.schedule.cron.is_none())
         .map(|s| s.schedule.interval_secs)
         .min()
         .unwrap_or(60);
@@ -1197,7 +1742,10 @@ fn next_delay(config: &Config, state_store_fallback_active: bool) -> Duration {
         .max()
         .unwrap_or(0);
     let delta = if jitter_secs > 0 {
-        rand::random_range(-(jitter_secs as i64)..=(jitter_secs as i64))
+        jitter::random_range(
+            jitter::Stream::Schedule,
+            -(jitter_secs as i64)..=(jitter_secs as i64),
+        )
     } else {
         0
     };
@@ -1208,7 +1756,11 @@ fn next_delay(config: &Config, state_store_fallback_active: bool) -> Duration {
             secs = (secs as f64 * mult).ceil().max(1.0) as u64;
         }
     }
-    Duration::from_secs(secs)
+    let delay = Duration::from_secs(secs);
+    match cron_delay {
+        Some(c) if c < delay => c,
+        _ => delay,
+    }
 }
 
 #[cfg(test)]
@@ -1275,4 +1827,67 @@ sources:
             "when fallback active, delay = interval * reduced_frequency_multiplier"
         );
     }
+
+    #[test]
+    fn test_next_delay_jitter_seed_reproducible() {
+        let dir = std::env::temp_dir().join("hel_next_delay_seed_test");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("helr.yaml");
+        std::fs::write(
+            &path,
+            r#"
+global:
+  jitter_seed: 42
+sources:
+  s1:
+    url: "https://example.com/"
+    schedule:
+      interval_secs: 60
+      jitter_secs: 30
+"#,
+        )
+        .unwrap();
+        let config = Config::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let run = |seed: Option<u64>| {
+            jitter::set_seed(seed);
+            (0..8)
+                .map(|_| next_delay(&config, false).as_secs())
+                .collect::<Vec<_>>()
+        };
+        let first = run(config.global.jitter_seed);
+        let second = run(config.global.jitter_seed);
+        let other = run(Some(7));
+        jitter::set_seed(None);
+        assert_eq!(first, second, "same seed, same delay sequence");
+        assert_ne!(first, other, "different seed, different sequence");
+        assert!(first.iter().all(|s| (30..=90).contains(s)), "{:?}", first);
+    }
+
+    #[test]
+    fn test_next_delay_cron_every_minute_is_sub_minute() {
+        let dir = std::env::temp_dir().join("hel_next_delay_cron_test");
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("helr.yaml");
+        std::fs::write(
+            &path,
+            r#"
+sources:
+  s1:
+    url: "https://example.com/"
+    schedule:
+      interval_secs: 3600
+      cron: "* * * * *"
+"#,
+        )
+        .unwrap();
+        let config = Config::load(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let delay = next_delay(&config, false);
+        assert!(
+            delay <= Duration::from_secs(60),
+            "every-minute cron waits until the next minute boundary, got {:?}",
+            delay
+        );
+    }
 }
@@ -3,7 +3,7 @@
 
 use std::sync::OnceLock;
 
-use prometheus::{Encoder, IntCounterVec, IntGaugeVec, Opts, TextEncoder};
+use prometheus::{Encoder, GaugeVec, IntCounter, IntCounterVec, IntGaugeVec, Opts, TextEncoder};
 
 static METRICS: OnceLock<MetricsInner> = OnceLock::new();
 
@@ -15,7 +15,13 @@ struct MetricsInner {
     events_dropped_total: IntCounterVec,
     pending_events: IntGaugeVec,
     request_duration_seconds: prometheus::HistogramVec,
+    request_latency_ema_seconds: GaugeVec,
     circuit_breaker_state: IntGaugeVec,
+    circuit_state: IntGaugeVec,
+    sources_shed_total: IntCounterVec,
+    disk_buffer_corrupt_total: IntCounter,
+    pagination_loops_total: IntCounterVec,
+    ticks_skipped_total: IntCounterVec,
 }
 
 /// Initialize metrics and register with the default registry. Call once when metrics are enabled.
@@ -53,6 +59,13 @@ pub fn init() -> Result<(), prometheus::Error> {
         .buckets(prometheus::exponential_buckets(0.05, 2.0, 10).unwrap()),
         &["source"],
     )?;
+    let request_latency_ema_seconds = GaugeVec::new(
+        Opts::new(
+            "helr_request_latency_ema_seconds",
+            "Exponential moving average of HTTP request latency in seconds by source (trend for slow sources)",
+        ),
+        &["source"],
+    )?;
     let circuit_breaker_state = IntGaugeVec::new(
         Opts::new(
             "helr_circuit_breaker_state",
@@ -60,6 +73,13 @@ pub fn init() -> Result<(), prometheus::Error> {
         ),
         &["source"],
     )?;
+    let circuit_state = IntGaugeVec::new(
+        Opts::new(
+            "helr_circuit_state",
+            "Circuit breaker state ordered by severity: 0=closed, 1=half_open, 2=open",
+        ),
+        &["source"],
+    )?;
     let events_dropped_total = IntCounterVec::new(
         Opts::new(
             "helr_events_dropped_total",
@@ -74,6 +94,31 @@ pub fn init() -> Result<(), prometheus::Error> {
         ),
         &["source"],
     )?;
+    let sources_shed_total = IntCounterVec::new(
+        Opts::new(
+            "helr_sources_shed_total",
+            "Source polls skipped by load shedding (priority below skip_priority_below while under load)",
+        ),
+        &["source"],
+    )?;
+    let disk_buffer_corrupt_total = IntCounter::new(
+        "helr_disk_buffer_corrupt_total",
+        "Disk buffer lines skipped on drain because they were not valid JSON (e.g. half-written before a crash)",
+    )?;
+    let pagination_loops_total = IntCounterVec::new(
+        Opts::new(
+            "helr_pagination_loops_total",
+            "Cursor pagination stopped because the API returned the same cursor as the current page",
+        ),
+        &["source"],
+    )?;
+    let ticks_skipped_total = IntCounterVec::new(
+        Opts::new(
+            "helr_ticks_skipped_total",
+            "Scheduled polls skipped because the previous poll for the source was still running (schedule.skip_if_running)",
+        ),
+        &["source"],
+    )?;
 
     prometheus::register(Box::new(requests_total.clone()))?;
     prometheus::register(Box::new(events_emitted_total.clone()))?;
@@ -82,7 +127,13 @@ pub fn init() -> Result<(), prometheus::Error> {
     prometheus::register(Box::new(events_dropped_total.clone()))?;
     prometheus::register(Box::new(pending_events.clone()))?;
     prometheus::register(Box::new(request_duration_seconds.clone()))?;
+    prometheus::register(Box::new(request_latency_ema_seconds.clone()))?;
     prometheus::register(Box::new(circuit_breaker_state.clone()))?;
+    prometheus::register(Box::new(circuit_state.clone()))?;
+    prometheus::register(Box::new(sources_shed_total.clone()))?;
+    prometheus::register(Box::new(disk_buffer_corrupt_total.clone()))?;
+    prometheus::register(Box::new(pagination_loops_total.clone()))?;
+    prometheus::register(Box::new(ticks_skipped_total.clone()))?;
 
     let _ = METRICS.set(MetricsInner {
         requests_total,
@@ -92,7 +143,13 @@ pub fn init() -> Result<(), prometheus::Error> {
         events_dropped_total,
         pending_events,
         request_duration_seconds,
+        request_latency_ema_seconds,
         circuit_breaker_state,
+        circuit_state,
+        sources_shed_total,
+        disk_buffer_corrupt_total,
+        pagination_loops_total,
+        ticks_skipped_total,
     });
     Ok(())
 }
@@ -141,6 +198,43 @@ pub fn record_event_dropped(source: &str, reason: &str) {
     }
 }
 
+/// Record one source poll skipped by load shedding.
+pub fn record_source_shed(source: &str) {
+    if let Some(m) = METRICS.get() {
+        m.sources_shed_total.with_label_values(&[source]).inc();
+    }
+}
+
+/// Record one scheduled poll skipped because the source's previous poll was still running.
+pub fn record_tick_skipped(source: &str) {
+    if let Some(m) = METRICS.get() {
+        m.ticks_skipped_total.with_label_values(&[source]).inc();
+    }
+}
+
+/// Record corrupt disk buffer lines skipped on drain.
+pub fn record_disk_buffer_corrupt(count: u64) {
+    if let Some(m) = METRICS.get() {
+        m.disk_buffer_corrupt_total.inc_by(count);
+    }
+}
+
+/// Record one cursor pagination loop (next cursor equal to the current one) that stopped a poll.
+pub fn record_pagination_loop(source: &str) {
+    if let Some(m) = METRICS.get() {
+        m.pagination_loops_total.with_label_values(&[source]).inc();
+    }
+}
+
+/// Set the request latency EMA (seconds) for a source.
+pub fn set_request_latency_ema(source: &str, secs: f64) {
+    if let Some(m) = METRICS.get() {
+        m.request_latency_ema_seconds
+            .with_label_values(&[source])
+            .set(secs);
+    }
+}
+
 /// Set pending (queued) event count for a source. Used by backpressure sink.
 pub fn set_pending_events(source: &str, count: i64) {
     if let Some(m) = METRICS.get() {
@@ -150,15 +244,17 @@ pub fn set_pending_events(source: &str, count: i64) {
     }
 }
 
-/// Set circuit breaker state for a source: "closed" => 0, "open" => 1, "half_open" => 2.
+/// Set circuit breaker state for a source. helr_circuit_breaker_state: "closed" => 0, "open" => 1, "half_open" => 2;
+/// helr_circuit_state (ordered by severity): "closed" => 0, "half_open" => 1, "open" => 2.
 pub fn set_circuit_state(source: &str, state: CircuitStateValue) {
     if let Some(m) = METRICS.get() {
-        let v = match state {
-            CircuitStateValue::Closed => 0,
-            CircuitStateValue::Open => 1,
-            CircuitStateValue::HalfOpen => 2,
+        let (v, severity) = match state {
+            CircuitStateValue::Closed => (0, 0),
+            CircuitStateValue::Open => (1, 2),
+            CircuitStateValue::HalfOpen => (2, 1),
         };
         m.circuit_breaker_state.with_label_values(&[source]).set(v);
+        m.circuit_state.with_label_values(&[source]).set(severity);
     }
 }
 
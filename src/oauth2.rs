@@ -14,8 +14,14 @@ use tokio::sync::RwLock;
 use tracing::debug;
 
 /// Per-source cache: (access_token, expires_at). Refreshed when expired or missing.
+/// Keyed by source id, or by [`token_cache_key`] when sources share a `token_cache_key`.
 pub type OAuth2TokenCache = Arc<RwLock<HashMap<String, (String, Instant)>>>;
 
+/// One lock per cache key so concurrent sources sharing a key make a single token request.
+static FETCH_LOCKS: std::sync::LazyLock<
+    std::sync::Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+> = std::sync::LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
 /// Buffer before expiry to refresh (seconds).
 const REFRESH_BUFFER_SECS: u64 = 60;
 
@@ -23,11 +29,33 @@ pub fn new_oauth2_token_cache() -> OAuth2TokenCache {
     Arc::new(RwLock::new(HashMap::new()))
 }
 
+/// Token cache entry for a source: its OAuth2 `token_cache_key` when set (shared), else the source id.
+pub fn token_cache_key(source_id: &str, auth: Option<&AuthConfig>) -> String {
+    match auth {
+        Some(AuthConfig::OAuth2 {
+            token_cache_key: Some(key),
+            ..
+        }) => format!("shared:{}", key),
+        _ => source_id.to_string(),
+    }
+}
+
 /// Invalidate cached token for a source so the next request triggers a refresh (e.g. after 401).
-pub async fn invalidate_token(cache: &OAuth2TokenCache, source_id: &str) {
+/// `cache_key` is the source id or, for shared tokens, the result of [`token_cache_key`].
+pub async fn invalidate_token(cache: &OAuth2TokenCache, cache_key: &str) {
     let mut g = cache.write().await;
-    g.remove(source_id);
-    debug!(source = %source_id, "oauth2 token invalidated");
+    g.remove(cache_key);
+    debug!(cache_key = %cache_key, "oauth2 token invalidated");
+}
+
+/// Cached token for `cache_key` when it is still valid past the refresh buffer.
+async fn cached_token(cache: &OAuth2TokenCache, cache_key: &str) -> Option<String> {
+    let g = cache.read().await;
+    g.get(cache_key)
+        .filter(|(_, expires_at)| {
+            Instant::now() + Duration::from_secs(REFRESH_BUFFER_SECS) < *expires_at
+        })
+        .map(|(token, _)| token.clone())
 }
 
 /// Returns valid access_token for the source, refreshing if needed. With dpop: true, dpop_key_cache must be Some.
@@ -52,6 +80,10 @@ pub async fn get_oauth_token(
             refresh_token_file,
             scopes,
             dpop,
+            audience,
+            extra_params,
+            token_response_fields,
+            token_cache_key: _,
         } => (
             token_url,
             client_id_env,
@@ -64,6 +96,9 @@ pub async fn get_oauth_token(
             refresh_token_file.as_deref(),
             scopes.as_deref().unwrap_or(&[]),
             *dpop,
+            audience.as_deref().filter(|s| !s.is_empty()),
+            extra_params,
+            token_response_fields.clone().unwrap_or_default(),
         ),
         _ => anyhow::bail!("get_oauth_token requires OAuth2 auth"),
     };
@@ -80,21 +115,29 @@ pub async fn get_oauth_token(
         refresh_token_file,
         scopes,
         dpop,
+        audience,
+        extra_params,
+        response_fields,
     ) = oauth;
 
     let use_private_key_jwt =
         client_private_key_env.is_some() || client_private_key_file.is_some_and(|p| !p.is_empty());
-    let now = Instant::now();
-    let buffer = Duration::from_secs(REFRESH_BUFFER_SECS);
-
-    {
-        let g = cache.read().await;
-        if let Some((token, expires_at)) = g.get(source_id)
-            && now + buffer < *expires_at
-        {
-            return Ok(token.clone());
-        }
+    let cache_key = token_cache_key(source_id, Some(auth));
+    if let Some(token) = cached_token(cache, &cache_key).await {
+        return Ok(token);
     }
+    // Single flight per key: whoever waited on the lock reuses the token the holder just fetched.
+    let fetch_lock = FETCH_LOCKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(cache_key.clone())
+        .or_default()
+        .clone();
+    let _fetching = fetch_lock.lock().await;
+    if let Some(token) = cached_token(cache, &cache_key).await {
+        return Ok(token);
+    }
+    let now = Instant::now();
 
     let client_id = config::read_secret(client_id_file, client_id_env)?;
     crate::audit::log_credential_access(audit, source_id, "oauth2_client_id");
@@ -131,6 +174,12 @@ pub async fn get_oauth_token(
             form.insert("scope".into(), scopes.join(" "));
         }
     }
+    if let Some(aud) = audience {
+        form.insert("audience".into(), aud.to_string());
+    }
+    for (k, v) in extra_params {
+        form.entry(k.clone()).or_insert_with(|| v.clone());
+    }
 
     if use_private_key_jwt {
         let private_key_pem = config::read_secret(
@@ -231,21 +280,11 @@ pub async fn get_oauth_token(
             if !retry_status.is_success() {
                 anyhow::bail!("oauth2 token error {}: {}", retry_status, retry_body);
             }
-            let json: serde_json::Value =
-                serde_json::from_str(&retry_body).context("oauth2 token response json")?;
-            let access_token = json
-                .get("access_token")
-                .and_then(|v| v.as_str())
-                .context("oauth2 response missing access_token")?
-                .to_string();
-            let expires_in = json
-                .get("expires_in")
-                .and_then(|v| v.as_u64())
-                .unwrap_or(3600);
+            let (access_token, expires_in) = parse_token_response(&retry_body, &response_fields)?;
             let expires_at = now + Duration::from_secs(expires_in);
             {
                 let mut g = cache.write().await;
-                g.insert(source_id.to_string(), (access_token.clone(), expires_at));
+                g.insert(cache_key, (access_token.clone(), expires_at));
             }
             debug!(source = %source_id, expires_in, "oauth2 token refreshed (with DPoP nonce)");
             return Ok(access_token);
@@ -261,27 +300,43 @@ pub async fn get_oauth_token(
         anyhow::bail!("oauth2 token error {}: {}", status, body);
     }
 
-    let json: serde_json::Value =
-        serde_json::from_str(&body).context("oauth2 token response json")?;
-    let access_token = json
-        .get("access_token")
-        .and_then(|v| v.as_str())
-        .context("oauth2 response missing access_token")?
-        .to_string();
-    let expires_in = json
-        .get("expires_in")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(3600);
+    let (access_token, expires_in) = parse_token_response(&body, &response_fields)?;
     let expires_at = now + Duration::from_secs(expires_in);
 
     {
         let mut g = cache.write().await;
-        g.insert(source_id.to_string(), (access_token.clone(), expires_at));
+        g.insert(cache_key, (access_token.clone(), expires_at));
     }
     debug!(source = %source_id, expires_in, "oauth2 token refreshed");
     Ok(access_token)
 }
 
+/// Read (access_token, expires_in) from a token response body using the configured keys.
+/// expires_in may be a number or numeric string; defaults to 3600 when absent.
+fn parse_token_response(
+    body: &str,
+    fields: &config::TokenResponseFields,
+) -> anyhow::Result<(String, u64)> {
+    let json: serde_json::Value =
+        serde_json::from_str(body).context("oauth2 token response json")?;
+    let at_path = |path: &str| {
+        path.split('.')
+            .try_fold(&json, |v, seg| v.get(seg))
+            .cloned()
+    };
+    let access_token = at_path(&fields.access_token)
+        .and_then(|v| v.as_str().map(str::to_string))
+        .with_context(|| format!("oauth2 response missing {}", fields.access_token))?;
+    let expires_in = at_path(&fields.expires_in)
+        .and_then(|v| match v {
+            serde_json::Value::Number(n) => n.as_u64(),
+            serde_json::Value::String(s) => s.parse().ok(),
+            _ => None,
+        })
+        .unwrap_or(3600);
+    Ok((access_token, expires_in))
+}
+
 /// Build client_assertion JWT for private_key_jwt (RFC 7523): iss/sub=client_id, aud=token_url, RS256.
 fn build_client_assertion(
     client_id: &str,
@@ -450,3 +505,148 @@ pub async fn get_google_sa_token(
     debug!(source = %source_id, expires_in, "google service account token obtained");
     Ok(access_token)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn get_oauth_token_sends_audience_and_extra_params() {
+        use wiremock::matchers::{body_string_contains, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/oauth/token"))
+            .and(body_string_contains(
+                "audience=https%3A%2F%2Fapi.example.com",
+            ))
+            .and(body_string_contains("resource=logs"))
+            .and(body_string_contains("grant_type=client_credentials"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "tok-aud",
+                "expires_in": 3600
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir = std::env::temp_dir().join("helr_oauth2_audience_test");
+        let _ = std::fs::create_dir_all(&dir);
+        let id_path = dir.join("client_id");
+        let secret_path = dir.join("client_secret");
+        std::fs::write(&id_path, "my-client").unwrap();
+        std::fs::write(&secret_path, "my-secret").unwrap();
+
+        let mut extra_params = HashMap::new();
+        extra_params.insert("resource".to_string(), "logs".to_string());
+        extra_params.insert("grant_type".to_string(), "ignored".to_string());
+        let auth = AuthConfig::OAuth2 {
+            token_url: format!("{}/oauth/token", mock_server.uri()),
+            client_id_env: "HELR_TEST_UNUSED_CLIENT_ID".to_string(),
+            client_id_file: Some(id_path.to_string_lossy().into_owned()),
+            client_secret_env: None,
+            client_secret_file: Some(secret_path.to_string_lossy().into_owned()),
+            client_private_key_env: None,
+            client_private_key_file: None,
+            refresh_token_env: None,
+            refresh_token_file: None,
+            scopes: None,
+            dpop: false,
+            audience: Some("https://api.example.com".to_string()),
+            extra_params,
+            token_response_fields: None,
+            token_cache_key: None,
+        };
+
+        let cache = new_oauth2_token_cache();
+        let client = Client::new();
+        let token = get_oauth_token(&cache, &client, "auth0", &auth, None, None)
+            .await
+            .unwrap();
+        assert_eq!(token, "tok-aud");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn shared_token_cache_key_fetches_one_token_for_two_sources() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/oauth/token"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({
+                        "access_token": "tok-shared",
+                        "expires_in": 3600
+                    }))
+                    .set_delay(Duration::from_millis(100)),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir = std::env::temp_dir().join("helr_oauth2_shared_cache_test");
+        let _ = std::fs::create_dir_all(&dir);
+        let id_path = dir.join("client_id");
+        let secret_path = dir.join("client_secret");
+        std::fs::write(&id_path, "my-client").unwrap();
+        std::fs::write(&secret_path, "my-secret").unwrap();
+
+        let auth = AuthConfig::OAuth2 {
+            token_url: format!("{}/oauth/token", mock_server.uri()),
+            client_id_env: "HELR_TEST_UNUSED_CLIENT_ID".to_string(),
+            client_id_file: Some(id_path.to_string_lossy().into_owned()),
+            client_secret_env: None,
+            client_secret_file: Some(secret_path.to_string_lossy().into_owned()),
+            client_private_key_env: None,
+            client_private_key_file: None,
+            refresh_token_env: None,
+            refresh_token_file: None,
+            scopes: None,
+            dpop: false,
+            audience: None,
+            extra_params: HashMap::new(),
+            token_response_fields: None,
+            token_cache_key: Some("acme-app".to_string()),
+        };
+
+        let cache = new_oauth2_token_cache();
+        let client = Client::new();
+        let (a, b) = tokio::join!(
+            get_oauth_token(&cache, &client, "okta-system-log", &auth, None, None),
+            get_oauth_token(&cache, &client, "okta-users", &auth, None, None),
+        );
+        assert_eq!(a.unwrap(), "tok-shared");
+        assert_eq!(b.unwrap(), "tok-shared");
+        assert_eq!(
+            token_cache_key("okta-users", Some(&auth)),
+            token_cache_key("okta-system-log", Some(&auth))
+        );
+        assert_eq!(token_cache_key("okta-users", None), "okta-users");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn parse_token_response_non_standard_fields() {
+        let fields = config::TokenResponseFields {
+            access_token: "accessToken".to_string(),
+            expires_in: "expiresInSeconds".to_string(),
+        };
+        let body = r#"{"accessToken":"tok-custom","expiresInSeconds":"1800"}"#;
+        let (token, expires_in) = parse_token_response(body, &fields).unwrap();
+        assert_eq!(token, "tok-custom");
+        assert_eq!(expires_in, 1800);
+
+        let defaults = config::TokenResponseFields::default();
+        let err = parse_token_response(body, &defaults).unwrap_err();
+        assert!(err.to_string().contains("access_token"));
+        let (token, expires_in) =
+            parse_token_response(r#"{"access_token":"std","expires_in":60}"#, &defaults).unwrap();
+        assert_eq!((token.as_str(), expires_in), ("std", 60));
+    }
+}
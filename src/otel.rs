@@ -0,0 +1,123 @@
+//! OpenTelemetry trace export (global `otel`): a tracing layer that ships spans (e.g. `poll_one_source`)
+//! to an OTLP/HTTP collector. Requires the `otel` Cargo feature; without it, configs with `otel` are rejected at load.
+
+use crate::config::OtelConfig;
+
+/// Tracing layer exporting spans to the configured collector.
+#[cfg(feature = "otel")]
+pub type OtelLayer = tracing_opentelemetry::OpenTelemetryLayer<
+    tracing_subscriber::Registry,
+    opentelemetry_sdk::trace::SdkTracer,
+>;
+
+#[cfg(not(feature = "otel"))]
+pub type OtelLayer = tracing_subscriber::layer::Identity;
+
+#[cfg(feature = "otel")]
+static PROVIDER: std::sync::OnceLock<opentelemetry_sdk::trace::SdkTracerProvider> =
+    std::sync::OnceLock::new();
+
+/// Check that `global.otel` can be honoured by this build (config validation).
+#[cfg(feature = "otel")]
+pub fn check(_cfg: &OtelConfig) -> anyhow::Result<()> {
+    Ok(())
+}
+
+#[cfg(not(feature = "otel"))]
+pub fn check(_cfg: &OtelConfig) -> anyhow::Result<()> {
+    anyhow::bail!("global.otel requires building with --features otel")
+}
+
+/// Build the layer for `init_logging`; None when `otel` is not configured. Keeps the provider for [`shutdown`].
+#[cfg(feature = "otel")]
+pub fn layer(cfg: Option<&OtelConfig>) -> anyhow::Result<Option<OtelLayer>> {
+    let Some(cfg) = cfg else {
+        return Ok(None);
+    };
+    let (provider, layer) = build(cfg)?;
+    let _ = PROVIDER.set(provider);
+    Ok(Some(layer))
+}
+
+#[cfg(not(feature = "otel"))]
+pub fn layer(_cfg: Option<&OtelConfig>) -> anyhow::Result<Option<OtelLayer>> {
+    Ok(None)
+}
+
+/// Flush pending spans and stop the exporter. Blocking; call once on exit.
+#[cfg(feature = "otel")]
+pub fn shutdown() {
+    if let Some(provider) = PROVIDER.get()
+        && let Err(e) = provider.shutdown()
+    {
+        tracing::warn!(error = %e, "otel shutdown failed");
+    }
+}
+
+#[cfg(not(feature = "otel"))]
+pub fn shutdown() {}
+
+#[cfg(feature = "otel")]
+fn build(
+    cfg: &OtelConfig,
+) -> anyhow::Result<(opentelemetry_sdk::trace::SdkTracerProvider, OtelLayer)> {
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::WithExportConfig;
+
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(cfg.endpoint.as_str())
+        .build()
+        .map_err(|e| anyhow::anyhow!("otel exporter {:?}: {}", cfg.endpoint, e))?;
+    let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(
+            opentelemetry_sdk::Resource::builder()
+                .with_service_name(cfg.service_name.clone())
+                .build(),
+        )
+        .build();
+    let tracer = provider.tracer("helr");
+    Ok((provider, tracing_opentelemetry::layer().with_tracer(tracer)))
+}
+
+#[cfg(all(test, feature = "otel"))]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn otel_exports_spans_to_collector() {
+        use wiremock::matchers::{method, path};
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(method("POST"))
+            .and(path("/v1/traces"))
+            .respond_with(wiremock::ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        let cfg = OtelConfig {
+            endpoint: format!("{}/v1/traces", server.uri()),
+            service_name: "helr-test".to_string(),
+        };
+        let (provider, layer) = tokio::task::spawn_blocking(move || build(&cfg))
+            .await
+            .unwrap()
+            .unwrap();
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("poll_one_source", source_id = "okta").in_scope(|| {
+                tracing::info!("polled");
+            });
+        });
+        tokio::task::spawn_blocking(move || provider.shutdown())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        let body = String::from_utf8_lossy(&requests[0].body);
+        assert!(body.contains("poll_one_source"), "span name not exported");
+        assert!(body.contains("helr-test"), "service.name not exported");
+    }
+}
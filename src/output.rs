@@ -31,6 +31,18 @@ pub trait EventSink: Send + Sync {
     fn flush(&self) -> anyhow::Result<()> {
         Ok(())
     }
+
+    /// Shutdown path when flush did not finish in time: move lines still queued in memory to durable
+    /// storage so they are replayed on next start. Returns how many lines were persisted (0 when the sink has none or nowhere to put them).
+    fn persist_pending(&self) -> anyhow::Result<u64> {
+        Ok(0)
+    }
+
+    /// Take lines this sink accepted but still holds in memory without having passed them on (e.g. a partial
+    /// batch), so a wrapping sink's `persist_pending` can save them for replay. Default: none.
+    fn take_buffered(&self) -> Vec<String> {
+        Vec::new()
+    }
 }
 
 /// Emit to stdout. Handles BrokenPipe (e.g. consumer exited) by returning error so caller can exit non-zero.
@@ -93,6 +105,15 @@ struct FileSinkInner {
     bytes_written: u64,
     rotation: RotationPolicy,
     open_date: Option<chrono::NaiveDate>,
+    /// When true, gzip each rotated segment to `<name>.gz` and remove the plaintext.
+    compress_on_rotate: bool,
+    /// When set, `sync_data` after every N writes (1 = each line is durable before write_line returns).
+    fsync_every: Option<u64>,
+    writes_since_sync: u64,
+    /// When true, write `<rotated>.sha256` and `<rotated>.meta.json` next to each rotated segment.
+    write_manifest: bool,
+    /// Envelope key the manifest's first/last ts are read from (global.envelope_keys.ts, default "ts").
+    ts_key: String,
 }
 
 impl FileSink {
@@ -108,10 +129,47 @@ impl FileSink {
                 bytes_written,
                 rotation,
                 open_date,
+                compress_on_rotate: false,
+                fsync_every: None,
+                writes_since_sync: 0,
+                write_manifest: false,
+                ts_key: "ts".to_string(),
             }),
         })
     }
 
+    /// Gzip rotated segments (the active file stays uncompressed for live tailing).
+    pub fn with_compress_on_rotate(self, compress: bool) -> Self {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.compress_on_rotate = compress;
+        }
+        self
+    }
+
+    /// fsync the file (`sync_data`) after every `every` writes, and on flush and rotation. None disables.
+    pub fn with_fsync(self, every: Option<u64>) -> Self {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.fsync_every = every.map(|n| n.max(1));
+        }
+        self
+    }
+
+    /// Write a SHA-256 checksum and a `.meta.json` manifest (lines, bytes, first/last ts) for each rotated segment.
+    pub fn with_manifest(self, write_manifest: bool) -> Self {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.write_manifest = write_manifest;
+        }
+        self
+    }
+
+    /// Read manifest timestamps under the configured envelope `ts` key.
+    pub fn with_envelope_keys(self, keys: &crate::event::EnvelopeKeys) -> Self {
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.ts_key = keys.ts.clone();
+        }
+        self
+    }
+
     fn open_file(
         path: &std::path::Path,
         rotation: &RotationPolicy,
@@ -142,8 +200,12 @@ impl FileSink {
         }
         if let Some(mut f) = inner.file.take() {
             f.flush()?;
+            if inner.fsync_every.is_some() {
+                f.sync_data()?;
+            }
             drop(f);
         }
+        inner.writes_since_sync = 0;
         let stem = inner
             .path
             .file_stem()
@@ -162,6 +224,31 @@ impl FileSink {
         let rotated = parent.join(format!("{}.{}.{}", stem, suffix, ext));
         if inner.path.exists() {
             std::fs::rename(&inner.path, &rotated)?;
+            let stats = if inner.write_manifest {
+                match segment_stats(&rotated, &inner.ts_key) {
+                    Ok(stats) => Some(stats),
+                    Err(e) => {
+                        tracing::warn!(path = %rotated.display(), error = %e, "read rotated output for manifest failed");
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+            let mut archived = rotated.clone();
+            if inner.compress_on_rotate {
+                match gzip_file(&rotated) {
+                    Ok(gz) => archived = gz,
+                    Err(e) => {
+                        tracing::warn!(path = %rotated.display(), error = %e, "gzip rotated output failed, keeping plaintext")
+                    }
+                }
+            }
+            if let Some(stats) = stats
+                && let Err(e) = write_manifest(&archived, &stats)
+            {
+                tracing::warn!(path = %archived.display(), error = %e, "write rotated output manifest failed");
+            }
         }
         let (file, open_date) = Self::open_file(&inner.path, &inner.rotation)?;
         inner.file = Some(file);
@@ -171,6 +258,89 @@ impl FileSink {
     }
 }
 
+/// Line and byte counts and first/last envelope timestamp of a rotated (plaintext) segment.
+struct SegmentStats {
+    lines: u64,
+    bytes: u64,
+    first_ts: Option<String>,
+    last_ts: Option<String>,
+}
+
+fn segment_stats(path: &Path, ts_key: &str) -> anyhow::Result<SegmentStats> {
+    use std::io::BufRead;
+
+    let mut stats = SegmentStats {
+        lines: 0,
+        bytes: 0,
+        first_ts: None,
+        last_ts: None,
+    };
+    let mut reader = std::io::BufReader::new(std::fs::File::open(path)?);
+    let mut line = String::new();
+    loop {
+        line.clear();
+        let n = reader.read_line(&mut line)?;
+        if n == 0 {
+            break;
+        }
+        stats.bytes += n as u64;
+        stats.lines += 1;
+        let ts = serde_json::from_str::<serde_json::Value>(line.trim_end())
+            .ok()
+            .and_then(|v| v.get(ts_key).and_then(|t| t.as_str()).map(String::from));
+        if let Some(ts) = ts {
+            if stats.first_ts.is_none() {
+                stats.first_ts = Some(ts.clone());
+            }
+            stats.last_ts = Some(ts);
+        }
+    }
+    Ok(stats)
+}
+
+/// Write `<archived>.sha256` (sha256sum format, checksum of the archived file as stored, e.g. the `.gz`)
+/// and `<archived>.meta.json` (checksum plus the plaintext segment's lines, bytes and first/last ts).
+fn write_manifest(archived: &Path, stats: &SegmentStats) -> anyhow::Result<()> {
+    use sha2::Digest;
+
+    let mut hasher = sha2::Sha256::new();
+    std::io::copy(&mut std::fs::File::open(archived)?, &mut hasher)?;
+    let sha256 = format!("{:x}", hasher.finalize());
+    let file_name = archived
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    std::fs::write(
+        format!("{}.sha256", archived.display()),
+        format!("{}  {}\n", sha256, file_name),
+    )?;
+    let meta = serde_json::json!({
+        "file": file_name,
+        "sha256": sha256,
+        "lines": stats.lines,
+        "bytes": stats.bytes,
+        "first_ts": stats.first_ts,
+        "last_ts": stats.last_ts,
+    });
+    std::fs::write(
+        format!("{}.meta.json", archived.display()),
+        serde_json::to_vec_pretty(&meta)?,
+    )?;
+    Ok(())
+}
+
+/// Gzip `path` into `<path>.gz` and remove the plaintext on success.
+fn gzip_file(path: &Path) -> anyhow::Result<PathBuf> {
+    let gz_path = PathBuf::from(format!("{}.gz", path.display()));
+    let mut input = std::fs::File::open(path)?;
+    let output = std::fs::File::create(&gz_path)?;
+    let mut encoder = flate2::write::GzEncoder::new(output, flate2::Compression::default());
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    std::fs::remove_file(path)?;
+    Ok(gz_path)
+}
+
 impl EventSink for FileSink {
     fn write_line_from_source(&self, source: Option<&str>, line: &str) -> anyhow::Result<()> {
         let _ = source;
@@ -183,10 +353,18 @@ impl EventSink for FileSink {
             .lock()
             .map_err(|e| anyhow::anyhow!("lock: {}", e))?;
         self.maybe_rotate(&mut inner)?;
+        let inner = &mut *inner;
         if let Some(ref mut f) = inner.file {
             let buf = format!("{}\n", line);
             f.write_all(buf.as_bytes())?;
             inner.bytes_written += buf.len() as u64;
+            if let Some(every) = inner.fsync_every {
+                inner.writes_since_sync += 1;
+                if inner.writes_since_sync >= every {
+                    f.sync_data()?;
+                    inner.writes_since_sync = 0;
+                }
+            }
         }
         Ok(())
     }
@@ -196,8 +374,13 @@ impl EventSink for FileSink {
             .inner
             .lock()
             .map_err(|e| anyhow::anyhow!("lock: {}", e))?;
+        let inner = &mut *inner;
         if let Some(ref mut f) = inner.file {
             f.flush()?;
+            if inner.fsync_every.is_some() && inner.writes_since_sync > 0 {
+                f.sync_data()?;
+                inner.writes_since_sync = 0;
+            }
         }
         Ok(())
     }
@@ -222,6 +405,10 @@ struct BackpressureState {
     strategy: BackpressureStrategyConfig,
     drop_policy: DropPolicyConfig,
     pending_per_source: HashMap<String, i64>,
+    /// Queue depth at which `under_load` is set.
+    high_watermark: usize,
+    /// Queue depth below which `under_load` is cleared.
+    low_watermark: usize,
     memory_threshold_mb: Option<u64>,
     max_queue_age_secs: Option<u64>,
     disk_buffer_path: Option<PathBuf>,
@@ -247,6 +434,19 @@ struct BackpressureInner {
     disk_buffer_mutex: Option<Arc<Mutex<()>>>,
     /// When set, set to true when queue full or memory over; cleared when queue drains below threshold. Used for load shedding (skip low-priority sources).
     under_load: Option<Arc<AtomicBool>>,
+    /// Set once the queue was persisted at shutdown: the disk buffer is kept for the next start instead of drained.
+    keep_disk_buffer: AtomicBool,
+}
+
+/// Next `under_load` value for queue `depth`: engages at `high`, disengages below `low`, otherwise unchanged (hysteresis).
+fn under_load_after(active: bool, depth: usize, high: usize, low: usize) -> bool {
+    if depth >= high {
+        true
+    } else if depth < low {
+        false
+    } else {
+        active
+    }
 }
 
 /// Path for the "previous full segment" when segmenting is used (e.g. spill.ndjson -> spill.ndjson.old).
@@ -258,33 +458,62 @@ fn drain_disk_buffer(path: &Path, file_lock: &Mutex<()>) -> Vec<String> {
     let _guard = file_lock.lock().unwrap();
     let old_path = disk_buffer_old_path(path);
     let mut lines = Vec::new();
+    let mut corrupt = 0u64;
     if old_path.exists()
         && let Ok(file) = std::fs::File::open(&old_path)
     {
-        let reader = BufReader::new(file);
-        for line in reader.lines().map_while(Result::ok) {
-            if !line.trim().is_empty() {
-                lines.push(line);
-            }
-        }
+        read_disk_buffer_lines(file, &mut lines, &mut corrupt);
         let _ = std::fs::remove_file(&old_path);
     }
     if path.exists() {
         if let Ok(file) = std::fs::File::open(path) {
-            let reader = BufReader::new(file);
-            for line in reader.lines().map_while(Result::ok) {
-                if !line.trim().is_empty() {
-                    lines.push(line);
-                }
-            }
+            read_disk_buffer_lines(file, &mut lines, &mut corrupt);
         }
         if let Ok(f) = std::fs::File::create(path) {
             let _ = f.set_len(0);
         }
     }
+    if corrupt > 0 {
+        tracing::warn!(
+            path = %path.display(),
+            corrupt,
+            "disk buffer: skipped lines that are not valid JSON (e.g. half-written before a crash)"
+        );
+        metrics::record_disk_buffer_corrupt(corrupt);
+    }
     lines
 }
 
+/// Append non-empty lines that parse as JSON; count the rest (including invalid UTF-8) as corrupt.
+fn read_disk_buffer_lines(file: std::fs::File, lines: &mut Vec<String>, corrupt: &mut u64) {
+    let mut reader = BufReader::new(file);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        match reader.read_until(b'\n', &mut buf) {
+            Ok(0) => break,
+            Ok(_) => {}
+            Err(_) => {
+                *corrupt += 1;
+                break;
+            }
+        }
+        let Ok(line) = std::str::from_utf8(&buf) else {
+            *corrupt += 1;
+            continue;
+        };
+        let line = line.trim_end_matches(['\n', '\r']);
+        if line.trim().is_empty() {
+            continue;
+        }
+        if serde_json::from_str::<serde::de::IgnoredAny>(line).is_ok() {
+            lines.push(line.to_string());
+        } else {
+            *corrupt += 1;
+        }
+    }
+}
+
 /// Total size in bytes of disk buffer (path + path.old).
 fn disk_buffer_total_size(path: &Path) -> u64 {
     let old_path = disk_buffer_old_path(path);
@@ -302,7 +531,9 @@ fn backpressure_writer_loop(inner: Arc<dyn EventSink>, shared: Arc<BackpressureI
                     let path = guard.disk_buffer_path.clone();
                     let file_lock = shared.disk_buffer_mutex.clone();
                     drop(guard);
-                    if let (Some(p), Some(lock)) = (path, file_lock) {
+                    if let (Some(p), Some(lock)) = (path, file_lock)
+                        && !shared.keep_disk_buffer.load(Ordering::SeqCst)
+                    {
                         for l in drain_disk_buffer(&p, &lock) {
                             let _ = inner.write_line(&l);
                         }
@@ -328,9 +559,14 @@ fn backpressure_writer_loop(inner: Arc<dyn EventSink>, shared: Arc<BackpressureI
                 && shared.disk_buffer_mutex.is_some())
             .then(|| guard.disk_buffer_path.clone())
             .flatten();
-            let queue_below_threshold = guard.queue.len() < guard.cap.saturating_mul(3) / 4;
-            if queue_below_threshold && let Some(ref flag) = shared.under_load {
-                flag.store(false, Ordering::Relaxed);
+            if let Some(ref flag) = shared.under_load {
+                let active = under_load_after(
+                    flag.load(Ordering::Relaxed),
+                    guard.queue.len(),
+                    guard.high_watermark,
+                    guard.low_watermark,
+                );
+                flag.store(active, Ordering::Relaxed);
             }
             shared.not_full.notify_one();
             drop(guard);
@@ -344,6 +580,7 @@ fn backpressure_writer_loop(inner: Arc<dyn EventSink>, shared: Arc<BackpressureI
         }
         if let Some(path) = drain_disk
             && let Some(ref lock) = shared.disk_buffer_mutex
+            && !shared.keep_disk_buffer.load(Ordering::SeqCst)
         {
             let lines = drain_disk_buffer(&path, lock);
             for l in &lines {
@@ -370,7 +607,8 @@ pub struct BackpressureSink {
 
 impl BackpressureSink {
     /// Build a backpressure sink wrapping `inner`. Uses config detection, strategy, disk_buffer, and age/memory limits.
-    /// When `under_load` is provided, it is set to true when queue is full or memory over threshold, and cleared when queue drains below 75% of cap (for load shedding).
+    /// When `under_load` is provided, it is set to true when the queue reaches `detection.high_watermark` (default: full) or memory is over threshold,
+    /// and cleared when the queue drains below `detection.low_watermark` (default: 75% of cap), for load shedding.
     pub fn new(
         inner: Arc<dyn EventSink>,
         config: &BackpressureConfig,
@@ -380,6 +618,24 @@ impl BackpressureSink {
         if cap == 0 {
             anyhow::bail!("backpressure.detection.event_queue_size must be > 0");
         }
+        let high_watermark = config.detection.high_watermark.unwrap_or(cap);
+        let low_watermark = config
+            .detection
+            .low_watermark
+            .unwrap_or(cap.saturating_mul(3) / 4);
+        if high_watermark == 0 || high_watermark > cap {
+            anyhow::bail!(
+                "backpressure.detection.high_watermark must be between 1 and event_queue_size ({})",
+                cap
+            );
+        }
+        if low_watermark > high_watermark {
+            anyhow::bail!(
+                "backpressure.detection.low_watermark ({}) must not exceed high_watermark ({})",
+                low_watermark,
+                high_watermark
+            );
+        }
         let (disk_buffer_path, disk_buffer_mutex, disk_max_bytes, disk_segment_bytes) =
             match (&config.strategy, &config.disk_buffer) {
                 (BackpressureStrategyConfig::DiskBuffer, Some(d)) => {
@@ -402,6 +658,8 @@ impl BackpressureSink {
                 strategy: config.strategy,
                 drop_policy: config.drop_policy,
                 pending_per_source: HashMap::new(),
+                high_watermark,
+                low_watermark,
                 memory_threshold_mb: config.detection.memory_threshold_mb,
                 max_queue_age_secs: config.max_queue_age_secs,
                 disk_buffer_path: disk_buffer_path.clone(),
@@ -416,6 +674,7 @@ impl BackpressureSink {
             closed: AtomicBool::new(false),
             disk_buffer_mutex,
             under_load,
+            keep_disk_buffer: AtomicBool::new(false),
         });
         let inner_clone = inner.clone();
         let shared_clone = shared.clone();
@@ -471,7 +730,8 @@ impl BackpressureSink {
                 .saturating_add(line_owned.len() as u64)
                 > guard.stdout_buffer_size;
 
-        let is_under_load = guard.queue.len() >= guard.cap || over_memory || over_stdout_bytes;
+        let is_under_load =
+            guard.queue.len() >= guard.high_watermark || over_memory || over_stdout_bytes;
         if is_under_load && let Some(ref flag) = shared.under_load {
             flag.store(true, Ordering::Relaxed);
         }
@@ -593,6 +853,47 @@ impl EventSink for BackpressureSink {
         drop(guard);
         self.inner.flush()
     }
+
+    /// Append the in-memory queue to the disk buffer (disk_buffer strategy only) and keep the buffer for replay on next start.
+    /// Lines the inner sink still holds (`take_buffered`, e.g. a partial JSON array batch) go first, as they are older.
+    /// A line the writer is already writing is not included.
+    fn persist_pending(&self) -> anyhow::Result<u64> {
+        let (Some(path), Some(lock)) = (
+            self.shared.state.lock().unwrap().disk_buffer_path.clone(),
+            self.shared.disk_buffer_mutex.clone(),
+        ) else {
+            return Ok(0);
+        };
+        let pending: Vec<String> = {
+            let mut guard = self.shared.state.lock().unwrap();
+            guard.total_queued_bytes = 0;
+            for (s, count) in guard.pending_per_source.iter_mut() {
+                *count = 0;
+                metrics::set_pending_events(s, 0);
+            }
+            self.shared.keep_disk_buffer.store(true, Ordering::SeqCst);
+            let queued: Vec<String> = guard.queue.drain(..).map(|(_, line, _)| line).collect();
+            drop(guard);
+            let mut pending = self.inner.take_buffered();
+            pending.extend(queued);
+            pending
+        };
+        self.shared.not_full.notify_all();
+        self.shared.empty_for_flush.notify_all();
+        if pending.is_empty() {
+            return Ok(0);
+        }
+        let _file_guard = lock.lock().unwrap();
+        let mut f = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)?;
+        for line in &pending {
+            writeln!(f, "{}", line)?;
+        }
+        f.sync_all()?;
+        Ok(pending.len() as u64)
+    }
 }
 
 impl Drop for BackpressureSink {
@@ -600,8 +901,303 @@ impl Drop for BackpressureSink {
         self.shared.closed.store(true, Ordering::SeqCst);
         self.shared.not_empty.notify_one();
         if let Some(h) = self.writer_handle.take() {
-            let _ = h.join();
+            // After persist_pending the writer may still be blocked on a stalled inner sink; don't wait for it.
+            if !self.shared.keep_disk_buffer.load(Ordering::SeqCst) {
+                let _ = h.join();
+            }
+        }
+    }
+}
+
+// --- Ring buffer (tail) sink ---
+
+/// Wraps another sink and keeps the last `capacity` lines written through it in memory, for GET /tail.
+pub struct RingBufferSink {
+    inner: Arc<dyn EventSink>,
+    capacity: usize,
+    lines: Mutex<VecDeque<String>>,
+}
+
+impl RingBufferSink {
+    pub fn new(inner: Arc<dyn EventSink>, capacity: usize) -> Self {
+        Self {
+            inner,
+            capacity,
+            lines: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// The most recent `n` retained lines (all of them when `n` is None), oldest first.
+    pub fn tail(&self, n: Option<usize>) -> Vec<String> {
+        let lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        let skip = n.map_or(0, |n| lines.len().saturating_sub(n));
+        lines.iter().skip(skip).cloned().collect()
+    }
+
+    fn retain(&self, line: &str) {
+        let mut lines = self.lines.lock().unwrap_or_else(|e| e.into_inner());
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line.to_string());
+    }
+}
+
+impl EventSink for RingBufferSink {
+    fn write_line(&self, line: &str) -> anyhow::Result<()> {
+        self.write_line_from_source(None, line)
+    }
+
+    fn write_line_from_source(&self, source: Option<&str>, line: &str) -> anyhow::Result<()> {
+        self.inner.write_line_from_source(source, line)?;
+        self.retain(line);
+        Ok(())
+    }
+
+    fn flush(&self) -> anyhow::Result<()> {
+        self.inner.flush()
+    }
+
+    fn persist_pending(&self) -> anyhow::Result<u64> {
+        self.inner.persist_pending()
+    }
+}
+
+// --- CSV sink ---
+
+/// Wraps another sink and rewrites each NDJSON line as a CSV row of the configured columns.
+/// The header row (the column paths) is written before the first row. Missing fields are empty cells;
+/// objects and arrays are written as compact JSON. Lines that are not valid JSON are logged and dropped.
+pub struct CsvSink {
+    inner: Arc<dyn EventSink>,
+    columns: Vec<String>,
+    header_written: Mutex<bool>,
+}
+
+impl CsvSink {
+    pub fn new(inner: Arc<dyn EventSink>, columns: Vec<String>) -> anyhow::Result<Self> {
+        if columns.is_empty() {
+            anyhow::bail!("output.csv.columns must not be empty");
+        }
+        Ok(Self {
+            inner,
+            columns,
+            header_written: Mutex::new(false),
+        })
+    }
+
+    /// CSV row for one NDJSON line, or None when the line is not valid JSON.
+    fn row(&self, line: &str) -> Option<String> {
+        let value = serde_json::from_str::<serde_json::Value>(line).ok()?;
+        let cells: Vec<String> = self
+            .columns
+            .iter()
+            .map(|path| {
+                let mut v = &value;
+                for segment in path.split('.') {
+                    match v.get(segment) {
+                        Some(next) => v = next,
+                        None => return String::new(),
+                    }
+                }
+                match v {
+                    serde_json::Value::Null => String::new(),
+                    serde_json::Value::String(s) => csv_field(s),
+                    other => csv_field(&other.to_string()),
+                }
+            })
+            .collect();
+        Some(cells.join(","))
+    }
+}
+
+/// Quote a CSV field when it contains a comma, quote, or line break (RFC 4180); inner quotes are doubled.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+impl EventSink for CsvSink {
+    fn write_line(&self, line: &str) -> anyhow::Result<()> {
+        self.write_line_from_source(None, line)
+    }
+
+    fn write_line_from_source(&self, source: Option<&str>, line: &str) -> anyhow::Result<()> {
+        let Some(row) = self.row(line) else {
+            tracing::warn!(
+                source = source.unwrap_or("unknown"),
+                line,
+                "csv sink: line is not valid JSON, dropping"
+            );
+            metrics::record_event_dropped(source.unwrap_or("unknown"), "invalid_json");
+            return Ok(());
+        };
+        let mut header_written = self
+            .header_written
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if !*header_written {
+            let header: Vec<String> = self.columns.iter().map(|c| csv_field(c)).collect();
+            self.inner
+                .write_line_from_source(source, &header.join(","))?;
+            *header_written = true;
+        }
+        self.inner.write_line_from_source(source, &row)
+    }
+
+    fn flush(&self) -> anyhow::Result<()> {
+        self.inner.flush()
+    }
+
+    fn persist_pending(&self) -> anyhow::Result<u64> {
+        self.inner.persist_pending()
+    }
+}
+
+// --- JSON array batching sink ---
+
+/// Wraps another sink and buffers lines per source, writing each `batch_size` of them as one JSON array line
+/// (`[line1,line2,...]`) labelled with that source. A partial batch is written on flush, or by a background
+/// thread once its oldest line is `flush_interval` old.
+pub struct BatchArraySink {
+    shared: Arc<BatchArrayShared>,
+}
+
+struct BatchArrayShared {
+    inner: Arc<dyn EventSink>,
+    batch_size: usize,
+    /// Partial batch per source label.
+    buffers: Mutex<HashMap<Option<String>, PartialBatch>>,
+}
+
+struct PartialBatch {
+    /// When the first line arrived.
+    since: Instant,
+    lines: Vec<String>,
+}
+
+impl BatchArraySink {
+    /// `flush_interval`: max time a partial batch waits before it is written (None: only on size or flush).
+    pub fn new(
+        inner: Arc<dyn EventSink>,
+        batch_size: usize,
+        flush_interval: Option<std::time::Duration>,
+    ) -> anyhow::Result<Self> {
+        if batch_size == 0 {
+            anyhow::bail!("output.batch_array.batch_size must be > 0");
+        }
+        let shared = Arc::new(BatchArrayShared {
+            inner,
+            batch_size,
+            buffers: Mutex::new(HashMap::new()),
+        });
+        if let Some(interval) = flush_interval.filter(|d| !d.is_zero()) {
+            let weak = Arc::downgrade(&shared);
+            let tick = interval.min(std::time::Duration::from_secs(1));
+            thread::Builder::new()
+                .name("helr-batch-array-flush".into())
+                .spawn(move || {
+                    loop {
+                        thread::sleep(tick);
+                        let Some(shared) = weak.upgrade() else {
+                            break;
+                        };
+                        if let Err(e) = shared.write_due(interval) {
+                            tracing::warn!(error = %e, "batch_array: interval flush failed");
+                        }
+                    }
+                })?;
+        }
+        Ok(Self { shared })
+    }
+}
+
+impl BatchArrayShared {
+    fn write_batch(&self, source: Option<&str>, lines: &[String]) -> anyhow::Result<()> {
+        if lines.is_empty() {
+            return Ok(());
+        }
+        self.inner
+            .write_line_from_source(source, &format!("[{}]", lines.join(",")))
+    }
+
+    /// Write the partial batches whose first line is at least `max_age` old.
+    fn write_due(&self, max_age: std::time::Duration) -> anyhow::Result<()> {
+        let due: Vec<(Option<String>, Vec<String>)> = {
+            let mut buffers = self.buffers.lock().unwrap_or_else(|e| e.into_inner());
+            let keys: Vec<Option<String>> = buffers
+                .iter()
+                .filter(|(_, b)| b.since.elapsed() >= max_age)
+                .map(|(k, _)| k.clone())
+                .collect();
+            keys.into_iter()
+                .filter_map(|k| buffers.remove(&k).map(|b| (k, b.lines)))
+                .collect()
+        };
+        for (source, lines) in due {
+            self.write_batch(source.as_deref(), &lines)?;
+        }
+        Ok(())
+    }
+
+    fn take_all(&self) -> Vec<(Option<String>, Vec<String>)> {
+        let mut buffers = self.buffers.lock().unwrap_or_else(|e| e.into_inner());
+        let mut all: Vec<(Option<String>, PartialBatch)> = buffers.drain().collect();
+        all.sort_by_key(|(_, b)| b.since);
+        all.into_iter().map(|(k, b)| (k, b.lines)).collect()
+    }
+}
+
+impl EventSink for BatchArraySink {
+    fn write_line(&self, line: &str) -> anyhow::Result<()> {
+        self.write_line_from_source(None, line)
+    }
+
+    fn write_line_from_source(&self, source: Option<&str>, line: &str) -> anyhow::Result<()> {
+        let full = {
+            let mut buffers = self
+                .shared
+                .buffers
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            let key = source.map(str::to_string);
+            let batch = buffers.entry(key.clone()).or_insert_with(|| PartialBatch {
+                since: Instant::now(),
+                lines: Vec::with_capacity(self.shared.batch_size),
+            });
+            batch.lines.push(line.to_string());
+            if batch.lines.len() < self.shared.batch_size {
+                return Ok(());
+            }
+            buffers.remove(&key).map(|b| b.lines).unwrap_or_default()
+        };
+        self.shared.write_batch(source, &full)
+    }
+
+    fn flush(&self) -> anyhow::Result<()> {
+        for (source, lines) in self.shared.take_all() {
+            self.shared.write_batch(source.as_deref(), &lines)?;
         }
+        self.shared.inner.flush()
+    }
+
+    fn persist_pending(&self) -> anyhow::Result<u64> {
+        self.shared.inner.persist_pending()
+    }
+
+    /// The partial batches' lines (oldest batch first), followed by whatever the inner sink holds.
+    fn take_buffered(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .shared
+            .take_all()
+            .into_iter()
+            .flat_map(|(_, lines)| lines)
+            .collect();
+        lines.extend(self.shared.inner.take_buffered());
+        lines
     }
 }
 
@@ -622,57 +1218,89 @@ enum HttpSinkMsg {
     Flush(tokio::sync::oneshot::Sender<()>),
 }
 
+/// Batching and retry knobs shared by the HTTP and Loki sinks.
+struct BatchPostOptions {
+    batch_size: usize,
+    batch_timeout: std::time::Duration,
+    max_retries: u32,
+    /// Sink name for `helr_output_errors_total` (e.g. "http_sink").
+    name: &'static str,
+    /// Prefix for log messages (e.g. "http sink").
+    log_prefix: &'static str,
+}
+
 impl HttpSink {
     /// Spawn the background worker on the current tokio runtime.
     pub fn new(url: String, config: &crate::config::HttpOutputConfig) -> anyhow::Result<Self> {
-        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
-        let batch_size = config.batch_size;
-        let batch_timeout = std::time::Duration::from_millis(config.batch_timeout_ms);
-        let max_retries = config.max_retries;
-        let timeout = std::time::Duration::from_secs(config.timeout_secs);
-
-        let mut default_headers = reqwest::header::HeaderMap::new();
-        for (k, v) in &config.headers {
-            let name = reqwest::header::HeaderName::from_bytes(k.as_bytes())
-                .map_err(|e| anyhow::anyhow!("invalid header name '{k}': {e}"))?;
-            let val = reqwest::header::HeaderValue::from_str(v)
-                .map_err(|e| anyhow::anyhow!("invalid header value for '{k}': {e}"))?;
-            default_headers.insert(name, val);
-        }
-        if !default_headers.contains_key(reqwest::header::CONTENT_TYPE) {
-            default_headers.insert(
-                reqwest::header::CONTENT_TYPE,
-                reqwest::header::HeaderValue::from_static("application/x-ndjson"),
-            );
-        }
-
-        let client = reqwest::Client::builder()
-            .timeout(timeout)
-            .default_headers(default_headers)
-            .build()?;
+        let client =
+            batch_post_client(&config.headers, "application/x-ndjson", config.timeout_secs)?;
+        let options = BatchPostOptions {
+            batch_size: config.batch_size,
+            batch_timeout: std::time::Duration::from_millis(config.batch_timeout_ms),
+            max_retries: config.max_retries,
+            name: "http_sink",
+            log_prefix: "http sink",
+        };
+        Ok(Self::spawn(client, url, options, encode_ndjson_batch))
+    }
 
+    fn spawn<F>(client: reqwest::Client, url: String, options: BatchPostOptions, encode: F) -> Self
+    where
+        F: Fn(&[String]) -> String + Send + Sync + 'static,
+    {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
         let rt = tokio::runtime::Handle::current();
-        rt.spawn(http_sink_worker(
-            rx,
-            client,
-            url,
-            batch_size,
-            batch_timeout,
-            max_retries,
-        ));
+        rt.spawn(http_sink_worker(rx, client, url, options, encode));
+        Self { tx, rt }
+    }
+}
+
+/// Client with default headers; `content_type` is added unless the headers override Content-Type.
+fn batch_post_client(
+    headers: &HashMap<String, String>,
+    content_type: &'static str,
+    timeout_secs: u64,
+) -> anyhow::Result<reqwest::Client> {
+    let mut default_headers = reqwest::header::HeaderMap::new();
+    for (k, v) in headers {
+        let name = reqwest::header::HeaderName::from_bytes(k.as_bytes())
+            .map_err(|e| anyhow::anyhow!("invalid header name '{k}': {e}"))?;
+        let val = reqwest::header::HeaderValue::from_str(v)
+            .map_err(|e| anyhow::anyhow!("invalid header value for '{k}': {e}"))?;
+        default_headers.insert(name, val);
+    }
+    if !default_headers.contains_key(reqwest::header::CONTENT_TYPE) {
+        default_headers.insert(
+            reqwest::header::CONTENT_TYPE,
+            reqwest::header::HeaderValue::from_static(content_type),
+        );
+    }
+    Ok(reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(timeout_secs))
+        .default_headers(default_headers)
+        .build()?)
+}
 
-        Ok(Self { tx, rt })
+fn encode_ndjson_batch(lines: &[String]) -> String {
+    let mut body = String::with_capacity(lines.iter().map(|l| l.len() + 1).sum());
+    for line in lines {
+        body.push_str(line);
+        body.push('\n');
     }
+    body
 }
 
-async fn http_sink_worker(
+async fn http_sink_worker<F>(
     mut rx: tokio::sync::mpsc::UnboundedReceiver<HttpSinkMsg>,
     client: reqwest::Client,
     url: String,
-    batch_size: usize,
-    batch_timeout: std::time::Duration,
-    max_retries: u32,
-) {
+    options: BatchPostOptions,
+    encode: F,
+) where
+    F: Fn(&[String]) -> String,
+{
+    let batch_size = options.batch_size;
+    let batch_timeout = options.batch_timeout;
     let mut buf: Vec<String> = Vec::with_capacity(batch_size);
     let mut flush_waiters: Vec<tokio::sync::oneshot::Sender<()>> = Vec::new();
     let sleep = tokio::time::sleep(batch_timeout);
@@ -691,14 +1319,14 @@ async fn http_sink_worker(
                             timer_active = true;
                         }
                         if buf.len() >= batch_size {
-                            http_sink_flush_batch(&client, &url, &mut buf, max_retries).await;
+                            http_sink_flush_batch(&client, &url, &mut buf, &options, &encode).await;
                             timer_active = false;
                             for w in flush_waiters.drain(..) { let _ = w.send(()); }
                         }
                     }
                     Some(HttpSinkMsg::Flush(waiter)) => {
                         if !buf.is_empty() {
-                            http_sink_flush_batch(&client, &url, &mut buf, max_retries).await;
+                            http_sink_flush_batch(&client, &url, &mut buf, &options, &encode).await;
                             timer_active = false;
                         }
                         let _ = waiter.send(());
@@ -706,7 +1334,7 @@ async fn http_sink_worker(
                     }
                     None => {
                         if !buf.is_empty() {
-                            http_sink_flush_batch(&client, &url, &mut buf, max_retries).await;
+                            http_sink_flush_batch(&client, &url, &mut buf, &options, &encode).await;
                         }
                         for w in flush_waiters.drain(..) { let _ = w.send(()); }
                         return;
@@ -715,7 +1343,7 @@ async fn http_sink_worker(
             }
             () = &mut sleep, if timer_active => {
                 if !buf.is_empty() {
-                    http_sink_flush_batch(&client, &url, &mut buf, max_retries).await;
+                    http_sink_flush_batch(&client, &url, &mut buf, &options, &encode).await;
                 }
                 timer_active = false;
                 for w in flush_waiters.drain(..) { let _ = w.send(()); }
@@ -724,25 +1352,27 @@ async fn http_sink_worker(
     }
 }
 
-async fn http_sink_flush_batch(
+async fn http_sink_flush_batch<F>(
     client: &reqwest::Client,
     url: &str,
     buf: &mut Vec<String>,
-    max_retries: u32,
-) {
-    let mut body = String::with_capacity(buf.iter().map(|l| l.len() + 1).sum());
-    for line in buf.iter() {
-        body.push_str(line);
-        body.push('\n');
-    }
+    options: &BatchPostOptions,
+    encode: &F,
+) where
+    F: Fn(&[String]) -> String,
+{
+    let body = encode(buf);
     let count = buf.len();
     buf.clear();
+    let max_retries = options.max_retries;
+    let sink = options.name;
+    let log_prefix = options.log_prefix;
 
     let mut attempt = 0u32;
     loop {
         match client.post(url).body(body.clone()).send().await {
             Ok(resp) if resp.status().is_success() => {
-                tracing::debug!(lines = count, status = %resp.status(), "http sink: batch sent");
+                tracing::debug!(lines = count, status = %resp.status(), "{log_prefix}: batch sent");
                 return;
             }
             Ok(resp) if resp.status().is_server_error() && attempt < max_retries => {
@@ -750,16 +1380,16 @@ async fn http_sink_flush_batch(
                 let delay = std::time::Duration::from_millis(100 * 2u64.pow(attempt));
                 tracing::warn!(
                     status = %resp.status(), attempt, max_retries,
-                    "http sink: retryable error, backing off {delay:?}",
+                    "{log_prefix}: retryable error, backing off {delay:?}",
                 );
                 tokio::time::sleep(delay).await;
             }
             Ok(resp) => {
                 tracing::error!(
                     status = %resp.status(), lines = count,
-                    "http sink: non-retryable response, dropping batch",
+                    "{log_prefix}: non-retryable response, dropping batch",
                 );
-                metrics::record_output_error("http_sink");
+                metrics::record_output_error(sink);
                 return;
             }
             Err(e) if (e.is_timeout() || e.is_connect()) && attempt < max_retries => {
@@ -767,13 +1397,13 @@ async fn http_sink_flush_batch(
                 let delay = std::time::Duration::from_millis(100 * 2u64.pow(attempt));
                 tracing::warn!(
                     error = %e, attempt, max_retries,
-                    "http sink: transient error, backing off {delay:?}",
+                    "{log_prefix}: transient error, backing off {delay:?}",
                 );
                 tokio::time::sleep(delay).await;
             }
             Err(e) => {
-                tracing::error!(error = %e, lines = count, "http sink: send failed, dropping batch");
-                metrics::record_output_error("http_sink");
+                tracing::error!(error = %e, lines = count, "{log_prefix}: send failed, dropping batch");
+                metrics::record_output_error(sink);
                 return;
             }
         }
@@ -803,35 +1433,184 @@ impl EventSink for HttpSink {
     }
 }
 
+// --- Loki push sink ---
+
+/// Push events to Grafana Loki (`/loki/api/v1/push` JSON format) with batching and retry.
+///
+/// Each batch is grouped into streams by the envelope producer label (as Loki label `source`)
+/// plus the configured static labels; entry timestamps are the envelope `ts` in nanoseconds.
+/// Both fields are read under their configured names (global.envelope_keys).
+pub struct LokiSink {
+    inner: HttpSink,
+}
+
+impl LokiSink {
+    /// Spawn the background worker on the current tokio runtime.
+    /// `keys` are the envelope key names the lines are written with (global.envelope_keys).
+    pub fn new(
+        config: &crate::config::LokiOutputConfig,
+        keys: crate::event::EnvelopeKeys,
+    ) -> anyhow::Result<Self> {
+        let client = batch_post_client(&config.headers, "application/json", config.timeout_secs)?;
+        let options = BatchPostOptions {
+            batch_size: config.batch_size,
+            batch_timeout: std::time::Duration::from_millis(config.batch_timeout_ms),
+            max_retries: config.max_retries,
+            name: "loki_sink",
+            log_prefix: "loki sink",
+        };
+        let labels = config.labels.clone();
+        let inner = HttpSink::spawn(client, config.endpoint.clone(), options, move |lines| {
+            encode_loki_push(lines, &keys, &labels)
+        });
+        Ok(Self { inner })
+    }
+}
+
+/// Build a Loki push body: `{"streams":[{"stream":{labels},"values":[["<ns>","<line>"],...]}]}`.
+fn encode_loki_push(
+    lines: &[String],
+    keys: &crate::event::EnvelopeKeys,
+    static_labels: &HashMap<String, String>,
+) -> String {
+    let mut streams: std::collections::BTreeMap<String, Vec<serde_json::Value>> =
+        std::collections::BTreeMap::new();
+    for line in lines {
+        let parsed = serde_json::from_str::<serde_json::Value>(line).ok();
+        let source = parsed
+            .as_ref()
+            .and_then(|v| v.get(&keys.source))
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+            .to_string();
+        let ts_ns = parsed
+            .as_ref()
+            .and_then(|v| v.get(&keys.ts))
+            .and_then(|v| v.as_str())
+            .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+            .and_then(|dt| dt.timestamp_nanos_opt())
+            .or_else(|| chrono::Utc::now().timestamp_nanos_opt())
+            .unwrap_or(0);
+        streams
+            .entry(source)
+            .or_default()
+            .push(serde_json::json!([ts_ns.to_string(), line]));
+    }
+    let streams: Vec<serde_json::Value> = streams
+        .into_iter()
+        .map(|(source, values)| {
+            let mut stream: serde_json::Map<String, serde_json::Value> = static_labels
+                .iter()
+                .map(|(k, v)| (k.clone(), serde_json::Value::String(v.clone())))
+                .collect();
+            stream.insert("source".to_string(), serde_json::Value::String(source));
+            serde_json::json!({ "stream": stream, "values": values })
+        })
+        .collect();
+    serde_json::json!({ "streams": streams }).to_string()
+}
+
+impl EventSink for LokiSink {
+    fn write_line(&self, line: &str) -> anyhow::Result<()> {
+        self.inner.write_line(line)
+    }
+
+    fn flush(&self) -> anyhow::Result<()> {
+        self.inner.flush()
+    }
+}
+
 // --- NATS sink ---
 
-/// Publish NDJSON lines to a NATS subject via core publish (fire-and-forget).
+/// Publish NDJSON lines to NATS: core publish (fire-and-forget) or, with `jetstream`, JetStream publish
+/// whose acks are awaited on `flush()`. With `subject_prefix`, each line goes to `<prefix>.<source>`.
 ///
 /// Uses a tokio channel + background task so `write_line` stays sync.
 #[cfg(feature = "nats")]
 pub struct NatsSink {
     tx: tokio::sync::mpsc::UnboundedSender<NatsSinkMsg>,
     rt: tokio::runtime::Handle,
+    subject: String,
+    subject_prefix: Option<String>,
 }
 
 #[cfg(feature = "nats")]
 enum NatsSinkMsg {
-    Line(String),
+    Line(String, String),
     Flush(tokio::sync::oneshot::Sender<()>),
 }
 
+/// Max JetStream publishes awaiting an ack before the worker stops to collect them.
+#[cfg(feature = "nats")]
+const NATS_MAX_PENDING_ACKS: usize = 256;
+
 #[cfg(feature = "nats")]
 impl NatsSink {
-    /// Connect to NATS and spawn the publish worker.
-    pub async fn connect(server_url: &str, subject: &str) -> anyhow::Result<Self> {
-        let client = async_nats::connect(server_url)
+    /// Connect to NATS (with `cfg` credentials) and spawn the publish worker. `subject` is used when
+    /// `cfg.subject_prefix` is unset or a line has no source.
+    pub async fn connect(
+        server_url: &str,
+        subject: &str,
+        cfg: &crate::config::NatsOutputConfig,
+    ) -> anyhow::Result<Self> {
+        let mut options = match &cfg.credentials_file {
+            Some(path) => async_nats::ConnectOptions::with_credentials_file(path)
+                .await
+                .map_err(|e| anyhow::anyhow!("nats credentials_file {path:?}: {e}"))?,
+            None => async_nats::ConnectOptions::new(),
+        };
+        if let Some(env) = &cfg.token_env {
+            options = options.token(crate::config::read_secret(None, env)?);
+        }
+        if let Some(user) = &cfg.user {
+            let password = match &cfg.password_env {
+                Some(env) => crate::config::read_secret(None, env)?,
+                None => String::new(),
+            };
+            options = options.user_and_password(user.clone(), password);
+        }
+        let client = options
+            .connect(server_url)
             .await
             .map_err(|e| anyhow::anyhow!("nats connect to {server_url}: {e}"))?;
-        let subject = async_nats::Subject::from(subject);
+        let jetstream = cfg
+            .jetstream
+            .then(|| async_nats::jetstream::new(client.clone()));
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
         let rt = tokio::runtime::Handle::current();
-        rt.spawn(nats_sink_worker(rx, client, subject));
-        Ok(Self { tx, rt })
+        rt.spawn(nats_sink_worker(rx, client, jetstream));
+        Ok(Self {
+            tx,
+            rt,
+            subject: subject.to_string(),
+            subject_prefix: cfg.subject_prefix.clone(),
+        })
+    }
+
+    /// Subject for a line from `source`: `<subject_prefix>.<source>` (source made subject-safe) or the fixed subject.
+    fn subject_for(&self, source: Option<&str>) -> String {
+        match (&self.subject_prefix, source) {
+            (Some(prefix), Some(source)) => format!("{}.{}", prefix, nats_subject_token(source)),
+            _ => self.subject.clone(),
+        }
+    }
+}
+
+/// Make a source id usable as one NATS subject token (no `.`, wildcards or whitespace).
+#[cfg_attr(not(feature = "nats"), allow(dead_code))]
+fn nats_subject_token(source: &str) -> String {
+    let token: String = source
+        .chars()
+        .map(|c| match c {
+            '.' | '*' | '>' => '_',
+            c if c.is_whitespace() => '_',
+            c => c,
+        })
+        .collect();
+    if token.is_empty() {
+        "_".to_string()
+    } else {
+        token
     }
 }
 
@@ -839,17 +1618,33 @@ impl NatsSink {
 async fn nats_sink_worker(
     mut rx: tokio::sync::mpsc::UnboundedReceiver<NatsSinkMsg>,
     client: async_nats::Client,
-    subject: async_nats::Subject,
+    jetstream: Option<async_nats::jetstream::Context>,
 ) {
+    let mut pending = Vec::new();
     while let Some(msg) = rx.recv().await {
         match msg {
-            NatsSinkMsg::Line(line) => {
-                if let Err(e) = client.publish(subject.clone(), line.into()).await {
-                    tracing::error!(error = %e, "nats sink: publish failed");
-                    metrics::record_output_error("nats_sink");
+            NatsSinkMsg::Line(subject, line) => match &jetstream {
+                Some(js) => match js.publish(subject, line.into()).await {
+                    Ok(ack) => {
+                        pending.push(ack);
+                        if pending.len() >= NATS_MAX_PENDING_ACKS {
+                            await_jetstream_acks(&mut pending).await;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!(error = %e, "nats sink: jetstream publish failed");
+                        metrics::record_output_error("nats_sink");
+                    }
+                },
+                None => {
+                    if let Err(e) = client.publish(subject, line.into()).await {
+                        tracing::error!(error = %e, "nats sink: publish failed");
+                        metrics::record_output_error("nats_sink");
+                    }
                 }
-            }
+            },
             NatsSinkMsg::Flush(waiter) => {
+                await_jetstream_acks(&mut pending).await;
                 if let Err(e) = client.flush().await {
                     tracing::warn!(error = %e, "nats sink: flush failed");
                 }
@@ -859,11 +1654,29 @@ async fn nats_sink_worker(
     }
 }
 
+/// Wait for every outstanding JetStream ack; failures (no stream, timeout) are logged and counted.
+#[cfg(feature = "nats")]
+async fn await_jetstream_acks(pending: &mut Vec<async_nats::jetstream::context::PublishAckFuture>) {
+    for ack in pending.drain(..) {
+        if let Err(e) = ack.await {
+            tracing::error!(error = %e, "nats sink: jetstream ack failed");
+            metrics::record_output_error("nats_sink");
+        }
+    }
+}
+
 #[cfg(feature = "nats")]
 impl EventSink for NatsSink {
     fn write_line(&self, line: &str) -> anyhow::Result<()> {
+        self.write_line_from_source(None, line)
+    }
+
+    fn write_line_from_source(&self, source: Option<&str>, line: &str) -> anyhow::Result<()> {
         self.tx
-            .send(NatsSinkMsg::Line(line.to_string()))
+            .send(NatsSinkMsg::Line(
+                self.subject_for(source),
+                line.to_string(),
+            ))
             .map_err(|_| anyhow::anyhow!("nats sink worker closed"))
     }
 
@@ -906,6 +1719,200 @@ pub fn parse_nats_url(url: &str) -> (String, String) {
     }
 }
 
+// --- systemd journal sink ---
+
+/// Write each NDJSON line to the systemd journal as one entry. MESSAGE is the event (JSON, or the raw string
+/// for string events); SOURCE, ENDPOINT and EVENT_TS come from the envelope and HELR_ENVELOPE holds the full line.
+#[cfg(all(feature = "journald", target_os = "linux"))]
+pub struct JournaldSink {
+    syslog_identifier: String,
+    keys: crate::event::EnvelopeKeys,
+}
+
+#[cfg(all(feature = "journald", target_os = "linux"))]
+impl JournaldSink {
+    /// `keys` are the envelope key names the lines are written with (global.envelope_keys).
+    pub fn new(
+        cfg: &crate::config::JournaldOutputConfig,
+        keys: crate::event::EnvelopeKeys,
+    ) -> Self {
+        Self {
+            syslog_identifier: cfg.syslog_identifier.clone(),
+            keys,
+        }
+    }
+
+    /// Journal MESSAGE and fields for one envelope line; non-JSON lines are sent as the MESSAGE alone.
+    fn entry(&self, source: Option<&str>, line: &str) -> (String, Vec<(&'static str, String)>) {
+        let mut fields = vec![("SYSLOG_IDENTIFIER", self.syslog_identifier.clone())];
+        let Ok(envelope) = serde_json::from_str::<serde_json::Value>(line) else {
+            if let Some(s) = source {
+                fields.push(("SOURCE", s.to_string()));
+            }
+            return (line.to_string(), fields);
+        };
+        let str_field = |key: &str| envelope.get(key).and_then(|v| v.as_str()).map(String::from);
+        if let Some(s) = source
+            .map(String::from)
+            .or_else(|| str_field(&self.keys.source))
+        {
+            fields.push(("SOURCE", s));
+        }
+        if let Some(endpoint) = str_field(&self.keys.endpoint) {
+            fields.push(("ENDPOINT", endpoint));
+        }
+        if let Some(ts) = str_field(&self.keys.ts) {
+            fields.push(("EVENT_TS", ts));
+        }
+        fields.push(("HELR_ENVELOPE", line.to_string()));
+        let message = match envelope.get(&self.keys.event) {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(event) => event.to_string(),
+            None => line.to_string(),
+        };
+        (message, fields)
+    }
+}
+
+#[cfg(all(feature = "journald", target_os = "linux"))]
+impl EventSink for JournaldSink {
+    fn write_line(&self, line: &str) -> anyhow::Result<()> {
+        self.write_line_from_source(None, line)
+    }
+
+    fn write_line_from_source(&self, source: Option<&str>, line: &str) -> anyhow::Result<()> {
+        let (message, fields) = self.entry(source, line);
+        libsystemd::logging::journal_send(
+            libsystemd::logging::Priority::Info,
+            &message,
+            fields.into_iter(),
+        )
+        .map_err(|e| anyhow::anyhow!("journald write: {}", e))
+    }
+}
+
+// --- OTLP logs sink ---
+
+/// Export each NDJSON line as an OTLP LogRecord over gRPC. Body is the event (JSON, or the raw string for
+/// string events), timestamp comes from the envelope `ts`, and `source`, `endpoint` and each `meta` field
+/// (as `meta.<key>`) become attributes. Records are batched and exported in the background.
+#[cfg(feature = "otlp-logs")]
+pub struct OtlpLogsSink {
+    provider: opentelemetry_sdk::logs::SdkLoggerProvider,
+    logger: opentelemetry_sdk::logs::SdkLogger,
+    keys: crate::event::EnvelopeKeys,
+}
+
+#[cfg(feature = "otlp-logs")]
+impl OtlpLogsSink {
+    /// Build the exporter; must run inside the Tokio runtime (the gRPC channel is spawned on it).
+    /// `keys` are the envelope key names the lines are written with (global.envelope_keys).
+    pub fn new(
+        cfg: &crate::config::OtlpOutputConfig,
+        keys: crate::event::EnvelopeKeys,
+    ) -> anyhow::Result<Self> {
+        use opentelemetry::logs::LoggerProvider as _;
+        use opentelemetry_otlp::WithExportConfig;
+
+        let exporter = opentelemetry_otlp::LogExporter::builder()
+            .with_tonic()
+            .with_endpoint(cfg.endpoint.as_str())
+            .build()
+            .map_err(|e| anyhow::anyhow!("otlp log exporter {:?}: {}", cfg.endpoint, e))?;
+        let provider = opentelemetry_sdk::logs::SdkLoggerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(
+                opentelemetry_sdk::Resource::builder()
+                    .with_service_name(cfg.service_name.clone())
+                    .build(),
+            )
+            .build();
+        let logger = provider.logger("helr");
+        Ok(Self {
+            provider,
+            logger,
+            keys,
+        })
+    }
+
+    /// Fill `record` from one envelope line; non-JSON lines become the body as-is.
+    fn fill_record(
+        record: &mut opentelemetry_sdk::logs::SdkLogRecord,
+        keys: &crate::event::EnvelopeKeys,
+        source: Option<&str>,
+        line: &str,
+    ) {
+        use opentelemetry::logs::{AnyValue, LogRecord as _, Severity};
+
+        record.set_observed_timestamp(std::time::SystemTime::now());
+        record.set_severity_number(Severity::Info);
+        let Ok(envelope) = serde_json::from_str::<serde_json::Value>(line) else {
+            if let Some(s) = source {
+                record.add_attribute("source", s.to_string());
+            }
+            record.set_body(AnyValue::from(line.to_string()));
+            return;
+        };
+        let str_field = |key: &str| envelope.get(key).and_then(|v| v.as_str()).map(String::from);
+        if let Some(s) = source.map(String::from).or_else(|| str_field(&keys.source)) {
+            record.add_attribute("source", s);
+        }
+        if let Some(endpoint) = str_field(&keys.endpoint) {
+            record.add_attribute("endpoint", endpoint);
+        }
+        if let Some(ts) =
+            str_field(&keys.ts).and_then(|ts| chrono::DateTime::parse_from_rfc3339(&ts).ok())
+        {
+            record.set_timestamp(ts.with_timezone(&chrono::Utc).into());
+        }
+        if let Some(meta) = envelope.get(&keys.meta).and_then(|m| m.as_object()) {
+            for (key, value) in meta {
+                let value = match value {
+                    serde_json::Value::String(s) => AnyValue::from(s.clone()),
+                    serde_json::Value::Bool(b) => AnyValue::from(*b),
+                    serde_json::Value::Number(n) if n.is_i64() => {
+                        AnyValue::from(n.as_i64().unwrap_or_default())
+                    }
+                    serde_json::Value::Number(n) if n.is_f64() => {
+                        AnyValue::from(n.as_f64().unwrap_or_default())
+                    }
+                    serde_json::Value::Null => continue,
+                    other => AnyValue::from(other.to_string()),
+                };
+                record.add_attribute(format!("meta.{}", key), value);
+            }
+        }
+        let body = match envelope.get(&keys.event) {
+            Some(serde_json::Value::String(s)) => s.clone(),
+            Some(event) => event.to_string(),
+            None => line.to_string(),
+        };
+        record.set_body(AnyValue::from(body));
+    }
+}
+
+#[cfg(feature = "otlp-logs")]
+impl EventSink for OtlpLogsSink {
+    fn write_line(&self, line: &str) -> anyhow::Result<()> {
+        self.write_line_from_source(None, line)
+    }
+
+    fn write_line_from_source(&self, source: Option<&str>, line: &str) -> anyhow::Result<()> {
+        use opentelemetry::logs::Logger as _;
+
+        let mut record = self.logger.create_log_record();
+        Self::fill_record(&mut record, &self.keys, source, line);
+        self.logger.emit(record);
+        Ok(())
+    }
+
+    fn flush(&self) -> anyhow::Result<()> {
+        self.provider
+            .force_flush()
+            .map_err(|e| anyhow::anyhow!("otlp log export flush: {}", e))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -913,6 +1920,17 @@ mod tests {
     use std::fs;
     use std::sync::Mutex as StdMutex;
 
+    /// Envelope keys renamed from every default, as with global.envelope_keys set.
+    fn renamed_envelope_keys() -> crate::event::EnvelopeKeys {
+        crate::event::EnvelopeKeys {
+            ts: "@timestamp".to_string(),
+            source: "producer".to_string(),
+            endpoint: "path".to_string(),
+            event: "data".to_string(),
+            meta: "helr".to_string(),
+        }
+    }
+
     /// Sink that records all lines for tests.
     struct RecordingSink {
         lines: StdMutex<Vec<String>>,
@@ -989,6 +2007,8 @@ mod tests {
                 stdout_buffer_size: 65536,
                 event_queue_size,
                 memory_threshold_mb: None,
+                high_watermark: None,
+                low_watermark: None,
             },
             strategy,
             disk_buffer: None,
@@ -1062,6 +2082,45 @@ mod tests {
         assert_eq!(lines, ["first", "second", "third"]);
     }
 
+    #[test]
+    fn backpressure_persist_pending_writes_queue_to_disk_buffer() {
+        let dir = std::env::temp_dir().join("helr_backpressure_persist_pending");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("buffer.ndjson");
+        let (tx, rx) = std::sync::mpsc::sync_channel(0);
+        let inner = Arc::new(GatedRecordingSink::new(tx));
+        let mut cfg = backpressure_config(
+            10,
+            BackpressureStrategyConfig::DiskBuffer,
+            DropPolicyConfig::OldestFirst,
+        );
+        cfg.disk_buffer = Some(crate::config::BackpressureDiskBufferConfig {
+            path: path.to_string_lossy().into_owned(),
+            max_size_mb: 10,
+            segment_size_mb: 10,
+        });
+        let sink = BackpressureSink::new(inner.clone(), &cfg, None).unwrap();
+        // Writer pops "first" and blocks in write_line (stalled consumer).
+        sink.write_line_from_source(Some("s"), r#"{"n":1}"#)
+            .unwrap();
+        rx.recv().unwrap();
+        sink.write_line_from_source(Some("s"), r#"{"n":2}"#)
+            .unwrap();
+        sink.write_line_from_source(Some("s"), r#"{"n":3}"#)
+            .unwrap();
+
+        assert_eq!(sink.persist_pending().unwrap(), 2);
+        drop(sink);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"n\":2}\n{\"n\":3}\n");
+        // The in-flight line completes; persisted lines are kept for the next start, not re-emitted.
+        inner.open_gate();
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        assert_eq!(inner.lines(), [r#"{"n":1}"#]);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn backpressure_drop_random_one_dropped() {
         let inner = Arc::new(RecordingSink::new());
@@ -1117,6 +2176,161 @@ mod tests {
         }
     }
 
+    #[test]
+    fn csv_sink_writes_header_and_rows() {
+        let inner = Arc::new(RecordingSink::new());
+        let sink = CsvSink::new(
+            inner.clone(),
+            vec![
+                "ts".to_string(),
+                "source".to_string(),
+                "event.actor.id".to_string(),
+                "event.msg".to_string(),
+                "event.tags".to_string(),
+            ],
+        )
+        .unwrap();
+        sink.write_line_from_source(
+            Some("okta"),
+            r#"{"ts":"2024-01-01T00:00:00Z","source":"okta","event":{"actor":{"id":42},"msg":"said \"hi\", left","tags":["a","b"]}}"#,
+        )
+        .unwrap();
+        sink.write_line_from_source(
+            Some("okta"),
+            r#"{"ts":"2024-01-01T00:00:01Z","source":"okta","event":{"msg":null}}"#,
+        )
+        .unwrap();
+        sink.write_line_from_source(Some("okta"), "not json")
+            .unwrap();
+        sink.flush().unwrap();
+        assert_eq!(
+            inner.lines(),
+            [
+                "ts,source,event.actor.id,event.msg,event.tags",
+                r#"2024-01-01T00:00:00Z,okta,42,"said ""hi"", left","[""a"",""b""]""#,
+                "2024-01-01T00:00:01Z,okta,,,",
+            ]
+        );
+        assert!(CsvSink::new(inner, vec![]).is_err());
+    }
+
+    #[test]
+    fn batch_array_sink_writes_arrays_of_batch_size() {
+        let inner = Arc::new(RecordingSink::new());
+        let sink = BatchArraySink::new(inner.clone(), 2, None).unwrap();
+        for id in 1..=5 {
+            sink.write_line_from_source(Some("s"), &format!(r#"{{"id":{}}}"#, id))
+                .unwrap();
+        }
+        assert_eq!(
+            inner.lines(),
+            [r#"[{"id":1},{"id":2}]"#, r#"[{"id":3},{"id":4}]"#]
+        );
+        sink.flush().unwrap();
+        let lines = inner.lines();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2], r#"[{"id":5}]"#);
+        for line in &lines {
+            assert!(serde_json::from_str::<Vec<serde_json::Value>>(line).is_ok());
+        }
+        sink.flush().unwrap();
+        assert_eq!(inner.lines().len(), 3, "empty flush writes nothing");
+        assert!(BatchArraySink::new(inner, 0, None).is_err());
+    }
+
+    #[test]
+    fn batch_array_sink_writes_partial_batch_after_flush_interval() {
+        let inner = Arc::new(RecordingSink::new());
+        let sink = BatchArraySink::new(
+            inner.clone(),
+            10,
+            Some(std::time::Duration::from_millis(50)),
+        )
+        .unwrap();
+        sink.write_line_from_source(Some("s"), r#"{"id":1}"#)
+            .unwrap();
+        assert!(inner.lines().is_empty());
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        assert_eq!(inner.lines(), [r#"[{"id":1}]"#]);
+    }
+
+    #[test]
+    fn backpressure_persist_pending_includes_partial_batch() {
+        let dir = std::env::temp_dir().join("helr_batch_array_persist_pending");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("buffer.ndjson");
+        let inner = Arc::new(RecordingSink::new());
+        let batch: Arc<dyn EventSink> =
+            Arc::new(BatchArraySink::new(inner.clone(), 10, None).unwrap());
+        let mut cfg = backpressure_config(
+            10,
+            BackpressureStrategyConfig::DiskBuffer,
+            DropPolicyConfig::OldestFirst,
+        );
+        cfg.disk_buffer = Some(crate::config::BackpressureDiskBufferConfig {
+            path: path.to_string_lossy().into_owned(),
+            max_size_mb: 10,
+            segment_size_mb: 10,
+        });
+        let sink = BackpressureSink::new(batch, &cfg, None).unwrap();
+        sink.write_line_from_source(Some("s"), r#"{"n":1}"#)
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(100));
+
+        assert_eq!(sink.persist_pending().unwrap(), 1);
+        drop(sink);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"n\":1}\n");
+        assert!(inner.lines().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn backpressure_under_load_hysteresis() {
+        let (high, low) = (8, 3);
+        let mut active = false;
+        for depth in [0, 5, 7] {
+            active = under_load_after(active, depth, high, low);
+            assert!(!active, "below high at depth {}", depth);
+        }
+        active = under_load_after(active, 8, high, low);
+        assert!(active, "engages at high watermark");
+        for depth in [7, 5, 3] {
+            active = under_load_after(active, depth, high, low);
+            assert!(
+                active,
+                "stays engaged between watermarks at depth {}",
+                depth
+            );
+        }
+        active = under_load_after(active, 2, high, low);
+        assert!(!active, "disengages below low watermark");
+        active = under_load_after(active, 5, high, low);
+        assert!(!active, "stays disengaged until high is reached again");
+    }
+
+    #[test]
+    fn backpressure_watermarks_validated() {
+        let inner = Arc::new(RecordingSink::new());
+        let mut cfg = backpressure_config(
+            10,
+            BackpressureStrategyConfig::Block,
+            DropPolicyConfig::OldestFirst,
+        );
+        cfg.detection.high_watermark = Some(4);
+        cfg.detection.low_watermark = Some(6);
+        match BackpressureSink::new(inner.clone(), &cfg, None) {
+            Err(e) => assert!(e.to_string().contains("low_watermark")),
+            Ok(_) => panic!("expected error for low_watermark > high_watermark"),
+        }
+        cfg.detection.high_watermark = Some(11);
+        cfg.detection.low_watermark = None;
+        match BackpressureSink::new(inner, &cfg, None) {
+            Err(e) => assert!(e.to_string().contains("high_watermark")),
+            Ok(_) => panic!("expected error for high_watermark > event_queue_size"),
+        }
+    }
+
     #[test]
     fn backpressure_event_queue_size_zero_fails() {
         let inner = Arc::new(RecordingSink::new());
@@ -1160,6 +2374,56 @@ mod tests {
         assert!(parse_rotation("hourly").is_err());
     }
 
+    #[test]
+    fn drain_disk_buffer_skips_truncated_lines() {
+        let dir = std::env::temp_dir().join("helr_disk_buffer_corrupt_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("buffer.ndjson");
+        fs::write(
+            disk_buffer_old_path(&path),
+            "{\"event\":{\"id\":\"old\"}}\n",
+        )
+        .unwrap();
+        fs::write(
+            &path,
+            "{\"event\":{\"id\":\"good\"}}\n{\"event\":{\"id\":\"trunc",
+        )
+        .unwrap();
+        let lines = drain_disk_buffer(&path, &Mutex::new(()));
+        assert_eq!(
+            lines,
+            vec![
+                r#"{"event":{"id":"old"}}"#.to_string(),
+                r#"{"event":{"id":"good"}}"#.to_string()
+            ]
+        );
+        assert!(!disk_buffer_old_path(&path).exists());
+        assert_eq!(fs::metadata(&path).unwrap().len(), 0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn drain_disk_buffer_keeps_lines_after_invalid_utf8() {
+        let dir = std::env::temp_dir().join("helr_disk_buffer_utf8_test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("buffer.ndjson");
+        let mut contents = b"{\"event\":{\"id\":\"a\"}}\n".to_vec();
+        contents.extend_from_slice(b"{\"event\":\"\xff\xfe\"}\n");
+        contents.extend_from_slice(b"{\"event\":{\"id\":\"b\"}}\r\n");
+        fs::write(&path, contents).unwrap();
+        let lines = drain_disk_buffer(&path, &Mutex::new(()));
+        assert_eq!(
+            lines,
+            vec![
+                r#"{"event":{"id":"a"}}"#.to_string(),
+                r#"{"event":{"id":"b"}}"#.to_string()
+            ]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn file_sink_append_and_rotate_by_size() {
         let dir = std::env::temp_dir().join("helr_output_test");
@@ -1201,6 +2465,158 @@ mod tests {
         let _ = fs::remove_file(&rotated_path);
     }
 
+    #[test]
+    fn file_sink_manifest_matches_rotated_file() {
+        use sha2::Digest;
+
+        let dir = std::env::temp_dir().join("helr_output_manifest_test");
+        let _ = fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.ndjson");
+
+        let sink = FileSink::new(&path, RotationPolicy::SizeBytes(100))
+            .unwrap()
+            .with_manifest(true);
+        let lines = [
+            r#"{"ts":"2024-01-01T00:00:00Z","source":"a","event":{"n":1}}"#,
+            r#"{"ts":"2024-01-01T00:00:05Z","source":"a","event":{"n":2}}"#,
+        ];
+        for line in lines {
+            sink.write_line(line).unwrap();
+        }
+        sink.write_line("after_rotate").unwrap();
+        sink.flush().unwrap();
+
+        let rotated = fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .find(|p| p != &path && p.extension().is_some_and(|x| x == "ndjson"))
+            .expect("rotated file");
+        let content = fs::read(&rotated).unwrap();
+        let expected_sha = format!("{:x}", sha2::Sha256::digest(&content));
+        let name = rotated.file_name().unwrap().to_string_lossy().into_owned();
+
+        let sha_file = fs::read_to_string(format!("{}.sha256", rotated.display())).unwrap();
+        assert_eq!(sha_file, format!("{}  {}\n", expected_sha, name));
+        let meta: serde_json::Value =
+            serde_json::from_slice(&fs::read(format!("{}.meta.json", rotated.display())).unwrap())
+                .unwrap();
+        assert_eq!(meta["file"], name);
+        assert_eq!(meta["sha256"], expected_sha);
+        assert_eq!(meta["lines"], 2);
+        assert_eq!(meta["bytes"], content.len() as u64);
+        assert_eq!(meta["first_ts"], "2024-01-01T00:00:00Z");
+        assert_eq!(meta["last_ts"], "2024-01-01T00:00:05Z");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn file_sink_manifest_reads_renamed_ts_key() {
+        let dir = std::env::temp_dir().join("helr_output_manifest_keys_test");
+        let _ = fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.ndjson");
+
+        let sink = FileSink::new(&path, RotationPolicy::SizeBytes(100))
+            .unwrap()
+            .with_manifest(true)
+            .with_envelope_keys(&renamed_envelope_keys());
+        sink.write_line(r#"{"@timestamp":"2024-01-01T00:00:00Z","ts":"x","data":{"n":1}}"#)
+            .unwrap();
+        sink.write_line(r#"{"@timestamp":"2024-01-01T00:00:05Z","ts":"y","data":{"n":2}}"#)
+            .unwrap();
+        sink.write_line("after_rotate").unwrap();
+        sink.flush().unwrap();
+
+        let meta_path = fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .find(|p| p.to_string_lossy().ends_with(".meta.json"))
+            .expect("manifest");
+        let meta: serde_json::Value =
+            serde_json::from_slice(&fs::read(meta_path).unwrap()).unwrap();
+        assert_eq!(meta["first_ts"], "2024-01-01T00:00:00Z");
+        assert_eq!(meta["last_ts"], "2024-01-01T00:00:05Z");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn file_sink_compress_on_rotate_writes_gz() {
+        use std::io::Read;
+
+        let dir = std::env::temp_dir().join("helr_output_gzip_test");
+        let _ = fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.ndjson");
+
+        let sink = FileSink::new(&path, RotationPolicy::SizeBytes(20))
+            .unwrap()
+            .with_compress_on_rotate(true);
+        sink.write_line("first_line").unwrap();
+        sink.write_line("second_line").unwrap(); // 23 bytes total (>= 20)
+        sink.write_line("after_rotate").unwrap();
+        sink.flush().unwrap();
+
+        // Active file stays plaintext.
+        assert_eq!(fs::read_to_string(&path).unwrap().trim(), "after_rotate");
+        let entries: Vec<PathBuf> = fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p != &path)
+            .collect();
+        assert_eq!(
+            entries.len(),
+            1,
+            "only the .gz segment remains: {:?}",
+            entries
+        );
+        let gz = &entries[0];
+        assert!(gz.to_string_lossy().ends_with(".ndjson.gz"));
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(fs::File::open(gz).unwrap())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, "first_line\nsecond_line\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn file_sink_fsync_writes_complete_lines() {
+        let dir = std::env::temp_dir().join("helr_output_fsync_test");
+        let _ = fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("out.ndjson");
+
+        let sink = FileSink::new(&path, RotationPolicy::None)
+            .unwrap()
+            .with_fsync(Some(1));
+        sink.write_line(r#"{"id":1}"#).unwrap();
+        sink.write_line(r#"{"id":2}"#).unwrap();
+        // Each write was synced; the content is on disk without an explicit flush.
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "{\"id\":1}\n{\"id\":2}\n"
+        );
+
+        let batched = FileSink::new(&dir.join("batched.ndjson"), RotationPolicy::None)
+            .unwrap()
+            .with_fsync(Some(10));
+        batched.write_line("a").unwrap();
+        batched.flush().unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("batched.ndjson")).unwrap(),
+            "a\n"
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn parse_nats_url_with_subject() {
         let (server, subject) = super::parse_nats_url("nats://localhost:4222/helr.events");
@@ -1222,6 +2638,139 @@ mod tests {
         assert_eq!(subject, "helr.events");
     }
 
+    #[test]
+    fn nats_subject_token_replaces_separators() {
+        assert_eq!(super::nats_subject_token("okta-audit"), "okta-audit");
+        assert_eq!(super::nats_subject_token("gws.login *x>"), "gws_login__x_");
+        assert_eq!(super::nats_subject_token(""), "_");
+    }
+
+    /// Publishes through JetStream to `<prefix>.<source>`; runs only when NATS_URL points at a JetStream-enabled server.
+    #[cfg(feature = "nats")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn nats_sink_jetstream_publishes_per_source_subject() {
+        let Ok(url) = std::env::var("NATS_URL") else {
+            eprintln!("NATS_URL not set; skipping");
+            return;
+        };
+        let client = async_nats::connect(&url).await.unwrap();
+        let js = async_nats::jetstream::new(client);
+        let _ = js.delete_stream("HELR_SINK_TEST").await;
+        let stream = js
+            .create_stream(async_nats::jetstream::stream::Config {
+                name: "HELR_SINK_TEST".to_string(),
+                subjects: vec!["helr.sinktest.>".to_string()],
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let cfg = crate::config::NatsOutputConfig {
+            subject_prefix: Some("helr.sinktest".to_string()),
+            jetstream: true,
+            ..Default::default()
+        };
+        let sink = NatsSink::connect(&url, "helr.events", &cfg).await.unwrap();
+        sink.write_line_from_source(Some("okta.audit"), r#"{"event":1}"#)
+            .unwrap();
+        sink.write_line_from_source(Some("gws"), r#"{"event":2}"#)
+            .unwrap();
+        tokio::task::spawn_blocking(move || sink.flush())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let msg = stream
+            .get_last_raw_message_by_subject("helr.sinktest.okta_audit")
+            .await
+            .unwrap();
+        assert_eq!(&msg.payload[..], br#"{"event":1}"#);
+        let mut stream = stream;
+        assert_eq!(stream.info().await.unwrap().state.messages, 2);
+        js.delete_stream("HELR_SINK_TEST").await.unwrap();
+    }
+
+    #[cfg(all(feature = "journald", target_os = "linux"))]
+    #[test]
+    fn journald_sink_maps_envelope_fields() {
+        let sink = JournaldSink::new(
+            &crate::config::JournaldOutputConfig {
+                syslog_identifier: "helr-test".to_string(),
+            },
+            crate::event::EnvelopeKeys::default(),
+        );
+        let line = r#"{"source":"okta","endpoint":"/api/v1/logs","ts":"2026-01-01T00:00:00Z","event":{"id":"1"},"meta":{}}"#;
+        let (message, fields) = sink.entry(None, line);
+        assert_eq!(message, r#"{"id":"1"}"#);
+        let get = |k: &str| {
+            fields
+                .iter()
+                .find(|(f, _)| *f == k)
+                .map(|(_, v)| v.as_str())
+        };
+        assert_eq!(get("SYSLOG_IDENTIFIER"), Some("helr-test"));
+        assert_eq!(get("SOURCE"), Some("okta"));
+        assert_eq!(get("ENDPOINT"), Some("/api/v1/logs"));
+        assert_eq!(get("EVENT_TS"), Some("2026-01-01T00:00:00Z"));
+        assert_eq!(get("HELR_ENVELOPE"), Some(line));
+
+        let (message, fields) = sink.entry(Some("gws"), "not json");
+        assert_eq!(message, "not json");
+        assert!(fields.contains(&("SOURCE", "gws".to_string())));
+    }
+
+    #[cfg(all(feature = "journald", target_os = "linux"))]
+    #[test]
+    fn journald_sink_reads_renamed_envelope_keys() {
+        let sink = JournaldSink::new(
+            &crate::config::JournaldOutputConfig {
+                syslog_identifier: "helr-test".to_string(),
+            },
+            renamed_envelope_keys(),
+        );
+        let line = r#"{"producer":"okta","path":"/api/v1/logs","@timestamp":"2026-01-01T00:00:00Z","data":{"id":"1"},"helr":{}}"#;
+        let (message, fields) = sink.entry(None, line);
+        assert_eq!(message, r#"{"id":"1"}"#);
+        assert!(fields.contains(&("SOURCE", "okta".to_string())));
+        assert!(fields.contains(&("ENDPOINT", "/api/v1/logs".to_string())));
+        assert!(fields.contains(&("EVENT_TS", "2026-01-01T00:00:00Z".to_string())));
+    }
+
+    /// Writes one entry to the local journal; runs only when journald is listening.
+    #[cfg(all(feature = "journald", target_os = "linux"))]
+    #[test]
+    fn journald_sink_writes_line() {
+        if !std::path::Path::new("/run/systemd/journal/socket").exists() {
+            eprintln!("no systemd journal socket; skipping");
+            return;
+        }
+        let sink = JournaldSink::new(
+            &crate::config::JournaldOutputConfig {
+                syslog_identifier: "helr-test".to_string(),
+            },
+            crate::event::EnvelopeKeys::default(),
+        );
+        sink.write_line_from_source(
+            Some("okta"),
+            r#"{"source":"okta","endpoint":"/","ts":"2026-01-01T00:00:00Z","event":{"id":"1"},"meta":{}}"#,
+        )
+        .unwrap();
+        sink.flush().unwrap();
+    }
+
+    #[test]
+    fn ring_buffer_sink_keeps_last_lines_and_forwards() {
+        let inner = Arc::new(RecordingSink::new());
+        let sink = RingBufferSink::new(inner.clone(), 3);
+        for i in 1..=5 {
+            sink.write_line(&format!("line{}", i)).unwrap();
+        }
+        assert_eq!(inner.lines().len(), 5);
+        assert_eq!(sink.tail(None), vec!["line3", "line4", "line5"]);
+        assert_eq!(sink.tail(Some(2)), vec!["line4", "line5"]);
+        assert_eq!(sink.tail(Some(10)).len(), 3);
+    }
+
     #[test]
     fn parse_nats_url_trailing_slash() {
         let (server, subject) = super::parse_nats_url("nats://localhost:4222/");
@@ -1296,4 +2845,217 @@ mod tests {
         // First 2 calls return 503, third succeeds => 3 total calls.
         assert_eq!(call_count.load(Ordering::SeqCst), 3);
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn loki_sink_pushes_streams_with_labels_and_ns_timestamps() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/loki/api/v1/push"))
+            .and(header("content-type", "application/json"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let cfg = crate::config::LokiOutputConfig {
+            endpoint: format!("{}/loki/api/v1/push", mock_server.uri()),
+            labels: [("env".to_string(), "prod".to_string())]
+                .into_iter()
+                .collect(),
+            batch_size: 10,
+            batch_timeout_ms: 500,
+            headers: HashMap::new(),
+            timeout_secs: 5,
+            max_retries: 0,
+        };
+        let sink = LokiSink::new(&cfg, crate::event::EnvelopeKeys::default()).unwrap();
+        let okta = r#"{"ts":"2024-01-15T10:00:00.5Z","source":"okta","event":{"id":"1"}}"#;
+        let gws = r#"{"ts":"2024-01-15T10:00:01Z","source":"gws","event":{"id":"2"}}"#;
+        sink.write_line(okta).unwrap();
+        sink.write_line(gws).unwrap();
+        sink.flush().unwrap();
+
+        let requests = mock_server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        let streams = body["streams"].as_array().unwrap();
+        assert_eq!(streams.len(), 2);
+        // Streams are ordered by source label.
+        assert_eq!(
+            streams[0]["stream"],
+            serde_json::json!({"source": "gws", "env": "prod"})
+        );
+        assert_eq!(
+            streams[0]["values"],
+            serde_json::json!([["1705312801000000000", gws]])
+        );
+        assert_eq!(
+            streams[1]["stream"],
+            serde_json::json!({"source": "okta", "env": "prod"})
+        );
+        assert_eq!(
+            streams[1]["values"],
+            serde_json::json!([["1705312800500000000", okta]])
+        );
+    }
+
+    #[test]
+    fn loki_push_reads_renamed_envelope_keys() {
+        let line = r#"{"@timestamp":"2024-01-15T10:00:00.5Z","producer":"okta","data":{"id":"1"}}"#;
+        let body = encode_loki_push(
+            &[line.to_string()],
+            &renamed_envelope_keys(),
+            &HashMap::new(),
+        );
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(
+            body["streams"],
+            serde_json::json!([{
+                "stream": {"source": "okta"},
+                "values": [["1705312800500000000", line]],
+            }])
+        );
+    }
+
+    #[cfg(feature = "otlp-logs")]
+    #[test]
+    fn otlp_log_record_maps_envelope() {
+        use opentelemetry::logs::{AnyValue, Logger as _, LoggerProvider as _};
+        let provider = opentelemetry_sdk::logs::SdkLoggerProvider::builder().build();
+        let logger = provider.logger("helr-test");
+        let mut record = logger.create_log_record();
+        let line = r#"{"ts":"2024-01-15T10:00:00Z","source":"okta","endpoint":"/api/v1/logs","event":{"id":"e1"},"meta":{"cursor":"c1","status":200}}"#;
+        OtlpLogsSink::fill_record(
+            &mut record,
+            &crate::event::EnvelopeKeys::default(),
+            None,
+            line,
+        );
+
+        assert_eq!(
+            record.body(),
+            Some(&AnyValue::from(r#"{"id":"e1"}"#.to_string()))
+        );
+        let ts = chrono::DateTime::parse_from_rfc3339("2024-01-15T10:00:00Z").unwrap();
+        assert_eq!(
+            record.timestamp(),
+            Some(ts.with_timezone(&chrono::Utc).into())
+        );
+        let attrs: std::collections::HashMap<String, AnyValue> = record
+            .attributes_iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+        assert_eq!(attrs["source"], AnyValue::from("okta".to_string()));
+        assert_eq!(
+            attrs["endpoint"],
+            AnyValue::from("/api/v1/logs".to_string())
+        );
+        assert_eq!(attrs["meta.cursor"], AnyValue::from("c1".to_string()));
+        assert_eq!(attrs["meta.status"], AnyValue::from(200i64));
+    }
+
+    #[cfg(feature = "otlp-logs")]
+    #[test]
+    fn otlp_log_record_reads_renamed_envelope_keys() {
+        use opentelemetry::logs::{AnyValue, Logger as _, LoggerProvider as _};
+        let provider = opentelemetry_sdk::logs::SdkLoggerProvider::builder().build();
+        let logger = provider.logger("helr-test");
+        let mut record = logger.create_log_record();
+        let line = r#"{"@timestamp":"2024-01-15T10:00:00Z","producer":"okta","path":"/api/v1/logs","data":{"id":"e1"},"helr":{"cursor":"c1"}}"#;
+        OtlpLogsSink::fill_record(&mut record, &renamed_envelope_keys(), None, line);
+
+        assert_eq!(
+            record.body(),
+            Some(&AnyValue::from(r#"{"id":"e1"}"#.to_string()))
+        );
+        assert!(record.timestamp().is_some());
+        let attrs: std::collections::HashMap<String, AnyValue> = record
+            .attributes_iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+        assert_eq!(attrs["source"], AnyValue::from("okta".to_string()));
+        assert_eq!(
+            attrs["endpoint"],
+            AnyValue::from("/api/v1/logs".to_string())
+        );
+        assert_eq!(attrs["meta.cursor"], AnyValue::from("c1".to_string()));
+    }
+
+    /// Exports over gRPC to an in-process OTLP logs collector, flushing the way `--once` and `/drain` do.
+    #[cfg(feature = "otlp-logs")]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn otlp_logs_sink_exports_to_collector() {
+        use opentelemetry_proto::tonic::collector::logs::v1::logs_service_server::{
+            LogsService, LogsServiceServer,
+        };
+        use opentelemetry_proto::tonic::collector::logs::v1::{
+            ExportLogsServiceRequest, ExportLogsServiceResponse,
+        };
+        use opentelemetry_proto::tonic::common::v1::any_value::Value;
+
+        struct Collector(tokio::sync::mpsc::UnboundedSender<ExportLogsServiceRequest>);
+
+        #[tonic::async_trait]
+        impl LogsService for Collector {
+            async fn export(
+                &self,
+                request: tonic::Request<ExportLogsServiceRequest>,
+            ) -> Result<tonic::Response<ExportLogsServiceResponse>, tonic::Status> {
+                let _ = self.0.send(request.into_inner());
+                Ok(tonic::Response::new(ExportLogsServiceResponse {
+                    partial_success: None,
+                }))
+            }
+        }
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(LogsServiceServer::new(Collector(tx)))
+                .serve_with_incoming(tonic::transport::server::TcpIncoming::from(listener)),
+        );
+
+        let sink = OtlpLogsSink::new(
+            &crate::config::OtlpOutputConfig {
+                endpoint,
+                service_name: "helr-test".to_string(),
+            },
+            crate::event::EnvelopeKeys::default(),
+        )
+        .unwrap();
+        sink.write_line_from_source(
+            Some("okta"),
+            r#"{"ts":"2024-01-15T10:00:00Z","source":"okta","endpoint":"/logs","event":{"id":"e1"},"meta":{}}"#,
+        )
+        .unwrap();
+        tokio::task::spawn_blocking(move || sink.flush())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let request = tokio::time::timeout(std::time::Duration::from_secs(10), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        let records: Vec<_> = request
+            .resource_logs
+            .iter()
+            .flat_map(|r| &r.scope_logs)
+            .flat_map(|s| &s.log_records)
+            .collect();
+        assert_eq!(records.len(), 1);
+        let body = records[0].body.as_ref().and_then(|b| b.value.as_ref());
+        assert!(
+            matches!(body, Some(Value::StringValue(s)) if s == r#"{"id":"e1"}"#),
+            "{:?}",
+            body
+        );
+        assert!(records[0].attributes.iter().any(|kv| kv.key == "source"
+            && matches!(kv.value.as_ref().and_then(|v| v.value.as_ref()), Some(Value::StringValue(s)) if s == "okta")));
+    }
 }
@@ -2,39 +2,48 @@
 //!
 //! Format: Link: <url>; rel="next"[, <url2>; rel="prev"]
 
+use crate::config::DuplicateRelSelection;
 use reqwest::header::HeaderMap;
 
 /// Parse Link header and return the URL for the given `rel` (e.g. "next").
-/// Handles multiple Link headers and comma-separated entries in one header.
-pub fn next_link_from_headers(headers: &HeaderMap, rel: &str) -> Option<String> {
-    let link_values = headers.get_all(reqwest::header::LINK);
-    for value in link_values {
-        if let Ok(s) = value.to_str()
-            && let Some(url) = parse_link_header(s, rel)
-        {
-            return Some(url);
+/// Handles multiple Link headers and comma-separated entries in one header; when more than one entry
+/// matches `rel`, `selection` picks the first or last in header order.
+pub fn next_link_from_headers(
+    headers: &HeaderMap,
+    rel: &str,
+    selection: DuplicateRelSelection,
+) -> Option<String> {
+    let mut matches = Vec::new();
+    for value in headers.get_all(reqwest::header::LINK) {
+        if let Ok(s) = value.to_str() {
+            parse_link_header(s, rel, &mut matches);
         }
     }
-    None
+    match selection {
+        DuplicateRelSelection::PreferFirst => matches.into_iter().next(),
+        DuplicateRelSelection::PreferLast => matches.pop(),
+    }
 }
 
-/// Parse a single Link header value string. Format: <uri>; rel="next"[, <uri2>; rel="prev"]
-fn parse_link_header(s: &str, want_rel: &str) -> Option<String> {
+/// Parse a single Link header value string, pushing every URI whose rel matches `want_rel` onto `out`.
+/// Format: <uri>; rel="next"[, <uri2>; rel="prev"]. Stops at the first malformed entry.
+fn parse_link_header(s: &str, want_rel: &str, out: &mut Vec<String>) {
     for part in s.split(',') {
         let part = part.trim();
-        let (uri, rest) = part.strip_prefix('<')?.split_once('>')?;
+        let Some((uri, rest)) = part.strip_prefix('<').and_then(|p| p.split_once('>')) else {
+            return;
+        };
         let rest = rest.trim().trim_start_matches(';').trim();
         for param in rest.split(';') {
             let param = param.trim();
             if let Some(rel_val) = param.strip_prefix("rel=") {
                 let rel_val = rel_val.trim().trim_matches('"').trim();
                 if rel_val.eq_ignore_ascii_case(want_rel) {
-                    return Some(uri.trim().to_string());
+                    out.push(uri.trim().to_string());
                 }
             }
         }
     }
-    None
 }
 
 #[cfg(test)]
@@ -52,7 +61,7 @@ mod tests {
     fn parse_next_link() {
         let h = header_map(r#"<https://api.example.com/logs?after=xyz>; rel="next""#);
         assert_eq!(
-            next_link_from_headers(&h, "next"),
+            next_link_from_headers(&h, "next", DuplicateRelSelection::PreferFirst),
             Some("https://api.example.com/logs?after=xyz".into())
         );
     }
@@ -63,7 +72,7 @@ mod tests {
             r#"<https://api.example.com/logs>; rel="self", <https://api.example.com/logs?after=abc>; rel="next""#,
         );
         assert_eq!(
-            next_link_from_headers(&h, "next"),
+            next_link_from_headers(&h, "next", DuplicateRelSelection::PreferFirst),
             Some("https://api.example.com/logs?after=abc".into())
         );
     }
@@ -71,14 +80,17 @@ mod tests {
     #[test]
     fn no_next_returns_none() {
         let h = header_map(r#"<https://api.example.com/logs>; rel="self""#);
-        assert_eq!(next_link_from_headers(&h, "next"), None);
+        assert_eq!(
+            next_link_from_headers(&h, "next", DuplicateRelSelection::PreferFirst),
+            None
+        );
     }
 
     #[test]
     fn rel_case_insensitive() {
         let h = header_map(r#"<https://api.example.com/next>; rel="Next""#);
         assert_eq!(
-            next_link_from_headers(&h, "next"),
+            next_link_from_headers(&h, "next", DuplicateRelSelection::PreferFirst),
             Some("https://api.example.com/next".into())
         );
     }
@@ -86,18 +98,56 @@ mod tests {
     #[test]
     fn empty_link_header_returns_none() {
         let h = header_map("");
-        assert_eq!(next_link_from_headers(&h, "next"), None);
+        assert_eq!(
+            next_link_from_headers(&h, "next", DuplicateRelSelection::PreferFirst),
+            None
+        );
     }
 
     #[test]
     fn malformed_link_missing_angle_returns_none() {
         let h = header_map(r#"https://api.example.com/next; rel="next""#);
-        assert_eq!(next_link_from_headers(&h, "next"), None);
+        assert_eq!(
+            next_link_from_headers(&h, "next", DuplicateRelSelection::PreferFirst),
+            None
+        );
     }
 
     #[test]
     fn malformed_link_unclosed_brace_returns_none() {
         let h = header_map(r#"<https://api.example.com/next; rel="next""#);
-        assert_eq!(next_link_from_headers(&h, "next"), None);
+        assert_eq!(
+            next_link_from_headers(&h, "next", DuplicateRelSelection::PreferFirst),
+            None
+        );
+    }
+
+    #[test]
+    fn duplicate_next_rels_follow_selection() {
+        let mut h = HeaderMap::new();
+        h.append(
+            LINK,
+            HeaderValue::from_static(r#"<https://api.example.com/logs?page=2>; rel="next""#),
+        );
+        h.append(
+            LINK,
+            HeaderValue::from_static(r#"<https://cdn.example.com/preload>; rel="next""#),
+        );
+        assert_eq!(
+            next_link_from_headers(&h, "next", DuplicateRelSelection::PreferFirst),
+            Some("https://api.example.com/logs?page=2".into())
+        );
+        assert_eq!(
+            next_link_from_headers(&h, "next", DuplicateRelSelection::PreferLast),
+            Some("https://cdn.example.com/preload".into())
+        );
+
+        let h = header_map(
+            r#"<https://cdn.example.com/preload>; rel="next", <https://api.example.com/logs?page=2>; rel="next""#,
+        );
+        assert_eq!(
+            next_link_from_headers(&h, "next", DuplicateRelSelection::PreferLast),
+            Some("https://api.example.com/logs?page=2".into())
+        );
     }
 }
@@ -0,0 +1,170 @@
+//! In-memory poll state shared across ticks: HTTP clients, request caps, rate-limit windows and pending start seeds.
+
+use crate::client::build_source_client;
+use crate::clock::Clock;
+use crate::config::{GlobalConfig, SourceConfig};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+
+/// A semaphore with the cap it was built for.
+type CappedSemaphore = (usize, Arc<Semaphore>);
+
+/// Last seen rate-limit window of one bucket: remaining requests and reset Unix timestamp.
+#[derive(Debug, Default)]
+struct RateLimitWindow {
+    remaining: Option<i64>,
+    reset_ts: Option<i64>,
+}
+
+/// Poll state that outlives a tick but is not persisted. Built once in main and passed to every tick
+/// (like the circuit and token caches), so each process, reload and test gets its own.
+#[derive(Default)]
+pub struct PollCaches {
+    /// HTTP client per source with the fingerprint of the config it was built from, so pooled connections
+    /// (and pool_idle_timeout_secs, tcp_keepalive_secs, max_connections) carry over between ticks.
+    clients: Mutex<HashMap<String, (String, reqwest::Client)>>,
+    /// Request semaphore for global.bulkhead.max_concurrent_requests_total.
+    global_requests: Mutex<Option<CappedSemaphore>>,
+    /// Per-source semaphores for resilience.max_connections, so overlapping polls of one source stay under the cap.
+    connections: Mutex<HashMap<String, CappedSemaphore>>,
+    /// Rate-limit windows per (source, bucket). The bucket is the value of rate_limit.headers.resource_header
+    /// ("" when unset or absent from the response).
+    rate_limit_buckets: Mutex<HashMap<(String, String), RateLimitWindow>>,
+    /// start_mode latest seeds not yet saved, by (source id, state key). Kept across failed ticks so retries
+    /// start from the same point.
+    start_seeds: Mutex<HashMap<(String, String), String>>,
+}
+
+/// Fresh, empty poll caches.
+pub fn new_poll_caches() -> Arc<PollCaches> {
+    Arc::new(PollCaches::default())
+}
+
+impl PollCaches {
+    /// Drop per-source entries (reload with restart_sources_on_sighup). The global request semaphore is kept;
+    /// it is rebuilt on its own when the cap changes.
+    pub fn clear_sources(&self) {
+        lock(&self.clients).clear();
+        lock(&self.connections).clear();
+        lock(&self.rate_limit_buckets).clear();
+        lock(&self.start_seeds).clear();
+    }
+
+    /// The source's cached HTTP client; built on first use and rebuilt when its url or resilience config changes.
+    pub(crate) fn source_client(
+        &self,
+        source_id: &str,
+        source: &SourceConfig,
+    ) -> anyhow::Result<reqwest::Client> {
+        let fingerprint =
+            serde_json::to_string(&(&source.url, &source.resilience)).unwrap_or_default();
+        let mut g = lock(&self.clients);
+        if let Some((f, client)) = g.get(source_id)
+            && *f == fingerprint
+        {
+            return Ok(client.clone());
+        }
+        let client = build_source_client(source)?;
+        g.insert(source_id.to_string(), (fingerprint, client.clone()));
+        Ok(client)
+    }
+
+    /// Semaphore for the global request cap, or None when uncapped; rebuilt when the cap changes (e.g. on reload).
+    pub(crate) fn global_request_semaphore(&self, global: &GlobalConfig) -> Option<Arc<Semaphore>> {
+        let cap = global
+            .bulkhead
+            .as_ref()
+            .and_then(|b| b.max_concurrent_requests_total)
+            .filter(|&n| n > 0)?;
+        let mut g = lock(&self.global_requests);
+        match g.as_ref() {
+            Some((c, s)) if *c == cap as usize => Some(s.clone()),
+            _ => {
+                let s = Arc::new(Semaphore::new(cap as usize));
+                *g = Some((cap as usize, s.clone()));
+                Some(s)
+            }
+        }
+    }
+
+    /// The source's max_connections semaphore, or None when uncapped; rebuilt when the cap changes.
+    pub(crate) fn connection_semaphore(
+        &self,
+        source_id: &str,
+        source: &SourceConfig,
+    ) -> Option<Arc<Semaphore>> {
+        let cap = source
+            .resilience
+            .as_ref()
+            .and_then(|r| r.max_connections)
+            .filter(|&n| n > 0)?;
+        let mut g = lock(&self.connections);
+        match g.get(source_id) {
+            Some((c, s)) if *c == cap => Some(s.clone()),
+            _ => {
+                let s = Arc::new(Semaphore::new(cap));
+                g.insert(source_id.to_string(), (cap, s.clone()));
+                Some(s)
+            }
+        }
+    }
+
+    /// Update the bucket's window from a response; headers missing from the response keep their last value.
+    pub(crate) fn record_rate_limit_bucket(
+        &self,
+        source_id: &str,
+        bucket: &str,
+        remaining: Option<i64>,
+        reset_ts: Option<i64>,
+    ) {
+        let mut buckets = lock(&self.rate_limit_buckets);
+        let entry = buckets
+            .entry((source_id.to_string(), bucket.to_string()))
+            .or_default();
+        if remaining.is_some() {
+            entry.remaining = remaining;
+        }
+        if reset_ts.is_some() {
+            entry.reset_ts = reset_ts;
+        }
+    }
+
+    /// Seconds to wait before the bucket's next request: until reset when remaining is 0 or 1, else 0.
+    pub(crate) fn rate_limit_bucket_wait_secs(
+        &self,
+        source_id: &str,
+        bucket: &str,
+        clock: &dyn Clock,
+    ) -> u64 {
+        match lock(&self.rate_limit_buckets).get(&(source_id.to_string(), bucket.to_string())) {
+            Some(RateLimitWindow {
+                remaining: Some(remaining),
+                reset_ts: Some(reset_ts),
+            }) if *remaining <= 1 => super::helpers::secs_until(*reset_ts, clock),
+            _ => 0,
+        }
+    }
+
+    /// The pending start_mode latest seed for this state key, or now when there is none yet.
+    pub(crate) fn pending_start_seed(
+        &self,
+        source_id: &str,
+        key: &str,
+        clock: &dyn Clock,
+    ) -> String {
+        lock(&self.start_seeds)
+            .entry((source_id.to_string(), key.to_string()))
+            .or_insert_with(|| super::helpers::start_mode_now(clock))
+            .clone()
+    }
+
+    /// Remove and return the pending start seed for this state key.
+    pub(crate) fn take_start_seed(&self, source_id: &str, key: &str) -> Option<String> {
+        lock(&self.start_seeds).remove(&(source_id.to_string(), key.to_string()))
+    }
+}
+
+fn lock<T>(m: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    m.lock().unwrap_or_else(|e| e.into_inner())
+}
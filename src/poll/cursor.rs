@@ -1,9 +1,9 @@
 use crate::circuit::{self, CircuitStore};
+use crate::clock::Clock;
 use crate::config::{
-    CheckpointTiming, CursorExpiredBehavior, GlobalConfig, HttpMethod, InvalidUtf8Behavior,
-    OnParseErrorBehavior, SourceConfig,
+    CheckpointTiming, CursorExpiredBehavior, GlobalConfig, HttpMethod, SourceConfig,
 };
-use crate::dedupe::{self, DedupeStore};
+use crate::dedupe::DedupeStore;
 use crate::dpop::DPoPKeyCache;
 use crate::metrics;
 use crate::oauth2::OAuth2TokenCache;
@@ -18,8 +18,11 @@ use std::time::{Duration, Instant};
 use tokio::sync::Semaphore;
 
 use super::ClientRateLimiter;
+use super::caches::PollCaches;
+use super::emit::{EmitCtx, emit_parsed_event};
 use super::helpers::*;
 use super::parse::*;
+use super::subrequest::SubrequestCtx;
 
 /// Cursor-in-body pagination: get cursor from response JSON path, pass as query param on next request.
 #[allow(clippy::too_many_arguments)]
@@ -31,6 +34,7 @@ pub(super) async fn poll_cursor_pagination(
     client: &reqwest::Client,
     cursor_param: &str,
     cursor_path: &str,
+    has_more_path: Option<&str>,
     max_pages: u32,
     circuit_store: CircuitStore,
     token_cache: OAuth2TokenCache,
@@ -40,13 +44,41 @@ pub(super) async fn poll_cursor_pagination(
     record_state: Option<Arc<RecordState>>,
     rate_limiter: Option<&Arc<ClientRateLimiter>>,
     request_semaphore: Option<Arc<Semaphore>>,
+    poll_caches: Arc<PollCaches>,
+    clock: &dyn Clock,
 ) -> anyhow::Result<()> {
     let start = Instant::now();
+    let emit_ctx = EmitCtx {
+        global,
+        clock,
+        source_id,
+        source,
+        dedupe_store: &dedupe_store,
+        event_sink: &event_sink,
+        sub: SubrequestCtx {
+            client,
+            token_cache: &token_cache,
+            dpop_key_cache: dpop_key_cache.as_ref(),
+            rate_limiter,
+            request_semaphore: &request_semaphore,
+            poll_caches: &poll_caches,
+        },
+    };
     let base_url = source.url.as_str();
     let mut cursor: Option<String> = store
         .get(source_id, "cursor")
         .await?
         .filter(|s| !s.is_empty());
+    if cursor.is_none()
+        && let Some(seed) = env_seed(source.initial_cursor_env.as_deref())
+    {
+        tracing::info!(
+            source = %source_id,
+            env = source.initial_cursor_env.as_deref().unwrap_or_default(),
+            "no saved cursor: starting from initial_cursor_env"
+        );
+        cursor = Some(seed);
+    }
     let mut page = 0u32;
     let mut total_events = 0u64;
     let mut total_bytes: u64 = 0;
@@ -79,7 +111,15 @@ pub(super) async fn poll_cursor_pagination(
                     (u.to_string(), None)
                 }
                 (HttpMethod::Get, None) => (
-                    url_with_first_request_params(&store, source_id, source, base_url).await?,
+                    url_with_first_request_params(
+                        &store,
+                        source_id,
+                        source,
+                        base_url,
+                        &poll_caches,
+                        clock,
+                    )
+                    .await?,
                     None,
                 ),
                 (HttpMethod::Post, Some(c)) => {
@@ -87,7 +127,15 @@ pub(super) async fn poll_cursor_pagination(
                     (base_url.to_string(), Some(body))
                 }
                 (HttpMethod::Post, None) => (
-                    url_with_first_request_params(&store, source_id, source, base_url).await?,
+                    url_with_first_request_params(
+                        &store,
+                        source_id,
+                        source,
+                        base_url,
+                        &poll_caches,
+                        clock,
+                    )
+                    .await?,
                     None,
                 ),
             };
@@ -103,61 +151,33 @@ pub(super) async fn poll_cursor_pagination(
         if let Some(limiter) = rate_limiter {
             limiter.until_ready().await;
         }
-        let _request_permit = match &request_semaphore {
-            Some(s) => Some(
-                s.acquire()
-                    .await
-                    .map_err(|e| anyhow::anyhow!("bulkhead request acquire: {}", e))?,
-            ),
-            None => None,
-        };
+        let _request_permit =
+            acquire_request_permits(global, source_id, source, &request_semaphore, &poll_caches)
+                .await?;
         let req_start = std::time::Instant::now();
-        let response = match execute_with_retry(
-            client,
-            source,
+        let response = record_request_outcome(
+            &circuit_store,
             source_id,
-            &url,
-            body_override.as_ref(),
-            source.resilience.as_ref().and_then(|r| r.retries.as_ref()),
-            source
-                .resilience
-                .as_ref()
-                .and_then(|r| r.rate_limit.as_ref()),
-            Some(&token_cache),
-            dpop_key_cache.as_ref(),
-            global.audit.as_ref(),
-        )
-        .await
-        {
-            Ok(r) => {
-                let success = r.status().as_u16() < 500;
-                if let Some(cb) = source
-                    .resilience
-                    .as_ref()
-                    .and_then(|r| r.circuit_breaker.as_ref())
-                {
-                    circuit::record_result(&circuit_store, source_id, cb, success).await;
-                }
-                metrics::record_request(
-                    source_id,
-                    status_class(r.status().as_u16()),
-                    req_start.elapsed().as_secs_f64(),
-                );
-                r
-            }
-            Err(e) => {
-                if let Some(cb) = source
+            source,
+            req_start,
+            execute_with_retry(
+                client,
+                source,
+                source_id,
+                &url,
+                body_override.as_ref(),
+                source.resilience.as_ref().and_then(|r| r.retries.as_ref()),
+                source
                     .resilience
                     .as_ref()
-                    .and_then(|r| r.circuit_breaker.as_ref())
-                {
-                    circuit::record_result(&circuit_store, source_id, cb, false).await;
-                }
-                metrics::record_request(source_id, "error", req_start.elapsed().as_secs_f64());
-                metrics::record_error(source_id);
-                return Err(e).context("http request");
-            }
-        };
+                    .and_then(|r| r.rate_limit.as_ref()),
+                Some(&token_cache),
+                dpop_key_cache.as_ref(),
+                global.audit.as_ref(),
+            )
+            .await,
+        )
+        .await?;
 
         maybe_adaptive_sleep_after_response(
             response.headers(),
@@ -166,6 +186,8 @@ pub(super) async fn poll_cursor_pagination(
                 .resilience
                 .as_ref()
                 .and_then(|r| r.rate_limit.as_ref()),
+            &poll_caches,
+            clock,
         )
         .await;
 
@@ -181,16 +203,19 @@ pub(super) async fn poll_cursor_pagination(
         let mut _streamed = false;
 
         #[cfg(feature = "streaming")]
-        if source.response_streaming.is_some() {
+        if streaming_parse_enabled(source) {
             use super::streaming;
             use crate::config::StreamingMode;
 
             let use_full = source.response_streaming == Some(StreamingMode::Full)
-                && source.on_invalid_utf8.is_none();
+                && source.on_invalid_utf8.is_none()
+                && source.body_decrypt.is_none()
+                && !is_content_encoded(&record_headers)
+                && !allows_empty_body(source, record_status);
 
             if use_full {
                 let resp = response.take().unwrap();
-                if !status.is_success() {
+                if !is_success_status(source, status.as_u16()) {
                     let body_bytes = read_body_with_limit(resp, source.max_response_bytes).await?;
                     if let Some(ref rs) = record_state {
                         rs.save(
@@ -203,11 +228,7 @@ pub(super) async fn poll_cursor_pagination(
                     }
                     let body_lossy = String::from_utf8_lossy(&body_bytes);
                     if cursor.is_some() && status.as_u16() >= 400 && status.as_u16() < 500 {
-                        let lower = body_lossy.to_lowercase();
-                        let is_expired = status.as_u16() == 410
-                            || lower.contains("expired")
-                            || lower.contains("invalid cursor")
-                            || lower.contains("cursor invalid");
+                        let is_expired = is_cursor_expired(source, status.as_u16(), &body_bytes);
                         if is_expired
                             && source.on_cursor_error == Some(CursorExpiredBehavior::Reset)
                         {
@@ -243,11 +264,18 @@ pub(super) async fn poll_cursor_pagination(
                     let event_value = match result {
                         Ok(v) => v,
                         Err(e) => {
-                            if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                                tracing::warn!(source = %source_id, error = %e, "parse error, stopping cursor pagination");
-                                return Ok(());
-                            }
-                            return Err(e).context("streaming parse element");
+                            handle_parse_error(
+                                global,
+                                source_id,
+                                source,
+                                record_url.as_str(),
+                                record_status,
+                                None,
+                                e,
+                                "parse error, stopping cursor pagination",
+                            )
+                            .context("streaming parse element")?;
+                            return Ok(());
                         }
                     };
                     let event_value = match streaming::unwrap_event_object(event_value, obj_path) {
@@ -263,22 +291,14 @@ pub(super) async fn poll_cursor_pagination(
                         );
                     }
                     if let Some(ref st) = source.state {
-                        update_max_timestamp_single(
-                            &mut watermark_max_ts,
-                            &event_value,
-                            &st.watermark_field,
-                        );
+                        update_watermark_single(&mut watermark_max_ts, &event_value, st);
                     }
-                    if let Some(d) = &source.dedupe {
-                        let id = event_id(&event_value, &d.id_path).unwrap_or_default();
-                        if dedupe::seen_and_add(&dedupe_store, source_id, id, d.capacity).await {
-                            continue;
-                        }
+                    if emit_parsed_event(&emit_ctx, &path, Some(&record_headers), event_value, None)
+                        .await?
+                    {
+                        total_events += 1;
+                        emitted_count += 1;
                     }
-                    total_events += 1;
-                    emitted_count += 1;
-                    let emitted = build_emitted_event(source, source_id, &path, event_value);
-                    emit_event_line(global, source_id, source, &event_sink, &emitted)?;
                 }
 
                 join_handle
@@ -289,7 +309,7 @@ pub(super) async fn poll_cursor_pagination(
                     .await
                     .map_err(|_| anyhow::anyhow!("metadata channel closed"))?
                     .context("parse metadata")?;
-                next_cursor = json_path_str(&metadata, cursor_path).filter(|s| !s.is_empty());
+                next_cursor = next_cursor_from(&metadata, cursor_path, has_more_path);
 
                 if let (Some(rs), Some(tp)) = (&record_state, &tee_path) {
                     let body = std::fs::read(tp).context("read tee file for recording")?;
@@ -315,14 +335,10 @@ pub(super) async fn poll_cursor_pagination(
                         &body_bytes,
                     )?;
                 }
-                if !status.is_success() {
+                if !is_success_status(source, status.as_u16()) {
                     let body_lossy = String::from_utf8_lossy(&body_bytes);
                     if cursor.is_some() && status.as_u16() >= 400 && status.as_u16() < 500 {
-                        let lower = body_lossy.to_lowercase();
-                        let is_expired = status.as_u16() == 410
-                            || lower.contains("expired")
-                            || lower.contains("invalid cursor")
-                            || lower.contains("cursor invalid");
+                        let is_expired = is_cursor_expired(source, status.as_u16(), &body_bytes);
                         if is_expired
                             && source.on_cursor_error == Some(CursorExpiredBehavior::Reset)
                         {
@@ -337,28 +353,45 @@ pub(super) async fn poll_cursor_pagination(
                     }
                     anyhow::bail!("http {} {}", status, body_lossy);
                 }
-                let parse_result = match streaming::parse_streaming(&body_bytes, source) {
-                    Ok(r) => r,
-                    Err(e) => {
-                        if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                            tracing::warn!(source = %source_id, error = %e, "parse error, stopping cursor pagination");
+                let body_bytes = maybe_decompress_body(source, &record_headers, body_bytes)?;
+                let body_bytes = maybe_decrypt_body(source, &record_headers, body_bytes)?;
+                total_bytes += body_bytes.len() as u64;
+                let parse_result =
+                    match streaming::parse_streaming(&body_bytes, source, record_status) {
+                        Ok(r) => r,
+                        Err(e) => {
+                            handle_parse_error(
+                                global,
+                                source_id,
+                                source,
+                                record_url.as_str(),
+                                record_status,
+                                Some(&body_bytes),
+                                e,
+                                "parse error, stopping cursor pagination",
+                            )
+                            .context("streaming parse")?;
                             return Ok(());
                         }
-                        return Err(e).context("streaming parse");
-                    }
-                };
-                next_cursor =
-                    json_path_str(parse_result.metadata(), cursor_path).filter(|s| !s.is_empty());
+                    };
+                next_cursor = next_cursor_from(parse_result.metadata(), cursor_path, has_more_path);
                 let obj_path = source.response_event_object_path.as_deref();
                 for result in parse_result.iter(&body_bytes) {
                     let event_value = match result {
                         Ok(v) => v,
                         Err(e) => {
-                            if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                                tracing::warn!(source = %source_id, error = %e, "parse error, stopping cursor pagination");
-                                return Ok(());
-                            }
-                            return Err(e).context("parse event element");
+                            handle_parse_error(
+                                global,
+                                source_id,
+                                source,
+                                record_url.as_str(),
+                                record_status,
+                                Some(&body_bytes),
+                                e,
+                                "parse error, stopping cursor pagination",
+                            )
+                            .context("parse event element")?;
+                            return Ok(());
                         }
                     };
                     let event_value = match streaming::unwrap_event_object(event_value, obj_path) {
@@ -374,22 +407,14 @@ pub(super) async fn poll_cursor_pagination(
                         );
                     }
                     if let Some(ref st) = source.state {
-                        update_max_timestamp_single(
-                            &mut watermark_max_ts,
-                            &event_value,
-                            &st.watermark_field,
-                        );
+                        update_watermark_single(&mut watermark_max_ts, &event_value, st);
                     }
-                    if let Some(d) = &source.dedupe {
-                        let id = event_id(&event_value, &d.id_path).unwrap_or_default();
-                        if dedupe::seen_and_add(&dedupe_store, source_id, id, d.capacity).await {
-                            continue;
-                        }
+                    if emit_parsed_event(&emit_ctx, &path, Some(&record_headers), event_value, None)
+                        .await?
+                    {
+                        total_events += 1;
+                        emitted_count += 1;
                     }
-                    total_events += 1;
-                    emitted_count += 1;
-                    let emitted = build_emitted_event(source, source_id, &path, event_value);
-                    emit_event_line(global, source_id, source, &event_sink, &emitted)?;
                 }
                 _streamed = true;
             }
@@ -407,14 +432,10 @@ pub(super) async fn poll_cursor_pagination(
                     &body_bytes,
                 )?;
             }
-            if !status.is_success() {
+            if !is_success_status(source, status.as_u16()) {
                 let body_lossy = String::from_utf8_lossy(&body_bytes);
                 if cursor.is_some() && status.as_u16() >= 400 && status.as_u16() < 500 {
-                    let lower = body_lossy.to_lowercase();
-                    let is_expired = status.as_u16() == 410
-                        || lower.contains("expired")
-                        || lower.contains("invalid cursor")
-                        || lower.contains("cursor invalid");
+                    let is_expired = is_cursor_expired(source, status.as_u16(), &body_bytes);
                     if is_expired && source.on_cursor_error == Some(CursorExpiredBehavior::Reset) {
                         store_set_or_skip(&store, source_id, source, global, "cursor", "").await?;
                         tracing::warn!(
@@ -426,6 +447,8 @@ pub(super) async fn poll_cursor_pagination(
                 }
                 anyhow::bail!("http {} {}", status, body_lossy);
             }
+            let body_bytes = maybe_decompress_body(source, &record_headers, body_bytes)?;
+            let body_bytes = maybe_decrypt_body(source, &record_headers, body_bytes)?;
             total_bytes += body_bytes.len() as u64;
             if let Some(limit) = max_bytes
                 && total_bytes > limit
@@ -438,65 +461,81 @@ pub(super) async fn poll_cursor_pagination(
                 );
                 break;
             }
-            let value: serde_json::Value = match source.on_invalid_utf8 {
-                Some(InvalidUtf8Behavior::Replace) | Some(InvalidUtf8Behavior::Escape) => {
-                    let body = bytes_to_string(&body_bytes, source.on_invalid_utf8)?;
-                    match serde_json::from_str(&body) {
-                        Ok(v) => v,
-                        Err(e) => {
-                            if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                                tracing::warn!(source = %source_id, error = %e, "parse error, stopping cursor pagination");
-                                return Ok(());
-                            }
-                            return Err(e).context("parse response json");
-                        }
-                    }
+            let value = match parse_response_value(
+                &body_bytes,
+                source,
+                Some(&record_headers),
+                record_status,
+            ) {
+                Ok(v) => v,
+                Err(e) => {
+                    handle_parse_error(
+                        global,
+                        source_id,
+                        source,
+                        record_url.as_str(),
+                        record_status,
+                        Some(&body_bytes),
+                        e,
+                        "parse error, stopping cursor pagination",
+                    )?;
+                    return Ok(());
                 }
-                _ => match serde_json::from_slice(&body_bytes) {
-                    Ok(v) => v,
-                    Err(e) => {
-                        if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                            tracing::warn!(source = %source_id, error = %e, "parse error, stopping cursor pagination");
-                            return Ok(());
-                        }
-                        return Err(e).context("parse response json");
-                    }
-                },
             };
-            next_cursor = json_path_str(&value, cursor_path).filter(|s| !s.is_empty());
-            let events = match parse_events_from_value_for_source(value, source) {
+            next_cursor = next_cursor_from(&value, cursor_path, has_more_path);
+            let (events, kinds) = match parse_kinded_events_from_value_for_source(value, source) {
                 Ok(ev) => ev,
                 Err(e) => {
-                    if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                        tracing::warn!(source = %source_id, error = %e, "parse error, stopping cursor pagination");
-                        return Ok(());
-                    }
-                    return Err(e).context("extract events");
+                    handle_parse_error(
+                        global,
+                        source_id,
+                        source,
+                        record_url.as_str(),
+                        record_status,
+                        Some(&body_bytes),
+                        e,
+                        "parse error, stopping cursor pagination",
+                    )
+                    .context("extract events")?;
+                    return Ok(());
                 }
             };
             if let Some(ref inc) = source.incremental_from {
                 update_max_timestamp(&mut incremental_max_ts, &events, &inc.event_timestamp_path);
             }
             if let Some(ref st) = source.state {
-                update_max_timestamp(&mut watermark_max_ts, &events, &st.watermark_field);
+                update_watermark(&mut watermark_max_ts, &events, st);
             }
             event_count = events.len();
-            for event_value in events {
-                if let Some(d) = &source.dedupe {
-                    let id = event_id(&event_value, &d.id_path).unwrap_or_default();
-                    if dedupe::seen_and_add(&dedupe_store, source_id, id, d.capacity).await {
-                        continue;
-                    }
+            for (i, event_value) in events.into_iter().enumerate() {
+                if emit_parsed_event(
+                    &emit_ctx,
+                    &path,
+                    Some(&record_headers),
+                    event_value,
+                    kinds.get(i),
+                )
+                .await?
+                {
+                    total_events += 1;
+                    emitted_count += 1;
                 }
-                total_events += 1;
-                emitted_count += 1;
-                let emitted = build_emitted_event(source, source_id, &path, event_value);
-                emit_event_line(global, source_id, source, &event_sink, &emitted)?;
             }
         }
         metrics::record_events(source_id, emitted_count);
         let checkpoint_per_page = source.checkpoint != Some(CheckpointTiming::EndOfTick);
         match next_cursor {
+            Some(c) if cursor.as_deref() == Some(c.as_str()) => {
+                // Saved cursor is unchanged, so the next tick retries from here.
+                pending_cursor = Some(c);
+                tracing::warn!(
+                    source = %source_id,
+                    page = page,
+                    "pagination loop: next cursor equals the current one, stopping"
+                );
+                metrics::record_pagination_loop(source_id);
+                break;
+            }
             Some(c) => {
                 if checkpoint_per_page {
                     store_set_or_skip(&store, source_id, source, global, "cursor", &c).await?;
@@ -533,5 +572,30 @@ pub(super) async fn poll_cursor_pagination(
     }
     store_incremental_from_after_poll(&store, source_id, source, global, incremental_max_ts).await;
     store_watermark_after_poll(&store, source_id, source, global, watermark_max_ts).await;
+    emit_tick_summary(
+        global,
+        source_id,
+        source,
+        &event_sink,
+        page,
+        total_events,
+        total_bytes,
+        start,
+        clock,
+    )?;
     Ok(())
 }
+
+/// Next cursor from response JSON: non-empty string at cursor_path, unless has_more_path resolves to false.
+fn next_cursor_from(
+    value: &serde_json::Value,
+    cursor_path: &str,
+    has_more_path: Option<&str>,
+) -> Option<String> {
+    if let Some(p) = has_more_path
+        && json_path_bool(value, p) == Some(false)
+    {
+        return None;
+    }
+    json_path_str(value, cursor_path).filter(|s| !s.is_empty())
+}
@@ -0,0 +1,74 @@
+//! Per-event pipeline shared by every pagination strategy: dedupe, subrequest enrichment, envelope, emit.
+
+use crate::clock::Clock;
+use crate::config::{GlobalConfig, SourceConfig};
+use crate::dedupe::{self, DedupeStore};
+use crate::output::EventSink;
+use std::sync::Arc;
+
+use super::helpers::emit_event_line;
+use super::parse::{build_emitted_event, event_id};
+use super::subrequest::{SubrequestCtx, enrich_event};
+
+/// What a poll needs to turn parsed events into emitted lines.
+pub(super) struct EmitCtx<'a> {
+    pub clock: &'a dyn Clock,
+    pub global: &'a GlobalConfig,
+    pub source_id: &'a str,
+    pub source: &'a SourceConfig,
+    pub dedupe_store: &'a DedupeStore,
+    pub event_sink: &'a Arc<dyn EventSink>,
+    pub sub: SubrequestCtx<'a>,
+}
+
+/// Dedupe, enrich and emit one parsed event. Ok(false) when it was dropped (duplicate, schema violation,
+/// oversized line skipped) instead of written.
+/// When enrichment or the write fails, the event's dedupe id is forgotten so the retried tick emits it.
+pub(super) async fn emit_parsed_event(
+    ctx: &EmitCtx<'_>,
+    path: &str,
+    headers: Option<&reqwest::header::HeaderMap>,
+    event_value: serde_json::Value,
+    kind: Option<&String>,
+) -> anyhow::Result<bool> {
+    let dedupe_id = match &ctx.source.dedupe {
+        Some(d) => {
+            let id = event_id(&event_value, &d.id_path).unwrap_or_default();
+            if dedupe::seen_and_add(ctx.dedupe_store, ctx.source_id, id.clone(), d.capacity).await {
+                return Ok(false);
+            }
+            Some(id)
+        }
+        None => None,
+    };
+    let result = async {
+        let event_value =
+            enrich_event(&ctx.sub, ctx.global, ctx.source_id, ctx.source, event_value).await?;
+        let mut emitted = build_emitted_event(
+            ctx.source,
+            ctx.source_id,
+            path,
+            headers,
+            event_value,
+            dedupe_id.as_deref(),
+            ctx.clock,
+        );
+        if let Some(kind) = kind {
+            emitted = emitted.with_kind(kind.clone());
+        }
+        emit_event_line(
+            ctx.global,
+            ctx.source_id,
+            ctx.source,
+            ctx.event_sink,
+            emitted,
+        )
+    }
+    .await;
+    if result.is_err()
+        && let Some(id) = &dedupe_id
+    {
+        dedupe::forget(ctx.dedupe_store, ctx.source_id, id).await;
+    }
+    result
+}
@@ -0,0 +1,248 @@
+use crate::circuit::{self, CircuitStore};
+use crate::clock::Clock;
+use crate::config::{GlobalConfig, SourceConfig};
+use crate::dedupe::DedupeStore;
+use crate::dpop::DPoPKeyCache;
+use crate::metrics;
+use crate::oauth2::OAuth2TokenCache;
+use crate::output::EventSink;
+use crate::retry::execute_with_retry;
+use crate::state::StateStore;
+use anyhow::Context;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+use super::ClientRateLimiter;
+use super::caches::PollCaches;
+use super::emit::{EmitCtx, emit_parsed_event};
+use super::helpers::*;
+use super::subrequest::SubrequestCtx;
+
+/// How often an open stream saves its watermark / incremental state.
+const STREAM_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Long-lived stream (source.streaming): one request whose body stays open, each NDJSON line emitted as it
+/// arrives until the server closes the connection, the request timeout fires, or `deadline` (poll_tick_secs)
+/// passes. Any of these ends the tick cleanly; the next tick reconnects with the saved watermark.
+/// Watermark / incremental state is also checkpointed every [`STREAM_CHECKPOINT_INTERVAL`] while the stream
+/// is open. Delivery is at-least-once: if the stream fails, events after the last checkpoint are emitted again.
+#[allow(clippy::too_many_arguments)]
+pub(super) async fn poll_event_stream(
+    store: Arc<dyn StateStore>,
+    source_id: &str,
+    source: &SourceConfig,
+    global: &GlobalConfig,
+    client: &reqwest::Client,
+    circuit_store: CircuitStore,
+    token_cache: OAuth2TokenCache,
+    dpop_key_cache: Option<DPoPKeyCache>,
+    dedupe_store: DedupeStore,
+    event_sink: Arc<dyn EventSink>,
+    rate_limiter: Option<&Arc<ClientRateLimiter>>,
+    request_semaphore: Option<Arc<Semaphore>>,
+    poll_caches: Arc<PollCaches>,
+    deadline: Option<Duration>,
+    clock: &dyn Clock,
+) -> anyhow::Result<()> {
+    let start = Instant::now();
+    let deadline = deadline.map(|d| tokio::time::Instant::now() + d);
+    if let Some(cb) = source
+        .resilience
+        .as_ref()
+        .and_then(|r| r.circuit_breaker.as_ref())
+    {
+        circuit::allow_request(&circuit_store, source_id, cb)
+            .await
+            .context("circuit open")?;
+    }
+    if let Some(limiter) = rate_limiter {
+        limiter.until_ready().await;
+    }
+    let url =
+        url_with_first_request_params(&store, source_id, source, &source.url, &poll_caches, clock)
+            .await?;
+    let request_permit =
+        acquire_request_permits(global, source_id, source, &request_semaphore, &poll_caches)
+            .await?;
+    let req_start = std::time::Instant::now();
+    let mut response = record_request_outcome(
+        &circuit_store,
+        source_id,
+        source,
+        req_start,
+        execute_with_retry(
+            client,
+            source,
+            source_id,
+            &url,
+            None,
+            source.resilience.as_ref().and_then(|r| r.retries.as_ref()),
+            source
+                .resilience
+                .as_ref()
+                .and_then(|r| r.rate_limit.as_ref()),
+            Some(&token_cache),
+            dpop_key_cache.as_ref(),
+            global.audit.as_ref(),
+        )
+        .await,
+    )
+    .await?;
+    // Connected: the open body should not count against the request caps for the rest of the stream.
+    drop(request_permit);
+
+    let record_url = response.url().clone();
+    let record_status = response.status().as_u16();
+    let record_headers = response.headers().clone();
+    let path = record_url.path().to_string();
+    if !is_success_status(source, record_status) {
+        let body_bytes = read_body_with_limit(response, source.max_response_bytes).await?;
+        let body_str = String::from_utf8_lossy(&body_bytes);
+        anyhow::bail!("http {} {}", record_status, body_str);
+    }
+
+    let mut incremental_max_ts: Option<String> = None;
+    let mut watermark_max_ts: Option<String> = None;
+    let mut emitted_count = 0u64;
+    let mut total_bytes = 0u64;
+    let mut line_no = 0u64;
+    let mut pending: Vec<u8> = Vec::new();
+    let mut lines: Vec<Vec<u8>> = Vec::new();
+    let mut ended = false;
+    let mut last_checkpoint = Instant::now();
+    let emit_ctx = EmitCtx {
+        clock,
+        global,
+        source_id,
+        source,
+        dedupe_store: &dedupe_store,
+        event_sink: &event_sink,
+        sub: SubrequestCtx {
+            client,
+            token_cache: &token_cache,
+            dpop_key_cache: dpop_key_cache.as_ref(),
+            rate_limiter,
+            request_semaphore: &request_semaphore,
+            poll_caches: &poll_caches,
+        },
+    };
+
+    while !ended {
+        let next = match deadline {
+            Some(d) => match tokio::time::timeout_at(d, response.chunk()).await {
+                Ok(r) => r,
+                Err(_) => {
+                    tracing::debug!(source = %source_id, "stream: poll tick deadline reached, disconnecting");
+                    Ok(None)
+                }
+            },
+            None => response.chunk().await,
+        };
+        match next {
+            Ok(Some(chunk)) => {
+                total_bytes += chunk.len() as u64;
+                pending.extend_from_slice(&chunk);
+                while let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+                    lines.push(pending.drain(..=pos).collect());
+                }
+                if let Some(limit) = source.max_response_bytes
+                    && pending.len() as u64 > limit
+                {
+                    anyhow::bail!(
+                        "stream line size {} exceeds max_response_bytes {}",
+                        pending.len(),
+                        limit
+                    );
+                }
+            }
+            Ok(None) => ended = true,
+            Err(e) if e.is_timeout() => {
+                tracing::debug!(source = %source_id, "stream: request timeout reached, disconnecting");
+                ended = true;
+            }
+            Err(e) => return Err(e).context("read stream chunk"),
+        }
+        if ended && !pending.is_empty() {
+            lines.push(std::mem::take(&mut pending));
+        }
+
+        let mut batch_emitted = 0u64;
+        for raw in lines.drain(..) {
+            let text = bytes_to_string(&raw, source.on_invalid_utf8)?;
+            let line = text.trim_end_matches(['\n', '\r']);
+            if line.trim().is_empty() {
+                continue;
+            }
+            line_no += 1;
+            let event_value: serde_json::Value = match serde_json::from_str(line)
+                .with_context(|| format!("parse stream line {}", line_no))
+            {
+                Ok(v) => v,
+                Err(e) => {
+                    handle_parse_error(
+                        global,
+                        source_id,
+                        source,
+                        record_url.as_str(),
+                        record_status,
+                        Some(&raw),
+                        e,
+                        "parse error, skipping line",
+                    )?;
+                    continue;
+                }
+            };
+            if let Some(ref inc) = source.incremental_from {
+                update_max_timestamp_single(
+                    &mut incremental_max_ts,
+                    &event_value,
+                    &inc.event_timestamp_path,
+                );
+            }
+            if let Some(ref st) = source.state {
+                update_watermark_single(&mut watermark_max_ts, &event_value, st);
+            }
+            if emit_parsed_event(&emit_ctx, &path, Some(&record_headers), event_value, None).await?
+            {
+                batch_emitted += 1;
+            }
+        }
+        emitted_count += batch_emitted;
+        metrics::record_events(source_id, batch_emitted);
+        if last_checkpoint.elapsed() >= STREAM_CHECKPOINT_INTERVAL {
+            store_incremental_from_after_poll(
+                &store,
+                source_id,
+                source,
+                global,
+                incremental_max_ts.clone(),
+            )
+            .await;
+            store_watermark_after_poll(&store, source_id, source, global, watermark_max_ts.clone())
+                .await;
+            last_checkpoint = Instant::now();
+        }
+    }
+
+    store_incremental_from_after_poll(&store, source_id, source, global, incremental_max_ts).await;
+    store_watermark_after_poll(&store, source_id, source, global, watermark_max_ts).await;
+    tracing::info!(
+        source = %source_id,
+        events = emitted_count,
+        duration_ms = start.elapsed().as_millis(),
+        "stream closed"
+    );
+    emit_tick_summary(
+        global,
+        source_id,
+        source,
+        &event_sink,
+        1,
+        emitted_count,
+        total_bytes,
+        start,
+        clock,
+    )?;
+    Ok(())
+}
@@ -1,6 +1,10 @@
+use super::caches::PollCaches;
+use crate::circuit::{self, CircuitStore};
+use crate::clock::Clock;
 use crate::config::{
-    GlobalConfig, InvalidUtf8Behavior, MaxEventBytesBehavior, OnStateWriteErrorBehavior,
-    RateLimitConfig, SourceConfig,
+    GlobalConfig, InvalidUtf8Behavior, MaxEventBytesBehavior, OnParseErrorBehavior,
+    OnStateWriteErrorBehavior, QueryEncoding, RateLimitConfig, RequestIdScheme, SourceConfig,
+    SourceStateConfig, StartMode,
 };
 use crate::event::EmittedEvent;
 use crate::metrics;
@@ -8,10 +12,96 @@ use crate::output::EventSink;
 use crate::retry::rate_limit_info_from_headers;
 use crate::state::StateStore;
 use anyhow::Context;
-use chrono::Utc;
 use futures_util::StreamExt;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Permits held for the duration of one HTTP request (per-source bulkhead, source connection cap, then global
+/// request cap).
+pub(crate) struct RequestPermits {
+    _source: Option<OwnedSemaphorePermit>,
+    _connection: Option<OwnedSemaphorePermit>,
+    _global: Option<OwnedSemaphorePermit>,
+}
+
+/// Acquire the source's request permit (when capped), its resilience.max_connections permit, and the
+/// global.bulkhead.max_concurrent_requests_total permit.
+pub(crate) async fn acquire_request_permits(
+    global: &GlobalConfig,
+    source_id: &str,
+    source_config: &SourceConfig,
+    request_semaphore: &Option<Arc<Semaphore>>,
+    poll_caches: &PollCaches,
+) -> anyhow::Result<RequestPermits> {
+    let source = match request_semaphore {
+        Some(s) => Some(
+            s.clone()
+                .acquire_owned()
+                .await
+                .map_err(|e| anyhow::anyhow!("bulkhead request acquire: {}", e))?,
+        ),
+        None => None,
+    };
+    let connection = match poll_caches.connection_semaphore(source_id, source_config) {
+        Some(s) => Some(
+            s.acquire_owned()
+                .await
+                .map_err(|e| anyhow::anyhow!("max_connections acquire: {}", e))?,
+        ),
+        None => None,
+    };
+    let global = match poll_caches.global_request_semaphore(global) {
+        Some(s) => Some(
+            s.acquire_owned()
+                .await
+                .map_err(|e| anyhow::anyhow!("bulkhead global request acquire: {}", e))?,
+        ),
+        None => None,
+    };
+    Ok(RequestPermits {
+        _source: source,
+        _connection: connection,
+        _global: global,
+    })
+}
+
+/// Book-keep one source request once `execute_with_retry` returns: circuit breaker result (5xx and transport
+/// errors count as failures), request count and latency, and the error counter on failure.
+pub(crate) async fn record_request_outcome(
+    circuit_store: &CircuitStore,
+    source_id: &str,
+    source: &SourceConfig,
+    req_start: std::time::Instant,
+    result: anyhow::Result<reqwest::Response>,
+) -> anyhow::Result<reqwest::Response> {
+    let cb = source
+        .resilience
+        .as_ref()
+        .and_then(|r| r.circuit_breaker.as_ref());
+    match result {
+        Ok(r) => {
+            if let Some(cb) = cb {
+                circuit::record_result(circuit_store, source_id, cb, r.status().as_u16() < 500)
+                    .await;
+            }
+            super::record_request(
+                source_id,
+                status_class(r.status().as_u16()),
+                req_start.elapsed().as_secs_f64(),
+            );
+            Ok(r)
+        }
+        Err(e) => {
+            if let Some(cb) = cb {
+                circuit::record_result(circuit_store, source_id, cb, false).await;
+            }
+            super::record_request(source_id, "error", req_start.elapsed().as_secs_f64());
+            metrics::record_error(source_id);
+            Err(e).context("http request")
+        }
+    }
+}
 
 /// Convert response body bytes to string; apply on_invalid_utf8 policy (replace/escape/fail).
 pub(crate) fn bytes_to_string(
@@ -27,6 +117,176 @@ pub(crate) fn bytes_to_string(
     }
 }
 
+/// When global.emit_tick_summary is set, emit one envelope for this poll with `meta.summary: true`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn emit_tick_summary(
+    global: &GlobalConfig,
+    source_id: &str,
+    source: &SourceConfig,
+    event_sink: &Arc<dyn EventSink>,
+    pages: u32,
+    events: u64,
+    bytes: u64,
+    start: std::time::Instant,
+    clock: &dyn Clock,
+) -> anyhow::Result<()> {
+    if !global.emit_tick_summary {
+        return Ok(());
+    }
+    let endpoint = reqwest::Url::parse(&source.url)
+        .map(|u| u.path().to_string())
+        .unwrap_or_default();
+    let emitted = EmittedEvent::new(
+        clock.now().to_rfc3339(),
+        effective_source_label(source, source_id),
+        endpoint,
+        serde_json::json!({
+            "pages": pages,
+            "events": events,
+            "bytes": bytes,
+            "duration_ms": start.elapsed().as_millis() as u64,
+        }),
+    )
+    .with_summary();
+    emit_event_line(global, source_id, source, event_sink, emitted).map(|_| ())
+}
+
+/// Emit one envelope for a circuit breaker state transition with `meta.circuit_state` set to the new state.
+pub(crate) fn emit_circuit_event(
+    global: &GlobalConfig,
+    source_id: &str,
+    source: &SourceConfig,
+    event_sink: &Arc<dyn EventSink>,
+    from: &str,
+    to: &str,
+    clock: &dyn Clock,
+) -> anyhow::Result<()> {
+    let endpoint = reqwest::Url::parse(&source.url)
+        .map(|u| u.path().to_string())
+        .unwrap_or_default();
+    let emitted = EmittedEvent::new(
+        clock.now().to_rfc3339(),
+        effective_source_label(source, source_id),
+        endpoint,
+        serde_json::json!({
+            "circuit_state": to,
+            "previous": from,
+        }),
+    )
+    .with_circuit_state(to.to_string());
+    emit_event_line(global, source_id, source, event_sink, emitted).map(|_| ())
+}
+
+/// Record a response that failed to parse in `global.dead_letter` (no-op when unset), with the URL redacted the way
+/// the audit log does. `body` is None when it was streamed rather than buffered.
+pub(crate) fn dead_letter(
+    global: &GlobalConfig,
+    source_id: &str,
+    source: &SourceConfig,
+    url: &str,
+    status: u16,
+    error: &anyhow::Error,
+    body: Option<&[u8]>,
+) {
+    let url = crate::audit::redact_url_with(url, crate::client::api_key_query_param(source));
+    crate::dead_letter::record(
+        global.dead_letter.as_ref(),
+        source_id,
+        &url,
+        status,
+        error,
+        body,
+    );
+}
+
+/// A response (or one element of it) failed to parse: dead-letter it, then apply `on_parse_error`.
+/// Ok means skip (logged with `skip_msg`); Err is the error for the caller to propagate.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn handle_parse_error(
+    global: &GlobalConfig,
+    source_id: &str,
+    source: &SourceConfig,
+    url: &str,
+    status: u16,
+    body: Option<&[u8]>,
+    error: anyhow::Error,
+    skip_msg: &str,
+) -> anyhow::Result<()> {
+    dead_letter(global, source_id, source, url, status, &error, body);
+    if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
+        tracing::warn!(source = %source_id, error = %error, "{}", skip_msg);
+        return Ok(());
+    }
+    Err(error)
+}
+
+/// Success status for this source: any 2xx or one of its `accept_status_codes`.
+pub(crate) fn is_success_status(source: &SourceConfig, status: u16) -> bool {
+    (200..300).contains(&status) || source.accept_status_codes.contains(&status)
+}
+
+/// Statuses whose body may be empty: 204 No Content and the source's `accept_status_codes`.
+pub(crate) fn allows_empty_body(source: &SourceConfig, status: u16) -> bool {
+    status == 204 || source.accept_status_codes.contains(&status)
+}
+
+/// True when a response carries no events: a whitespace-only body on a status from [`allows_empty_body`].
+/// A blank body on any other status is left to the parser, which fails on it.
+pub(crate) fn is_empty_success(source: &SourceConfig, status: u16, body_bytes: &[u8]) -> bool {
+    allows_empty_body(source, status) && body_bytes.iter().all(u8::is_ascii_whitespace)
+}
+
+/// Whether a 4xx response to a cursor request means the cursor expired. 410 always does; otherwise the
+/// source's `cursor_error_path`/`cursor_error_values` are checked when set, else the body is searched for
+/// "expired" / "invalid cursor" / "cursor invalid".
+pub(crate) fn is_cursor_expired(source: &SourceConfig, status: u16, body: &[u8]) -> bool {
+    if status == 410 {
+        return true;
+    }
+    if let Some(path) = source.cursor_error_path.as_deref() {
+        let Ok(value) = serde_json::from_slice::<serde_json::Value>(body) else {
+            return false;
+        };
+        return match value_at_path_as_string(&value, path) {
+            Some(v) => {
+                source.cursor_error_values.is_empty() || source.cursor_error_values.contains(&v)
+            }
+            None => false,
+        };
+    }
+    let lower = String::from_utf8_lossy(body).to_lowercase();
+    lower.contains("expired")
+        || lower.contains("invalid cursor")
+        || lower.contains("cursor invalid")
+}
+
+/// Apply the source's `body_decrypt` (when configured) to a successful response body before parsing.
+pub(crate) fn maybe_decrypt_body(
+    source: &SourceConfig,
+    headers: &reqwest::header::HeaderMap,
+    body: Vec<u8>,
+) -> anyhow::Result<Vec<u8>> {
+    match &source.body_decrypt {
+        Some(cfg) => crate::decrypt::decrypt_body(cfg, headers, &body),
+        None => Ok(body),
+    }
+}
+
+/// Decode a gzip/deflate `Content-Encoding` response body, enforcing the source's `compression` limits.
+pub(crate) fn maybe_decompress_body(
+    source: &SourceConfig,
+    headers: &reqwest::header::HeaderMap,
+    body: Vec<u8>,
+) -> anyhow::Result<Vec<u8>> {
+    crate::decompress::decompress_body(source.compression.as_ref(), headers, body)
+}
+
+/// True when the response carries a non-identity Content-Encoding (body must be buffered and decoded).
+#[cfg_attr(not(feature = "streaming"), allow(dead_code))]
+pub(crate) fn is_content_encoded(headers: &reqwest::header::HeaderMap) -> bool {
+    crate::decompress::content_encoding(headers).is_some()
+}
+
 /// Stream response body with early abort when `max_bytes` is exceeded.
 /// Checks Content-Length upfront when available, then enforces the limit chunk-by-chunk.
 pub(crate) async fn read_body_with_limit(
@@ -44,20 +304,66 @@ pub(crate) async fn read_body_with_limit(
     let mut buf = Vec::with_capacity(capacity);
     while let Some(chunk) = stream.next().await {
         let chunk = chunk.context("read body chunk")?;
-        buf.extend_from_slice(&chunk);
+        // Check before copying so an oversized chunk is never buffered; returning drops the stream
+        // and closes the connection without reading the rest of the body.
+        let total = buf.len() as u64 + chunk.len() as u64;
         if let Some(limit) = max_bytes
-            && buf.len() as u64 > limit
+            && total > limit
         {
             anyhow::bail!(
                 "response body size {} exceeds max_response_bytes {}",
-                buf.len(),
+                total,
                 limit
             );
         }
+        buf.extend_from_slice(&chunk);
     }
     Ok(buf)
 }
 
+/// New request id for a hook poll (hook context requestId, meta.request_id on its events), per global.request_id_scheme.
+#[cfg_attr(not(feature = "hooks"), allow(dead_code))]
+pub(crate) fn new_request_id(scheme: RequestIdScheme) -> String {
+    match scheme {
+        RequestIdScheme::Monotonic => {
+            static LAST: std::sync::atomic::AtomicI64 = std::sync::atomic::AtomicI64::new(0);
+            let now = chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0);
+            let prev = LAST
+                .fetch_update(
+                    std::sync::atomic::Ordering::Relaxed,
+                    std::sync::atomic::Ordering::Relaxed,
+                    |last| Some(now.max(last + 1)),
+                )
+                .unwrap_or(now);
+            format!("helr-{}", now.max(prev + 1))
+        }
+        RequestIdScheme::Uuid => {
+            let mut b: [u8; 16] = rand::random();
+            b[6] = (b[6] & 0x0f) | 0x40; // version 4
+            b[8] = (b[8] & 0x3f) | 0x80; // RFC 4122 variant
+            let hex: String = b.iter().map(|x| format!("{:02x}", x)).collect();
+            format!(
+                "{}-{}-{}-{}-{}",
+                &hex[0..8],
+                &hex[8..12],
+                &hex[12..16],
+                &hex[16..20],
+                &hex[20..32]
+            )
+        }
+        RequestIdScheme::Ulid => {
+            const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+            let ms = chrono::Utc::now().timestamp_millis().max(0) as u128 & ((1 << 48) - 1);
+            let random = rand::random::<u128>() & ((1 << 80) - 1);
+            let value = (ms << 80) | random;
+            (0..26)
+                .rev()
+                .map(|i| CROCKFORD[((value >> (i * 5)) & 0x1f) as usize] as char)
+                .collect()
+        }
+    }
+}
+
 /// Value for the producer label in NDJSON: source_label_value if set, else the config source key.
 pub(crate) fn effective_source_label(source: &SourceConfig, source_id: &str) -> String {
     source
@@ -67,7 +373,7 @@ pub(crate) fn effective_source_label(source: &SourceConfig, source_id: &str) ->
         .to_string()
 }
 
-/// Key for the producer label in NDJSON: source override, else global, else "source".
+/// Key for the producer label in NDJSON: source override, else global, else envelope_keys.source, else "source".
 pub(crate) fn effective_source_label_key<'a>(
     global: &'a GlobalConfig,
     source: &'a SourceConfig,
@@ -76,19 +382,63 @@ pub(crate) fn effective_source_label_key<'a>(
         .source_label_key
         .as_deref()
         .or(global.source_label_key.as_deref())
+        .or(global
+            .envelope_keys
+            .as_ref()
+            .and_then(|k| k.source.as_deref()))
         .unwrap_or("source")
 }
 
-/// Emit one event line; enforce max_line_bytes, record output errors.
+/// This machine's hostname for meta.collector_host (and the default instance_id); "unknown" if unavailable.
+pub(crate) fn collector_host() -> String {
+    static HOST: std::sync::LazyLock<String> = std::sync::LazyLock::new(|| {
+        sysinfo::System::host_name().unwrap_or_else(|| "unknown".to_string())
+    });
+    HOST.clone()
+}
+
+/// Emit one event line; drop events failing schema_file, enforce max_line_bytes, record output errors.
+/// Ok(false) when the event was dropped (schema violation, `max_line_bytes_behavior: skip`) rather than written.
 pub(crate) fn emit_event_line(
     global: &GlobalConfig,
     source_id: &str,
     source: &SourceConfig,
     event_sink: &Arc<dyn EventSink>,
-    emitted: &EmittedEvent,
-) -> anyhow::Result<()> {
+    emitted: EmittedEvent,
+) -> anyhow::Result<bool> {
+    if let Some(path) = &source.schema_file
+        && emitted.meta.summary.is_none()
+        && emitted.meta.circuit_state.is_none()
+        && let Err(e) = crate::schema::validate(path, &emitted.event)
+    {
+        tracing::warn!(source = %source_id, error = %e, "event failed schema validation, dropping");
+        metrics::record_event_dropped(source_id, "schema_violation");
+        let body = serde_json::to_vec(&emitted.event).unwrap_or_default();
+        dead_letter(
+            global,
+            source_id,
+            source,
+            &emitted.endpoint,
+            0,
+            &e,
+            Some(&body),
+        );
+        return Ok(false);
+    }
+    let emitted = if global.include_instance_id {
+        let host = collector_host();
+        let instance = global.instance_id.clone().unwrap_or_else(|| host.clone());
+        emitted.with_instance(instance, host)
+    } else {
+        emitted
+    };
+    let emitted = match &global.schema_version {
+        Some(v) => emitted.with_schema_version(v.clone()),
+        None => emitted,
+    };
+    let counts_as_event = emitted.meta.summary.is_none() && emitted.meta.circuit_state.is_none();
     let label_key = effective_source_label_key(global, source);
-    let line = emitted.to_ndjson_line_with_label_key(label_key)?;
+    let line = emitted.to_ndjson_line_with_keys(label_key, global.envelope_keys.as_ref())?;
     if let Some(max) = source.max_line_bytes
         && line.len() as u64 > max
     {
@@ -113,7 +463,10 @@ pub(crate) fn emit_event_line(
                     .inspect_err(|_e| {
                         metrics::record_output_error(source_id);
                     })?;
-                return Ok(());
+                if counts_as_event {
+                    super::EVENTS_EMITTED.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                }
+                return Ok(true);
             }
             MaxEventBytesBehavior::Skip => {
                 tracing::warn!(
@@ -122,7 +475,7 @@ pub(crate) fn emit_event_line(
                     max = max,
                     "event line exceeds max_line_bytes, skipping"
                 );
-                return Ok(());
+                return Ok(false);
             }
             MaxEventBytesBehavior::Fail => {
                 anyhow::bail!(
@@ -137,7 +490,11 @@ pub(crate) fn emit_event_line(
         .write_line_from_source(Some(source_id), &line)
         .inspect_err(|_e| {
             metrics::record_output_error(source_id);
-        })
+        })?;
+    if counts_as_event {
+        super::EVENTS_EMITTED.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    }
+    Ok(true)
 }
 
 /// Set state key; on error, fail or skip checkpoint per source config or global degradation.emit_without_checkpoint.
@@ -197,39 +554,283 @@ pub(crate) fn watermark_state_key(source: &SourceConfig) -> Option<&str> {
         .map(|s| s.state_key.as_deref().unwrap_or("watermark"))
 }
 
-/// Build first-request URL: add watermark state, incremental_from state, or from; and query_params.
-/// Used when there is no saved cursor/next_url (link_header, cursor, and page_offset first page).
+/// Build first-request URL: [`url_with_start_params`], then query_params and the rotating param.
+/// Used when there is no saved cursor/next_url (single page, link_header, cursor and event stream).
 pub(crate) async fn url_with_first_request_params(
     store: &Arc<dyn StateStore>,
     source_id: &str,
     source: &SourceConfig,
     url: &str,
+    poll_caches: &PollCaches,
+    clock: &dyn Clock,
+) -> anyhow::Result<String> {
+    let url = url_with_start_params(store, source_id, source, url, poll_caches, clock).await?;
+    let mut u = reqwest::Url::parse(&url).context("parse url for first-request params")?;
+    append_query_params(&mut u, source);
+    append_rotating_param(store, source_id, source, &mut u).await?;
+    Ok(u.to_string())
+}
+
+/// State key holding the start_mode latest seed of a source with neither state nor incremental_from.
+const START_SEED_KEY: &str = "start_seed";
+
+/// Add where the poll starts from: watermark state, incremental_from state, or from. Page/offset pagination
+/// sends these on every page. With start_mode latest and no saved state (and no from), seeds the
+/// watermark/incremental state to now; the seed is saved by [`save_start_seed`] once the poll succeeds.
+pub(crate) async fn url_with_start_params(
+    store: &Arc<dyn StateStore>,
+    source_id: &str,
+    source: &SourceConfig,
+    url: &str,
+    poll_caches: &PollCaches,
+    clock: &dyn Clock,
 ) -> anyhow::Result<String> {
     let mut u = reqwest::Url::parse(url).context("parse url for first-request params")?;
+    let seed_now = source.start_mode == StartMode::Latest;
+    let seed_env = env_seed(source.initial_watermark_env.as_deref());
     if let Some(ref st) = source.state {
-        if let Some(key) = watermark_state_key(source)
-            && let Some(val) = store.get(source_id, key).await?.filter(|s| !s.is_empty())
-        {
-            u.query_pairs_mut().append_pair(&st.watermark_param, &val);
+        if let Some(key) = watermark_state_key(source) {
+            match store.get(source_id, key).await?.filter(|s| !s.is_empty()) {
+                Some(val) => {
+                    let val = apply_lookback(&val, st.lookback_secs);
+                    u.query_pairs_mut().append_pair(&st.watermark_param, &val);
+                }
+                None if seed_env.is_some() => {
+                    let val = seed_env.unwrap_or_default();
+                    tracing::info!(source = %source_id, watermark = %val, "no saved watermark: starting from initial_watermark_env");
+                    u.query_pairs_mut().append_pair(&st.watermark_param, &val);
+                }
+                None if seed_now => {
+                    let now = poll_caches.pending_start_seed(source_id, key, clock);
+                    tracing::info!(source = %source_id, watermark = %now, "start_mode latest: seeded watermark");
+                    u.query_pairs_mut().append_pair(&st.watermark_param, &now);
+                }
+                None => {}
+            }
         }
     } else if let Some(ref inc) = source.incremental_from {
-        if let Some(val) = store
+        match store
             .get(source_id, &inc.state_key)
             .await?
             .filter(|s| !s.is_empty())
         {
-            u.query_pairs_mut().append_pair(&inc.param_name, &val);
+            Some(val) => {
+                let val = apply_lookback(&val, inc.lookback_secs);
+                u.query_pairs_mut().append_pair(&inc.param_name, &val);
+            }
+            None if seed_env.is_some() => {
+                let val = seed_env.unwrap_or_default();
+                tracing::info!(source = %source_id, state_key = %inc.state_key, value = %val, "no saved incremental_from state: starting from initial_watermark_env");
+                u.query_pairs_mut().append_pair(&inc.param_name, &val);
+            }
+            None if seed_now => {
+                let now = poll_caches.pending_start_seed(source_id, &inc.state_key, clock);
+                tracing::info!(source = %source_id, state_key = %inc.state_key, value = %now, "start_mode latest: seeded incremental_from state");
+                u.query_pairs_mut().append_pair(&inc.param_name, &now);
+            }
+            None => {}
         }
     } else if let Some(ref from_val) = source.from {
         let param = source.from_param.as_deref().unwrap_or("since");
         u.query_pairs_mut().append_pair(param, from_val);
+    } else if seed_now {
+        let param = source.from_param.as_deref().unwrap_or("since");
+        let seed = match store
+            .get(source_id, START_SEED_KEY)
+            .await?
+            .filter(|s| !s.is_empty())
+        {
+            Some(seed) => seed,
+            None => {
+                let now = poll_caches.pending_start_seed(source_id, START_SEED_KEY, clock);
+                tracing::info!(source = %source_id, value = %now, "start_mode latest: seeded start");
+                now
+            }
+        };
+        u.query_pairs_mut().append_pair(param, &seed);
+    }
+    Ok(u.to_string())
+}
+
+/// After a successful poll: save the start_mode latest seed unless the poll already stored state for that key.
+pub(crate) async fn save_start_seed(
+    store: &Arc<dyn StateStore>,
+    source_id: &str,
+    source: &SourceConfig,
+    global: &GlobalConfig,
+    poll_caches: &PollCaches,
+) -> anyhow::Result<()> {
+    let key = match (
+        watermark_state_key(source),
+        source.incremental_from.as_ref(),
+    ) {
+        (Some(key), _) => key,
+        (None, Some(inc)) => inc.state_key.as_str(),
+        (None, None) => START_SEED_KEY,
+    };
+    let Some(seed) = poll_caches.take_start_seed(source_id, key) else {
+        return Ok(());
+    };
+    if store
+        .get(source_id, key)
+        .await?
+        .is_some_and(|s| !s.is_empty())
+    {
+        return Ok(());
+    }
+    store_set_or_skip(store, source_id, source, global, key, &seed).await
+}
+
+/// State key holding the next rotating_params index.
+const ROTATING_PARAMS_INDEX_KEY: &str = "rotating_params_index";
+
+/// This tick's rotating_params index (from state), or None when the source has no rotating_params.
+async fn rotating_param_index(
+    store: &Arc<dyn StateStore>,
+    source_id: &str,
+    source: &SourceConfig,
+) -> anyhow::Result<Option<usize>> {
+    let Some(ref rp) = source.rotating_params else {
+        return Ok(None);
+    };
+    if rp.values.is_empty() {
+        return Ok(None);
+    }
+    let index = store
+        .get(source_id, ROTATING_PARAMS_INDEX_KEY)
+        .await?
+        .and_then(|s| s.parse::<usize>().ok())
+        .unwrap_or(0)
+        % rp.values.len();
+    Ok(Some(index))
+}
+
+/// Append the source's rotating_params value for this tick. Call once per tick, on the first request only;
+/// the counter only moves on via [`advance_rotating_param`] once the poll succeeds.
+pub(crate) async fn append_rotating_param(
+    store: &Arc<dyn StateStore>,
+    source_id: &str,
+    source: &SourceConfig,
+    u: &mut reqwest::Url,
+) -> anyhow::Result<()> {
+    let (Some(index), Some(rp)) = (
+        rotating_param_index(store, source_id, source).await?,
+        source.rotating_params.as_ref(),
+    ) else {
+        return Ok(());
+    };
+    let value = &rp.values[index];
+    tracing::debug!(source = %source_id, param = %rp.param, value = %value, "rotating param");
+    append_query_param(u, &rp.param, value, source.query_encoding);
+    Ok(())
+}
+
+/// After a successful poll, save the next rotating_params index so the following tick uses the next value.
+/// A failed tick leaves it alone and retries the same value.
+pub(crate) async fn advance_rotating_param(
+    store: &Arc<dyn StateStore>,
+    source_id: &str,
+    source: &SourceConfig,
+    global: &GlobalConfig,
+) -> anyhow::Result<()> {
+    let (Some(index), Some(rp)) = (
+        rotating_param_index(store, source_id, source).await?,
+        source.rotating_params.as_ref(),
+    ) else {
+        return Ok(());
+    };
+    let next = (index + 1) % rp.values.len();
+    store_set_or_skip(
+        store,
+        source_id,
+        source,
+        global,
+        ROTATING_PARAMS_INDEX_KEY,
+        &next.to_string(),
+    )
+    .await
+}
+
+/// Append the source's query_params to `u` using its query_encoding.
+pub(crate) fn append_query_params(u: &mut reqwest::Url, source: &SourceConfig) {
+    let Some(ref params) = source.query_params else {
+        return;
+    };
+    for (k, v) in params {
+        append_query_param(u, k, &v.to_param_value(), source.query_encoding);
     }
-    if let Some(ref params) = source.query_params {
-        for (k, v) in params {
-            u.query_pairs_mut().append_pair(k, &v.to_param_value());
+}
+
+/// Append one `key=value` pair with the given encoding.
+pub(crate) fn append_query_param(
+    u: &mut reqwest::Url,
+    key: &str,
+    value: &str,
+    encoding: QueryEncoding,
+) {
+    let pair = match encoding {
+        QueryEncoding::Form => {
+            u.query_pairs_mut().append_pair(key, value);
+            return;
+        }
+        QueryEncoding::Standard => format!("{}={}", percent_encode(key), percent_encode(value)),
+        QueryEncoding::Raw => format!("{}={}", key, value),
+    };
+    let query = match u.query().filter(|q| !q.is_empty()) {
+        Some(q) => format!("{}&{}", q, pair),
+        None => pair,
+    };
+    u.set_query(Some(&query));
+}
+
+/// RFC 3986 percent-encoding: keep unreserved characters (ALPHA / DIGIT / - . _ ~), escape the rest.
+/// Used for standard query encoding and subrequest URL path segments.
+pub(crate) fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
         }
     }
-    Ok(u.to_string())
+    out
+}
+
+/// Non-empty value of the given env var (initial_cursor_env / initial_watermark_env), if set.
+pub(crate) fn env_seed(var: Option<&str>) -> Option<String> {
+    var.and_then(|v| std::env::var(v).ok())
+        .filter(|s| !s.is_empty())
+}
+
+/// Stored watermark minus `lookback_secs` (state/incremental_from `lookback_secs`), keeping its format:
+/// RFC3339 stays RFC3339 with the same offset; integers are epoch millis above 1e12, else epoch seconds.
+/// Values that are neither are returned unchanged.
+pub(crate) fn apply_lookback(value: &str, lookback_secs: Option<u64>) -> String {
+    let Some(secs) = lookback_secs.filter(|s| *s > 0) else {
+        return value.to_string();
+    };
+    let lookback = chrono::Duration::seconds(secs.min(i64::MAX as u64 / 1000) as i64);
+    if let Ok(ts) = chrono::DateTime::parse_from_rfc3339(value) {
+        let shifted = ts - lookback;
+        return shifted.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, value.ends_with('Z'));
+    }
+    if let Ok(n) = value.trim().parse::<i64>() {
+        let delta = if n.unsigned_abs() > 1_000_000_000_000 {
+            lookback.num_milliseconds()
+        } else {
+            lookback.num_seconds()
+        };
+        return n.saturating_sub(delta).to_string();
+    }
+    value.to_string()
+}
+
+/// Current time as RFC3339 (seconds precision, Z) for start_mode latest seeding.
+pub(crate) fn start_mode_now(clock: &dyn Clock) -> String {
+    clock
+        .now()
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
 }
 
 /// Sync version for tests (no store; uses source.from only; state/incremental_from require store).
@@ -246,11 +847,7 @@ pub(crate) fn url_with_first_request_params_sync(
         let param = source.from_param.as_deref().unwrap_or("since");
         u.query_pairs_mut().append_pair(param, from_val);
     }
-    if let Some(ref params) = source.query_params {
-        for (k, v) in params {
-            u.query_pairs_mut().append_pair(k, &v.to_param_value());
-        }
-    }
+    append_query_params(&mut u, source);
     Ok(u.to_string())
 }
 
@@ -291,6 +888,51 @@ pub(crate) fn update_max_timestamp_single(
     }
 }
 
+/// Update the watermark max from events at state.watermark_field (numeric comparison when watermark_numeric).
+pub(crate) fn update_watermark(
+    max_ts: &mut Option<String>,
+    events: &[serde_json::Value],
+    state: &SourceStateConfig,
+) {
+    for event in events {
+        update_watermark_single(max_ts, event, state);
+    }
+}
+
+/// Update the watermark max from a single event (streaming path).
+pub(crate) fn update_watermark_single(
+    max_ts: &mut Option<String>,
+    event: &serde_json::Value,
+    state: &SourceStateConfig,
+) {
+    if !state.watermark_numeric {
+        update_max_timestamp_single(max_ts, event, &state.watermark_field);
+        return;
+    }
+    if let Some(v) = value_at_path_as_string(event, &state.watermark_field)
+        && max_ts
+            .as_ref()
+            .is_none_or(|m| numeric_greater(v.as_str(), m.as_str()))
+    {
+        *max_ts = Some(v);
+    }
+}
+
+/// True when `a` > `b` as numbers: i64 when both parse as integers, else f64.
+/// Unparseable values never replace a parseable max; two unparseable values compare as strings.
+fn numeric_greater(a: &str, b: &str) -> bool {
+    let (a, b) = (a.trim(), b.trim());
+    if let (Ok(x), Ok(y)) = (a.parse::<i64>(), b.parse::<i64>()) {
+        return x > y;
+    }
+    match (a.parse::<f64>().ok(), b.parse::<f64>().ok()) {
+        (Some(x), Some(y)) => x > y,
+        (Some(_), None) => true,
+        (None, Some(_)) => false,
+        (None, None) => a > b,
+    }
+}
+
 /// Store incremental_from state after poll when configured.
 pub(crate) async fn store_incremental_from_after_poll(
     store: &Arc<dyn StateStore>,
@@ -333,31 +975,41 @@ pub(crate) fn status_class(status: u16) -> &'static str {
     }
 }
 
+/// Whole seconds from `clock`'s now until the Unix timestamp `ts` (0 when already past).
+pub(crate) fn secs_until(ts: i64, clock: &dyn Clock) -> u64 {
+    (ts - clock.now().timestamp()).max(0) as u64
+}
+
 /// If adaptive rate limiting is enabled and remaining is 0 or low, sleep until reset (or a short delay).
+/// With headers.resource_header, remaining/reset are tracked per bucket so one exhausted resource does not
+/// throttle responses from another.
 pub(crate) async fn maybe_adaptive_sleep_after_response(
     headers: &reqwest::header::HeaderMap,
     source_id: &str,
     rate_limit_config: Option<&RateLimitConfig>,
+    poll_caches: &PollCaches,
+    clock: &dyn Clock,
 ) {
     let rl = match rate_limit_config {
         Some(r) if r.adaptive == Some(true) => r,
         _ => return,
     };
     let info = rate_limit_info_from_headers(headers, rl.headers.as_ref());
-    if info.remaining.is_none_or(|n| n > 1) {
-        return;
-    }
-    let reset_ts = match info.reset_ts {
-        Some(t) => t,
-        _ => return,
-    };
-    let now = Utc::now().timestamp();
-    let wait_secs = (reset_ts - now).max(0) as u64;
+    let bucket = rl
+        .headers
+        .as_ref()
+        .and_then(|m| m.resource_header.as_deref())
+        .and_then(|h| headers.get(h))
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    poll_caches.record_rate_limit_bucket(source_id, bucket, info.remaining, info.reset_ts);
+    let wait_secs = poll_caches.rate_limit_bucket_wait_secs(source_id, bucket, clock);
     if wait_secs > 0 {
         tracing::debug!(
             source = %source_id,
+            bucket,
             remaining = ?info.remaining,
-            reset_ts,
+            reset_ts = ?info.reset_ts,
             wait_secs,
             "adaptive rate limit: remaining <= 1, waiting until reset"
         );
@@ -1,5 +1,5 @@
 use crate::circuit::CircuitStore;
-use crate::client::build_client;
+use crate::clock::Clock;
 use crate::config::{GlobalConfig, HooksConfig, HttpMethod, SourceConfig, SourceHooksConfig};
 use crate::dedupe::{self, DedupeStore};
 use crate::dpop::DPoPKeyCache;
@@ -15,7 +15,6 @@ use crate::replay::RecordState;
 use crate::retry::execute_with_retry;
 use crate::state::StateStore;
 use anyhow::Context;
-use chrono::Utc;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -23,6 +22,7 @@ use tokio::sync::Semaphore;
 
 use super::ClientRateLimiter;
 use super::HOOK_AUTH_CACHE;
+use super::caches::PollCaches;
 use super::helpers::*;
 use super::parse::*;
 
@@ -43,8 +43,10 @@ pub(super) async fn poll_with_hooks(
     _record_state: Option<Arc<RecordState>>,
     rate_limiter: Option<&Arc<ClientRateLimiter>>,
     request_semaphore: Option<Arc<Semaphore>>,
+    poll_caches: Arc<PollCaches>,
+    clock: &dyn Clock,
 ) -> anyhow::Result<()> {
-    let client = build_client(source.resilience.as_ref())?;
+    let client = poll_caches.source_client(source_id, source)?;
     let max_pages = 100u32;
     let mut url = store
         .get(source_id, "next_url")
@@ -52,7 +54,10 @@ pub(super) async fn poll_with_hooks(
         .filter(|s| !s.is_empty())
         .unwrap_or_else(|| source.url.clone());
     let mut body_override: Option<serde_json::Value> = source.body.clone();
+    let start = Instant::now();
     let mut all_events: Vec<HookEvent> = Vec::new();
+    let mut pages = 0u32;
+    let mut total_bytes = 0u64;
     let path_for_emit = url.clone();
     let label = effective_source_label(source, source_id);
 
@@ -85,7 +90,7 @@ pub(super) async fn poll_with_hooks(
             let init_ctx = HookContext {
                 env: std::env::vars().collect(),
                 state: init_state,
-                request_id: format!("helr-{}", Utc::now().timestamp_nanos_opt().unwrap_or(0)),
+                request_id: new_request_id(global.request_id_scheme),
                 source_id: source_id.to_string(),
                 default_since: source.from.clone(),
                 pagination: None,
@@ -103,18 +108,14 @@ pub(super) async fn poll_with_hooks(
         }
     };
 
-    for _page in 1..=max_pages {
+    for page in 1..=max_pages {
+        pages = page;
         if let Some(limiter) = rate_limiter {
             limiter.until_ready().await;
         }
-        let _permit = match &request_semaphore {
-            Some(s) => Some(
-                s.acquire()
-                    .await
-                    .map_err(|e| anyhow::anyhow!("bulkhead: {}", e))?,
-            ),
-            None => None,
-        };
+        let _permit =
+            acquire_request_permits(global, source_id, source, &request_semaphore, &poll_caches)
+                .await?;
 
         let state_keys = store.list_keys(source_id).await?;
         let mut state_map = HashMap::new();
@@ -130,7 +131,7 @@ pub(super) async fn poll_with_hooks(
         let ctx = HookContext {
             env: std::env::vars().collect(),
             state: state_map,
-            request_id: format!("helr-{}", Utc::now().timestamp_nanos_opt().unwrap_or(0)),
+            request_id: new_request_id(global.request_id_scheme),
             source_id: source_id.to_string(),
             default_since: source.from.clone(),
             pagination: if pagination.is_empty() {
@@ -236,7 +237,9 @@ pub(super) async fn poll_with_hooks(
                 req = req.json(&body_to_send);
             }
             let req = req.build().context("build request")?;
-            client.execute(req).await.context("http request")?
+            crate::retry::execute_audited(&client, req, source, source_id, global.audit.as_ref())
+                .await
+                .context("http request")?
         } else {
             execute_with_retry(
                 &client,
@@ -262,7 +265,15 @@ pub(super) async fn poll_with_hooks(
             .iter()
             .filter_map(|(k, v)| Some((k.as_str().to_string(), v.to_str().ok()?.to_string())))
             .collect();
+        let response_headers = response.headers().clone();
         let body_bytes = read_body_with_limit(response, source.max_response_bytes).await?;
+        let body_bytes = if is_success_status(source, status) {
+            let body_bytes = maybe_decompress_body(source, &response_headers, body_bytes)?;
+            maybe_decrypt_body(source, &response_headers, body_bytes)?
+        } else {
+            body_bytes
+        };
+        total_bytes += body_bytes.len() as u64;
         let body_str = String::from_utf8_lossy(&body_bytes).to_string();
         let body_json: serde_json::Value =
             serde_json::from_str(&body_str).unwrap_or(serde_json::Value::String(body_str.clone()));
@@ -272,31 +283,47 @@ pub(super) async fn poll_with_hooks(
             body: body_json,
         };
 
-        let events: Vec<HookEvent> =
-            match call_parse_response(script, &ctx, &hook_response, hooks_config).await? {
-                ev if !ev.is_empty() => ev,
-                _ => {
-                    let parsed = parse_events_from_body_for_source(&body_bytes, source)?;
-                    parsed
-                        .into_iter()
-                        .map(|event_value| {
-                            let ts = event_ts_with_field(
-                                &event_value,
-                                source
-                                    .transform
-                                    .as_ref()
-                                    .and_then(|t| t.timestamp_field.as_deref()),
-                            );
-                            HookEvent {
-                                ts,
-                                source: label.clone(),
-                                event: event_value,
-                                meta: None,
-                            }
-                        })
-                        .collect()
-                }
-            };
+        let parsed = match call_parse_response(script, &ctx, &hook_response, hooks_config).await {
+            Ok(ev) if !ev.is_empty() => Ok(ev),
+            Ok(_) => parse_events_from_body_for_source(&body_bytes, source, status).map(|parsed| {
+                parsed
+                    .into_iter()
+                    .map(|event_value| {
+                        let ts = event_ts_with_field(
+                            &event_value,
+                            source
+                                .transform
+                                .as_ref()
+                                .and_then(|t| t.timestamp_field.as_deref()),
+                            clock,
+                        );
+                        HookEvent {
+                            ts,
+                            source: label.clone(),
+                            event: event_value,
+                            meta: None,
+                        }
+                    })
+                    .collect()
+            }),
+            Err(e) => Err(e),
+        };
+        let events: Vec<HookEvent> = match parsed {
+            Ok(ev) => ev,
+            Err(e) => {
+                handle_parse_error(
+                    global,
+                    source_id,
+                    source,
+                    &final_url,
+                    status,
+                    Some(&body_bytes),
+                    e,
+                    "parse error, stopping pagination",
+                )?;
+                break;
+            }
+        };
 
         for he in &events {
             if let Some(d) = &source.dedupe {
@@ -328,8 +355,12 @@ pub(super) async fn poll_with_hooks(
                 emitted = emitted.with_cursor(c.to_string());
             }
             emitted = emitted.with_request_id(ctx.request_id.clone());
-            emit_event_line(global, source_id, source, &event_sink, &emitted)?;
-            metrics::record_events(source_id, 1);
+            if source.include_ingested_at {
+                emitted = emitted.with_ingested_at(clock.now().to_rfc3339());
+            }
+            if emit_event_line(global, source_id, source, &event_sink, emitted)? {
+                metrics::record_events(source_id, 1);
+            }
         }
         all_events.extend(events);
 
@@ -366,7 +397,7 @@ pub(super) async fn poll_with_hooks(
     let commit_ctx = HookContext {
         env: std::env::vars().collect(),
         state: state_map,
-        request_id: format!("helr-{}", Utc::now().timestamp_nanos_opt().unwrap_or(0)),
+        request_id: new_request_id(global.request_id_scheme),
         source_id: source_id.to_string(),
         default_since: source.from.clone(),
         pagination: if pagination.is_empty() {
@@ -380,5 +411,16 @@ pub(super) async fn poll_with_hooks(
     for (key, value) in to_commit {
         store_set_or_skip(&store, source_id, source, global, &key, &value).await?;
     }
+    emit_tick_summary(
+        global,
+        source_id,
+        source,
+        &event_sink,
+        pages,
+        all_events.len() as u64,
+        total_bytes,
+        start,
+        clock,
+    )?;
     Ok(())
 }
@@ -1,6 +1,7 @@
 use crate::circuit::{self, CircuitStore};
-use crate::config::{CheckpointTiming, GlobalConfig, OnParseErrorBehavior, SourceConfig};
-use crate::dedupe::{self, DedupeStore};
+use crate::clock::Clock;
+use crate::config::{CheckpointTiming, DuplicateRelSelection, GlobalConfig, SourceConfig};
+use crate::dedupe::DedupeStore;
 use crate::dpop::DPoPKeyCache;
 use crate::metrics;
 use crate::oauth2::OAuth2TokenCache;
@@ -14,8 +15,11 @@ use std::time::{Duration, Instant};
 use tokio::sync::Semaphore;
 
 use super::ClientRateLimiter;
+use super::caches::PollCaches;
+use super::emit::{EmitCtx, emit_parsed_event};
 use super::helpers::*;
 use super::parse::*;
+use super::subrequest::SubrequestCtx;
 use crate::pagination::next_link_from_headers;
 
 /// Link-header pagination: follow rel="next" until no more or max_pages.
@@ -28,6 +32,7 @@ pub(super) async fn poll_link_header(
     client: &reqwest::Client,
     rel: &str,
     max_pages: u32,
+    duplicate_rel: DuplicateRelSelection,
     circuit_store: CircuitStore,
     token_cache: OAuth2TokenCache,
     dpop_key_cache: Option<DPoPKeyCache>,
@@ -36,15 +41,34 @@ pub(super) async fn poll_link_header(
     record_state: Option<Arc<RecordState>>,
     rate_limiter: Option<&Arc<ClientRateLimiter>>,
     request_semaphore: Option<Arc<Semaphore>>,
+    poll_caches: Arc<PollCaches>,
+    clock: &dyn Clock,
 ) -> anyhow::Result<()> {
     let start = Instant::now();
+    let emit_ctx = EmitCtx {
+        global,
+        clock,
+        source_id,
+        source,
+        dedupe_store: &dedupe_store,
+        event_sink: &event_sink,
+        sub: SubrequestCtx {
+            client,
+            token_cache: &token_cache,
+            dpop_key_cache: dpop_key_cache.as_ref(),
+            rate_limiter,
+            request_semaphore: &request_semaphore,
+            poll_caches: &poll_caches,
+        },
+    };
     let from_store = store
         .get(source_id, "next_url")
         .await?
         .filter(|s| !s.is_empty());
     let mut url: String = from_store.clone().unwrap_or_else(|| source.url.clone());
     if from_store.is_none() {
-        url = url_with_first_request_params(&store, source_id, source, &url).await?;
+        url = url_with_first_request_params(&store, source_id, source, &url, &poll_caches, clock)
+            .await?;
     }
 
     let mut page = 0u32;
@@ -84,62 +108,33 @@ pub(super) async fn poll_link_header(
         if let Some(limiter) = rate_limiter {
             limiter.until_ready().await;
         }
-        let _request_permit = match &request_semaphore {
-            Some(s) => Some(
-                s.acquire()
-                    .await
-                    .map_err(|e| anyhow::anyhow!("bulkhead request acquire: {}", e))?,
-            ),
-            None => None,
-        };
+        let _request_permit =
+            acquire_request_permits(global, source_id, source, &request_semaphore, &poll_caches)
+                .await?;
         let req_start = std::time::Instant::now();
-        let response = match execute_with_retry(
-            client,
-            source,
+        let response = record_request_outcome(
+            &circuit_store,
             source_id,
-            &url,
-            None, // link_header: same URL per page; body from source when POST
-            source.resilience.as_ref().and_then(|r| r.retries.as_ref()),
-            source
-                .resilience
-                .as_ref()
-                .and_then(|r| r.rate_limit.as_ref()),
-            Some(&token_cache),
-            dpop_key_cache.as_ref(),
-            global.audit.as_ref(),
-        )
-        .await
-        {
-            Ok(r) => {
-                let success = r.status().as_u16() < 500;
-                if let Some(cb) = source
-                    .resilience
-                    .as_ref()
-                    .and_then(|r| r.circuit_breaker.as_ref())
-                {
-                    circuit::record_result(&circuit_store, source_id, cb, success).await;
-                }
-                let status = r.status().as_u16();
-                metrics::record_request(
-                    source_id,
-                    status_class(status),
-                    req_start.elapsed().as_secs_f64(),
-                );
-                r
-            }
-            Err(e) => {
-                if let Some(cb) = source
+            source,
+            req_start,
+            execute_with_retry(
+                client,
+                source,
+                source_id,
+                &url,
+                None, // link_header: same URL per page; body from source when POST
+                source.resilience.as_ref().and_then(|r| r.retries.as_ref()),
+                source
                     .resilience
                     .as_ref()
-                    .and_then(|r| r.circuit_breaker.as_ref())
-                {
-                    circuit::record_result(&circuit_store, source_id, cb, false).await;
-                }
-                metrics::record_request(source_id, "error", req_start.elapsed().as_secs_f64());
-                metrics::record_error(source_id);
-                return Err(e).context("http request");
-            }
-        };
+                    .and_then(|r| r.rate_limit.as_ref()),
+                Some(&token_cache),
+                dpop_key_cache.as_ref(),
+                global.audit.as_ref(),
+            )
+            .await,
+        )
+        .await?;
 
         maybe_adaptive_sleep_after_response(
             response.headers(),
@@ -148,11 +143,13 @@ pub(super) async fn poll_link_header(
                 .resilience
                 .as_ref()
                 .and_then(|r| r.rate_limit.as_ref()),
+            &poll_caches,
+            clock,
         )
         .await;
 
         let status = response.status();
-        let next_url = next_link_from_headers(response.headers(), rel);
+        let next_url = next_link_from_headers(response.headers(), rel, duplicate_rel);
         let base_url = response.url().clone();
         let path = base_url.path().to_string();
         let record_url = response.url().clone();
@@ -164,17 +161,24 @@ pub(super) async fn poll_link_header(
         let mut _streamed = false;
 
         #[cfg(feature = "streaming")]
-        if source.response_streaming.is_some() {
+        if streaming_parse_enabled(source) {
             use super::streaming;
             use crate::config::StreamingMode;
 
             let use_full = source.response_streaming == Some(StreamingMode::Full)
-                && source.on_invalid_utf8.is_none();
+                && source.on_invalid_utf8.is_none()
+                && source.body_decrypt.is_none()
+                && !is_content_encoded(&record_headers)
+                && !allows_empty_body(source, record_status);
 
             if use_full {
                 let resp = response.take().unwrap();
-                if !status.is_success() {
-                    anyhow::bail!("http {} {}", status, resp.text().await.unwrap_or_default());
+                if !is_success_status(source, status.as_u16()) {
+                    let body = read_body_with_limit(resp, source.max_response_bytes)
+                        .await
+                        .map(|b| String::from_utf8_lossy(&b).into_owned())
+                        .unwrap_or_default();
+                    anyhow::bail!("http {} {}", status, body);
                 }
                 let tee_path = record_state.as_ref().map(|_| {
                     std::env::temp_dir().join(format!(
@@ -196,11 +200,18 @@ pub(super) async fn poll_link_header(
                     let event_value = match result {
                         Ok(v) => v,
                         Err(e) => {
-                            if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                                tracing::warn!(source = %source_id, error = %e, "parse error, stopping pagination");
-                                break;
-                            }
-                            return Err(e).context("streaming parse element");
+                            handle_parse_error(
+                                global,
+                                source_id,
+                                source,
+                                record_url.as_str(),
+                                record_status,
+                                None,
+                                e,
+                                "parse error, stopping pagination",
+                            )
+                            .context("streaming parse element")?;
+                            break;
                         }
                     };
                     let event_value = match streaming::unwrap_event_object(event_value, obj_path) {
@@ -216,22 +227,14 @@ pub(super) async fn poll_link_header(
                         );
                     }
                     if let Some(ref st) = source.state {
-                        update_max_timestamp_single(
-                            &mut watermark_max_ts,
-                            &event_value,
-                            &st.watermark_field,
-                        );
+                        update_watermark_single(&mut watermark_max_ts, &event_value, st);
                     }
-                    if let Some(d) = &source.dedupe {
-                        let id = event_id(&event_value, &d.id_path).unwrap_or_default();
-                        if dedupe::seen_and_add(&dedupe_store, source_id, id, d.capacity).await {
-                            continue;
-                        }
+                    if emit_parsed_event(&emit_ctx, &path, Some(&record_headers), event_value, None)
+                        .await?
+                    {
+                        total_events += 1;
+                        emitted_count += 1;
                     }
-                    total_events += 1;
-                    emitted_count += 1;
-                    let emitted = build_emitted_event(source, source_id, &path, event_value);
-                    emit_event_line(global, source_id, source, &event_sink, &emitted)?;
                 }
 
                 join_handle
@@ -252,8 +255,12 @@ pub(super) async fn poll_link_header(
                 _streamed = true;
             } else {
                 let resp = response.take().unwrap();
-                if !status.is_success() {
-                    anyhow::bail!("http {} {}", status, resp.text().await.unwrap_or_default());
+                if !is_success_status(source, status.as_u16()) {
+                    let body = read_body_with_limit(resp, source.max_response_bytes)
+                        .await
+                        .map(|b| String::from_utf8_lossy(&b).into_owned())
+                        .unwrap_or_default();
+                    anyhow::bail!("http {} {}", status, body);
                 }
                 let body_bytes = read_body_with_limit(resp, source.max_response_bytes).await?;
                 if let Some(ref rs) = record_state {
@@ -265,17 +272,27 @@ pub(super) async fn poll_link_header(
                         &body_bytes,
                     )?;
                 }
+                let body_bytes = maybe_decompress_body(source, &record_headers, body_bytes)?;
+                let body_bytes = maybe_decrypt_body(source, &record_headers, body_bytes)?;
                 total_bytes += body_bytes.len() as u64;
-                let parse_result = match streaming::parse_streaming(&body_bytes, source) {
-                    Ok(r) => r,
-                    Err(e) => {
-                        if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                            tracing::warn!(source = %source_id, error = %e, "parse error, stopping pagination");
+                let parse_result =
+                    match streaming::parse_streaming(&body_bytes, source, record_status) {
+                        Ok(r) => r,
+                        Err(e) => {
+                            handle_parse_error(
+                                global,
+                                source_id,
+                                source,
+                                record_url.as_str(),
+                                record_status,
+                                Some(&body_bytes),
+                                e,
+                                "parse error, stopping pagination",
+                            )
+                            .context("streaming parse")?;
                             break;
                         }
-                        return Err(e).context("streaming parse");
-                    }
-                };
+                    };
                 let obj_path = source.response_event_object_path.as_deref();
                 for result in parse_result.iter(&body_bytes) {
                     let event_value = result.context("parse event element")?;
@@ -292,22 +309,14 @@ pub(super) async fn poll_link_header(
                         );
                     }
                     if let Some(ref st) = source.state {
-                        update_max_timestamp_single(
-                            &mut watermark_max_ts,
-                            &event_value,
-                            &st.watermark_field,
-                        );
+                        update_watermark_single(&mut watermark_max_ts, &event_value, st);
                     }
-                    if let Some(d) = &source.dedupe {
-                        let id = event_id(&event_value, &d.id_path).unwrap_or_default();
-                        if dedupe::seen_and_add(&dedupe_store, source_id, id, d.capacity).await {
-                            continue;
-                        }
+                    if emit_parsed_event(&emit_ctx, &path, Some(&record_headers), event_value, None)
+                        .await?
+                    {
+                        total_events += 1;
+                        emitted_count += 1;
                     }
-                    total_events += 1;
-                    emitted_count += 1;
-                    let emitted = build_emitted_event(source, source_id, &path, event_value);
-                    emit_event_line(global, source_id, source, &event_sink, &emitted)?;
                 }
                 _streamed = true;
             }
@@ -315,8 +324,12 @@ pub(super) async fn poll_link_header(
 
         if !_streamed {
             let resp = response.take().unwrap();
-            if !status.is_success() {
-                anyhow::bail!("http {} {}", status, resp.text().await.unwrap_or_default());
+            if !is_success_status(source, status.as_u16()) {
+                let body = read_body_with_limit(resp, source.max_response_bytes)
+                    .await
+                    .map(|b| String::from_utf8_lossy(&b).into_owned())
+                    .unwrap_or_default();
+                anyhow::bail!("http {} {}", status, body);
             }
             let body_bytes = read_body_with_limit(resp, source.max_response_bytes).await?;
             if let Some(ref rs) = record_state {
@@ -328,35 +341,51 @@ pub(super) async fn poll_link_header(
                     &body_bytes,
                 )?;
             }
+            let body_bytes = maybe_decompress_body(source, &record_headers, body_bytes)?;
+            let body_bytes = maybe_decrypt_body(source, &record_headers, body_bytes)?;
             total_bytes += body_bytes.len() as u64;
-            let events = match parse_events_from_body_for_source(&body_bytes, source) {
+            let (events, kinds) = match parse_kinded_events_from_body_for_source(
+                &body_bytes,
+                source,
+                Some(&record_headers),
+                record_status,
+            ) {
                 Ok(ev) => ev,
                 Err(e) => {
-                    if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                        tracing::warn!(source = %source_id, error = %e, "parse error, stopping pagination");
-                        break;
-                    }
-                    return Err(e).context("parse response");
+                    handle_parse_error(
+                        global,
+                        source_id,
+                        source,
+                        record_url.as_str(),
+                        record_status,
+                        Some(&body_bytes),
+                        e,
+                        "parse error, stopping pagination",
+                    )
+                    .context("parse response")?;
+                    break;
                 }
             };
             if let Some(ref inc) = source.incremental_from {
                 update_max_timestamp(&mut incremental_max_ts, &events, &inc.event_timestamp_path);
             }
             if let Some(ref st) = source.state {
-                update_max_timestamp(&mut watermark_max_ts, &events, &st.watermark_field);
+                update_watermark(&mut watermark_max_ts, &events, st);
             }
             event_count = events.len();
-            for event_value in events {
-                if let Some(d) = &source.dedupe {
-                    let id = event_id(&event_value, &d.id_path).unwrap_or_default();
-                    if dedupe::seen_and_add(&dedupe_store, source_id, id, d.capacity).await {
-                        continue;
-                    }
+            for (i, event_value) in events.into_iter().enumerate() {
+                if emit_parsed_event(
+                    &emit_ctx,
+                    &path,
+                    Some(&record_headers),
+                    event_value,
+                    kinds.get(i),
+                )
+                .await?
+                {
+                    total_events += 1;
+                    emitted_count += 1;
                 }
-                total_events += 1;
-                emitted_count += 1;
-                let emitted = build_emitted_event(source, source_id, &path, event_value);
-                emit_event_line(global, source_id, source, &event_sink, &emitted)?;
             }
         }
         metrics::record_events(source_id, emitted_count);
@@ -365,6 +394,22 @@ pub(super) async fn poll_link_header(
         let hit_max_bytes = max_bytes.is_some() && total_bytes > max_bytes.unwrap();
         if let Some(next) = next_url {
             let absolute = base_url.join(&next).context("resolve next URL")?;
+            // Okta-style polling: once caught up, `next` repeats the URL just fetched. Stop here and
+            // resume from that URL next tick instead of refetching it until max_pages.
+            if absolute.as_str() == url {
+                if checkpoint_per_page {
+                    store.set(source_id, "next_url", absolute.as_str()).await?;
+                }
+                pending_next_url = Some(absolute.to_string());
+                tracing::info!(
+                    source = %source_id,
+                    pages = page,
+                    events = total_events,
+                    duration_ms = start.elapsed().as_millis(),
+                    "poll completed (next link equals current URL; resuming from it next tick)"
+                );
+                break;
+            }
             if checkpoint_per_page {
                 store.set(source_id, "next_url", absolute.as_str()).await?;
             }
@@ -408,5 +453,16 @@ pub(super) async fn poll_link_header(
     }
     store_incremental_from_after_poll(&store, source_id, source, global, incremental_max_ts).await;
     store_watermark_after_poll(&store, source_id, source, global, watermark_max_ts).await;
+    emit_tick_summary(
+        global,
+        source_id,
+        source,
+        &event_sink,
+        page,
+        total_events,
+        total_bytes,
+        start,
+        clock,
+    )?;
     Ok(())
 }
@@ -1,6 +1,9 @@
 //! Single poll tick: load state → fetch pages (link-header) → emit NDJSON → commit state.
 
+mod caches;
 mod cursor;
+mod emit;
+mod event_stream;
 mod helpers;
 #[cfg(feature = "hooks")]
 mod hooks;
@@ -10,9 +13,10 @@ mod parse;
 mod single_page;
 #[cfg(feature = "streaming")]
 mod streaming;
+mod subrequest;
 
 use crate::circuit::CircuitStore;
-use crate::client::build_client;
+use crate::clock::Clock;
 use crate::config::{Config, GlobalConfig, PaginationConfig, SourceConfig};
 use crate::dedupe::DedupeStore;
 use crate::dpop::DPoPKeyCache;
@@ -21,6 +25,7 @@ use crate::oauth2::OAuth2TokenCache;
 use crate::output::EventSink;
 use crate::replay::RecordState;
 use crate::state::StateStore;
+use chrono::Timelike;
 use governor::{Quota, RateLimiter};
 use std::collections::HashMap;
 use std::num::NonZeroU32;
@@ -29,6 +34,9 @@ use std::sync::atomic::Ordering;
 use std::time::Duration;
 use tokio::sync::{RwLock, Semaphore};
 
+pub use caches::{PollCaches, new_poll_caches};
+pub(crate) use helpers::read_body_with_limit;
+
 /// Type for client-side rate limiter (governor direct limiter).
 type ClientRateLimiter = RateLimiter<
     governor::state::NotKeyed,
@@ -36,6 +44,14 @@ type ClientRateLimiter = RateLimiter<
     governor::clock::DefaultClock,
 >;
 
+/// Events emitted across all sources since process start (summary envelopes excluded); used for idle shutdown.
+static EVENTS_EMITTED: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Total events emitted so far (excludes tick summaries).
+pub fn events_emitted_total() -> u64 {
+    EVENTS_EMITTED.load(Ordering::Relaxed)
+}
+
 /// Shared store of last error message per source (for health endpoints).
 pub type LastErrorStore = Arc<RwLock<HashMap<String, String>>>;
 
@@ -43,8 +59,30 @@ pub type LastErrorStore = Arc<RwLock<HashMap<String, String>>>;
 static HOOK_AUTH_CACHE: std::sync::LazyLock<crate::hooks::HookAuthCache> =
     std::sync::LazyLock::new(crate::hooks::new_hook_auth_cache);
 
+/// Drop per-source state derived from config and secrets (reload with restart_sources_on_sighup):
+/// circuit breakers, OAuth2 tokens, DPoP keys, hook auth results, HTTP clients, connection caps, rate-limit
+/// windows and pending start seeds. Clients (and their mTLS identity) are rebuilt on the next tick and
+/// file-based secrets are read per request, so the next tick picks up rotated certs, keys and token files.
+pub async fn reset_source_caches(
+    circuit_store: &CircuitStore,
+    token_cache: &OAuth2TokenCache,
+    dpop_key_cache: Option<&DPoPKeyCache>,
+    poll_caches: &PollCaches,
+) {
+    circuit_store.write().await.clear();
+    poll_caches.clear_sources();
+    token_cache.write().await.clear();
+    if let Some(cache) = dpop_key_cache {
+        cache.write().await.clear();
+    }
+    #[cfg(feature = "hooks")]
+    HOOK_AUTH_CACHE.write().await.clear();
+}
+
 /// Run one poll tick for all sources (or only those matching source_filter).
-/// Sources are polled concurrently (one task per source).
+/// Sources are polled concurrently (one task per source). When `startup` is true (first tick after start),
+/// a source with schedule.startup_jitter_secs gets its first poll scheduled after its own random delay: that
+/// poll runs on its own and the tick does not wait for it; later ticks skip the source until it has run.
 #[allow(clippy::too_many_arguments)]
 pub async fn run_one_tick(
     config: &Config,
@@ -58,8 +96,11 @@ pub async fn run_one_tick(
     record_state: Option<Arc<RecordState>>,
     last_errors: LastErrorStore,
     global_sources_semaphore: Option<Arc<Semaphore>>,
+    poll_caches: Arc<PollCaches>,
     under_load_flag: Option<Arc<std::sync::atomic::AtomicBool>>,
     skip_priority_below: Option<u32>,
+    startup: bool,
+    clock: Arc<dyn Clock>,
 ) -> anyhow::Result<()> {
     let mut handles = Vec::new();
     for (source_id, source) in &config.sources {
@@ -68,6 +109,18 @@ pub async fn run_one_tick(
         {
             continue;
         }
+        if source.disabled {
+            tracing::debug!(source = %source_id, "source disabled, skipping");
+            continue;
+        }
+        if startup_poll_pending(source_id) {
+            tracing::debug!(source = %source_id, "startup jitter: first poll not run yet, skipping");
+            continue;
+        }
+        if !cron_due(source_id, source, &*clock) {
+            tracing::debug!(source = %source_id, "cron schedule not due, skipping");
+            continue;
+        }
         if let (Some(flag), Some(threshold)) = (&under_load_flag, skip_priority_below)
             && flag.load(Ordering::Relaxed)
         {
@@ -79,9 +132,22 @@ pub async fn run_one_tick(
                     threshold,
                     "load shedding: skipping low-priority source"
                 );
+                metrics::record_source_shed(source_id);
                 continue;
             }
         }
+        let running_guard = if source.schedule.skip_if_running {
+            match try_claim_source_tick(source_id) {
+                Some(guard) => Some(guard),
+                None => {
+                    tracing::debug!(source = %source_id, "previous poll still running, skipping tick");
+                    metrics::record_tick_skipped(source_id);
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
         let effective_request_cap = source
             .resilience
             .as_ref()
@@ -109,6 +175,8 @@ pub async fn run_one_tick(
         let event_sink = event_sink.clone();
         let record_state = record_state.clone();
         let global_sources_semaphore_clone = global_sources_semaphore.clone();
+        let poll_caches = poll_caches.clone();
+        let clock = clock.clone();
         let rate_limiter: Option<Arc<ClientRateLimiter>> = source
             .resilience
             .as_ref()
@@ -128,8 +196,30 @@ pub async fn run_one_tick(
             .resilience
             .as_ref()
             .and_then(|r| r.timeouts.as_ref())
-            .and_then(|t| t.poll_tick_secs);
+            .and_then(|t| t.poll_tick_secs)
+            // Streaming sources end their own read at the tick deadline instead of failing the tick.
+            .filter(|_| !source.streaming);
+        let source_alert = source.alert.clone();
+        let result_clock = clock.clone();
+        let startup_delay = if startup {
+            source
+                .schedule
+                .startup_jitter_secs
+                .filter(|&n| n > 0)
+                .map(startup_jitter_delay)
+        } else {
+            None
+        };
         let h = tokio::spawn(async move {
+            let _running_guard = running_guard;
+            if let Some(delay) = startup_delay {
+                tracing::debug!(
+                    source = %source_id_key,
+                    delay_ms = delay.as_millis() as u64,
+                    "startup jitter: first poll scheduled"
+                );
+                tokio::time::sleep(delay).await;
+            }
             let _source_permit = match &global_sources_semaphore_clone {
                 Some(s) => Some(
                     s.acquire()
@@ -139,7 +229,7 @@ pub async fn run_one_tick(
                 None => None,
             };
             let poll_fut = poll_one_source(
-                store,
+                store.clone(),
                 &source_id_key,
                 &source,
                 &global,
@@ -151,6 +241,8 @@ pub async fn run_one_tick(
                 record_state,
                 rate_limiter,
                 request_semaphore,
+                poll_caches.clone(),
+                clock,
             );
             match poll_tick_secs {
                 Some(secs) => match tokio::time::timeout(Duration::from_secs(secs), poll_fut).await
@@ -159,40 +251,257 @@ pub async fn run_one_tick(
                     Err(_) => Err(anyhow::anyhow!("poll tick timed out after {}s", secs)),
                 },
                 None => poll_fut.await,
-            }
+            }?;
+            helpers::save_start_seed(&store, &source_id_key, &source, &global, &poll_caches)
+                .await?;
+            helpers::advance_rotating_param(&store, &source_id_key, &source, &global).await
         });
-        handles.push((source_id, h));
+        if startup_delay.is_some() {
+            // Deferred first poll: record its outcome when it completes instead of holding up this tick.
+            STARTUP_PENDING
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(source_id.clone());
+            let source_id = source_id.clone();
+            let alert_cfg = source_alert;
+            let last_errors = last_errors.clone();
+            let clock = result_clock;
+            let deferred = tokio::spawn(async move {
+                let result = h.await;
+                STARTUP_PENDING
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .remove(&source_id);
+                if let Err(e) = record_poll_result(
+                    &source_id,
+                    alert_cfg.as_ref(),
+                    result,
+                    &last_errors,
+                    &*clock,
+                )
+                .await
+                {
+                    tracing::error!(source = %source_id, "startup poll: {:#}", e);
+                }
+            });
+            STARTUP_TASKS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(deferred);
+        } else {
+            handles.push((source_id, h));
+        }
     }
     for (source_id, h) in handles {
-        match h.await {
-            Ok(Ok(())) => {}
-            Ok(Err(e)) => {
-                metrics::record_error(source_id);
-                let msg = format!("{:#}", e);
-                last_errors
-                    .write()
-                    .await
-                    .insert(source_id.clone(), msg.clone());
-                tracing::error!(source = %source_id, "poll failed: {:#}", e);
-                // Broken pipe to stdout is fatal: exit so caller can exit non-zero.
-                if msg.to_lowercase().contains("broken pipe") {
-                    return Err(e);
-                }
-            }
-            Err(e) => {
-                metrics::record_error(source_id);
-                let msg = format!("{:#}", e);
-                last_errors
-                    .write()
-                    .await
-                    .insert(source_id.clone(), msg.clone());
-                tracing::error!(source = %source_id, "task join failed: {:#}", e);
+        let alert_cfg = config.sources.get(source_id).and_then(|s| s.alert.as_ref());
+        record_poll_result(source_id, alert_cfg, h.await, &last_errors, &*clock).await?;
+    }
+    Ok(())
+}
+
+/// Record the outcome of one source's poll task: alert state, last success or last error, error metric.
+/// Err only for a broken pipe to stdout, which is fatal so the caller can exit non-zero.
+async fn record_poll_result(
+    source_id: &str,
+    alert_cfg: Option<&crate::config::AlertConfig>,
+    result: Result<anyhow::Result<()>, tokio::task::JoinError>,
+    last_errors: &LastErrorStore,
+    clock: &dyn Clock,
+) -> anyhow::Result<()> {
+    match result {
+        Ok(Ok(())) => {
+            crate::alert::record_success(source_id);
+            record_poll_success(source_id, clock);
+        }
+        Ok(Err(e)) => {
+            metrics::record_error(source_id);
+            let msg = format!("{:#}", e);
+            last_errors
+                .write()
+                .await
+                .insert(source_id.to_string(), msg.clone());
+            tracing::error!(source = %source_id, "poll failed: {:#}", e);
+            crate::alert::record_failure(source_id, alert_cfg, &msg, clock).await;
+            if msg.to_lowercase().contains("broken pipe") {
+                return Err(e);
             }
         }
+        Err(e) => {
+            metrics::record_error(source_id);
+            let msg = format!("{:#}", e);
+            last_errors
+                .write()
+                .await
+                .insert(source_id.to_string(), msg.clone());
+            tracing::error!(source = %source_id, "task join failed: {:#}", e);
+            crate::alert::record_failure(source_id, alert_cfg, &msg, clock).await;
+        }
     }
     Ok(())
 }
 
+/// Sources whose startup-jittered first poll is scheduled but has not completed yet.
+static STARTUP_PENDING: std::sync::LazyLock<std::sync::Mutex<std::collections::HashSet<String>>> =
+    std::sync::LazyLock::new(Default::default);
+
+/// Deferred startup polls, awaited by [`wait_startup_polls`].
+static STARTUP_TASKS: std::sync::LazyLock<std::sync::Mutex<Vec<tokio::task::JoinHandle<()>>>> =
+    std::sync::LazyLock::new(Default::default);
+
+/// Wait for the startup-jittered first polls scheduled so far (`--once` exits only after they ran).
+pub async fn wait_startup_polls() {
+    let tasks = std::mem::take(&mut *STARTUP_TASKS.lock().unwrap_or_else(|e| e.into_inner()));
+    for task in tasks {
+        let _ = task.await;
+    }
+}
+
+fn startup_poll_pending(source_id: &str) -> bool {
+    STARTUP_PENDING
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .contains(source_id)
+}
+
+/// Time of each source's last successful poll (for readyz ready_requires_first_success).
+static LAST_SUCCESS: std::sync::LazyLock<
+    std::sync::Mutex<HashMap<String, chrono::DateTime<chrono::Utc>>>,
+> = std::sync::LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+fn record_poll_success(source_id: &str, clock: &dyn Clock) {
+    LAST_SUCCESS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(source_id.to_string(), clock.now());
+}
+
+/// When the source last completed a successful poll in this process, if ever.
+pub fn last_success(source_id: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    LAST_SUCCESS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(source_id)
+        .copied()
+}
+
+/// Smoothing factor for the per-source request latency EMA: weight of the newest sample.
+const LATENCY_EMA_ALPHA: f64 = 0.2;
+
+/// Exponential moving average of request latency (seconds) per source.
+static LATENCY_EMA: std::sync::LazyLock<std::sync::Mutex<HashMap<String, f64>>> =
+    std::sync::LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Next EMA value after `sample`; the first sample seeds the average.
+fn latency_ema_update(prev: Option<f64>, sample: f64) -> f64 {
+    match prev {
+        Some(prev) => LATENCY_EMA_ALPHA * sample + (1.0 - LATENCY_EMA_ALPHA) * prev,
+        None => sample,
+    }
+}
+
+/// Record one HTTP request: request metrics plus the source's latency EMA (helr_request_latency_ema_seconds).
+fn record_request(source_id: &str, status_class: &str, duration_secs: f64) {
+    metrics::record_request(source_id, status_class, duration_secs);
+    let ema = {
+        let mut map = LATENCY_EMA.lock().unwrap_or_else(|e| e.into_inner());
+        let ema = latency_ema_update(map.get(source_id).copied(), duration_secs);
+        map.insert(source_id.to_string(), ema);
+        ema
+    };
+    metrics::set_request_latency_ema(source_id, ema);
+}
+
+/// Last time each cron-scheduled source was checked; a source is due when a cron match falls after it.
+static CRON_LAST_CHECK: std::sync::LazyLock<
+    std::sync::Mutex<HashMap<String, chrono::DateTime<chrono::Utc>>>,
+> = std::sync::LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Per-source lock held for the duration of a poll when schedule.skip_if_running is set.
+static SOURCE_TICK_LOCKS: std::sync::LazyLock<
+    std::sync::Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+> = std::sync::LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Try to take the source's tick lock; None while a previous poll for the source still holds it.
+fn try_claim_source_tick(source_id: &str) -> Option<tokio::sync::OwnedMutexGuard<()>> {
+    let lock = SOURCE_TICK_LOCKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(source_id.to_string())
+        .or_default()
+        .clone();
+    lock.try_lock_owned().ok()
+}
+
+/// True when the source has no schedule.cron or its cron matched since the previous check. On the first check
+/// (startup), true only when the cron matches the current second or schedule.cron_poll_on_startup is set.
+fn cron_due(source_id: &str, source: &SourceConfig, clock: &dyn Clock) -> bool {
+    let Ok(Some(schedule)) = source.schedule.cron_schedule() else {
+        return true;
+    };
+    let now = clock.now();
+    let mut last = CRON_LAST_CHECK.lock().unwrap_or_else(|e| e.into_inner());
+    let due = match last.get(source_id) {
+        None => {
+            source.schedule.cron_poll_on_startup
+                || now
+                    .with_nanosecond(0)
+                    .is_some_and(|second| schedule.includes(second))
+        }
+        Some(prev) => schedule.after(prev).next().is_some_and(|t| t <= now),
+    };
+    last.insert(source_id.to_string(), now);
+    due
+}
+
+/// Random delay in [0, max_secs] (millisecond resolution) for a source's first poll.
+fn startup_jitter_delay(max_secs: u64) -> Duration {
+    Duration::from_millis(crate::jitter::random_range(
+        crate::jitter::Stream::Startup,
+        0..=max_secs.saturating_mul(1000),
+    ))
+}
+
+/// Install (or clear) the circuit transition hook that writes circuit envelopes to this tick's sink.
+fn register_circuit_hook(
+    source_id: &str,
+    source: &SourceConfig,
+    global: &GlobalConfig,
+    event_sink: &Arc<dyn EventSink>,
+    clock: &Arc<dyn Clock>,
+) {
+    let enabled = source
+        .resilience
+        .as_ref()
+        .and_then(|r| r.circuit_breaker.as_ref())
+        .is_some_and(|cb| cb.enabled && cb.emit_circuit_events);
+    if !enabled {
+        crate::circuit::set_transition_hook(source_id, None);
+        return;
+    }
+    let source = source.clone();
+    let global = global.clone();
+    let event_sink = event_sink.clone();
+    let clock = clock.clone();
+    crate::circuit::set_transition_hook(
+        source_id,
+        Some(Arc::new(
+            move |id: &str, from: &'static str, to: &'static str| {
+                if let Err(e) = helpers::emit_circuit_event(
+                    &global,
+                    id,
+                    &source,
+                    &event_sink,
+                    from,
+                    to,
+                    &*clock,
+                ) {
+                    tracing::warn!(source = %id, error = %e, "failed to emit circuit event");
+                }
+            },
+        )),
+    );
+}
+
 #[allow(clippy::too_many_arguments)]
 #[tracing::instrument(skip(
     store,
@@ -204,7 +513,9 @@ pub async fn run_one_tick(
     dedupe_store,
     event_sink,
     record_state,
-    request_semaphore
+    request_semaphore,
+    poll_caches,
+    clock
 ))]
 async fn poll_one_source(
     store: Arc<dyn StateStore>,
@@ -219,7 +530,11 @@ async fn poll_one_source(
     record_state: Option<Arc<RecordState>>,
     rate_limiter: Option<Arc<ClientRateLimiter>>,
     request_semaphore: Option<Arc<Semaphore>>,
+    poll_caches: Arc<PollCaches>,
+    clock: Arc<dyn Clock>,
 ) -> anyhow::Result<()> {
+    register_circuit_hook(source_id, source, global, &event_sink, &clock);
+
     #[cfg(feature = "hooks")]
     if let Some(ref global_hooks) = global.hooks
         && global_hooks.enabled
@@ -253,14 +568,47 @@ async fn poll_one_source(
             record_state,
             rate_limiter.as_ref(),
             request_semaphore,
+            poll_caches,
+            &*clock,
         )
         .await;
     }
 
-    let client = build_client(source.resilience.as_ref())?;
+    let client = poll_caches.source_client(source_id, source)?;
+
+    if source.streaming {
+        let deadline = source
+            .resilience
+            .as_ref()
+            .and_then(|r| r.timeouts.as_ref())
+            .and_then(|t| t.poll_tick_secs)
+            .map(Duration::from_secs);
+        return event_stream::poll_event_stream(
+            store,
+            source_id,
+            source,
+            global,
+            &client,
+            circuit_store,
+            token_cache,
+            dpop_key_cache,
+            dedupe_store,
+            event_sink,
+            rate_limiter.as_ref(),
+            request_semaphore,
+            poll_caches,
+            deadline,
+            &*clock,
+        )
+        .await;
+    }
 
     match &source.pagination {
-        Some(PaginationConfig::LinkHeader { rel, max_pages }) => {
+        Some(PaginationConfig::LinkHeader {
+            rel,
+            max_pages,
+            duplicate_rel,
+        }) => {
             link_header::poll_link_header(
                 store,
                 source_id,
@@ -269,6 +617,7 @@ async fn poll_one_source(
                 &client,
                 rel.as_str(),
                 max_pages.unwrap_or(100),
+                *duplicate_rel,
                 circuit_store,
                 token_cache,
                 dpop_key_cache.clone(),
@@ -277,6 +626,8 @@ async fn poll_one_source(
                 record_state,
                 rate_limiter.as_ref(),
                 request_semaphore.clone(),
+                poll_caches.clone(),
+                &*clock,
             )
             .await
         }
@@ -284,6 +635,7 @@ async fn poll_one_source(
             cursor_param,
             cursor_path,
             max_pages,
+            has_more_path,
         }) => {
             cursor::poll_cursor_pagination(
                 store,
@@ -293,6 +645,7 @@ async fn poll_one_source(
                 &client,
                 cursor_param,
                 cursor_path,
+                has_more_path.as_deref(),
                 max_pages.unwrap_or(100),
                 circuit_store,
                 token_cache,
@@ -302,6 +655,8 @@ async fn poll_one_source(
                 record_state,
                 rate_limiter.as_ref(),
                 request_semaphore.clone(),
+                poll_caches.clone(),
+                &*clock,
             )
             .await
         }
@@ -310,6 +665,9 @@ async fn poll_one_source(
             limit_param,
             limit,
             max_pages,
+            offset_resume,
+            stop_on_total_path,
+            stop_after_empty_pages,
         }) => {
             page_offset::poll_page_offset_pagination(
                 store,
@@ -321,6 +679,9 @@ async fn poll_one_source(
                 limit_param,
                 *limit,
                 max_pages.unwrap_or(100),
+                *offset_resume,
+                stop_on_total_path.as_deref(),
+                *stop_after_empty_pages,
                 circuit_store,
                 token_cache,
                 dpop_key_cache.clone(),
@@ -329,6 +690,8 @@ async fn poll_one_source(
                 record_state,
                 rate_limiter.as_ref(),
                 request_semaphore.clone(),
+                poll_caches.clone(),
+                &*clock,
             )
             .await
         }
@@ -337,6 +700,9 @@ async fn poll_one_source(
             limit_param,
             limit,
             max_pages,
+            offset_resume,
+            stop_on_total_path,
+            stop_after_empty_pages,
         }) => {
             page_offset::poll_offset_pagination(
                 store,
@@ -348,6 +714,9 @@ async fn poll_one_source(
                 limit_param,
                 *limit,
                 max_pages.unwrap_or(100),
+                *offset_resume,
+                stop_on_total_path.as_deref(),
+                *stop_after_empty_pages,
                 circuit_store,
                 token_cache,
                 dpop_key_cache.clone(),
@@ -356,17 +725,28 @@ async fn poll_one_source(
                 record_state,
                 rate_limiter.as_ref(),
                 request_semaphore.clone(),
+                poll_caches.clone(),
+                &*clock,
             )
             .await
         }
         _ => {
+            let url = helpers::url_with_first_request_params(
+                &store,
+                source_id,
+                source,
+                &source.url,
+                &poll_caches,
+                &*clock,
+            )
+            .await?;
             return single_page::poll_single_page(
                 store,
                 source_id,
                 source,
                 global,
                 &client,
-                &source.url,
+                &url,
                 circuit_store,
                 token_cache,
                 dpop_key_cache.clone(),
@@ -375,6 +755,8 @@ async fn poll_one_source(
                 record_state.clone(),
                 rate_limiter.as_ref(),
                 request_semaphore,
+                poll_caches,
+                &*clock,
             )
             .await;
         }
@@ -387,6 +769,136 @@ mod tests {
     use super::parse::*;
     use crate::config::{GlobalConfig, SourceConfig};
 
+    #[test]
+    fn test_request_latency_ema_tracks_sequence() {
+        let source_id = "ema-test-source";
+        for secs in [1.0, 2.0, 2.0, 0.5] {
+            super::record_request(source_id, "2xx", secs);
+        }
+        // 1.0 seeds; then 0.2*2 + 0.8*1 = 1.2; 0.2*2 + 0.8*1.2 = 1.36; 0.2*0.5 + 0.8*1.36 = 1.188
+        let ema = *super::LATENCY_EMA.lock().unwrap().get(source_id).unwrap();
+        assert!((ema - 1.188).abs() < 1e-9, "ema = {}", ema);
+        assert_eq!(super::latency_ema_update(None, 3.0), 3.0);
+    }
+
+    #[test]
+    fn test_startup_jitter_delay_within_bound() {
+        for _ in 0..100 {
+            let d = super::startup_jitter_delay(2);
+            assert!(d <= std::time::Duration::from_secs(2));
+        }
+        assert_eq!(super::startup_jitter_delay(0), std::time::Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_startup_jitter_does_not_hold_up_startup_tick() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_string(r#"[{"id":"1"}]"#))
+            .mount(&server)
+            .await;
+        let yaml = format!(
+            r#"
+sources:
+  jittered-startup:
+    url: "{0}/jittered"
+    schedule:
+      startup_jitter_secs: 3600
+  immediate-startup:
+    url: "{0}/immediate"
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let sink = std::sync::Arc::new(CountingSink(Default::default()));
+        let start = std::time::Instant::now();
+        super::run_one_tick(
+            &config,
+            std::sync::Arc::new(crate::state::MemoryStateStore::new()),
+            None,
+            crate::circuit::new_circuit_store(),
+            crate::oauth2::new_oauth2_token_cache(),
+            None,
+            crate::dedupe::new_dedupe_store(),
+            sink.clone(),
+            None,
+            Default::default(),
+            None,
+            super::new_poll_caches(),
+            None,
+            None,
+            true,
+            std::sync::Arc::new(crate::clock::SystemClock),
+        )
+        .await
+        .unwrap();
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+        let requests = server.received_requests().await.unwrap();
+        assert!(requests.iter().any(|r| r.url.path() == "/immediate"));
+    }
+
+    #[tokio::test]
+    async fn test_startup_jitter_emits_first_poll_only_after_delay() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_string(r#"[{"id":"1"}]"#))
+            .mount(&server)
+            .await;
+        let yaml = format!(
+            r#"
+sources:
+  jittered-first-poll:
+    url: "{}/jittered"
+    schedule:
+      startup_jitter_secs: 60
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let sink = std::sync::Arc::new(CountingSink(Default::default()));
+        let emitted = || sink.0.load(std::sync::atomic::Ordering::SeqCst);
+
+        tokio::time::pause();
+        super::run_one_tick(
+            &config,
+            std::sync::Arc::new(crate::state::MemoryStateStore::new()),
+            None,
+            crate::circuit::new_circuit_store(),
+            crate::oauth2::new_oauth2_token_cache(),
+            None,
+            crate::dedupe::new_dedupe_store(),
+            sink.clone(),
+            None,
+            Default::default(),
+            None,
+            super::new_poll_caches(),
+            None,
+            None,
+            true,
+            std::sync::Arc::new(crate::clock::SystemClock),
+        )
+        .await
+        .unwrap();
+        // The deferred poll is parked on its delay; nothing is requested or emitted yet.
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(emitted(), 0);
+        assert!(server.received_requests().await.unwrap().is_empty());
+
+        // Past the jitter bound the first poll runs; real time again for the HTTP round trip.
+        tokio::time::advance(std::time::Duration::from_secs(61)).await;
+        tokio::time::resume();
+        tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            while emitted() == 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("first poll runs once the delay has passed");
+        assert_eq!(emitted(), 1);
+    }
+
     #[test]
     fn test_parse_events_from_value_top_level_array() {
         let v = serde_json::json!([{"id": 1}, {"id": 2}]);
@@ -504,6 +1016,37 @@ query_params:
         assert!(url.contains("filter="));
     }
 
+    #[test]
+    fn test_query_encoding_variants() {
+        let base = "https://example.com/logs?since=2024-01-01";
+        let url_for = |encoding: &str, filter: &str| {
+            let yaml = format!(
+                "url: \"https://example.com/logs\"\nquery_encoding: {}\nquery_params:\n  filter: '{}'\n",
+                encoding, filter
+            );
+            let source: SourceConfig = serde_yaml_ng::from_str(&yaml).unwrap();
+            url_with_first_request_params_sync(base, &source).unwrap()
+        };
+        let filter = r#"eventType eq "user.session start""#;
+        assert_eq!(
+            url_for("form", filter),
+            "https://example.com/logs?since=2024-01-01&filter=eventType+eq+%22user.session+start%22"
+        );
+        assert_eq!(
+            url_for("standard", filter),
+            "https://example.com/logs?since=2024-01-01&filter=eventType%20eq%20%22user.session%20start%22"
+        );
+        // Raw: an already-encoded value is not encoded again.
+        assert_eq!(
+            url_for("raw", "eventType%20eq%20%22user.session%20start%22"),
+            "https://example.com/logs?since=2024-01-01&filter=eventType%20eq%20%22user.session%20start%22"
+        );
+        assert_eq!(
+            url_for("form", "eventType%20eq"),
+            "https://example.com/logs?since=2024-01-01&filter=eventType%2520eq"
+        );
+    }
+
     #[test]
     fn test_url_with_first_request_params_sync_state_takes_precedence_over_from() {
         let yaml = r#"
@@ -522,6 +1065,53 @@ state:
         );
     }
 
+    #[tokio::test]
+    async fn test_watermark_lookback_subtracts_from_rfc3339() {
+        let yaml = r#"
+url: "https://example.com/logs"
+state:
+  watermark_field: "published"
+  watermark_param: "since"
+  lookback_secs: 300
+"#;
+        let source: SourceConfig = serde_yaml_ng::from_str(yaml).unwrap();
+        let store: std::sync::Arc<dyn crate::state::StateStore> =
+            std::sync::Arc::new(crate::state::MemoryStateStore::new());
+        store
+            .set("okta", "watermark", "2024-01-01T00:02:00.500Z")
+            .await
+            .unwrap();
+        let url = url_with_first_request_params(
+            &store,
+            "okta",
+            &source,
+            "https://example.com/logs",
+            &super::PollCaches::default(),
+            &crate::clock::SystemClock,
+        )
+        .await
+        .unwrap();
+        assert!(
+            url.contains("since=2023-12-31T23%3A57%3A00.500Z"),
+            "watermark minus 5m: {}",
+            url
+        );
+        assert_eq!(
+            store.get("okta", "watermark").await.unwrap().as_deref(),
+            Some("2024-01-01T00:02:00.500Z"),
+            "stored watermark is not rewritten"
+        );
+
+        assert_eq!(
+            apply_lookback("2024-01-01T00:00:00+02:00", Some(60)),
+            "2023-12-31T23:59:00+02:00"
+        );
+        assert_eq!(apply_lookback("1700000000", Some(60)), "1699999940");
+        assert_eq!(apply_lookback("1700000000000", Some(60)), "1699999940000");
+        assert_eq!(apply_lookback("cursor-abc", Some(60)), "cursor-abc");
+        assert_eq!(apply_lookback("1700000000", None), "1700000000");
+    }
+
     #[test]
     fn test_update_max_timestamp_dotted_path() {
         let events = vec![
@@ -534,6 +1124,115 @@ state:
         assert_eq!(max_ts.as_deref(), Some("3000"));
     }
 
+    #[test]
+    fn test_transform_redact_email_in_message() {
+        let source: SourceConfig = serde_yaml_ng::from_str(
+            r#"
+url: "https://example.com/logs"
+transform:
+  redact:
+    - field: displayMessage
+      pattern: "[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\\.[A-Za-z]{2,}"
+    - field: target
+      pattern: "\\d{3}-\\d{2}-(\\d{4})"
+      replacement: "***-**-$1"
+"#,
+        )
+        .unwrap();
+        let event = serde_json::json!({
+            "displayMessage": "Login by alice@example.com then bob.smith@corp.io",
+            "target": [{"ssn": "123-45-6789"}, "no match"],
+            "other": "carol@example.com"
+        });
+        let emitted = build_emitted_event(
+            &source,
+            "okta",
+            "/logs",
+            None,
+            event,
+            None,
+            &crate::clock::SystemClock,
+        );
+        assert_eq!(
+            emitted.event["displayMessage"],
+            "Login by [REDACTED] then [REDACTED]"
+        );
+        assert_eq!(emitted.event["target"][0]["ssn"], "***-**-6789");
+        assert_eq!(emitted.event["target"][1], "no match");
+        assert_eq!(emitted.event["other"], "carol@example.com");
+    }
+
+    #[test]
+    fn test_update_watermark_numeric() {
+        let events = vec![
+            serde_json::json!({"seq": "9"}),
+            serde_json::json!({"seq": "10"}),
+            serde_json::json!({"seq": 2}),
+        ];
+        let mut state = crate::config::SourceStateConfig {
+            watermark_field: "seq".to_string(),
+            watermark_param: "since".to_string(),
+            state_key: None,
+            watermark_numeric: false,
+            lookback_secs: None,
+        };
+        let mut max_ts: Option<String> = None;
+        update_watermark(&mut max_ts, &events, &state);
+        assert_eq!(
+            max_ts.as_deref(),
+            Some("9"),
+            "lexicographic: \"9\" > \"10\""
+        );
+
+        state.watermark_numeric = true;
+        let mut max_ts: Option<String> = None;
+        update_watermark(&mut max_ts, &events, &state);
+        assert_eq!(max_ts.as_deref(), Some("10"));
+
+        let mut max_ts = Some("1700000000.5".to_string());
+        update_watermark(
+            &mut max_ts,
+            &[serde_json::json!({"seq": "1700000000.25"})],
+            &state,
+        );
+        assert_eq!(max_ts.as_deref(), Some("1700000000.5"));
+    }
+
+    #[test]
+    fn test_new_request_id_schemes() {
+        use crate::config::RequestIdScheme;
+
+        let id = new_request_id(RequestIdScheme::Uuid);
+        let groups: Vec<&str> = id.split('-').collect();
+        assert_eq!(
+            groups.iter().map(|g| g.len()).collect::<Vec<_>>(),
+            vec![8, 4, 4, 4, 12],
+            "{id}"
+        );
+        assert!(
+            u128::from_str_radix(&id.replace('-', ""), 16).is_ok(),
+            "{id}"
+        );
+        assert!(groups[2].starts_with('4'), "version 4: {id}");
+        assert!(
+            matches!(&groups[3][..1], "8" | "9" | "a" | "b"),
+            "variant: {id}"
+        );
+        assert_ne!(id, new_request_id(RequestIdScheme::Uuid));
+
+        let ulid = new_request_id(RequestIdScheme::Ulid);
+        assert_eq!(ulid.len(), 26);
+        assert!(
+            ulid.chars()
+                .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase())
+        );
+
+        let a = new_request_id(RequestIdScheme::Monotonic);
+        let b = new_request_id(RequestIdScheme::Monotonic);
+        let n = |s: &str| s.trim_start_matches("helr-").parse::<i64>().unwrap();
+        assert!(n(&b) > n(&a), "{a} {b}");
+    }
+
     #[test]
     fn test_effective_source_label_default() {
         let yaml = r#"url: "https://example.com/logs""#;
@@ -569,6 +1268,220 @@ source_label_key: "origin"
         );
     }
 
+    #[test]
+    fn test_parse_kinded_events_from_multiple_paths() {
+        let yaml = r#"
+url: "https://example.com/combined"
+event_sources:
+  - path: alerts
+    kind: alert
+  - path: data.incidents
+    kind: incident
+  - path: missing
+    kind: other
+"#;
+        let source: SourceConfig = serde_yaml_ng::from_str(yaml).unwrap();
+        let body = br#"{"alerts":[{"id":"a1"},{"id":"a2"}],"data":{"incidents":[{"id":"i1"}]}}"#;
+        let (events, kinds) =
+            parse_kinded_events_from_body_for_source(body, &source, None, 200).unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0]["id"], "a1");
+        assert_eq!(events[1]["id"], "a2");
+        assert_eq!(events[2]["id"], "i1");
+        assert_eq!(kinds, vec!["alert", "alert", "incident"]);
+    }
+
+    #[test]
+    fn test_ndjson_crlf_body_and_trim_whitespace() {
+        let yaml = r#"
+url: "https://example.com/export"
+response_format: ndjson
+trim_whitespace: true
+transform:
+  id_field: id
+"#;
+        let source: SourceConfig = serde_yaml_ng::from_str(yaml).unwrap();
+        let body = b"{\"id\":\"1\",\"msg\":\"first\\r\"}\r\n\r\n{\"id\":\" 2 \",\"msg\":\"line\\r\\nbreak\"}\r\n";
+        let events = parse_events_from_body_for_source(body, &source, 200).unwrap();
+        assert_eq!(events.len(), 2);
+
+        let lines: Vec<String> = events
+            .into_iter()
+            .map(|e| {
+                build_emitted_event(
+                    &source,
+                    "export",
+                    "/export",
+                    None,
+                    e,
+                    None,
+                    &crate::clock::SystemClock,
+                )
+                .to_ndjson_line()
+                .unwrap()
+            })
+            .collect();
+        let first: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(first["event"]["msg"], "first");
+        let second: serde_json::Value = serde_json::from_str(&lines[1]).unwrap();
+        assert_eq!(second["meta"]["id"], "2");
+        // Embedded newlines inside values are escaped, never raw, so each envelope stays one line.
+        assert_eq!(second["event"]["msg"], "line\r\nbreak");
+        assert!(lines.iter().all(|l| !l.contains('\n') && !l.contains('\r')));
+
+        // Without trim_whitespace, only the line ending is stripped; values are kept as sent.
+        let source: SourceConfig = serde_yaml_ng::from_str(
+            "url: \"https://example.com/export\"\nresponse_format: ndjson\n",
+        )
+        .unwrap();
+        let events = parse_events_from_body_for_source(body, &source, 200).unwrap();
+        assert_eq!(events[0]["msg"], "first\r");
+        assert!(
+            parse_events_from_body_for_source(b"{\"id\":1}\r\nnot json\r\n", &source, 200).is_err()
+        );
+    }
+
+    #[test]
+    fn test_big_integer_ids_survive_parse_and_emit() {
+        let source: SourceConfig =
+            serde_yaml_ng::from_str("url: \"https://example.com/logs\"\n").unwrap();
+        // Beyond i64/u64 and beyond f64's 53-bit mantissa; serde_json's arbitrary_precision keeps the digits.
+        let body = br#"[{"id":123456789012345678901234567890,"seq":9007199254740993,"score":0.10000000000000000555}]"#;
+        let events = parse_events_from_body_for_source(body, &source, 200).unwrap();
+        let line = build_emitted_event(
+            &source,
+            "logs",
+            "/logs",
+            None,
+            events[0].clone(),
+            None,
+            &crate::clock::SystemClock,
+        )
+        .to_ndjson_line()
+        .unwrap();
+        assert!(
+            line.contains(
+                r#""event":{"id":123456789012345678901234567890,"score":0.10000000000000000555,"seq":9007199254740993}"#
+            ),
+            "{}",
+            line
+        );
+    }
+
+    #[test]
+    fn test_source_tags_added_to_meta() {
+        let source: SourceConfig = serde_yaml_ng::from_str(
+            r#"
+url: "https://example.com/logs"
+tags:
+  team: secops
+  env: prod
+"#,
+        )
+        .unwrap();
+        let line = build_emitted_event(
+            &source,
+            "tagged",
+            "/logs",
+            None,
+            serde_json::json!({"id": "1"}),
+            None,
+            &crate::clock::SystemClock,
+        )
+        .to_ndjson_line()
+        .unwrap();
+        assert!(
+            line.contains(r#""meta":{"tags":{"env":"prod","team":"secops"}}"#),
+            "{}",
+            line
+        );
+
+        let untagged: SourceConfig =
+            serde_yaml_ng::from_str("url: \"https://example.com/logs\"\n").unwrap();
+        let line = build_emitted_event(
+            &untagged,
+            "plain",
+            "/logs",
+            None,
+            serde_json::json!({}),
+            None,
+            &crate::clock::SystemClock,
+        )
+        .to_ndjson_line()
+        .unwrap();
+        assert!(line.contains(r#""meta":{}"#), "{}", line);
+    }
+
+    #[test]
+    fn test_auto_detect_format_dispatches_on_content_type() {
+        let source: SourceConfig = serde_yaml_ng::from_str(
+            "url: \"https://example.com/export\"\nauto_detect_format: true\n",
+        )
+        .unwrap();
+        let with_type = |ct: &str| {
+            let mut h = reqwest::header::HeaderMap::new();
+            h.insert(
+                reqwest::header::CONTENT_TYPE,
+                reqwest::header::HeaderValue::from_str(ct).unwrap(),
+            );
+            h
+        };
+        let ndjson = b"{\"id\":1}\n{\"id\":2}\n";
+        let json = br#"[{"id":1},{"id":2},{"id":3}]"#;
+
+        let h = with_type("application/x-ndjson");
+        let (events, _) =
+            parse_kinded_events_from_body_for_source(ndjson, &source, Some(&h), 200).unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1]["id"], 2);
+
+        let h = with_type("application/json; charset=utf-8");
+        let (events, _) =
+            parse_kinded_events_from_body_for_source(json, &source, Some(&h), 200).unwrap();
+        assert_eq!(events.len(), 3);
+        // The same NDJSON body labelled as JSON is parsed as one document and fails.
+        assert!(parse_kinded_events_from_body_for_source(ndjson, &source, Some(&h), 200).is_err());
+
+        let h = with_type("application/xml");
+        let err = parse_kinded_events_from_body_for_source(b"<logs/>", &source, Some(&h), 200)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("XML responses are not supported"), "{}", err);
+
+        // Unknown or missing content type: configured response_format (json).
+        let h = with_type("text/plain");
+        assert_eq!(
+            parse_kinded_events_from_body_for_source(json, &source, Some(&h), 200)
+                .unwrap()
+                .0
+                .len(),
+            3
+        );
+        assert_eq!(
+            parse_kinded_events_from_body_for_source(json, &source, None, 200)
+                .unwrap()
+                .0
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_blank_body_is_no_events_only_for_no_content_and_accepted_status() {
+        let source: crate::config::SourceConfig = serde_yaml_ng::from_str(
+            "url: \"https://example.com/logs\"\naccept_status_codes: [304]",
+        )
+        .unwrap();
+        for status in [204, 304] {
+            let (events, _) =
+                parse_kinded_events_from_body_for_source(b" \n", &source, None, status).unwrap();
+            assert!(events.is_empty(), "status {}", status);
+        }
+        assert!(parse_kinded_events_from_body_for_source(b"", &source, None, 200).is_err());
+        #[cfg(feature = "streaming")]
+        assert!(super::streaming::parse_streaming(b"", &source, 200).is_err());
+    }
+
     // --- Phase 1a tests: parse_events_from_body_for_source UTF-8 branching ---
 
     #[test]
@@ -576,7 +1489,7 @@ source_label_key: "origin"
         let yaml = r#"url: "https://example.com/logs""#;
         let source: SourceConfig = serde_yaml_ng::from_str(yaml).unwrap();
         let body = br#"[{"id":1},{"id":2}]"#;
-        let events = parse_events_from_body_for_source(body, &source).unwrap();
+        let events = parse_events_from_body_for_source(body, &source, 200).unwrap();
         assert_eq!(events.len(), 2);
         assert_eq!(events[0]["id"], serde_json::json!(1));
         assert_eq!(events[1]["id"], serde_json::json!(2));
@@ -595,7 +1508,7 @@ on_invalid_utf8: replace
         body.extend_from_slice(b"[{\"msg\":\"hello ");
         body.push(0xFF);
         body.extend_from_slice(b" world\"}]");
-        let events = parse_events_from_body_for_source(&body, &source);
+        let events = parse_events_from_body_for_source(&body, &source, 200);
         assert!(events.is_ok(), "should parse with lossy replacement");
         let events = events.unwrap();
         assert_eq!(events.len(), 1);
@@ -609,7 +1522,7 @@ on_invalid_utf8: replace
         let source: SourceConfig = serde_yaml_ng::from_str(yaml).unwrap();
         // Without on_invalid_utf8, from_slice is used and requires valid UTF-8 JSON
         let body: Vec<u8> = vec![0xff, 0xfe];
-        let result = parse_events_from_body_for_source(&body, &source);
+        let result = parse_events_from_body_for_source(&body, &source, 200);
         assert!(result.is_err(), "from_slice should reject non-JSON bytes");
     }
 
@@ -623,7 +1536,7 @@ response_events_path: "data.records"
 "#;
         let source: SourceConfig = serde_yaml_ng::from_str(yaml).unwrap();
         let body = br#"{"data":{"records":[{"id":"a"},{"id":"b"}]},"meta":"ignored"}"#;
-        let events = parse_events_from_body_for_source(body, &source).unwrap();
+        let events = parse_events_from_body_for_source(body, &source, 200).unwrap();
         assert_eq!(events.len(), 2);
         assert_eq!(events[0]["id"], serde_json::json!("a"));
         assert_eq!(events[1]["id"], serde_json::json!("b"));
@@ -638,7 +1551,7 @@ response_event_object_path: "node"
 "#;
         let source: SourceConfig = serde_yaml_ng::from_str(yaml).unwrap();
         let body = br#"{"data":{"edges":[{"node":{"id":1},"cursor":"c1"},{"node":{"id":2},"cursor":"c2"}]}}"#;
-        let events = parse_events_from_body_for_source(body, &source).unwrap();
+        let events = parse_events_from_body_for_source(body, &source, 200).unwrap();
         assert_eq!(events.len(), 2);
         assert_eq!(events[0], serde_json::json!({"id": 1}));
         assert_eq!(events[1], serde_json::json!({"id": 2}));
@@ -654,7 +1567,7 @@ response_event_object_path: "payload.data"
         let source: SourceConfig = serde_yaml_ng::from_str(yaml).unwrap();
         let body =
             br#"{"results":[{"payload":{"data":{"id":"x"}}},{"payload":{"data":{"id":"y"}}}]}"#;
-        let events = parse_events_from_body_for_source(body, &source).unwrap();
+        let events = parse_events_from_body_for_source(body, &source, 200).unwrap();
         assert_eq!(events.len(), 2);
         assert_eq!(events[0], serde_json::json!({"id": "x"}));
         assert_eq!(events[1], serde_json::json!({"id": "y"}));
@@ -668,7 +1581,7 @@ response_events_path: "nonexistent.path"
 "#;
         let source: SourceConfig = serde_yaml_ng::from_str(yaml).unwrap();
         let body = br#"{"data":[{"id":1}]}"#;
-        let result = parse_events_from_body_for_source(body, &source);
+        let result = parse_events_from_body_for_source(body, &source, 200);
         assert!(result.is_err());
         let err = result.unwrap_err().to_string();
         assert!(
@@ -725,6 +1638,1630 @@ response_events_path: "nonexistent.path"
         let result = read_body_with_limit(response, None).await.unwrap();
         assert_eq!(result.len(), body.len());
     }
+
+    #[tokio::test]
+    async fn test_read_body_with_limit_aborts_chunked_download_early() {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Chunked response (no Content-Length) of up to 1 GiB; the cap must stop the download mid-stream.
+        const CHUNK: usize = 64 * 1024;
+        const TOTAL_CHUNKS: u64 = 16 * 1024;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let written = std::sync::Arc::new(AtomicU64::new(0));
+        let written_server = written.clone();
+        let server = tokio::spawn(async move {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let mut req = [0u8; 1024];
+            let _ = sock.read(&mut req).await;
+            let head = "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ntransfer-encoding: chunked\r\n\r\n";
+            if sock.write_all(head.as_bytes()).await.is_err() {
+                return;
+            }
+            let mut frame = format!("{:x}\r\n", CHUNK).into_bytes();
+            frame.extend(std::iter::repeat_n(b'x', CHUNK));
+            frame.extend_from_slice(b"\r\n");
+            for _ in 0..TOTAL_CHUNKS {
+                if sock.write_all(&frame).await.is_err() {
+                    return;
+                }
+                written_server.fetch_add(CHUNK as u64, Ordering::SeqCst);
+            }
+            let _ = sock.write_all(b"0\r\n\r\n").await;
+        });
+
+        let response = reqwest::get(format!("http://{}/", addr)).await.unwrap();
+        assert!(response.content_length().is_none());
+        let limit = 256 * 1024;
+        let err = read_body_with_limit(response, Some(limit))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("exceeds max_response_bytes"), "{err}");
+        let reported: u64 = err
+            .trim_start_matches("response body size ")
+            .split(' ')
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        // Never buffered more than the cap plus one chunk.
+        assert!(reported <= limit + CHUNK as u64, "{err}");
+
+        // The connection is dropped on abort, so the server cannot push the whole body.
+        tokio::time::timeout(std::time::Duration::from_secs(10), server)
+            .await
+            .expect("server should stop once the client disconnects")
+            .unwrap();
+        assert!(
+            written.load(Ordering::SeqCst) < CHUNK as u64 * TOTAL_CHUNKS / 4,
+            "server wrote {} bytes",
+            written.load(Ordering::SeqCst)
+        );
+    }
+
+    #[test]
+    fn test_rename_fields_and_schema_version_stamp() {
+        let source: SourceConfig = serde_yaml_ng::from_str(
+            r#"
+url: "https://example.com/logs"
+transform:
+  id_field: uuid
+  rename_fields:
+    eventType: action
+"#,
+        )
+        .unwrap();
+        let global = GlobalConfig {
+            schema_version: Some("2".to_string()),
+            ..Default::default()
+        };
+        let event = serde_json::json!({"uuid": "u1", "eventType": "user.login", "actor": "a"});
+        let emitted = build_emitted_event(
+            &source,
+            "okta",
+            "/logs",
+            None,
+            event,
+            None,
+            &crate::clock::SystemClock,
+        );
+        assert_eq!(
+            emitted.event,
+            serde_json::json!({"uuid": "u1", "action": "user.login", "actor": "a"})
+        );
+        assert_eq!(emitted.meta.id.as_deref(), Some("u1"));
+
+        let capture = std::sync::Arc::new(crate::golden::CaptureSink::new());
+        let sink: std::sync::Arc<dyn crate::output::EventSink> = capture.clone();
+        emit_event_line(&global, "okta", &source, &sink, emitted).unwrap();
+        let line: serde_json::Value = serde_json::from_str(&capture.lines()[0]).unwrap();
+        assert_eq!(line["meta"]["schema_version"], "2");
+        assert_eq!(line["event"]["action"], "user.login");
+        assert!(line["event"].get("eventType").is_none());
+
+        // Renames apply in config order.
+        let chained: SourceConfig = serde_yaml_ng::from_str(
+            r#"
+url: "https://example.com/logs"
+transform:
+  rename_fields:
+    a: b
+    b: c
+"#,
+        )
+        .unwrap();
+        for _ in 0..8 {
+            let emitted = build_emitted_event(
+                &chained,
+                "okta",
+                "/logs",
+                None,
+                serde_json::json!({"a": 1}),
+                None,
+                &crate::clock::SystemClock,
+            );
+            assert_eq!(emitted.event, serde_json::json!({"c": 1}));
+        }
+    }
+
+    #[test]
+    fn test_include_ingested_at_stamps_collection_time() {
+        let source: SourceConfig = serde_yaml_ng::from_str(
+            r#"
+url: "https://example.com/logs"
+include_ingested_at: true
+transform:
+  timestamp_field: published
+"#,
+        )
+        .unwrap();
+        let clock = crate::clock::MockClock::new(
+            chrono::DateTime::parse_from_rfc3339("2024-05-01T12:00:00Z")
+                .unwrap()
+                .with_timezone(&chrono::Utc),
+        );
+        let event = serde_json::json!({"published": "2020-01-01T00:00:00Z", "id": "e1"});
+        let emitted = build_emitted_event(&source, "okta", "/logs", None, event, None, &clock);
+        assert_eq!(emitted.ts, "2020-01-01T00:00:00Z");
+        assert_eq!(
+            emitted.meta.ingested_at.as_deref(),
+            Some("2024-05-01T12:00:00+00:00"),
+            "ingested_at is the collection time"
+        );
+        let line: serde_json::Value =
+            serde_json::from_str(&emitted.to_ndjson_line().unwrap()).unwrap();
+        assert_eq!(line["ts"], "2020-01-01T00:00:00Z");
+        assert!(line["meta"]["ingested_at"].is_string());
+
+        let source: SourceConfig =
+            serde_yaml_ng::from_str("url: \"https://example.com/logs\"\n").unwrap();
+        let emitted = build_emitted_event(
+            &source,
+            "okta",
+            "/logs",
+            None,
+            serde_json::json!({}),
+            None,
+            &clock,
+        );
+        assert_eq!(
+            emitted.ts, "2024-05-01T12:00:00+00:00",
+            "ts falls back to the clock"
+        );
+        assert!(emitted.meta.ingested_at.is_none());
+    }
+
+    // --- Load shedding ---
+
+    struct CountingSink(std::sync::atomic::AtomicUsize);
+
+    impl crate::output::EventSink for CountingSink {
+        fn write_line(&self, _line: &str) -> anyhow::Result<()> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    /// One `run_one_tick` over `config` with fresh in-memory state and default options.
+    async fn tick(
+        config: &crate::config::Config,
+        sink: std::sync::Arc<dyn crate::output::EventSink>,
+    ) -> anyhow::Result<()> {
+        tick_with(
+            config,
+            std::sync::Arc::new(crate::state::MemoryStateStore::new()),
+            sink,
+            Default::default(),
+            super::new_poll_caches(),
+        )
+        .await
+    }
+
+    /// Like [`tick`], with the caller's state store, last-error store and poll caches.
+    async fn tick_with(
+        config: &crate::config::Config,
+        store: std::sync::Arc<dyn crate::state::StateStore>,
+        sink: std::sync::Arc<dyn crate::output::EventSink>,
+        last_errors: super::LastErrorStore,
+        poll_caches: std::sync::Arc<super::PollCaches>,
+    ) -> anyhow::Result<()> {
+        super::run_one_tick(
+            config,
+            store,
+            None,
+            crate::circuit::new_circuit_store(),
+            crate::oauth2::new_oauth2_token_cache(),
+            None,
+            crate::dedupe::new_dedupe_store(),
+            sink,
+            None,
+            last_errors,
+            None,
+            poll_caches,
+            None,
+            None,
+            false,
+            std::sync::Arc::new(crate::clock::SystemClock),
+        )
+        .await
+    }
+
+    /// Values of query param `param` across the requests `server` received, in order; requests without it are skipped.
+    async fn query_values(server: &wiremock::MockServer, param: &str) -> Vec<String> {
+        server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter_map(|r| {
+                r.url
+                    .query_pairs()
+                    .find(|(k, _)| k == param)
+                    .map(|(_, v)| v.into_owned())
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_skip_if_running_skips_ticks_while_previous_poll_runs() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .set_body_string(r#"[{"id":"1"}]"#)
+                    .set_delay(std::time::Duration::from_millis(600)),
+            )
+            .mount(&server)
+            .await;
+        let yaml = format!(
+            r#"
+sources:
+  skip-if-running-slow:
+    url: "{0}/logs"
+    schedule:
+      interval_secs: 1
+      skip_if_running: true
+"#,
+            server.uri()
+        );
+        let config: std::sync::Arc<crate::config::Config> =
+            std::sync::Arc::new(serde_yaml_ng::from_str(&yaml).unwrap());
+        let sink = std::sync::Arc::new(CountingSink(Default::default()));
+        let tick = |config: std::sync::Arc<crate::config::Config>,
+                    sink: std::sync::Arc<CountingSink>| async move {
+            tick(&config, sink).await
+        };
+
+        // Short interval: a new tick fires every 100ms while the first poll takes 600ms.
+        let mut handles = Vec::new();
+        for _ in 0..4 {
+            handles.push(tokio::spawn(tick(config.clone(), sink.clone())));
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        for h in handles {
+            h.await.unwrap().unwrap();
+        }
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+        assert_eq!(sink.0.load(std::sync::atomic::Ordering::Relaxed), 1);
+
+        // Once the slow poll has finished, the next tick runs again.
+        tick(config.clone(), sink.clone()).await.unwrap();
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_run_one_tick_sheds_low_priority_source_under_load() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_string(r#"[{"id":"1"}]"#))
+            .mount(&server)
+            .await;
+        let yaml = format!(
+            r#"
+sources:
+  low:
+    url: "{0}/low"
+    priority: 2
+  high:
+    url: "{0}/high"
+    priority: 9
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let sink = std::sync::Arc::new(CountingSink(Default::default()));
+        let under_load = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(true));
+
+        super::run_one_tick(
+            &config,
+            std::sync::Arc::new(crate::state::MemoryStateStore::new()),
+            None,
+            crate::circuit::new_circuit_store(),
+            crate::oauth2::new_oauth2_token_cache(),
+            None,
+            crate::dedupe::new_dedupe_store(),
+            sink.clone(),
+            None,
+            std::sync::Arc::new(tokio::sync::RwLock::new(Default::default())),
+            None,
+            super::new_poll_caches(),
+            Some(under_load),
+            Some(5),
+            false,
+            std::sync::Arc::new(crate::clock::SystemClock),
+        )
+        .await
+        .unwrap();
+
+        let paths: Vec<String> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| r.url.path().to_string())
+            .collect();
+        assert_eq!(paths, vec!["/high".to_string()]);
+        assert_eq!(sink.0.load(std::sync::atomic::Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_global_max_concurrent_requests_total_serializes_requests() {
+        use wiremock::matchers::{method, query_param};
+        let server = wiremock::MockServer::start().await;
+        let delay = std::time::Duration::from_millis(150);
+        wiremock::Mock::given(method("GET"))
+            .and(query_param("page", "2"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .set_body_string(r#"[{"id":"2"}]"#)
+                    .set_delay(delay),
+            )
+            .mount(&server)
+            .await;
+        for path in ["/a", "/b"] {
+            wiremock::Mock::given(method("GET"))
+                .and(wiremock::matchers::path(path))
+                .respond_with(
+                    wiremock::ResponseTemplate::new(200)
+                        .set_body_string(r#"[{"id":"1"}]"#)
+                        .insert_header(
+                            "Link",
+                            format!("<{}{}?page=2>; rel=\"next\"", server.uri(), path).as_str(),
+                        )
+                        .set_delay(delay),
+                )
+                .mount(&server)
+                .await;
+        }
+        let yaml = format!(
+            r#"
+global:
+  bulkhead:
+    max_concurrent_requests_total: 1
+sources:
+  a:
+    url: "{0}/a"
+    pagination:
+      strategy: link_header
+      rel: next
+  b:
+    url: "{0}/b"
+    pagination:
+      strategy: link_header
+      rel: next
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let sink = std::sync::Arc::new(CountingSink(Default::default()));
+
+        let start = std::time::Instant::now();
+        tick(&config, sink.clone()).await.unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(server.received_requests().await.unwrap().len(), 4);
+        assert_eq!(sink.0.load(std::sync::atomic::Ordering::Relaxed), 4);
+        // Four requests that never overlap take at least 4 × delay; unbounded they finish in ~2 × delay.
+        assert!(
+            elapsed >= delay * 4,
+            "requests overlapped: elapsed {:?}",
+            elapsed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_offset_resume_starts_next_tick_at_saved_page() {
+        use wiremock::matchers::{method, query_param};
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(method("GET"))
+            .and(query_param("page", "1"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_string(r#"[{"id":"1"},{"id":"2"}]"#),
+            )
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(method("GET"))
+            .and(query_param("page", "2"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_string(r#"[{"id":"3"}]"#))
+            .mount(&server)
+            .await;
+        let yaml = format!(
+            r#"
+sources:
+  pages:
+    url: "{}/events"
+    pagination:
+      strategy: page_offset
+      page_param: page
+      limit_param: limit
+      limit: 2
+      offset_resume: true
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let store: std::sync::Arc<dyn crate::state::StateStore> =
+            std::sync::Arc::new(crate::state::MemoryStateStore::new());
+        for _ in 0..2 {
+            tick_with(
+                &config,
+                store.clone(),
+                std::sync::Arc::new(CountingSink(Default::default())),
+                Default::default(),
+                super::new_poll_caches(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let pages = query_values(&server, "page").await;
+        // Tick 1 reads pages 1 and 2 (partial); tick 2 resumes at the partial page 2.
+        assert_eq!(pages, vec!["1", "2", "2"]);
+        assert_eq!(
+            store.get("pages", "resume_page").await.unwrap().as_deref(),
+            Some("2")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stop_after_empty_pages_skips_isolated_empty_page() {
+        use wiremock::matchers::{method, query_param};
+        let server = wiremock::MockServer::start().await;
+        for (page, body) in [
+            ("1", r#"[{"id":"1"},{"id":"2"}]"#),
+            ("2", "[]"),
+            ("3", r#"[{"id":"3"},{"id":"4"}]"#),
+            ("4", r#"[{"id":"5"}]"#),
+        ] {
+            wiremock::Mock::given(method("GET"))
+                .and(query_param("page", page))
+                .respond_with(wiremock::ResponseTemplate::new(200).set_body_string(body))
+                .mount(&server)
+                .await;
+        }
+        let run = |stop_after: Option<u32>| {
+            let extra = stop_after
+                .map(|n| format!("      stop_after_empty_pages: {}\n", n))
+                .unwrap_or_default();
+            let yaml = format!(
+                r#"
+sources:
+  sparse:
+    url: "{}/events"
+    pagination:
+      strategy: page_offset
+      page_param: page
+      limit_param: limit
+      limit: 2
+{}"#,
+                server.uri(),
+                extra
+            );
+            async move {
+                let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+                let sink = std::sync::Arc::new(CountingSink(Default::default()));
+                tick(&config, sink.clone()).await.unwrap();
+                sink.0.load(std::sync::atomic::Ordering::Relaxed)
+            }
+        };
+
+        // Default: the empty page 2 ends the poll.
+        assert_eq!(run(None).await, 2);
+        // Tolerating one empty page reads through to the short page 4.
+        assert_eq!(run(Some(2)).await, 5);
+        let pages = query_values(&server, "page").await;
+        assert_eq!(pages, vec!["1", "2", "1", "2", "3", "4"]);
+    }
+
+    #[test]
+    fn test_cron_due_advances_with_mock_clock() {
+        use crate::clock::MockClock;
+        let source: SourceConfig = serde_yaml_ng::from_str(
+            r#"
+url: "https://example.com/logs"
+schedule:
+  cron: "*/5 * * * *"
+"#,
+        )
+        .unwrap();
+        let start = chrono::DateTime::parse_from_rfc3339("2026-01-01T00:00:30Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let clock = MockClock::new(start);
+        let id = "cron-mock-clock";
+        assert!(
+            !super::cron_due(id, &source, &clock),
+            "no startup poll outside the cron"
+        );
+        assert!(
+            !super::cron_due(id, &source, &clock),
+            "no match since last check"
+        );
+        clock.advance(chrono::Duration::minutes(1));
+        assert!(
+            !super::cron_due(id, &source, &clock),
+            "00:01:30: window not reached"
+        );
+        clock.advance(chrono::Duration::minutes(4));
+        assert!(
+            super::cron_due(id, &source, &clock),
+            "00:05:30: 00:05 matched"
+        );
+        assert!(!super::cron_due(id, &source, &clock), "window consumed");
+        clock.set(start + chrono::Duration::minutes(30));
+        assert!(
+            super::cron_due(id, &source, &clock),
+            "missed windows fire once"
+        );
+
+        let on_match = MockClock::new(
+            chrono::DateTime::parse_from_rfc3339("2026-01-01T00:10:00.250Z")
+                .unwrap()
+                .with_timezone(&chrono::Utc),
+        );
+        assert!(
+            super::cron_due("cron-start-on-match", &source, &on_match),
+            "startup on a cron match polls"
+        );
+        let mut on_startup = source.clone();
+        on_startup.schedule.cron_poll_on_startup = true;
+        assert!(
+            super::cron_due("cron-poll-on-startup", &on_startup, &clock),
+            "cron_poll_on_startup polls at startup"
+        );
+
+        assert_eq!(
+            secs_until(start.timestamp() + 90, &MockClock::new(start)),
+            90
+        );
+        assert_eq!(secs_until(start.timestamp() - 5, &clock), 0);
+    }
+
+    #[tokio::test]
+    async fn test_rotating_params_cycle_one_value_per_tick() {
+        use wiremock::matchers::method;
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_string(r#"[{"id":"1"}]"#))
+            .mount(&server)
+            .await;
+        let yaml = format!(
+            r#"
+sources:
+  tenants:
+    url: "{}/events"
+    pagination:
+      strategy: link_header
+      rel: next
+    rotating_params:
+      param: shard
+      values: ["a", "b", "c"]
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let store: std::sync::Arc<dyn crate::state::StateStore> =
+            std::sync::Arc::new(crate::state::MemoryStateStore::new());
+        for _ in 0..4 {
+            tick_with(
+                &config,
+                store.clone(),
+                std::sync::Arc::new(CountingSink(Default::default())),
+                Default::default(),
+                super::new_poll_caches(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let shards = query_values(&server, "shard").await;
+        assert_eq!(shards, vec!["a", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_global_request_semaphore_follows_reloaded_cap() {
+        let caches = super::PollCaches::default();
+        let mut global: GlobalConfig =
+            serde_yaml_ng::from_str("bulkhead:\n  max_concurrent_requests_total: 1\n").unwrap();
+        let first = caches.global_request_semaphore(&global).unwrap();
+        assert!(std::sync::Arc::ptr_eq(
+            &first,
+            &caches.global_request_semaphore(&global).unwrap()
+        ));
+        global
+            .bulkhead
+            .as_mut()
+            .unwrap()
+            .max_concurrent_requests_total = Some(3);
+        let reloaded = caches.global_request_semaphore(&global).unwrap();
+        assert_eq!(reloaded.available_permits(), 3);
+        let _held = reloaded.try_acquire().unwrap();
+        assert_eq!(
+            super::PollCaches::default()
+                .global_request_semaphore(&global)
+                .unwrap()
+                .available_permits(),
+            3,
+            "separate caches do not share permits"
+        );
+        global.bulkhead = None;
+        assert!(caches.global_request_semaphore(&global).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_max_connections_caps_requests_across_ticks() {
+        let source: SourceConfig = serde_yaml_ng::from_str(
+            r#"
+url: "https://example.com/logs"
+resilience:
+  max_connections: 1
+"#,
+        )
+        .unwrap();
+        let global = GlobalConfig::default();
+        let caches = super::PollCaches::default();
+        // Each tick builds its own bulkhead semaphore; the connection cap is shared.
+        let held = acquire_request_permits(&global, "max-conn", &source, &None, &caches)
+            .await
+            .unwrap();
+        let second = tokio::time::timeout(
+            std::time::Duration::from_millis(100),
+            acquire_request_permits(&global, "max-conn", &source, &None, &caches),
+        )
+        .await;
+        assert!(second.is_err(), "second request waits for the connection");
+        drop(held);
+        acquire_request_permits(&global, "max-conn", &source, &None, &caches)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rotating_params_failed_tick_retries_same_value() {
+        use wiremock::matchers::{method, query_param};
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(method("GET"))
+            .and(query_param("shard", "b"))
+            .respond_with(wiremock::ResponseTemplate::new(500))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_string(r#"[{"id":"1"}]"#))
+            .mount(&server)
+            .await;
+        let yaml = format!(
+            r#"
+sources:
+  tenants:
+    url: "{}/events"
+    pagination:
+      strategy: link_header
+      rel: next
+    rotating_params:
+      param: shard
+      values: ["a", "b", "c"]
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let store: std::sync::Arc<dyn crate::state::StateStore> =
+            std::sync::Arc::new(crate::state::MemoryStateStore::new());
+        for _ in 0..4 {
+            tick_with(
+                &config,
+                store.clone(),
+                std::sync::Arc::new(CountingSink(Default::default())),
+                Default::default(),
+                super::new_poll_caches(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let shards = query_values(&server, "shard").await;
+        assert_eq!(shards, vec!["a", "b", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_start_mode_latest_saves_seed_only_after_successful_poll() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(500))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_string("[]"))
+            .mount(&server)
+            .await;
+        let yaml = format!(
+            r#"
+sources:
+  latest-seed:
+    url: "{}/events"
+    start_mode: latest
+    pagination:
+      strategy: link_header
+      rel: next
+    state:
+      watermark_field: "published"
+      watermark_param: "since"
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let store: std::sync::Arc<dyn crate::state::StateStore> =
+            std::sync::Arc::new(crate::state::MemoryStateStore::new());
+        let poll_caches = super::new_poll_caches();
+        let run = || {
+            tick_with(
+                &config,
+                store.clone(),
+                std::sync::Arc::new(CountingSink(Default::default())),
+                Default::default(),
+                poll_caches.clone(),
+            )
+        };
+
+        run().await.unwrap();
+        assert_eq!(
+            store.get("latest-seed", "watermark").await.unwrap(),
+            None,
+            "failed first poll leaves no seed"
+        );
+
+        run().await.unwrap();
+        let since = query_values(&server, "since").await;
+        assert_eq!(since.len(), 2);
+        assert_eq!(since[0], since[1], "retry starts from the same seed");
+        assert_eq!(
+            store
+                .get("latest-seed", "watermark")
+                .await
+                .unwrap()
+                .as_deref(),
+            Some(since[0].as_str()),
+            "seed saved after the successful poll"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_start_mode_latest_without_state_reuses_saved_seed_on_later_ticks() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_string("[]"))
+            .mount(&server)
+            .await;
+        let start = chrono::DateTime::parse_from_rfc3339("2026-03-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let clock = std::sync::Arc::new(crate::clock::MockClock::new(start));
+        let yaml = format!(
+            r#"
+sources:
+  single:
+    url: "{0}/single"
+    start_mode: latest
+  cursor:
+    url: "{0}/cursor"
+    start_mode: latest
+    pagination:
+      strategy: cursor
+      cursor_param: cursor
+      cursor_path: meta.next
+  paged:
+    url: "{0}/paged"
+    start_mode: latest
+    pagination:
+      strategy: page_offset
+      page_param: page
+      limit_param: limit
+      limit: 2
+  from-wins:
+    url: "{0}/from-wins"
+    start_mode: latest
+    from: "2020-01-01T00:00:00Z"
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let store: std::sync::Arc<dyn crate::state::StateStore> =
+            std::sync::Arc::new(crate::state::MemoryStateStore::new());
+        let poll_caches = super::new_poll_caches();
+        for _ in 0..2 {
+            super::run_one_tick(
+                &config,
+                store.clone(),
+                None,
+                crate::circuit::new_circuit_store(),
+                crate::oauth2::new_oauth2_token_cache(),
+                None,
+                crate::dedupe::new_dedupe_store(),
+                std::sync::Arc::new(CountingSink(Default::default())),
+                None,
+                Default::default(),
+                None,
+                poll_caches.clone(),
+                None,
+                None,
+                false,
+                clock.clone(),
+            )
+            .await
+            .unwrap();
+            clock.advance(chrono::Duration::hours(1));
+        }
+
+        let requests = server.received_requests().await.unwrap();
+        for path in ["/single", "/cursor", "/paged", "/from-wins"] {
+            let since: Vec<String> = requests
+                .iter()
+                .filter(|r| r.url.path() == path)
+                .filter_map(|r| {
+                    r.url
+                        .query_pairs()
+                        .find(|(k, _)| k == "since")
+                        .map(|(_, v)| v.into_owned())
+                })
+                .collect();
+            let expected = if path == "/from-wins" {
+                "2020-01-01T00:00:00Z"
+            } else {
+                "2026-03-01T12:00:00Z"
+            };
+            assert_eq!(
+                since,
+                vec![expected, expected],
+                "{path}: second tick keeps the first tick's start"
+            );
+        }
+        assert_eq!(
+            store.get("single", "start_seed").await.unwrap().as_deref(),
+            Some("2026-03-01T12:00:00Z")
+        );
+        assert_eq!(store.get("from-wins", "start_seed").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_stop_on_total_path_skips_trailing_empty_page() {
+        use wiremock::matchers::{method, query_param};
+        let server = wiremock::MockServer::start().await;
+        for (page, ids) in [("1", r#"["1","2"]"#), ("2", r#"["3","4"]"#)] {
+            wiremock::Mock::given(method("GET"))
+                .and(query_param("page", page))
+                .respond_with(
+                    wiremock::ResponseTemplate::new(200).set_body_string(format!(
+                        r#"{{"items": {}, "meta": {{"total": 4}}}}"#,
+                        ids
+                    )),
+                )
+                .mount(&server)
+                .await;
+        }
+        wiremock::Mock::given(method("GET"))
+            .and(query_param("page", "3"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .set_body_string(r#"{"items": [], "meta": {"total": 4}}"#),
+            )
+            .mount(&server)
+            .await;
+        let yaml = format!(
+            r#"
+sources:
+  pages:
+    url: "{}/events"
+    pagination:
+      strategy: page_offset
+      page_param: page
+      limit_param: limit
+      limit: 2
+      stop_on_total_path: meta.total
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let sink = std::sync::Arc::new(CountingSink(Default::default()));
+        tick(&config, sink.clone()).await.unwrap();
+
+        // 2 pages × limit 2 covers total 4: no request for the empty page 3.
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+        assert_eq!(sink.0.load(std::sync::atomic::Ordering::Relaxed), 4);
+    }
+
+    #[tokio::test]
+    async fn test_no_content_and_accepted_status_yield_no_events_without_error() {
+        use wiremock::matchers::{method, path};
+        let server = wiremock::MockServer::start().await;
+        for p in ["/single", "/cursor"] {
+            wiremock::Mock::given(method("GET"))
+                .and(path(p))
+                .respond_with(wiremock::ResponseTemplate::new(204))
+                .mount(&server)
+                .await;
+        }
+        wiremock::Mock::given(method("GET"))
+            .and(path("/not-modified"))
+            .respond_with(wiremock::ResponseTemplate::new(304))
+            .mount(&server)
+            .await;
+        let yaml = format!(
+            r#"
+sources:
+  single:
+    url: "{0}/single"
+  cursor:
+    url: "{0}/cursor"
+    response_events_path: data.items
+    pagination:
+      strategy: cursor
+      cursor_param: cursor
+      cursor_path: meta.next
+  not-modified:
+    url: "{0}/not-modified"
+    accept_status_codes: [304]
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let sink = std::sync::Arc::new(CountingSink(Default::default()));
+        let last_errors: super::LastErrorStore =
+            std::sync::Arc::new(tokio::sync::RwLock::new(Default::default()));
+        tick_with(
+            &config,
+            std::sync::Arc::new(crate::state::MemoryStateStore::new()),
+            sink.clone(),
+            last_errors.clone(),
+            super::new_poll_caches(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+        assert!(
+            last_errors.read().await.is_empty(),
+            "unexpected errors: {:?}",
+            last_errors.read().await
+        );
+        assert_eq!(sink.0.load(std::sync::atomic::Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_alert_webhook_fires_at_consecutive_failure_threshold() {
+        use wiremock::matchers::{method, path};
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(method("GET"))
+            .and(path("/logs"))
+            .respond_with(wiremock::ResponseTemplate::new(403))
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(method("POST"))
+            .and(path("/alert"))
+            .respond_with(wiremock::ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        let yaml = format!(
+            r#"
+sources:
+  alert-failing:
+    url: "{0}/logs"
+    alert:
+      webhook_url: "{0}/alert"
+      threshold_consecutive_failures: 2
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let alerts = || async {
+            server
+                .received_requests()
+                .await
+                .unwrap()
+                .into_iter()
+                .filter(|r| r.url.path() == "/alert")
+                .collect::<Vec<_>>()
+        };
+        let mut sent = Vec::new();
+        for _ in 0..3 {
+            tick(
+                &config,
+                std::sync::Arc::new(CountingSink(Default::default())),
+            )
+            .await
+            .unwrap();
+            sent.push(alerts().await.len());
+        }
+
+        assert_eq!(
+            sent,
+            vec![0, 1, 1],
+            "alert once, when the 2nd failure in a row happens"
+        );
+        let alert: serde_json::Value = serde_json::from_slice(&alerts().await[0].body).unwrap();
+        assert_eq!(alert["source"], "alert-failing");
+        assert_eq!(alert["consecutive_failures"], 2);
+        assert!(
+            alert["error"].as_str().unwrap().contains("403"),
+            "{}",
+            alert
+        );
+    }
+
+    #[tokio::test]
+    async fn test_capture_headers_copies_request_id_into_meta() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .insert_header("X-Request-Id", "req-abc123")
+                    .insert_header("X-Other", "ignored")
+                    .set_body_string(r#"[{"id":"1"},{"id":"2"}]"#),
+            )
+            .mount(&server)
+            .await;
+        let yaml = format!(
+            r#"
+sources:
+  captured:
+    url: "{0}/logs"
+    capture_headers: ["X-Request-Id", "X-RateLimit-Remaining"]
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let sink = std::sync::Arc::new(crate::golden::CaptureSink::new());
+        tick(&config, sink.clone()).await.unwrap();
+
+        let lines = sink.lines();
+        assert_eq!(lines.len(), 2);
+        for line in &lines {
+            let v: serde_json::Value = serde_json::from_str(line).unwrap();
+            assert_eq!(
+                v["meta"]["headers"],
+                serde_json::json!({"x-request-id": "req-abc123"}),
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_include_dedupe_id_adds_meta_dedupe_id() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_string(
+                r#"[{"event":{"id":"a"}},{"event":{"id":"b"}},{"event":{"id":"a"}},{"event":{"id":" c "}},{"other":1}]"#,
+            ))
+            .mount(&server)
+            .await;
+        let yaml = format!(
+            r#"
+sources:
+  deduped:
+    url: "{0}/logs"
+    trim_whitespace: true
+    dedupe:
+      id_path: event.id
+      include_dedupe_id: true
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let sink = std::sync::Arc::new(crate::golden::CaptureSink::new());
+        tick(&config, sink.clone()).await.unwrap();
+
+        let ids: Vec<serde_json::Value> = sink
+            .lines()
+            .iter()
+            .map(|l| {
+                serde_json::from_str::<serde_json::Value>(l).unwrap()["meta"]["dedupe_id"].clone()
+            })
+            .collect();
+        // The id the filter used: before trim_whitespace, and "" when the event has none.
+        assert_eq!(
+            ids,
+            serde_json::json!(["a", "b", " c ", ""])
+                .as_array()
+                .unwrap()
+                .clone()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streaming_source_emits_each_ndjson_line() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(
+                "{\"id\":\"1\"}\n{\"id\":\"2\"}\r\n\n{\"id\":\"3\"}",
+                "application/x-ndjson",
+            ))
+            .mount(&server)
+            .await;
+        let yaml = format!(
+            r#"
+sources:
+  firehose:
+    url: "{0}/stream"
+    streaming: true
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let sink = std::sync::Arc::new(crate::golden::CaptureSink::new());
+        tick(&config, sink.clone()).await.unwrap();
+
+        let ids: Vec<String> = sink
+            .lines()
+            .iter()
+            .map(|l| {
+                let v: serde_json::Value = serde_json::from_str(l).unwrap();
+                v["event"]["id"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(ids, vec!["1", "2", "3"]);
+    }
+
+    /// Keep-alive HTTP server answering every request with `[]`; counts accepted connections and requests.
+    async fn keep_alive_server() -> (
+        std::net::SocketAddr,
+        std::sync::Arc<std::sync::atomic::AtomicUsize>,
+        std::sync::Arc<std::sync::atomic::AtomicUsize>,
+        tokio::task::JoinHandle<()>,
+    ) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connections = std::sync::Arc::new(AtomicUsize::new(0));
+        let requests = std::sync::Arc::new(AtomicUsize::new(0));
+        let (connections_server, requests_server) = (connections.clone(), requests.clone());
+        let server = tokio::spawn(async move {
+            loop {
+                let (mut sock, _) = listener.accept().await.unwrap();
+                connections_server.fetch_add(1, Ordering::SeqCst);
+                let requests = requests_server.clone();
+                tokio::spawn(async move {
+                    let mut buf = [0u8; 4096];
+                    while let Ok(n) = sock.read(&mut buf).await {
+                        if n == 0 {
+                            return;
+                        }
+                        requests.fetch_add(1, Ordering::SeqCst);
+                        let resp = "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 2\r\n\r\n[]";
+                        if sock.write_all(resp.as_bytes()).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+        (addr, connections, requests, server)
+    }
+
+    #[tokio::test]
+    async fn test_source_client_reuses_connection_across_ticks() {
+        use std::sync::atomic::Ordering;
+        let (addr, connections, requests, server) = keep_alive_server().await;
+        let yaml = format!(
+            r#"
+sources:
+  pooled:
+    url: "http://{}/logs"
+    resilience:
+      pool_idle_timeout_secs: 60
+      tcp_keepalive_secs: 30
+"#,
+            addr
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let poll_caches = super::new_poll_caches();
+        for _ in 0..2 {
+            tick_with(
+                &config,
+                std::sync::Arc::new(crate::state::MemoryStateStore::new()),
+                std::sync::Arc::new(CountingSink(Default::default())),
+                Default::default(),
+                poll_caches.clone(),
+            )
+            .await
+            .unwrap();
+        }
+        server.abort();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            connections.load(Ordering::SeqCst),
+            1,
+            "second tick reuses the pooled connection"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_connections_pool_kept_across_ticks_until_cap_changes() {
+        use std::sync::atomic::Ordering;
+        let (addr, connections, requests, server) = keep_alive_server().await;
+        let yaml = format!(
+            r#"
+sources:
+  capped:
+    url: "http://{}/logs"
+    resilience:
+      max_connections: 1
+"#,
+            addr
+        );
+        let mut config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let poll_caches = super::new_poll_caches();
+        let run = |config: crate::config::Config| {
+            let poll_caches = poll_caches.clone();
+            async move {
+                tick_with(
+                    &config,
+                    std::sync::Arc::new(crate::state::MemoryStateStore::new()),
+                    std::sync::Arc::new(CountingSink(Default::default())),
+                    Default::default(),
+                    poll_caches,
+                )
+                .await
+                .unwrap();
+            }
+        };
+        run(config.clone()).await;
+        run(config.clone()).await;
+        assert_eq!(
+            connections.load(Ordering::SeqCst),
+            1,
+            "the idle pool of max_connections outlives the tick"
+        );
+
+        // A reload that changes the cap rebuilds the client (and its pool).
+        let resilience = config
+            .sources
+            .get_mut("capped")
+            .unwrap()
+            .resilience
+            .as_mut()
+            .unwrap();
+        resilience.max_connections = Some(2);
+        run(config).await;
+        server.abort();
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_source_ends_tick_at_poll_tick_deadline() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Chunked response that sends two lines and then holds the connection open.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let mut req = [0u8; 1024];
+            let _ = sock.read(&mut req).await;
+            let head = "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ntransfer-encoding: chunked\r\n\r\n";
+            sock.write_all(head.as_bytes()).await.unwrap();
+            for line in ["{\"id\":\"a\"}\n", "{\"id\":\"b\"}\n"] {
+                let frame = format!("{:x}\r\n{}\r\n", line.len(), line);
+                sock.write_all(frame.as_bytes()).await.unwrap();
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+        });
+        let yaml = format!(
+            r#"
+sources:
+  firehose:
+    url: "http://{0}/stream"
+    streaming: true
+    resilience:
+      timeouts:
+        poll_tick_secs: 1
+"#,
+            addr
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let sink = std::sync::Arc::new(crate::golden::CaptureSink::new());
+        let last_errors = std::sync::Arc::new(tokio::sync::RwLock::new(Default::default()));
+        tick_with(
+            &config,
+            std::sync::Arc::new(crate::state::MemoryStateStore::new()),
+            sink.clone(),
+            last_errors.clone(),
+            super::new_poll_caches(),
+        )
+        .await
+        .unwrap();
+        server.abort();
+
+        assert_eq!(sink.lines().len(), 2);
+        assert!(last_errors.read().await.is_empty());
+    }
+
+    /// An open stream releases its request permit once connected, so other sources under the same global
+    /// request cap keep polling while it stays open.
+    #[tokio::test]
+    async fn test_streaming_source_releases_request_permit_once_connected() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connected = std::sync::Arc::new(tokio::sync::Notify::new());
+        let release = std::sync::Arc::new(tokio::sync::Notify::new());
+        let (server_connected, server_release) = (connected.clone(), release.clone());
+        let server = tokio::spawn(async move {
+            let (mut sock, _) = listener.accept().await.unwrap();
+            let mut req = [0u8; 1024];
+            let _ = sock.read(&mut req).await;
+            let head = "HTTP/1.1 200 OK\r\ncontent-type: application/x-ndjson\r\ntransfer-encoding: chunked\r\n\r\n";
+            sock.write_all(head.as_bytes()).await.unwrap();
+            server_connected.notify_one();
+            server_release.notified().await;
+            let line = "{\"id\":\"a\"}\n";
+            let frame = format!("{:x}\r\n{}\r\n0\r\n\r\n", line.len(), line);
+            sock.write_all(frame.as_bytes()).await.unwrap();
+        });
+        let api = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_string(r#"[{"id":"b"}]"#))
+            .mount(&api)
+            .await;
+        let global = r#"
+global:
+  bulkhead:
+    max_concurrent_requests_total: 1
+"#;
+        let stream_config: crate::config::Config = serde_yaml_ng::from_str(&format!(
+            "{}\nsources:\n  firehose:\n    url: \"http://{}/stream\"\n    streaming: true\n    resilience:\n      timeouts:\n        poll_tick_secs: 10\n",
+            global, addr
+        ))
+        .unwrap();
+        let api_config: crate::config::Config = serde_yaml_ng::from_str(&format!(
+            "{}\nsources:\n  api:\n    url: \"{}/logs\"\n",
+            global,
+            api.uri()
+        ))
+        .unwrap();
+        let poll_caches = super::new_poll_caches();
+        let stream_sink = std::sync::Arc::new(crate::golden::CaptureSink::new());
+        let stream_tick = tokio::spawn({
+            let (sink, poll_caches) = (stream_sink.clone(), poll_caches.clone());
+            async move {
+                tick_with(
+                    &stream_config,
+                    std::sync::Arc::new(crate::state::MemoryStateStore::new()),
+                    sink,
+                    std::sync::Arc::new(tokio::sync::RwLock::new(Default::default())),
+                    poll_caches,
+                )
+                .await
+            }
+        });
+        connected.notified().await;
+
+        let api_sink = std::sync::Arc::new(crate::golden::CaptureSink::new());
+        tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            tick_with(
+                &api_config,
+                std::sync::Arc::new(crate::state::MemoryStateStore::new()),
+                api_sink.clone(),
+                std::sync::Arc::new(tokio::sync::RwLock::new(Default::default())),
+                poll_caches,
+            ),
+        )
+        .await
+        .expect("api poll waited on the open stream's request permit")
+        .unwrap();
+        assert_eq!(api_sink.lines().len(), 1);
+
+        release.notify_one();
+        stream_tick.await.unwrap().unwrap();
+        server.abort();
+        assert_eq!(stream_sink.lines().len(), 1);
+    }
+
+    /// Okta System Log polling: once caught up, `next` repeats the request URL. The tick stops there and the
+    /// next tick resumes from that URL rather than restarting or looping until max_pages.
+    #[tokio::test]
+    async fn test_link_header_next_equal_to_current_url_is_saved_for_next_tick() {
+        use wiremock::matchers::{method, query_param};
+        let server = wiremock::MockServer::start().await;
+        let polling_url = format!("{}/api/v1/logs?after=c1", server.uri());
+        let polling_links = format!(r#"<{0}>; rel="self", <{0}>; rel="next""#, polling_url);
+        wiremock::Mock::given(method("GET"))
+            .and(query_param("after", "c1"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .insert_header("Link", polling_links.as_str())
+                    .set_body_string("[]"),
+            )
+            .mount(&server)
+            .await;
+        let first_links = format!(
+            r#"<{0}/api/v1/logs>; rel="self", <{1}>; rel="next""#,
+            server.uri(),
+            polling_url
+        );
+        wiremock::Mock::given(method("GET"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .insert_header("Link", first_links.as_str())
+                    .set_body_string(r#"[{"uuid":"e1"}]"#),
+            )
+            .mount(&server)
+            .await;
+        let yaml = format!(
+            r#"
+sources:
+  okta:
+    url: "{0}/api/v1/logs"
+    pagination:
+      strategy: link_header
+      rel: next
+      max_pages: 10
+"#,
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let store: std::sync::Arc<dyn crate::state::StateStore> =
+            std::sync::Arc::new(crate::state::MemoryStateStore::new());
+        let sink = std::sync::Arc::new(crate::golden::CaptureSink::new());
+        for _ in 0..2 {
+            tick_with(
+                &config,
+                store.clone(),
+                sink.clone(),
+                Default::default(),
+                super::new_poll_caches(),
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(sink.lines().len(), 1);
+        assert_eq!(
+            store.get("okta", "next_url").await.unwrap().as_deref(),
+            Some(polling_url.as_str())
+        );
+        let queries: Vec<String> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| r.url.query().unwrap_or("").to_string())
+            .collect();
+        // Tick 1: first page, then the polling URL once; tick 2: the saved polling URL once.
+        assert_eq!(queries, vec!["", "after=c1", "after=c1"]);
+    }
+
+    #[test]
+    fn test_rate_limit_buckets_track_remaining_independently() {
+        let clock =
+            crate::clock::MockClock::new(chrono::DateTime::from_timestamp(1_000, 0).unwrap());
+        let caches = super::PollCaches::default();
+        let source = "rate-limit-buckets-test";
+        caches.record_rate_limit_bucket(source, "core", Some(0), Some(1_030));
+        caches.record_rate_limit_bucket(source, "search", Some(25), Some(1_060));
+        assert_eq!(
+            caches.rate_limit_bucket_wait_secs(source, "core", &clock),
+            30
+        );
+        assert_eq!(
+            caches.rate_limit_bucket_wait_secs(source, "search", &clock),
+            0
+        );
+
+        // A search response without a reset header keeps the bucket's last reset.
+        caches.record_rate_limit_bucket(source, "search", Some(1), None);
+        assert_eq!(
+            caches.rate_limit_bucket_wait_secs(source, "search", &clock),
+            60
+        );
+        caches.record_rate_limit_bucket(source, "core", Some(4999), Some(4_600));
+        assert_eq!(
+            caches.rate_limit_bucket_wait_secs(source, "core", &clock),
+            0
+        );
+        assert_eq!(
+            caches.rate_limit_bucket_wait_secs(source, "graphql", &clock),
+            0
+        );
+    }
+
+    #[test]
+    fn test_cursor_error_path_ignores_expired_word_in_event_body() {
+        let yaml = r#"
+sources:
+  structured:
+    url: "https://example.com/logs"
+    cursor_error_path: "error.code"
+    cursor_error_values: ["cursor_expired"]
+  substring:
+    url: "https://example.com/logs"
+"#;
+        let config: crate::config::Config = serde_yaml_ng::from_str(yaml).unwrap();
+        let structured = &config.sources["structured"];
+        let substring = &config.sources["substring"];
+        let real_error =
+            br#"{"error":{"code":"cursor_expired","message":"cursor no longer valid"}}"#;
+        let event_body =
+            br#"{"error":{"code":"bad_request"},"events":[{"msg":"password expired"}]}"#;
+
+        assert!(is_cursor_expired(structured, 400, real_error));
+        assert!(!is_cursor_expired(structured, 400, event_body));
+        assert!(is_cursor_expired(structured, 410, event_body));
+        // Without a path, substring matching still false-positives on the event text.
+        assert!(is_cursor_expired(substring, 400, event_body));
+    }
+
+    #[test]
+    fn test_streaming_rejects_pagination() {
+        let yaml = r#"
+sources:
+  firehose:
+    url: "https://example.com/stream"
+    streaming: true
+    pagination:
+      strategy: link_header
+"#;
+        let config: crate::config::Config = serde_yaml_ng::from_str(yaml).unwrap();
+        let err = crate::config::validate_streaming(&config).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("streaming cannot be combined with pagination")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unparseable_body_written_to_dead_letter() {
+        use wiremock::matchers::method;
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(method("GET"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_string("<html>oops</html>"))
+            .mount(&server)
+            .await;
+        let dead_letter_path = std::env::temp_dir().join(format!(
+            "helr_dead_letter_test_{}.ndjson",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&dead_letter_path);
+        let yaml = format!(
+            r#"
+global:
+  dead_letter:
+    path: "{}"
+sources:
+  broken:
+    url: "{}/events?api_key=secret123"
+    on_parse_error: skip
+"#,
+            dead_letter_path.display(),
+            server.uri()
+        );
+        let config: crate::config::Config = serde_yaml_ng::from_str(&yaml).unwrap();
+        let sink = std::sync::Arc::new(CountingSink(Default::default()));
+        tick(&config, sink.clone()).await.unwrap();
+
+        assert_eq!(sink.0.load(std::sync::atomic::Ordering::Relaxed), 0);
+        let contents = std::fs::read_to_string(&dead_letter_path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 1);
+        let record: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(record["source"], "broken");
+        assert_eq!(
+            record["url"],
+            format!("{}/events?api_key=REDACTED", server.uri())
+        );
+        assert_eq!(record["status"], 200);
+        assert_eq!(record["body"], "<html>oops</html>");
+        assert!(record["error"].as_str().unwrap().contains("parse"));
+        let _ = std::fs::remove_file(&dead_letter_path);
+    }
 }
 
 #[cfg(all(test, feature = "streaming"))]
@@ -734,11 +3271,11 @@ mod streaming_equivalence_tests {
     use crate::config::SourceConfig;
 
     fn standard_parse(body: &[u8], source: &SourceConfig) -> Vec<serde_json::Value> {
-        parse_events_from_body_for_source(body, source).unwrap()
+        parse_events_from_body_for_source(body, source, 200).unwrap()
     }
 
     fn streaming_parse(body: &[u8], source: &SourceConfig) -> Vec<serde_json::Value> {
-        let result = streaming::parse_streaming(body, source).unwrap();
+        let result = streaming::parse_streaming(body, source, 200).unwrap();
         let obj_path = source.response_event_object_path.as_deref();
         result
             .iter(body)
@@ -917,7 +3454,7 @@ url: "https://x.com"
 response_events_path: "events"
 "#,
         );
-        let result = streaming::parse_streaming(body, &s).unwrap();
+        let result = streaming::parse_streaming(body, &s, 200).unwrap();
         let meta = result.metadata();
         assert_eq!(meta["cursor"], "next123");
         assert_eq!(meta["total"], 2);
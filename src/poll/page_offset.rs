@@ -1,6 +1,7 @@
 use crate::circuit::{self, CircuitStore};
-use crate::config::{GlobalConfig, OnParseErrorBehavior, SourceConfig};
-use crate::dedupe::{self, DedupeStore};
+use crate::clock::Clock;
+use crate::config::{GlobalConfig, SourceConfig};
+use crate::dedupe::DedupeStore;
 use crate::dpop::DPoPKeyCache;
 use crate::metrics;
 use crate::oauth2::OAuth2TokenCache;
@@ -14,9 +15,30 @@ use std::time::{Duration, Instant};
 use tokio::sync::Semaphore;
 
 use super::ClientRateLimiter;
+use super::caches::PollCaches;
+use super::emit::{EmitCtx, emit_parsed_event};
 use super::helpers::*;
 use super::parse::*;
-/// Page/offset pagination: increment page (or offset) each request, stop when empty or max_pages.
+use super::subrequest::SubrequestCtx;
+/// State key for the page to start from next tick (page_offset with offset_resume).
+const PAGE_RESUME_KEY: &str = "resume_page";
+/// State key for the offset to start from next tick (offset with offset_resume).
+const OFFSET_RESUME_KEY: &str = "resume_offset";
+
+/// Saved page/offset to resume from; None when unset or unparsable.
+async fn load_resume_position(
+    store: &Arc<dyn StateStore>,
+    source_id: &str,
+    key: &str,
+) -> anyhow::Result<Option<u32>> {
+    Ok(store
+        .get(source_id, key)
+        .await?
+        .and_then(|v| v.trim().parse::<u32>().ok()))
+}
+
+/// Page/offset pagination: increment page (or offset) each request, stop on a short page, after
+/// `stop_after_empty_pages` consecutive empty pages, or at max_pages.
 #[allow(clippy::too_many_arguments)]
 pub(super) async fn poll_page_offset_pagination(
     store: Arc<dyn StateStore>,
@@ -28,6 +50,9 @@ pub(super) async fn poll_page_offset_pagination(
     limit_param: &str,
     limit: u32,
     max_pages: u32,
+    offset_resume: bool,
+    stop_on_total_path: Option<&str>,
+    stop_after_empty_pages: u32,
     circuit_store: CircuitStore,
     token_cache: OAuth2TokenCache,
     dpop_key_cache: Option<DPoPKeyCache>,
@@ -36,11 +61,32 @@ pub(super) async fn poll_page_offset_pagination(
     record_state: Option<Arc<RecordState>>,
     rate_limiter: Option<&Arc<ClientRateLimiter>>,
     request_semaphore: Option<Arc<Semaphore>>,
+    poll_caches: Arc<PollCaches>,
+    clock: &dyn Clock,
 ) -> anyhow::Result<()> {
     use reqwest::Url;
     let start = Instant::now();
-    let base_url = source.url.as_str();
+    let emit_ctx = EmitCtx {
+        global,
+        clock,
+        source_id,
+        source,
+        dedupe_store: &dedupe_store,
+        event_sink: &event_sink,
+        sub: SubrequestCtx {
+            client,
+            token_cache: &token_cache,
+            dpop_key_cache: dpop_key_cache.as_ref(),
+            rate_limiter,
+            request_semaphore: &request_semaphore,
+            poll_caches: &poll_caches,
+        },
+    };
+    let start_url =
+        url_with_start_params(&store, source_id, source, &source.url, &poll_caches, clock).await?;
     let mut total_events = 0u64;
+    let mut pages = 0u32;
+    let mut total_bytes = 0u64;
     let mut incremental_max_ts: Option<String> = None;
     let mut watermark_max_ts: Option<String> = None;
     let page_delay = source
@@ -48,24 +94,33 @@ pub(super) async fn poll_page_offset_pagination(
         .as_ref()
         .and_then(|r| r.rate_limit.as_ref())
         .and_then(|rl| rl.page_delay_secs);
-    for page in 1..=max_pages {
-        if page > 1
+    let first_page = if offset_resume {
+        load_resume_position(&store, source_id, PAGE_RESUME_KEY)
+            .await?
+            .unwrap_or(1)
+            .max(1)
+    } else {
+        1
+    };
+    let mut resume_page = first_page;
+    let mut empty_pages = 0u32;
+    for i in 0..max_pages {
+        let page = first_page + i;
+        pages = i + 1;
+        if i > 0
             && let Some(secs) = page_delay
         {
             tracing::debug!(source = %source_id, delay_secs = secs, "delay between pages");
             tokio::time::sleep(Duration::from_secs(secs)).await;
         }
-        let mut u = Url::parse(base_url).context("page/offset base url")?;
+        let mut u = Url::parse(&start_url).context("page/offset base url")?;
         u.query_pairs_mut()
             .append_pair(page_param, &page.to_string());
         u.query_pairs_mut()
             .append_pair(limit_param, &limit.to_string());
-        if page == 1
-            && let Some(ref params) = source.query_params
-        {
-            for (k, v) in params {
-                u.query_pairs_mut().append_pair(k, &v.to_param_value());
-            }
+        if i == 0 {
+            append_query_params(&mut u, source);
+            append_rotating_param(&store, source_id, source, &mut u).await?;
         }
         let url = u.to_string();
         if let Some(cb) = source
@@ -80,61 +135,33 @@ pub(super) async fn poll_page_offset_pagination(
         if let Some(limiter) = rate_limiter {
             limiter.until_ready().await;
         }
-        let _request_permit = match &request_semaphore {
-            Some(s) => Some(
-                s.acquire()
-                    .await
-                    .map_err(|e| anyhow::anyhow!("bulkhead request acquire: {}", e))?,
-            ),
-            None => None,
-        };
+        let _request_permit =
+            acquire_request_permits(global, source_id, source, &request_semaphore, &poll_caches)
+                .await?;
         let req_start = std::time::Instant::now();
-        let response = match execute_with_retry(
-            client,
-            source,
+        let response = record_request_outcome(
+            &circuit_store,
             source_id,
-            &url,
-            None, // page_offset: body from source when POST
-            source.resilience.as_ref().and_then(|r| r.retries.as_ref()),
-            source
-                .resilience
-                .as_ref()
-                .and_then(|r| r.rate_limit.as_ref()),
-            Some(&token_cache),
-            dpop_key_cache.as_ref(),
-            global.audit.as_ref(),
-        )
-        .await
-        {
-            Ok(r) => {
-                let success = r.status().as_u16() < 500;
-                if let Some(cb) = source
-                    .resilience
-                    .as_ref()
-                    .and_then(|r| r.circuit_breaker.as_ref())
-                {
-                    circuit::record_result(&circuit_store, source_id, cb, success).await;
-                }
-                metrics::record_request(
-                    source_id,
-                    status_class(r.status().as_u16()),
-                    req_start.elapsed().as_secs_f64(),
-                );
-                r
-            }
-            Err(e) => {
-                if let Some(cb) = source
+            source,
+            req_start,
+            execute_with_retry(
+                client,
+                source,
+                source_id,
+                &url,
+                None, // page_offset: body from source when POST
+                source.resilience.as_ref().and_then(|r| r.retries.as_ref()),
+                source
                     .resilience
                     .as_ref()
-                    .and_then(|r| r.circuit_breaker.as_ref())
-                {
-                    circuit::record_result(&circuit_store, source_id, cb, false).await;
-                }
-                metrics::record_request(source_id, "error", req_start.elapsed().as_secs_f64());
-                metrics::record_error(source_id);
-                return Err(e).context("http request");
-            }
-        };
+                    .and_then(|r| r.rate_limit.as_ref()),
+                Some(&token_cache),
+                dpop_key_cache.as_ref(),
+                global.audit.as_ref(),
+            )
+            .await,
+        )
+        .await?;
 
         maybe_adaptive_sleep_after_response(
             response.headers(),
@@ -143,6 +170,8 @@ pub(super) async fn poll_page_offset_pagination(
                 .resilience
                 .as_ref()
                 .and_then(|r| r.rate_limit.as_ref()),
+            &poll_caches,
+            clock,
         )
         .await;
 
@@ -153,19 +182,23 @@ pub(super) async fn poll_page_offset_pagination(
         let mut response = Some(response);
         let mut event_count = 0usize;
         let mut emitted_count = 0u64;
+        let mut total: Option<u64> = None;
         let mut _streamed = false;
 
         #[cfg(feature = "streaming")]
-        if source.response_streaming.is_some() {
+        if streaming_parse_enabled(source) {
             use super::streaming;
             use crate::config::StreamingMode;
 
             let use_full = source.response_streaming == Some(StreamingMode::Full)
-                && source.on_invalid_utf8.is_none();
+                && source.on_invalid_utf8.is_none()
+                && source.body_decrypt.is_none()
+                && !is_content_encoded(&record_headers)
+                && !allows_empty_body(source, record_status);
 
             if use_full {
                 let resp = response.take().unwrap();
-                if !(200..300).contains(&record_status) {
+                if !is_success_status(source, record_status) {
                     let body_bytes = read_body_with_limit(resp, source.max_response_bytes).await?;
                     if let Some(ref rs) = record_state {
                         rs.save(
@@ -186,7 +219,7 @@ pub(super) async fn poll_page_offset_pagination(
                         source_id.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
                     ))
                 });
-                let (mut event_rx, _meta_rx, join_handle) = streaming::stream_and_parse(
+                let (mut event_rx, meta_rx, join_handle) = streaming::stream_and_parse(
                     resp,
                     source.response_events_path.clone(),
                     source.max_response_bytes,
@@ -199,11 +232,18 @@ pub(super) async fn poll_page_offset_pagination(
                     let event_value = match result {
                         Ok(v) => v,
                         Err(e) => {
-                            if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                                tracing::warn!(source = %source_id, error = %e, "parse error, skipping page");
-                                continue;
-                            }
-                            return Err(e).context("streaming parse element");
+                            handle_parse_error(
+                                global,
+                                source_id,
+                                source,
+                                record_url.as_str(),
+                                record_status,
+                                None,
+                                e,
+                                "parse error, skipping page",
+                            )
+                            .context("streaming parse element")?;
+                            continue;
                         }
                     };
                     let event_value = match streaming::unwrap_event_object(event_value, obj_path) {
@@ -219,28 +259,28 @@ pub(super) async fn poll_page_offset_pagination(
                         );
                     }
                     if let Some(ref st) = source.state {
-                        update_max_timestamp_single(
-                            &mut watermark_max_ts,
-                            &event_value,
-                            &st.watermark_field,
-                        );
+                        update_watermark_single(&mut watermark_max_ts, &event_value, st);
                     }
-                    if let Some(d) = &source.dedupe {
-                        let id = event_id(&event_value, &d.id_path).unwrap_or_default();
-                        if dedupe::seen_and_add(&dedupe_store, source_id, id, d.capacity).await {
-                            continue;
-                        }
+                    if emit_parsed_event(&emit_ctx, &path, Some(&record_headers), event_value, None)
+                        .await?
+                    {
+                        total_events += 1;
+                        emitted_count += 1;
                     }
-                    total_events += 1;
-                    emitted_count += 1;
-                    let emitted = build_emitted_event(source, source_id, &path, event_value);
-                    emit_event_line(global, source_id, source, &event_sink, &emitted)?;
                 }
 
                 join_handle
                     .await
                     .map_err(|e| anyhow::anyhow!("streaming parser panicked: {}", e))??;
 
+                if let Some(p) = stop_on_total_path {
+                    let metadata = meta_rx
+                        .await
+                        .map_err(|_| anyhow::anyhow!("metadata channel closed"))?
+                        .context("parse metadata")?;
+                    total = json_path_u64(&metadata, p);
+                }
+
                 if let (Some(rs), Some(tp)) = (&record_state, &tee_path) {
                     let body = std::fs::read(tp).context("read tee file for recording")?;
                     rs.save(
@@ -265,20 +305,34 @@ pub(super) async fn poll_page_offset_pagination(
                         &body_bytes,
                     )?;
                 }
-                if !(200..300).contains(&record_status) {
+                if !is_success_status(source, record_status) {
                     let body_str = String::from_utf8_lossy(&body_bytes);
                     anyhow::bail!("http {} {}", record_status, body_str);
                 }
-                let parse_result = match streaming::parse_streaming(&body_bytes, source) {
-                    Ok(r) => r,
-                    Err(e) => {
-                        if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                            tracing::warn!(source = %source_id, error = %e, "parse error, skipping page");
+                let body_bytes = maybe_decompress_body(source, &record_headers, body_bytes)?;
+                let body_bytes = maybe_decrypt_body(source, &record_headers, body_bytes)?;
+                total_bytes += body_bytes.len() as u64;
+                let parse_result =
+                    match streaming::parse_streaming(&body_bytes, source, record_status) {
+                        Ok(r) => r,
+                        Err(e) => {
+                            handle_parse_error(
+                                global,
+                                source_id,
+                                source,
+                                record_url.as_str(),
+                                record_status,
+                                Some(&body_bytes),
+                                e,
+                                "parse error, skipping page",
+                            )
+                            .context("streaming parse")?;
                             continue;
                         }
-                        return Err(e).context("streaming parse");
-                    }
-                };
+                    };
+                if let Some(p) = stop_on_total_path {
+                    total = json_path_u64(parse_result.metadata(), p);
+                }
                 let obj_path = source.response_event_object_path.as_deref();
                 for result in parse_result.iter(&body_bytes) {
                     let event_value = result.context("parse event element")?;
@@ -295,22 +349,14 @@ pub(super) async fn poll_page_offset_pagination(
                         );
                     }
                     if let Some(ref st) = source.state {
-                        update_max_timestamp_single(
-                            &mut watermark_max_ts,
-                            &event_value,
-                            &st.watermark_field,
-                        );
+                        update_watermark_single(&mut watermark_max_ts, &event_value, st);
                     }
-                    if let Some(d) = &source.dedupe {
-                        let id = event_id(&event_value, &d.id_path).unwrap_or_default();
-                        if dedupe::seen_and_add(&dedupe_store, source_id, id, d.capacity).await {
-                            continue;
-                        }
+                    if emit_parsed_event(&emit_ctx, &path, Some(&record_headers), event_value, None)
+                        .await?
+                    {
+                        total_events += 1;
+                        emitted_count += 1;
                     }
-                    total_events += 1;
-                    emitted_count += 1;
-                    let emitted = build_emitted_event(source, source_id, &path, event_value);
-                    emit_event_line(global, source_id, source, &event_sink, &emitted)?;
                 }
                 _streamed = true;
             }
@@ -328,58 +374,123 @@ pub(super) async fn poll_page_offset_pagination(
                     &body_bytes,
                 )?;
             }
-            if !(200..300).contains(&record_status) {
+            if !is_success_status(source, record_status) {
                 let body_str = String::from_utf8_lossy(&body_bytes);
                 anyhow::bail!("http {} {}", record_status, body_str);
             }
-            let events = match parse_events_from_body_for_source(&body_bytes, source) {
+            let body_bytes = maybe_decompress_body(source, &record_headers, body_bytes)?;
+            let body_bytes = maybe_decrypt_body(source, &record_headers, body_bytes)?;
+            total_bytes += body_bytes.len() as u64;
+            let parsed =
+                parse_response_value(&body_bytes, source, Some(&record_headers), record_status)
+                    .and_then(|value| {
+                        if let Some(p) = stop_on_total_path {
+                            total = json_path_u64(&value, p);
+                        }
+                        parse_kinded_events_from_value_for_source(value, source)
+                    });
+            let (events, kinds) = match parsed {
                 Ok(ev) => ev,
                 Err(e) => {
-                    if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                        tracing::warn!(source = %source_id, error = %e, "parse error, skipping page");
-                        continue;
-                    }
-                    return Err(e).context("parse response");
+                    handle_parse_error(
+                        global,
+                        source_id,
+                        source,
+                        record_url.as_str(),
+                        record_status,
+                        Some(&body_bytes),
+                        e,
+                        "parse error, skipping page",
+                    )
+                    .context("parse response")?;
+                    continue;
                 }
             };
             if let Some(ref inc) = source.incremental_from {
                 update_max_timestamp(&mut incremental_max_ts, &events, &inc.event_timestamp_path);
             }
             if let Some(ref st) = source.state {
-                update_max_timestamp(&mut watermark_max_ts, &events, &st.watermark_field);
+                update_watermark(&mut watermark_max_ts, &events, st);
             }
             event_count = events.len();
-            for event_value in events {
-                if let Some(d) = &source.dedupe {
-                    let id = event_id(&event_value, &d.id_path).unwrap_or_default();
-                    if dedupe::seen_and_add(&dedupe_store, source_id, id, d.capacity).await {
-                        continue;
-                    }
+            for (i, event_value) in events.into_iter().enumerate() {
+                if emit_parsed_event(
+                    &emit_ctx,
+                    &path,
+                    Some(&record_headers),
+                    event_value,
+                    kinds.get(i),
+                )
+                .await?
+                {
+                    total_events += 1;
+                    emitted_count += 1;
                 }
-                total_events += 1;
-                emitted_count += 1;
-                let emitted = build_emitted_event(source, source_id, &path, event_value);
-                emit_event_line(global, source_id, source, &event_sink, &emitted)?;
             }
         }
         metrics::record_events(source_id, emitted_count);
-        if event_count < limit as usize {
+        empty_pages = if event_count == 0 { empty_pages + 1 } else { 0 };
+        // A partial page may still grow; re-read it (or the first page of an empty run) next tick.
+        if empty_pages <= 1 {
+            resume_page = page;
+        }
+        if event_count < limit as usize
+            && (event_count > 0 || empty_pages >= stop_after_empty_pages)
+        {
             tracing::info!(
                 source = %source_id,
-                pages = page,
+                pages,
                 events = total_events,
                 duration_ms = start.elapsed().as_millis(),
                 "poll completed (page/offset)"
             );
             break;
         }
-        if page == max_pages {
+        if event_count > 0 {
+            resume_page = page + 1;
+        }
+        if let Some(total) = total
+            && u64::from(page) * u64::from(limit) >= total
+        {
+            tracing::info!(
+                source = %source_id,
+                pages,
+                events = total_events,
+                total,
+                duration_ms = start.elapsed().as_millis(),
+                "poll completed (page/offset, total reached)"
+            );
+            break;
+        }
+        if pages == max_pages {
             tracing::warn!(source = %source_id, "reached max_pages {}", max_pages);
         }
     }
     store_set_or_skip(&store, source_id, source, global, "next_url", "").await?;
+    if offset_resume {
+        store_set_or_skip(
+            &store,
+            source_id,
+            source,
+            global,
+            PAGE_RESUME_KEY,
+            &resume_page.to_string(),
+        )
+        .await?;
+    }
     store_incremental_from_after_poll(&store, source_id, source, global, incremental_max_ts).await;
     store_watermark_after_poll(&store, source_id, source, global, watermark_max_ts).await;
+    emit_tick_summary(
+        global,
+        source_id,
+        source,
+        &event_sink,
+        pages,
+        total_events,
+        total_bytes,
+        start,
+        clock,
+    )?;
     Ok(())
 }
 
@@ -395,6 +506,9 @@ pub(super) async fn poll_offset_pagination(
     limit_param: &str,
     limit: u32,
     max_pages: u32,
+    offset_resume: bool,
+    stop_on_total_path: Option<&str>,
+    stop_after_empty_pages: u32,
     circuit_store: CircuitStore,
     token_cache: OAuth2TokenCache,
     dpop_key_cache: Option<DPoPKeyCache>,
@@ -403,11 +517,32 @@ pub(super) async fn poll_offset_pagination(
     record_state: Option<Arc<RecordState>>,
     rate_limiter: Option<&Arc<ClientRateLimiter>>,
     request_semaphore: Option<Arc<Semaphore>>,
+    poll_caches: Arc<PollCaches>,
+    clock: &dyn Clock,
 ) -> anyhow::Result<()> {
     use reqwest::Url;
     let start = Instant::now();
-    let base_url = source.url.as_str();
+    let emit_ctx = EmitCtx {
+        global,
+        clock,
+        source_id,
+        source,
+        dedupe_store: &dedupe_store,
+        event_sink: &event_sink,
+        sub: SubrequestCtx {
+            client,
+            token_cache: &token_cache,
+            dpop_key_cache: dpop_key_cache.as_ref(),
+            rate_limiter,
+            request_semaphore: &request_semaphore,
+            poll_caches: &poll_caches,
+        },
+    };
+    let start_url =
+        url_with_start_params(&store, source_id, source, &source.url, &poll_caches, clock).await?;
     let mut total_events = 0u64;
+    let mut pages = 0u32;
+    let mut total_bytes = 0u64;
     let mut incremental_max_ts: Option<String> = None;
     let mut watermark_max_ts: Option<String> = None;
     let page_delay = source
@@ -415,25 +550,32 @@ pub(super) async fn poll_offset_pagination(
         .as_ref()
         .and_then(|r| r.rate_limit.as_ref())
         .and_then(|rl| rl.page_delay_secs);
+    let first_offset = if offset_resume {
+        load_resume_position(&store, source_id, OFFSET_RESUME_KEY)
+            .await?
+            .unwrap_or(0)
+    } else {
+        0
+    };
+    let mut resume_offset = first_offset;
+    let mut empty_pages = 0u32;
     for page in 1..=max_pages {
-        let offset = (page - 1) * limit;
+        pages = page;
+        let offset = first_offset + (page - 1) * limit;
         if page > 1
             && let Some(secs) = page_delay
         {
             tracing::debug!(source = %source_id, delay_secs = secs, "delay between pages");
             tokio::time::sleep(Duration::from_secs(secs)).await;
         }
-        let mut u = Url::parse(base_url).context("offset base url")?;
+        let mut u = Url::parse(&start_url).context("offset base url")?;
         u.query_pairs_mut()
             .append_pair(offset_param, &offset.to_string());
         u.query_pairs_mut()
             .append_pair(limit_param, &limit.to_string());
-        if page == 1
-            && let Some(ref params) = source.query_params
-        {
-            for (k, v) in params {
-                u.query_pairs_mut().append_pair(k, &v.to_param_value());
-            }
+        if page == 1 {
+            append_query_params(&mut u, source);
+            append_rotating_param(&store, source_id, source, &mut u).await?;
         }
         let url = u.to_string();
         if let Some(cb) = source
@@ -448,61 +590,33 @@ pub(super) async fn poll_offset_pagination(
         if let Some(limiter) = rate_limiter {
             limiter.until_ready().await;
         }
-        let _request_permit = match &request_semaphore {
-            Some(s) => Some(
-                s.acquire()
-                    .await
-                    .map_err(|e| anyhow::anyhow!("bulkhead request acquire: {}", e))?,
-            ),
-            None => None,
-        };
+        let _request_permit =
+            acquire_request_permits(global, source_id, source, &request_semaphore, &poll_caches)
+                .await?;
         let req_start = std::time::Instant::now();
-        let response = match execute_with_retry(
-            client,
-            source,
+        let response = record_request_outcome(
+            &circuit_store,
             source_id,
-            &url,
-            None,
-            source.resilience.as_ref().and_then(|r| r.retries.as_ref()),
-            source
-                .resilience
-                .as_ref()
-                .and_then(|r| r.rate_limit.as_ref()),
-            Some(&token_cache),
-            dpop_key_cache.as_ref(),
-            global.audit.as_ref(),
-        )
-        .await
-        {
-            Ok(r) => {
-                let success = r.status().as_u16() < 500;
-                if let Some(cb) = source
-                    .resilience
-                    .as_ref()
-                    .and_then(|r| r.circuit_breaker.as_ref())
-                {
-                    circuit::record_result(&circuit_store, source_id, cb, success).await;
-                }
-                metrics::record_request(
-                    source_id,
-                    status_class(r.status().as_u16()),
-                    req_start.elapsed().as_secs_f64(),
-                );
-                r
-            }
-            Err(e) => {
-                if let Some(cb) = source
+            source,
+            req_start,
+            execute_with_retry(
+                client,
+                source,
+                source_id,
+                &url,
+                None,
+                source.resilience.as_ref().and_then(|r| r.retries.as_ref()),
+                source
                     .resilience
                     .as_ref()
-                    .and_then(|r| r.circuit_breaker.as_ref())
-                {
-                    circuit::record_result(&circuit_store, source_id, cb, false).await;
-                }
-                metrics::record_request(source_id, "error", req_start.elapsed().as_secs_f64());
-                metrics::record_error(source_id);
-                return Err(e).context("http request");
-            }
-        };
+                    .and_then(|r| r.rate_limit.as_ref()),
+                Some(&token_cache),
+                dpop_key_cache.as_ref(),
+                global.audit.as_ref(),
+            )
+            .await,
+        )
+        .await?;
 
         maybe_adaptive_sleep_after_response(
             response.headers(),
@@ -511,6 +625,8 @@ pub(super) async fn poll_offset_pagination(
                 .resilience
                 .as_ref()
                 .and_then(|r| r.rate_limit.as_ref()),
+            &poll_caches,
+            clock,
         )
         .await;
 
@@ -521,19 +637,23 @@ pub(super) async fn poll_offset_pagination(
         let mut response = Some(response);
         let mut event_count = 0usize;
         let mut emitted_count = 0u64;
+        let mut total: Option<u64> = None;
         let mut _streamed = false;
 
         #[cfg(feature = "streaming")]
-        if source.response_streaming.is_some() {
+        if streaming_parse_enabled(source) {
             use super::streaming;
             use crate::config::StreamingMode;
 
             let use_full = source.response_streaming == Some(StreamingMode::Full)
-                && source.on_invalid_utf8.is_none();
+                && source.on_invalid_utf8.is_none()
+                && source.body_decrypt.is_none()
+                && !is_content_encoded(&record_headers)
+                && !allows_empty_body(source, record_status);
 
             if use_full {
                 let resp = response.take().unwrap();
-                if !(200..300).contains(&record_status) {
+                if !is_success_status(source, record_status) {
                     let body_bytes = read_body_with_limit(resp, source.max_response_bytes).await?;
                     if let Some(ref rs) = record_state {
                         rs.save(
@@ -554,7 +674,7 @@ pub(super) async fn poll_offset_pagination(
                         source_id.replace(|c: char| !c.is_ascii_alphanumeric(), "_")
                     ))
                 });
-                let (mut event_rx, _meta_rx, join_handle) = streaming::stream_and_parse(
+                let (mut event_rx, meta_rx, join_handle) = streaming::stream_and_parse(
                     resp,
                     source.response_events_path.clone(),
                     source.max_response_bytes,
@@ -567,11 +687,18 @@ pub(super) async fn poll_offset_pagination(
                     let event_value = match result {
                         Ok(v) => v,
                         Err(e) => {
-                            if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                                tracing::warn!(source = %source_id, error = %e, "parse error, skipping page");
-                                continue;
-                            }
-                            return Err(e).context("streaming parse element");
+                            handle_parse_error(
+                                global,
+                                source_id,
+                                source,
+                                record_url.as_str(),
+                                record_status,
+                                None,
+                                e,
+                                "parse error, skipping page",
+                            )
+                            .context("streaming parse element")?;
+                            continue;
                         }
                     };
                     let event_value = match streaming::unwrap_event_object(event_value, obj_path) {
@@ -587,28 +714,28 @@ pub(super) async fn poll_offset_pagination(
                         );
                     }
                     if let Some(ref st) = source.state {
-                        update_max_timestamp_single(
-                            &mut watermark_max_ts,
-                            &event_value,
-                            &st.watermark_field,
-                        );
+                        update_watermark_single(&mut watermark_max_ts, &event_value, st);
                     }
-                    if let Some(d) = &source.dedupe {
-                        let id = event_id(&event_value, &d.id_path).unwrap_or_default();
-                        if dedupe::seen_and_add(&dedupe_store, source_id, id, d.capacity).await {
-                            continue;
-                        }
+                    if emit_parsed_event(&emit_ctx, &path, Some(&record_headers), event_value, None)
+                        .await?
+                    {
+                        total_events += 1;
+                        emitted_count += 1;
                     }
-                    total_events += 1;
-                    emitted_count += 1;
-                    let emitted = build_emitted_event(source, source_id, &path, event_value);
-                    emit_event_line(global, source_id, source, &event_sink, &emitted)?;
                 }
 
                 join_handle
                     .await
                     .map_err(|e| anyhow::anyhow!("streaming parser panicked: {}", e))??;
 
+                if let Some(p) = stop_on_total_path {
+                    let metadata = meta_rx
+                        .await
+                        .map_err(|_| anyhow::anyhow!("metadata channel closed"))?
+                        .context("parse metadata")?;
+                    total = json_path_u64(&metadata, p);
+                }
+
                 if let (Some(rs), Some(tp)) = (&record_state, &tee_path) {
                     let body = std::fs::read(tp).context("read tee file for recording")?;
                     rs.save(
@@ -633,20 +760,34 @@ pub(super) async fn poll_offset_pagination(
                         &body_bytes,
                     )?;
                 }
-                if !(200..300).contains(&record_status) {
+                if !is_success_status(source, record_status) {
                     let body_str = String::from_utf8_lossy(&body_bytes);
                     anyhow::bail!("http {} {}", record_status, body_str);
                 }
-                let parse_result = match streaming::parse_streaming(&body_bytes, source) {
-                    Ok(r) => r,
-                    Err(e) => {
-                        if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                            tracing::warn!(source = %source_id, error = %e, "parse error, skipping page");
+                let body_bytes = maybe_decompress_body(source, &record_headers, body_bytes)?;
+                let body_bytes = maybe_decrypt_body(source, &record_headers, body_bytes)?;
+                total_bytes += body_bytes.len() as u64;
+                let parse_result =
+                    match streaming::parse_streaming(&body_bytes, source, record_status) {
+                        Ok(r) => r,
+                        Err(e) => {
+                            handle_parse_error(
+                                global,
+                                source_id,
+                                source,
+                                record_url.as_str(),
+                                record_status,
+                                Some(&body_bytes),
+                                e,
+                                "parse error, skipping page",
+                            )
+                            .context("streaming parse")?;
                             continue;
                         }
-                        return Err(e).context("streaming parse");
-                    }
-                };
+                    };
+                if let Some(p) = stop_on_total_path {
+                    total = json_path_u64(parse_result.metadata(), p);
+                }
                 let obj_path = source.response_event_object_path.as_deref();
                 for result in parse_result.iter(&body_bytes) {
                     let event_value = result.context("parse event element")?;
@@ -663,22 +804,14 @@ pub(super) async fn poll_offset_pagination(
                         );
                     }
                     if let Some(ref st) = source.state {
-                        update_max_timestamp_single(
-                            &mut watermark_max_ts,
-                            &event_value,
-                            &st.watermark_field,
-                        );
+                        update_watermark_single(&mut watermark_max_ts, &event_value, st);
                     }
-                    if let Some(d) = &source.dedupe {
-                        let id = event_id(&event_value, &d.id_path).unwrap_or_default();
-                        if dedupe::seen_and_add(&dedupe_store, source_id, id, d.capacity).await {
-                            continue;
-                        }
+                    if emit_parsed_event(&emit_ctx, &path, Some(&record_headers), event_value, None)
+                        .await?
+                    {
+                        total_events += 1;
+                        emitted_count += 1;
                     }
-                    total_events += 1;
-                    emitted_count += 1;
-                    let emitted = build_emitted_event(source, source_id, &path, event_value);
-                    emit_event_line(global, source_id, source, &event_sink, &emitted)?;
                 }
                 _streamed = true;
             }
@@ -696,42 +829,69 @@ pub(super) async fn poll_offset_pagination(
                     &body_bytes,
                 )?;
             }
-            if !(200..300).contains(&record_status) {
+            if !is_success_status(source, record_status) {
                 let body_str = String::from_utf8_lossy(&body_bytes);
                 anyhow::bail!("http {} {}", record_status, body_str);
             }
-            let events = match parse_events_from_body_for_source(&body_bytes, source) {
+            let body_bytes = maybe_decompress_body(source, &record_headers, body_bytes)?;
+            let body_bytes = maybe_decrypt_body(source, &record_headers, body_bytes)?;
+            total_bytes += body_bytes.len() as u64;
+            let parsed =
+                parse_response_value(&body_bytes, source, Some(&record_headers), record_status)
+                    .and_then(|value| {
+                        if let Some(p) = stop_on_total_path {
+                            total = json_path_u64(&value, p);
+                        }
+                        parse_kinded_events_from_value_for_source(value, source)
+                    });
+            let (events, kinds) = match parsed {
                 Ok(ev) => ev,
                 Err(e) => {
-                    if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                        tracing::warn!(source = %source_id, error = %e, "parse error, skipping page");
-                        continue;
-                    }
-                    return Err(e).context("parse response");
+                    handle_parse_error(
+                        global,
+                        source_id,
+                        source,
+                        record_url.as_str(),
+                        record_status,
+                        Some(&body_bytes),
+                        e,
+                        "parse error, skipping page",
+                    )
+                    .context("parse response")?;
+                    continue;
                 }
             };
             if let Some(ref inc) = source.incremental_from {
                 update_max_timestamp(&mut incremental_max_ts, &events, &inc.event_timestamp_path);
             }
             if let Some(ref st) = source.state {
-                update_max_timestamp(&mut watermark_max_ts, &events, &st.watermark_field);
+                update_watermark(&mut watermark_max_ts, &events, st);
             }
             event_count = events.len();
-            for event_value in events {
-                if let Some(d) = &source.dedupe {
-                    let id = event_id(&event_value, &d.id_path).unwrap_or_default();
-                    if dedupe::seen_and_add(&dedupe_store, source_id, id, d.capacity).await {
-                        continue;
-                    }
+            for (i, event_value) in events.into_iter().enumerate() {
+                if emit_parsed_event(
+                    &emit_ctx,
+                    &path,
+                    Some(&record_headers),
+                    event_value,
+                    kinds.get(i),
+                )
+                .await?
+                {
+                    total_events += 1;
+                    emitted_count += 1;
                 }
-                total_events += 1;
-                emitted_count += 1;
-                let emitted = build_emitted_event(source, source_id, &path, event_value);
-                emit_event_line(global, source_id, source, &event_sink, &emitted)?;
             }
         }
         metrics::record_events(source_id, emitted_count);
-        if event_count < limit as usize {
+        empty_pages = if event_count == 0 { empty_pages + 1 } else { 0 };
+        // Within a run of empty pages, resume from the first one.
+        if empty_pages <= 1 {
+            resume_offset = offset + event_count as u32;
+        }
+        if event_count < limit as usize
+            && (event_count > 0 || empty_pages >= stop_after_empty_pages)
+        {
             tracing::info!(
                 source = %source_id,
                 pages = page,
@@ -741,12 +901,47 @@ pub(super) async fn poll_offset_pagination(
             );
             break;
         }
+        if let Some(total) = total
+            && u64::from(offset) + u64::from(limit) >= total
+        {
+            tracing::info!(
+                source = %source_id,
+                pages = page,
+                events = total_events,
+                total,
+                duration_ms = start.elapsed().as_millis(),
+                "poll completed (offset, total reached)"
+            );
+            break;
+        }
         if page == max_pages {
             tracing::warn!(source = %source_id, "reached max_pages {}", max_pages);
         }
     }
     store_set_or_skip(&store, source_id, source, global, "next_url", "").await?;
+    if offset_resume {
+        store_set_or_skip(
+            &store,
+            source_id,
+            source,
+            global,
+            OFFSET_RESUME_KEY,
+            &resume_offset.to_string(),
+        )
+        .await?;
+    }
     store_incremental_from_after_poll(&store, source_id, source, global, incremental_max_ts).await;
     store_watermark_after_poll(&store, source_id, source, global, watermark_max_ts).await;
+    emit_tick_summary(
+        global,
+        source_id,
+        source,
+        &event_sink,
+        pages,
+        total_events,
+        total_bytes,
+        start,
+        clock,
+    )?;
     Ok(())
 }
@@ -1,39 +1,181 @@
-use crate::config::{InvalidUtf8Behavior, SourceConfig};
+use crate::clock::Clock;
+use crate::config::{InvalidUtf8Behavior, RedactConfig, ResponseFormat, SourceConfig};
 use crate::event::EmittedEvent;
 use anyhow::Context;
-use chrono::Utc;
+use std::collections::BTreeMap;
 
-use super::helpers::{bytes_to_string, effective_source_label};
+use super::helpers::{bytes_to_string, effective_source_label, is_empty_success};
 
 /// Parse response body bytes using source config. Uses `from_slice` for valid UTF-8 sources,
 /// falls back to lossy `bytes_to_string` + `from_str` when `on_invalid_utf8` is Replace/Escape.
+#[cfg_attr(not(feature = "hooks"), allow(dead_code))] // hooks fallback parse
 pub(crate) fn parse_events_from_body_for_source(
     body_bytes: &[u8],
     source: &SourceConfig,
+    status: u16,
 ) -> anyhow::Result<Vec<serde_json::Value>> {
-    let value: serde_json::Value = match source.on_invalid_utf8 {
+    parse_kinded_events_from_body_for_source(body_bytes, source, None, status)
+        .map(|(events, _)| events)
+}
+
+/// Like `parse_events_from_body_for_source`, also returning the event_sources kind for each event
+/// (parallel to the events; empty when event_sources is unset). `headers` are the response headers, used
+/// for the Content-Type when source.auto_detect_format is set; `status` is the response status.
+pub(crate) fn parse_kinded_events_from_body_for_source(
+    body_bytes: &[u8],
+    source: &SourceConfig,
+    headers: Option<&reqwest::header::HeaderMap>,
+    status: u16,
+) -> anyhow::Result<(Vec<serde_json::Value>, Vec<String>)> {
+    let value = parse_response_value(body_bytes, source, headers, status)?;
+    parse_kinded_events_from_value_for_source(value, source)
+}
+
+/// Parse the response body per source.response_format and on_invalid_utf8. NDJSON bodies become an array
+/// of the line values, so event paths, raw_passthrough and jq see the same shape as a JSON array response.
+/// An empty body on 204 No Content or an `accept_status_codes` status parses as null, which yields no events;
+/// on any other status it is a parse error.
+pub(crate) fn parse_response_value(
+    body_bytes: &[u8],
+    source: &SourceConfig,
+    headers: Option<&reqwest::header::HeaderMap>,
+    status: u16,
+) -> anyhow::Result<serde_json::Value> {
+    if is_empty_success(source, status, body_bytes) {
+        return Ok(serde_json::Value::Null);
+    }
+    if response_format_for(source, headers)? == ResponseFormat::Ndjson {
+        let body = bytes_to_string(body_bytes, source.on_invalid_utf8)?;
+        return parse_ndjson(&body).map(serde_json::Value::Array);
+    }
+    match source.on_invalid_utf8 {
         Some(InvalidUtf8Behavior::Replace) | Some(InvalidUtf8Behavior::Escape) => {
             let body = bytes_to_string(body_bytes, source.on_invalid_utf8)?;
-            serde_json::from_str(&body).context("parse response json")?
+            serde_json::from_str(&body).context("parse response json")
         }
-        _ => serde_json::from_slice(body_bytes).context("parse response json")?,
+        _ => serde_json::from_slice(body_bytes).context("parse response json"),
+    }
+}
+
+/// Format to parse a response as: with source.auto_detect_format, chosen from the Content-Type
+/// (`application/json` or `*+json` → json, `application/x-ndjson` / `application/jsonl` → ndjson; XML is
+/// rejected as unsupported); otherwise, or for any other content type, source.response_format.
+pub(crate) fn response_format_for(
+    source: &SourceConfig,
+    headers: Option<&reqwest::header::HeaderMap>,
+) -> anyhow::Result<ResponseFormat> {
+    if !source.auto_detect_format {
+        return Ok(source.response_format);
+    }
+    let Some(content_type) = headers
+        .and_then(|h| h.get(reqwest::header::CONTENT_TYPE))
+        .and_then(|v| v.to_str().ok())
+    else {
+        return Ok(source.response_format);
     };
-    parse_events_from_value_for_source(value, source)
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    Ok(match mime.as_str() {
+        "application/x-ndjson"
+        | "application/ndjson"
+        | "application/jsonl"
+        | "application/x-jsonlines"
+        | "application/jsonlines" => ResponseFormat::Ndjson,
+        "application/json" => ResponseFormat::Json,
+        m if m.ends_with("+json") => ResponseFormat::Json,
+        m if m == "application/xml" || m == "text/xml" || m.ends_with("+xml") => {
+            anyhow::bail!(
+                "response content-type {:?}: XML responses are not supported",
+                content_type
+            )
+        }
+        _ => source.response_format,
+    })
+}
+
+/// Split an NDJSON body into values: LF or CRLF line endings (trailing `\r` stripped), blank lines skipped.
+fn parse_ndjson(body: &str) -> anyhow::Result<Vec<serde_json::Value>> {
+    let mut values = Vec::new();
+    for (i, line) in body.split('\n').enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.trim().is_empty() {
+            continue;
+        }
+        values.push(
+            serde_json::from_str(line)
+                .with_context(|| format!("parse response ndjson line {}", i + 1))?,
+        );
+    }
+    Ok(values)
+}
+
+/// Trim leading/trailing whitespace from every string value (source.trim_whitespace).
+fn trim_strings(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::String(s) => {
+            let trimmed = s.trim();
+            if trimmed.len() != s.len() {
+                *s = trimmed.to_string();
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(trim_strings),
+        serde_json::Value::Object(obj) => obj.values_mut().for_each(trim_strings),
+        _ => {}
+    }
+}
+
+/// True when the source's response can go through the streaming parser: response_streaming is set and
+/// no option needs the whole parsed body (event_sources, raw_passthrough, jq).
+#[cfg_attr(not(feature = "streaming"), allow(dead_code))]
+pub(crate) fn streaming_parse_enabled(source: &SourceConfig) -> bool {
+    source.response_streaming.is_some()
+        && source.event_sources.is_none()
+        && !source.raw_passthrough
+        && source.jq.is_none()
+        && source.response_format == ResponseFormat::Json
+        && !source.auto_detect_format
 }
 
 /// Extract events from parsed JSON using source's optional paths or default keys.
 /// Takes ownership of the Value tree to avoid cloning the events array.
-pub(crate) fn parse_events_from_value_for_source(
-    value: serde_json::Value,
+/// With event_sources, collects each path in order and returns the kind for each event alongside.
+/// With raw_passthrough, the whole body is the single event. With jq, the expression's output is the events.
+pub(crate) fn parse_kinded_events_from_value_for_source(
+    mut value: serde_json::Value,
     source: &SourceConfig,
-) -> anyhow::Result<Vec<serde_json::Value>> {
-    let path = source.response_events_path.as_deref();
+) -> anyhow::Result<(Vec<serde_json::Value>, Vec<String>)> {
+    if value.is_null() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+    if source.raw_passthrough {
+        return Ok((vec![value], Vec::new()));
+    }
+    if let Some(expr) = &source.jq {
+        return Ok((crate::jq::events_from_jq(expr, value)?, Vec::new()));
+    }
     let obj_path = source.response_event_object_path.as_deref();
-    if path.is_some() || obj_path.is_some() {
-        parse_events_from_value_with_path(value, path, obj_path)
-    } else {
-        parse_events_from_value(value)
+    if let Some(event_sources) = &source.event_sources {
+        let mut events = Vec::new();
+        let mut kinds = Vec::new();
+        for es in event_sources {
+            let arr = json_path_array(&mut value, &es.path).unwrap_or_default();
+            let arr = unwrap_event_objects(arr, obj_path);
+            kinds.extend(std::iter::repeat_n(es.kind.clone(), arr.len()));
+            events.extend(arr);
+        }
+        return Ok((events, kinds));
     }
+    let path = source.response_events_path.as_deref();
+    let events = if path.is_some() || obj_path.is_some() {
+        parse_events_from_value_with_path(value, path, obj_path)?
+    } else {
+        parse_events_from_value(value)?
+    };
+    Ok((events, Vec::new()))
 }
 
 /// Extract events array at dotted path, optionally unwrapping each element (e.g. edge.node).
@@ -123,6 +265,24 @@ pub(crate) fn json_path_str(value: &serde_json::Value, path: &str) -> Option<Str
     v.as_str().map(|s| s.to_string())
 }
 
+/// Get boolean at dotted path in JSON (e.g. "has_more", "meta.has_next").
+pub(crate) fn json_path_bool(value: &serde_json::Value, path: &str) -> Option<bool> {
+    let mut v = value;
+    for segment in path.split('.') {
+        v = v.get(segment)?;
+    }
+    v.as_bool()
+}
+
+/// Get unsigned integer at dotted path in JSON (e.g. "total", "meta.total_count"); numeric strings accepted.
+pub(crate) fn json_path_u64(value: &serde_json::Value, path: &str) -> Option<u64> {
+    let mut v = value;
+    for segment in path.split('.') {
+        v = v.get(segment)?;
+    }
+    v.as_u64().or_else(|| v.as_str()?.trim().parse().ok())
+}
+
 /// Extract event ID from JSON using dotted path (e.g. "uuid", "id", "event.id").
 pub(crate) fn event_id(event: &serde_json::Value, id_path: &str) -> Option<String> {
     let mut v = event;
@@ -133,7 +293,7 @@ pub(crate) fn event_id(event: &serde_json::Value, id_path: &str) -> Option<Strin
 }
 
 /// Fallback order when no timestamp_field config or path missing: published, timestamp, ts, created_at, then now.
-fn event_ts_fallback(event: &serde_json::Value) -> String {
+fn event_ts_fallback(event: &serde_json::Value, clock: &dyn Clock) -> String {
     let s = event
         .get("published")
         .or_else(|| event.get("timestamp"))
@@ -143,7 +303,7 @@ fn event_ts_fallback(event: &serde_json::Value) -> String {
         .unwrap_or("")
         .to_string();
     if s.is_empty() {
-        Utc::now().to_rfc3339()
+        clock.now().to_rfc3339()
     } else {
         s
     }
@@ -153,6 +313,7 @@ fn event_ts_fallback(event: &serde_json::Value) -> String {
 pub(crate) fn event_ts_with_field(
     event: &serde_json::Value,
     timestamp_field: Option<&str>,
+    clock: &dyn Clock,
 ) -> String {
     if let Some(path) = timestamp_field
         && let Some(s) = json_path_str(event, path)
@@ -160,23 +321,93 @@ pub(crate) fn event_ts_with_field(
     {
         return s;
     }
-    event_ts_fallback(event)
+    event_ts_fallback(event, clock)
+}
+
+/// Rename top-level keys of an object event (old -> new, in order); non-objects and missing keys are left as-is.
+fn rename_fields(
+    mut event: serde_json::Value,
+    renames: &indexmap::IndexMap<String, String>,
+) -> serde_json::Value {
+    if let Some(obj) = event.as_object_mut() {
+        for (from, to) in renames {
+            if let Some(v) = obj.remove(from) {
+                obj.insert(to.clone(), v);
+            }
+        }
+    }
+    event
+}
+
+/// Apply one redact rule to the string leaves at its dotted path. Patterns are validated at config load
+/// and compiled once per process.
+fn redact_field(event: &mut serde_json::Value, rule: &RedactConfig) {
+    static COMPILED: std::sync::LazyLock<
+        std::sync::Mutex<std::collections::HashMap<String, regex::Regex>>,
+    > = std::sync::LazyLock::new(Default::default);
+    let Some(target) = rule
+        .field
+        .split('.')
+        .try_fold(event, |v, seg| v.get_mut(seg))
+    else {
+        return;
+    };
+    let re = {
+        let mut cache = COMPILED.lock().unwrap_or_else(|e| e.into_inner());
+        match cache.get(&rule.pattern) {
+            Some(re) => re.clone(),
+            None => match regex::Regex::new(&rule.pattern) {
+                Ok(re) => cache.entry(rule.pattern.clone()).or_insert(re).clone(),
+                Err(_) => return,
+            },
+        }
+    };
+    redact_strings(target, &re, &rule.replacement);
+}
+
+fn redact_strings(value: &mut serde_json::Value, re: &regex::Regex, replacement: &str) {
+    match value {
+        serde_json::Value::String(s) => {
+            if let std::borrow::Cow::Owned(r) = re.replace_all(s, replacement) {
+                *s = r;
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for v in items {
+                redact_strings(v, re, replacement);
+            }
+        }
+        serde_json::Value::Object(obj) => {
+            for v in obj.values_mut() {
+                redact_strings(v, re, replacement);
+            }
+        }
+        _ => {}
+    }
 }
 
-/// Build NDJSON envelope from raw event using source transform (timestamp_field, id_field) when set.
+/// Build NDJSON envelope from raw event using source transform (timestamp_field, id_field, redact, rename_fields) when set.
 /// Takes ownership of event_value to avoid cloning (EmittedEvent::new already accepts owned Value).
+/// `dedupe_id` is the id the dedupe filter used for this event; it becomes meta.dedupe_id with dedupe.include_dedupe_id.
 pub(crate) fn build_emitted_event(
     source: &SourceConfig,
     source_id: &str,
     path: &str,
-    event_value: serde_json::Value,
+    response_headers: Option<&reqwest::header::HeaderMap>,
+    mut event_value: serde_json::Value,
+    dedupe_id: Option<&str>,
+    clock: &dyn Clock,
 ) -> EmittedEvent {
+    if source.trim_whitespace {
+        trim_strings(&mut event_value);
+    }
     let ts = event_ts_with_field(
         &event_value,
         source
             .transform
             .as_ref()
             .and_then(|t| t.timestamp_field.as_deref()),
+        clock,
     );
     let label = effective_source_label(source, source_id);
     let id = source
@@ -184,9 +415,52 @@ pub(crate) fn build_emitted_event(
         .as_ref()
         .and_then(|t| t.id_field.as_ref())
         .and_then(|id_path| event_id(&event_value, id_path));
+    let dedupe_id =
+        dedupe_id.filter(|_| source.dedupe.as_ref().is_some_and(|d| d.include_dedupe_id));
+    if let Some(t) = source.transform.as_ref() {
+        for rule in &t.redact {
+            redact_field(&mut event_value, rule);
+        }
+        if !t.rename_fields.is_empty() {
+            event_value = rename_fields(event_value, &t.rename_fields);
+        }
+    }
     let mut emitted = EmittedEvent::new(ts, label, path.to_string(), event_value);
     if let Some(id) = id {
         emitted = emitted.with_id(id);
     }
+    if let Some(id) = dedupe_id {
+        emitted = emitted.with_dedupe_id(id.to_string());
+    }
+    if source.raw_passthrough {
+        emitted = emitted.with_raw();
+    }
+    if source.include_ingested_at {
+        emitted = emitted.with_ingested_at(clock.now().to_rfc3339());
+    }
+    if let Some(headers) = response_headers
+        && !source.capture_headers.is_empty()
+    {
+        let captured: BTreeMap<String, String> = source
+            .capture_headers
+            .iter()
+            .filter_map(|name| {
+                let value = headers.get(name.as_str())?.to_str().ok()?;
+                Some((name.to_ascii_lowercase(), value.to_string()))
+            })
+            .collect();
+        if !captured.is_empty() {
+            emitted = emitted.with_headers(captured);
+        }
+    }
+    if !source.tags.is_empty() {
+        emitted = emitted.with_tags(
+            source
+                .tags
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        );
+    }
     emitted
 }
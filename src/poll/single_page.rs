@@ -1,6 +1,7 @@
 use crate::circuit::{self, CircuitStore};
-use crate::config::{GlobalConfig, OnParseErrorBehavior, SourceConfig};
-use crate::dedupe::{self, DedupeStore};
+use crate::clock::Clock;
+use crate::config::{GlobalConfig, SourceConfig};
+use crate::dedupe::DedupeStore;
 use crate::dpop::DPoPKeyCache;
 use crate::metrics;
 use crate::oauth2::OAuth2TokenCache;
@@ -14,8 +15,11 @@ use std::time::Instant;
 use tokio::sync::Semaphore;
 
 use super::ClientRateLimiter;
+use super::caches::PollCaches;
+use super::emit::{EmitCtx, emit_parsed_event};
 use super::helpers::*;
 use super::parse::*;
+use super::subrequest::SubrequestCtx;
 /// Single page (no pagination loop): one GET, emit events, clear next_url.
 #[allow(clippy::too_many_arguments)]
 pub(super) async fn poll_single_page(
@@ -33,8 +37,26 @@ pub(super) async fn poll_single_page(
     record_state: Option<Arc<RecordState>>,
     rate_limiter: Option<&Arc<ClientRateLimiter>>,
     request_semaphore: Option<Arc<Semaphore>>,
+    poll_caches: Arc<PollCaches>,
+    clock: &dyn Clock,
 ) -> anyhow::Result<()> {
     let start = Instant::now();
+    let emit_ctx = EmitCtx {
+        global,
+        clock,
+        source_id,
+        source,
+        dedupe_store: &dedupe_store,
+        event_sink: &event_sink,
+        sub: SubrequestCtx {
+            client,
+            token_cache: &token_cache,
+            dpop_key_cache: dpop_key_cache.as_ref(),
+            rate_limiter,
+            request_semaphore: &request_semaphore,
+            poll_caches: &poll_caches,
+        },
+    };
     if let Some(cb) = source
         .resilience
         .as_ref()
@@ -47,61 +69,33 @@ pub(super) async fn poll_single_page(
     if let Some(limiter) = rate_limiter {
         limiter.until_ready().await;
     }
-    let _request_permit = match &request_semaphore {
-        Some(s) => Some(
-            s.acquire()
-                .await
-                .map_err(|e| anyhow::anyhow!("bulkhead request acquire: {}", e))?,
-        ),
-        None => None,
-    };
+    let _request_permit =
+        acquire_request_permits(global, source_id, source, &request_semaphore, &poll_caches)
+            .await?;
     let req_start = std::time::Instant::now();
-    let response = match execute_with_retry(
-        client,
-        source,
+    let response = record_request_outcome(
+        &circuit_store,
         source_id,
-        url,
-        None, // single page: body from source when POST
-        source.resilience.as_ref().and_then(|r| r.retries.as_ref()),
-        source
-            .resilience
-            .as_ref()
-            .and_then(|r| r.rate_limit.as_ref()),
-        Some(&token_cache),
-        dpop_key_cache.as_ref(),
-        global.audit.as_ref(),
-    )
-    .await
-    {
-        Ok(r) => {
-            let success = r.status().as_u16() < 500;
-            if let Some(cb) = source
-                .resilience
-                .as_ref()
-                .and_then(|r| r.circuit_breaker.as_ref())
-            {
-                circuit::record_result(&circuit_store, source_id, cb, success).await;
-            }
-            metrics::record_request(
-                source_id,
-                status_class(r.status().as_u16()),
-                req_start.elapsed().as_secs_f64(),
-            );
-            r
-        }
-        Err(e) => {
-            if let Some(cb) = source
+        source,
+        req_start,
+        execute_with_retry(
+            client,
+            source,
+            source_id,
+            url,
+            None, // single page: body from source when POST
+            source.resilience.as_ref().and_then(|r| r.retries.as_ref()),
+            source
                 .resilience
                 .as_ref()
-                .and_then(|r| r.circuit_breaker.as_ref())
-            {
-                circuit::record_result(&circuit_store, source_id, cb, false).await;
-            }
-            metrics::record_request(source_id, "error", req_start.elapsed().as_secs_f64());
-            metrics::record_error(source_id);
-            return Err(e).context("http request");
-        }
-    };
+                .and_then(|r| r.rate_limit.as_ref()),
+            Some(&token_cache),
+            dpop_key_cache.as_ref(),
+            global.audit.as_ref(),
+        )
+        .await,
+    )
+    .await?;
 
     maybe_adaptive_sleep_after_response(
         response.headers(),
@@ -110,6 +104,8 @@ pub(super) async fn poll_single_page(
             .resilience
             .as_ref()
             .and_then(|r| r.rate_limit.as_ref()),
+        &poll_caches,
+        clock,
     )
     .await;
 
@@ -122,19 +118,23 @@ pub(super) async fn poll_single_page(
     let mut incremental_max_ts: Option<String> = None;
     let mut watermark_max_ts: Option<String> = None;
     let mut emitted_count = 0u64;
+    let mut total_bytes = 0u64;
     let mut _streamed = false;
 
     #[cfg(feature = "streaming")]
-    if source.response_streaming.is_some() {
+    if streaming_parse_enabled(source) {
         use super::streaming;
         use crate::config::StreamingMode;
 
         let use_full = source.response_streaming == Some(StreamingMode::Full)
-            && source.on_invalid_utf8.is_none();
+            && source.on_invalid_utf8.is_none()
+            && source.body_decrypt.is_none()
+            && !is_content_encoded(&record_headers)
+            && !allows_empty_body(source, record_status);
 
         if use_full {
             let resp = response.take().unwrap();
-            if !resp.status().is_success() {
+            if !is_success_status(source, resp.status().as_u16()) {
                 let body_bytes = read_body_with_limit(resp, source.max_response_bytes).await?;
                 if let Some(ref rs) = record_state {
                     rs.save(
@@ -168,15 +168,21 @@ pub(super) async fn poll_single_page(
                 let event_value = match result {
                     Ok(v) => v,
                     Err(e) => {
-                        if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                            tracing::warn!(source = %source_id, error = %e, "parse error, skipping");
-                            let _ = store_set_or_skip(
-                                &store, source_id, source, global, "next_url", "",
-                            )
-                            .await;
-                            return Ok(());
-                        }
-                        return Err(e).context("streaming parse element");
+                        handle_parse_error(
+                            global,
+                            source_id,
+                            source,
+                            record_url.as_str(),
+                            record_status,
+                            None,
+                            e,
+                            "parse error, skipping",
+                        )
+                        .context("streaming parse element")?;
+                        let _ =
+                            store_set_or_skip(&store, source_id, source, global, "next_url", "")
+                                .await;
+                        return Ok(());
                     }
                 };
                 let event_value = match streaming::unwrap_event_object(event_value, obj_path) {
@@ -191,21 +197,13 @@ pub(super) async fn poll_single_page(
                     );
                 }
                 if let Some(ref st) = source.state {
-                    update_max_timestamp_single(
-                        &mut watermark_max_ts,
-                        &event_value,
-                        &st.watermark_field,
-                    );
+                    update_watermark_single(&mut watermark_max_ts, &event_value, st);
                 }
-                if let Some(d) = &source.dedupe {
-                    let id = event_id(&event_value, &d.id_path).unwrap_or_default();
-                    if dedupe::seen_and_add(&dedupe_store, source_id, id, d.capacity).await {
-                        continue;
-                    }
+                if emit_parsed_event(&emit_ctx, &path, Some(&record_headers), event_value, None)
+                    .await?
+                {
+                    emitted_count += 1;
                 }
-                emitted_count += 1;
-                let emitted = build_emitted_event(source, source_id, &path, event_value);
-                emit_event_line(global, source_id, source, &event_sink, &emitted)?;
             }
 
             join_handle
@@ -236,21 +234,31 @@ pub(super) async fn poll_single_page(
                     &body_bytes,
                 )?;
             }
-            if !(200..300).contains(&record_status) {
+            if !is_success_status(source, record_status) {
                 let body_str = String::from_utf8_lossy(&body_bytes);
                 anyhow::bail!("http {} {}", record_status, body_str);
             }
-            let parse_result = match streaming::parse_streaming(&body_bytes, source) {
+            let body_bytes = maybe_decompress_body(source, &record_headers, body_bytes)?;
+            let body_bytes = maybe_decrypt_body(source, &record_headers, body_bytes)?;
+            total_bytes += body_bytes.len() as u64;
+            let parse_result = match streaming::parse_streaming(&body_bytes, source, record_status)
+            {
                 Ok(r) => r,
                 Err(e) => {
-                    if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                        tracing::warn!(source = %source_id, error = %e, "parse error, skipping");
-                        let _ =
-                            store_set_or_skip(&store, source_id, source, global, "next_url", "")
-                                .await;
-                        return Ok(());
-                    }
-                    return Err(e).context("streaming parse");
+                    handle_parse_error(
+                        global,
+                        source_id,
+                        source,
+                        record_url.as_str(),
+                        record_status,
+                        Some(&body_bytes),
+                        e,
+                        "parse error, skipping",
+                    )
+                    .context("streaming parse")?;
+                    let _ =
+                        store_set_or_skip(&store, source_id, source, global, "next_url", "").await;
+                    return Ok(());
                 }
             };
             let obj_path = source.response_event_object_path.as_deref();
@@ -268,21 +276,13 @@ pub(super) async fn poll_single_page(
                     );
                 }
                 if let Some(ref st) = source.state {
-                    update_max_timestamp_single(
-                        &mut watermark_max_ts,
-                        &event_value,
-                        &st.watermark_field,
-                    );
+                    update_watermark_single(&mut watermark_max_ts, &event_value, st);
                 }
-                if let Some(d) = &source.dedupe {
-                    let id = event_id(&event_value, &d.id_path).unwrap_or_default();
-                    if dedupe::seen_and_add(&dedupe_store, source_id, id, d.capacity).await {
-                        continue;
-                    }
+                if emit_parsed_event(&emit_ctx, &path, Some(&record_headers), event_value, None)
+                    .await?
+                {
+                    emitted_count += 1;
                 }
-                emitted_count += 1;
-                let emitted = build_emitted_event(source, source_id, &path, event_value);
-                emit_event_line(global, source_id, source, &event_sink, &emitted)?;
             }
             _streamed = true;
         }
@@ -300,38 +300,54 @@ pub(super) async fn poll_single_page(
                 &body_bytes,
             )?;
         }
-        if !(200..300).contains(&record_status) {
+        if !is_success_status(source, record_status) {
             let body_str = String::from_utf8_lossy(&body_bytes);
             anyhow::bail!("http {} {}", record_status, body_str);
         }
-        let events = match parse_events_from_body_for_source(&body_bytes, source) {
+        let body_bytes = maybe_decompress_body(source, &record_headers, body_bytes)?;
+        let body_bytes = maybe_decrypt_body(source, &record_headers, body_bytes)?;
+        total_bytes += body_bytes.len() as u64;
+        let (events, kinds) = match parse_kinded_events_from_body_for_source(
+            &body_bytes,
+            source,
+            Some(&record_headers),
+            record_status,
+        ) {
             Ok(ev) => ev,
             Err(e) => {
-                if source.on_parse_error == Some(OnParseErrorBehavior::Skip) {
-                    tracing::warn!(source = %source_id, error = %e, "parse error, skipping");
-                    let _ =
-                        store_set_or_skip(&store, source_id, source, global, "next_url", "").await;
-                    return Ok(());
-                }
-                return Err(e).context("parse response");
+                handle_parse_error(
+                    global,
+                    source_id,
+                    source,
+                    record_url.as_str(),
+                    record_status,
+                    Some(&body_bytes),
+                    e,
+                    "parse error, skipping",
+                )
+                .context("parse response")?;
+                let _ = store_set_or_skip(&store, source_id, source, global, "next_url", "").await;
+                return Ok(());
             }
         };
         if let Some(ref inc) = source.incremental_from {
             update_max_timestamp(&mut incremental_max_ts, &events, &inc.event_timestamp_path);
         }
         if let Some(ref st) = source.state {
-            update_max_timestamp(&mut watermark_max_ts, &events, &st.watermark_field);
+            update_watermark(&mut watermark_max_ts, &events, st);
         }
-        for event_value in events {
-            if let Some(d) = &source.dedupe {
-                let id = event_id(&event_value, &d.id_path).unwrap_or_default();
-                if dedupe::seen_and_add(&dedupe_store, source_id, id, d.capacity).await {
-                    continue;
-                }
+        for (i, event_value) in events.into_iter().enumerate() {
+            if emit_parsed_event(
+                &emit_ctx,
+                &path,
+                Some(&record_headers),
+                event_value,
+                kinds.get(i),
+            )
+            .await?
+            {
+                emitted_count += 1;
             }
-            emitted_count += 1;
-            let emitted = build_emitted_event(source, source_id, &path, event_value);
-            emit_event_line(global, source_id, source, &event_sink, &emitted)?;
         }
     }
     metrics::record_events(source_id, emitted_count);
@@ -345,5 +361,16 @@ pub(super) async fn poll_single_page(
         duration_ms = start.elapsed().as_millis(),
         "poll completed"
     );
+    emit_tick_summary(
+        global,
+        source_id,
+        source,
+        &event_sink,
+        1,
+        emitted_count,
+        total_bytes,
+        start,
+        clock,
+    )?;
     Ok(())
 }
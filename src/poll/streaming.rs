@@ -124,14 +124,19 @@ pub(crate) fn unwrap_event_object(
 }
 
 /// High-level helper: prepare metadata + iterator from buffered body bytes.
-/// Handles `on_invalid_utf8` by converting to lossy string first when needed.
+/// Handles `on_invalid_utf8` by converting to lossy string first when needed. A blank body on a status that allows
+/// one (`helpers::allows_empty_body`) yields no events.
 pub(crate) fn parse_streaming(
     body_bytes: &[u8],
     source: &crate::config::SourceConfig,
+    status: u16,
 ) -> anyhow::Result<StreamingParseResult> {
     use crate::config::InvalidUtf8Behavior;
 
     let events_path = source.response_events_path.as_deref();
+    if super::helpers::is_empty_success(source, status, body_bytes) {
+        return Ok(StreamingParseResult::Empty);
+    }
 
     match source.on_invalid_utf8 {
         Some(InvalidUtf8Behavior::Replace) | Some(InvalidUtf8Behavior::Escape) => {
@@ -169,12 +174,17 @@ pub(crate) enum StreamingParseResult {
         array_range: (usize, usize),
         metadata: serde_json::Value,
     },
+    /// Empty body on 204 No Content or an accepted status: no events, null metadata.
+    Empty,
 }
 
+static NULL_METADATA: serde_json::Value = serde_json::Value::Null;
+
 impl StreamingParseResult {
     pub(crate) fn metadata(&self) -> &serde_json::Value {
         match self {
             Self::Borrowed { metadata, .. } | Self::Owned { metadata, .. } => metadata,
+            Self::Empty => &NULL_METADATA,
         }
     }
 
@@ -184,6 +194,12 @@ impl StreamingParseResult {
             Self::Owned {
                 bytes, array_range, ..
             } => ArrayElementIter::new(bytes, *array_range),
+            Self::Empty => ArrayElementIter {
+                bytes: body_bytes,
+                pos: 0,
+                end: 0,
+                done: true,
+            },
         }
     }
 }
@@ -0,0 +1,181 @@
+//! Nested pagination: per-event child detail fetch (`subrequest`), merged into the parent event before emit.
+
+use crate::config::{GlobalConfig, HttpMethod, SourceConfig, SubrequestConfig};
+use crate::dpop::DPoPKeyCache;
+use crate::oauth2::OAuth2TokenCache;
+use crate::retry::execute_with_retry;
+use anyhow::Context;
+use std::borrow::Cow;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+use super::ClientRateLimiter;
+use super::caches::PollCaches;
+use super::helpers::{
+    acquire_request_permits, is_success_status, maybe_decompress_body, percent_encode,
+    read_body_with_limit, status_class, value_at_path_as_string,
+};
+
+/// Request context shared with the parent page request: same client, auth caches and rate limiter.
+pub(super) struct SubrequestCtx<'a> {
+    pub client: &'a reqwest::Client,
+    pub token_cache: &'a OAuth2TokenCache,
+    pub dpop_key_cache: Option<&'a DPoPKeyCache>,
+    pub rate_limiter: Option<&'a Arc<ClientRateLimiter>>,
+    /// Per-source bulkhead semaphore; detail fetches hold the same request permits as page requests.
+    pub request_semaphore: &'a Option<Arc<Semaphore>>,
+    pub poll_caches: &'a PollCaches,
+}
+
+/// When the source has `subrequest`, fetch the detail for this event and merge it under `merge_key`.
+/// Fetches run one at a time (after the source's rate limiter and bulkhead permits) with the source's auth and retries.
+pub(super) async fn enrich_event(
+    ctx: &SubrequestCtx<'_>,
+    global: &GlobalConfig,
+    source_id: &str,
+    source: &SourceConfig,
+    mut event: serde_json::Value,
+) -> anyhow::Result<serde_json::Value> {
+    let Some(sub) = &source.subrequest else {
+        return Ok(event);
+    };
+    let url = expand_url_template(&sub.url, &event)?;
+    let detail = fetch_detail(ctx, global, source_id, source, sub, &url).await?;
+    match event.as_object_mut() {
+        Some(obj) => {
+            obj.insert(sub.merge_key.clone(), detail);
+        }
+        None => {
+            let mut obj = serde_json::Map::new();
+            obj.insert("value".to_string(), event);
+            obj.insert(sub.merge_key.clone(), detail);
+            event = serde_json::Value::Object(obj);
+        }
+    }
+    Ok(event)
+}
+
+async fn fetch_detail(
+    ctx: &SubrequestCtx<'_>,
+    global: &GlobalConfig,
+    source_id: &str,
+    source: &SourceConfig,
+    sub: &SubrequestConfig,
+    url: &str,
+) -> anyhow::Result<serde_json::Value> {
+    if let Some(limiter) = ctx.rate_limiter {
+        limiter.until_ready().await;
+    }
+    // Detail endpoints are GETs even when the list request is a POST.
+    let detail_source: Cow<'_, SourceConfig> =
+        if source.method == HttpMethod::Get && source.body.is_none() {
+            Cow::Borrowed(source)
+        } else {
+            let mut s = source.clone();
+            s.method = HttpMethod::Get;
+            s.body = None;
+            Cow::Owned(s)
+        };
+    let _request_permit = acquire_request_permits(
+        global,
+        source_id,
+        source,
+        ctx.request_semaphore,
+        ctx.poll_caches,
+    )
+    .await?;
+    let req_start = std::time::Instant::now();
+    let response = execute_with_retry(
+        ctx.client,
+        &detail_source,
+        source_id,
+        url,
+        None,
+        source.resilience.as_ref().and_then(|r| r.retries.as_ref()),
+        source
+            .resilience
+            .as_ref()
+            .and_then(|r| r.rate_limit.as_ref()),
+        Some(ctx.token_cache),
+        ctx.dpop_key_cache,
+        global.audit.as_ref(),
+    )
+    .await
+    .inspect_err(|_| {
+        super::record_request(source_id, "error", req_start.elapsed().as_secs_f64());
+    })
+    .context("subrequest")?;
+    let status = response.status().as_u16();
+    super::record_request(
+        source_id,
+        status_class(status),
+        req_start.elapsed().as_secs_f64(),
+    );
+    let headers = response.headers().clone();
+    let body = read_body_with_limit(response, source.max_response_bytes).await?;
+    if !is_success_status(source, status) {
+        anyhow::bail!(
+            "subrequest {} http {} {}",
+            url,
+            status,
+            String::from_utf8_lossy(&body)
+        );
+    }
+    let body = maybe_decompress_body(source, &headers, body)?;
+    let value: serde_json::Value =
+        serde_json::from_slice(&body).context("subrequest response is not JSON")?;
+    Ok(match &sub.response_path {
+        Some(path) => path
+            .split('.')
+            .try_fold(&value, |v, seg| v.get(seg))
+            .cloned()
+            .unwrap_or(serde_json::Value::Null),
+        None => value,
+    })
+}
+
+/// Replace each `{path}` in the template with the URL-encoded value at that dotted path in the event.
+fn expand_url_template(template: &str, event: &serde_json::Value) -> anyhow::Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        let close = rest[open..]
+            .find('}')
+            .map(|i| open + i)
+            .ok_or_else(|| anyhow::anyhow!("subrequest url: unclosed '{{' in {:?}", template))?;
+        out.push_str(&rest[..open]);
+        let path = &rest[open + 1..close];
+        let value = value_at_path_as_string(event, path)
+            .ok_or_else(|| anyhow::anyhow!("subrequest url: event has no value at {:?}", path))?;
+        out.push_str(&percent_encode(&value));
+        rest = &rest[close + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn expand_url_template_substitutes_and_encodes() {
+        let event = json!({"id": "P1 2/3", "service": {"id": 42}});
+        let url = expand_url_template(
+            "https://api.example.com/incidents/{id}?service={service.id}",
+            &event,
+        )
+        .unwrap();
+        assert_eq!(
+            url,
+            "https://api.example.com/incidents/P1%202%2F3?service=42"
+        );
+    }
+
+    #[test]
+    fn expand_url_template_missing_field_errors() {
+        let err = expand_url_template("https://x/{missing}", &json!({"id": "1"})).unwrap_err();
+        assert!(err.to_string().contains("missing"));
+    }
+}
@@ -144,9 +144,11 @@ struct ReplayServerState {
 }
 
 /// Start the replay HTTP server. Binds to 127.0.0.1:port (port 0 = pick one). Returns (addr, join_handle).
+/// With `looping`, a source that has served all its recordings starts over from the first (used by `helr bench`).
 pub async fn start_replay_server(
     recordings: HashMap<String, Vec<Recording>>,
     port: u16,
+    looping: bool,
 ) -> anyhow::Result<(std::net::SocketAddr, tokio::task::JoinHandle<()>)> {
     let state = Arc::new(tokio::sync::RwLock::new(ReplayServerState {
         next: recordings.keys().map(|k| (k.clone(), 0)).collect(),
@@ -168,7 +170,7 @@ pub async fn start_replay_server(
                     let state = state.clone();
                     async move {
                         let mut g = state.write().await;
-                        let next = g.next.get_mut(&source_id).copied().unwrap_or(0);
+                        let mut next = g.next.get_mut(&source_id).copied().unwrap_or(0);
                         let responses = match g.responses.get(&source_id) {
                             Some(r) => r,
                             None => {
@@ -178,6 +180,9 @@ pub async fn start_replay_server(
                                     .unwrap();
                             }
                         };
+                        if looping && next >= responses.len() {
+                            next = 0;
+                        }
                         if next >= responses.len() {
                             return axum::response::Response::builder()
                                 .status(axum::http::StatusCode::NOT_FOUND)
@@ -1,17 +1,19 @@
 //! Retry layer for HTTP requests: exponential backoff, retryable status codes.
 //! On 429, uses Retry-After (or X-RateLimit-Reset) when configured.
 
-use crate::client::build_request;
+use crate::client::{build_request, redact_reqwest_error};
 use crate::config::{
-    AuditConfig, AuthConfig, HttpMethod, RateLimitConfig, RateLimitHeaderMapping, RetryConfig,
-    SourceConfig,
+    AuditConfig, AuthConfig, RateLimitConfig, RateLimitHeaderMapping, RetryBodyMatchConfig,
+    RetryConfig, SourceConfig,
 };
 use crate::dpop::{DPoPKeyCache, build_dpop_proof, get_or_create_dpop_key};
+use crate::jitter;
 use crate::oauth2::{OAuth2TokenCache, get_google_sa_token, get_oauth_token, invalidate_token};
 use anyhow::Context;
-use rand::RngExt;
 use reqwest::header::HeaderMap;
-use reqwest::{Client, Response};
+use reqwest::{Client, Response, ResponseBuilderExt};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
 use std::time::Duration;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::warn;
@@ -20,6 +22,94 @@ fn is_5xx(code: u16) -> bool {
     (500..600).contains(&code)
 }
 
+/// Compiled retry_on_body_match patterns by source, so each response only runs the regex.
+static BODY_MATCH_REGEX: LazyLock<Mutex<HashMap<String, regex::Regex>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The compiled `pattern` (validated at config load); recompiled only when the source's pattern changes.
+fn body_match_regex(source_id: &str, pattern: &str) -> Option<regex::Regex> {
+    let mut cache = BODY_MATCH_REGEX.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(re) = cache.get(source_id)
+        && re.as_str() == pattern
+    {
+        return Some(re.clone());
+    }
+    let re = regex::Regex::new(pattern).ok()?;
+    cache.insert(source_id.to_string(), re.clone());
+    Some(re)
+}
+
+/// Returns true if a (2xx) response body matches retries.retry_on_body_match.
+fn body_matches_retry_rule(source_id: &str, body: &[u8], rule: &RetryBodyMatchConfig) -> bool {
+    let text = String::from_utf8_lossy(body);
+    let target = match &rule.path {
+        Some(path) => {
+            let Ok(value) = serde_json::from_slice::<serde_json::Value>(body) else {
+                return false;
+            };
+            let mut cur = &value;
+            for segment in path.split('.') {
+                let next = match cur {
+                    serde_json::Value::Object(map) => map.get(segment),
+                    serde_json::Value::Array(arr) => {
+                        segment.parse::<usize>().ok().and_then(|i| arr.get(i))
+                    }
+                    _ => None,
+                };
+                match next {
+                    Some(v) => cur = v,
+                    None => return false,
+                }
+            }
+            Some(cur.clone())
+        }
+        None => None,
+    };
+    if let (Some(expected), Some(actual)) = (&rule.equals, &target)
+        && expected == actual
+    {
+        return true;
+    }
+    if let Some(pattern) = &rule.pattern
+        && let Some(re) = body_match_regex(source_id, pattern)
+    {
+        return match &target {
+            Some(serde_json::Value::String(s)) => re.is_match(s),
+            Some(other) => re.is_match(&other.to_string()),
+            None if rule.path.is_none() => re.is_match(&text),
+            None => false,
+        };
+    }
+    false
+}
+
+/// Buffer a successful response body (within max_response_bytes) for retry_on_body_match. Returns the rebuilt
+/// response (same status, headers, and URL) and whether the body matched the rule.
+async fn check_retry_body(
+    response: Response,
+    source: &SourceConfig,
+    source_id: &str,
+    rule: &RetryBodyMatchConfig,
+) -> anyhow::Result<(Response, bool)> {
+    let status = response.status();
+    let version = response.version();
+    let url = response.url().clone();
+    let headers = response.headers().clone();
+    let body = crate::poll::read_body_with_limit(response, source.max_response_bytes).await?;
+    let matched = body_matches_retry_rule(source_id, &body, rule);
+    let mut builder = axum::http::Response::builder()
+        .status(status)
+        .version(version)
+        .url(url);
+    if let Some(h) = builder.headers_mut() {
+        *h = headers;
+    }
+    let rebuilt = builder
+        .body(body)
+        .context("rebuild response after body check")?;
+    Ok((Response::from(rebuilt), matched))
+}
+
 /// Returns true if status should be retried. Uses `retryable_status_codes` when set, else 408, 429, 5xx.
 pub fn is_retryable_status_with_codes(
     status: reqwest::StatusCode,
@@ -42,8 +132,7 @@ fn backoff_duration(retry: &RetryConfig, attempt: u32) -> Duration {
     let base_secs = capped.min(u64::MAX as f64);
     let delay_secs = if let Some(j) = retry.jitter {
         let j = j.clamp(0.0, 1.0);
-        let mut rng = rand::rng();
-        let factor = 1.0 + rng.random_range(-j..=j);
+        let factor = 1.0 + jitter::random_range(jitter::Stream::Retry, -j..=j);
         (base_secs * factor).max(0.0)
     } else {
         base_secs
@@ -51,6 +140,22 @@ fn backoff_duration(retry: &RetryConfig, attempt: u32) -> Duration {
     Duration::from_secs_f64(delay_secs)
 }
 
+/// Request timeout for this attempt (0 = first) when retry.timeout_multiplier is set: base × multiplier^attempt,
+/// capped at the larger of base and max_backoff_secs (a retry never gets less time than the first attempt).
+/// None means keep the client's timeout.
+fn attempt_timeout(retry: &RetryConfig, base: Duration, attempt: u32) -> Option<Duration> {
+    let factor = retry.timeout_multiplier?;
+    if attempt == 0 || factor <= 0.0 {
+        return None;
+    }
+    let secs = base.as_secs_f64() * factor.powi(attempt as i32);
+    let capped = retry
+        .max_backoff_secs
+        .map(|max| secs.min((max as f64).max(base.as_secs_f64())))
+        .unwrap_or(secs);
+    Some(Duration::from_secs_f64(capped.min(u32::MAX as f64)))
+}
+
 /// Parse Retry-After (delta-seconds or HTTP-date) and optionally reset header (e.g. X-RateLimit-Reset).
 /// Uses header_mapping.reset_header when set, else tries X-RateLimit-Reset, X-Rate-Limit-Reset.
 /// Returns None if header missing or unparseable. Caps duration by max_cap_secs when given.
@@ -189,10 +294,7 @@ async fn dpop_proof_for_request(
         None => return Ok(None),
     };
     let key = get_or_create_dpop_key(key_cache, source_id).await?;
-    let method = match source.method {
-        HttpMethod::Get => "GET",
-        HttpMethod::Post => "POST",
-    };
+    let method = source.method.as_str();
     let iat = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .context("system time")?
@@ -207,6 +309,88 @@ async fn dpop_proof_for_request(
     Ok(Some(proof))
 }
 
+/// Send one attempt and record it in the audit log (global.audit.log_http_requests), response or not.
+pub(crate) async fn execute_audited(
+    client: &Client,
+    req: reqwest::Request,
+    source: &SourceConfig,
+    source_id: &str,
+    audit: Option<&AuditConfig>,
+) -> Result<Response, reqwest::Error> {
+    let method = req.method().clone();
+    let url = req.url().clone();
+    let secret_param = crate::client::api_key_query_param(source);
+    match client.execute(req).await {
+        Ok(response) => {
+            crate::audit::log_http_request(
+                audit,
+                source_id,
+                method.as_str(),
+                response.url().as_str(),
+                response.status().as_u16(),
+                response.content_length(),
+                secret_param,
+            );
+            Ok(response)
+        }
+        Err(e) => {
+            let e = redact_reqwest_error(e, source);
+            crate::audit::log_http_request_failed(
+                audit,
+                source_id,
+                method.as_str(),
+                url.as_str(),
+                &e.to_string(),
+                secret_param,
+            );
+            Err(e)
+        }
+    }
+}
+
+/// Execute a single request with source auth and headers; no retries, every status is returned as a response.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_once(
+    client: &Client,
+    source: &SourceConfig,
+    source_id: &str,
+    url: &str,
+    body: Option<&serde_json::Value>,
+    token_cache: Option<&OAuth2TokenCache>,
+    dpop_key_cache: Option<&DPoPKeyCache>,
+    audit: Option<&AuditConfig>,
+) -> anyhow::Result<Response> {
+    let bearer = bearer_for_request(
+        client,
+        source,
+        source_id,
+        token_cache,
+        dpop_key_cache,
+        audit,
+    )
+    .await?;
+    let dpop_proof = dpop_proof_for_request(
+        source_id,
+        source,
+        url,
+        dpop_key_cache,
+        None,
+        bearer.as_deref(),
+    )
+    .await?;
+    let req_ctx = crate::client::BuildRequestContext {
+        bearer_override: bearer.as_deref(),
+        body_override: body,
+        dpop_proof,
+        source_id,
+        audit,
+    };
+    let req = build_request(client, source, url, &req_ctx)?;
+    execute_audited(client, req, source, source_id, audit)
+        .await
+        .context("http request")
+}
+
 /// Execute a GET or POST request with optional retries. Uses source auth and headers.
 /// Retries on 408, 429, 5xx, and transport errors; on 429 uses Retry-After when rate_limit.respect_headers is true.
 #[allow(clippy::too_many_arguments)]
@@ -225,33 +409,17 @@ pub async fn execute_with_retry(
     let retry = match retry {
         Some(r) if r.max_attempts > 0 => r,
         _ => {
-            let bearer = bearer_for_request(
+            return execute_once(
                 client,
                 source,
                 source_id,
+                url,
+                body,
                 token_cache,
                 dpop_key_cache,
                 audit,
             )
-            .await?;
-            let dpop_proof = dpop_proof_for_request(
-                source_id,
-                source,
-                url,
-                dpop_key_cache,
-                None,
-                bearer.as_deref(),
-            )
-            .await?;
-            let req_ctx = crate::client::BuildRequestContext {
-                bearer_override: bearer.as_deref(),
-                body_override: body,
-                dpop_proof,
-                source_id,
-                audit,
-            };
-            let req = build_request(client, source, url, &req_ctx)?;
-            return client.execute(req).await.context("http request");
+            .await;
         }
     };
 
@@ -283,11 +451,48 @@ pub async fn execute_with_retry(
             source_id,
             audit,
         };
-        let req = build_request(client, source, url, &req_ctx)?;
-        match client.execute(req).await {
+        let mut req = build_request(client, source, url, &req_ctx)?;
+        if let Some(timeout) = attempt_timeout(
+            retry,
+            crate::client::request_timeout(source.resilience.as_ref()),
+            attempt,
+        ) {
+            *req.timeout_mut() = Some(timeout);
+        }
+        match execute_audited(client, req, source, source_id, audit).await {
             Ok(response) => {
-                if response.status().is_success() {
-                    return Ok(response);
+                if response.status().is_success()
+                    || source
+                        .accept_status_codes
+                        .contains(&response.status().as_u16())
+                {
+                    let Some(rule) = retry.retry_on_body_match.as_ref() else {
+                        return Ok(response);
+                    };
+                    let status = response.status();
+                    let (response, matched) =
+                        check_retry_body(response, source, source_id, rule).await?;
+                    if !matched {
+                        return Ok(response);
+                    }
+                    let body = response.text().await.unwrap_or_default();
+                    last_err = Some(anyhow::anyhow!(
+                        "http {} matched retry_on_body_match: {}",
+                        status,
+                        body
+                    ));
+                    if attempt + 1 < retry.max_attempts {
+                        let delay = backoff_duration(retry, attempt);
+                        warn!(
+                            status = %status,
+                            attempt = attempt + 1,
+                            max_attempts = retry.max_attempts,
+                            delay_secs = delay.as_secs_f64(),
+                            "response body matched retry_on_body_match, backing off"
+                        );
+                        tokio::time::sleep(delay).await;
+                    }
+                    continue;
                 }
                 if response.status().as_u16() == 401
                     && matches!(
@@ -299,14 +504,20 @@ pub async fn execute_with_retry(
                 {
                     auth_refresh_attempted = true;
                     let _ = response.text().await;
-                    invalidate_token(cache, source_id).await;
+                    invalidate_token(
+                        cache,
+                        &crate::oauth2::token_cache_key(source_id, source.auth.as_ref()),
+                    )
+                    .await;
                     warn!(source = %source_id, "401 Unauthorized, refreshed OAuth token, retrying");
                     continue;
                 }
-                if !is_retryable_status_with_codes(
-                    response.status(),
-                    retry.retryable_status_codes.as_deref(),
-                ) {
+                if !retry.retry_on.status
+                    || !is_retryable_status_with_codes(
+                        response.status(),
+                        retry.retryable_status_codes.as_deref(),
+                    )
+                {
                     let status = response.status();
                     let dpop_nonce_header = response
                         .headers()
@@ -346,7 +557,8 @@ pub async fn execute_with_retry(
                                 audit,
                             };
                             let retry_req = build_request(client, source, url, &retry_req_ctx)?;
-                            match client.execute(retry_req).await {
+                            match execute_audited(client, retry_req, source, source_id, audit).await
+                            {
                                 Ok(retry_response) if retry_response.status().is_success() => {
                                     return Ok(retry_response);
                                 }
@@ -409,8 +621,13 @@ pub async fn execute_with_retry(
                 }
             }
             Err(e) => {
+                let retry_class = if e.is_timeout() {
+                    retry.retry_on.timeout
+                } else {
+                    retry.retry_on.connect
+                };
                 last_err = Some(e.into());
-                if attempt + 1 < retry.max_attempts {
+                if retry_class && attempt + 1 < retry.max_attempts {
                     let delay = backoff_duration(retry, attempt);
                     warn!(
                         error = %last_err.as_ref().unwrap(),
@@ -479,6 +696,9 @@ mod tests {
             multiplier: 2.0,
             jitter: None,
             retryable_status_codes: None,
+            timeout_multiplier: None,
+            retry_on_body_match: None,
+            retry_on: Default::default(),
         };
         assert_eq!(backoff_duration(&retry, 0), Duration::from_secs(1));
         assert_eq!(backoff_duration(&retry, 1), Duration::from_secs(2));
@@ -486,6 +706,42 @@ mod tests {
         assert_eq!(backoff_duration(&retry, 10), Duration::from_secs(30)); // capped
     }
 
+    #[test]
+    fn test_attempt_timeout_escalates_on_retry() {
+        let mut retry = RetryConfig {
+            max_attempts: 3,
+            initial_backoff_secs: 1,
+            max_backoff_secs: Some(60),
+            multiplier: 2.0,
+            jitter: None,
+            retryable_status_codes: None,
+            timeout_multiplier: Some(3.0),
+            retry_on_body_match: None,
+            retry_on: Default::default(),
+        };
+        let base = Duration::from_secs(5);
+        assert_eq!(attempt_timeout(&retry, base, 0), None);
+        assert_eq!(
+            attempt_timeout(&retry, base, 1),
+            Some(Duration::from_secs(15))
+        );
+        assert_eq!(
+            attempt_timeout(&retry, base, 2),
+            Some(Duration::from_secs(45))
+        );
+        assert_eq!(
+            attempt_timeout(&retry, base, 3),
+            Some(Duration::from_secs(60))
+        ); // capped
+        retry.max_backoff_secs = Some(2);
+        assert_eq!(
+            attempt_timeout(&retry, base, 2),
+            Some(Duration::from_secs(5))
+        ); // cap below base: base wins
+        retry.timeout_multiplier = None;
+        assert_eq!(attempt_timeout(&retry, base, 1), None);
+    }
+
     #[test]
     fn test_retry_after_delta_seconds() {
         let mut headers = HeaderMap::new();
@@ -536,6 +792,9 @@ mod tests {
             multiplier: 2.0,
             jitter: Some(0.1),
             retryable_status_codes: None,
+            timeout_multiplier: None,
+            retry_on_body_match: None,
+            retry_on: Default::default(),
         };
         for attempt in 0..5 {
             let d = backoff_duration(&retry, attempt);
@@ -571,6 +830,7 @@ mod tests {
             limit_header: None,
             remaining_header: None,
             reset_header: Some("X-Rate-Limit-Reset".to_string()),
+            resource_header: None,
         };
         let d = retry_after_from_headers(&headers, None, Some(&mapping)).unwrap();
         assert!(d.as_secs() >= 28 && d.as_secs() <= 32);
@@ -598,10 +858,172 @@ mod tests {
             limit_header: Some("RateLimit-Limit".to_string()),
             remaining_header: Some("RateLimit-Remaining".to_string()),
             reset_header: Some("RateLimit-Reset".to_string()),
+            resource_header: None,
         };
         let info = rate_limit_info_from_headers(&headers, Some(&mapping));
         assert_eq!(info.limit, Some(50));
         assert_eq!(info.remaining, Some(0));
         assert_eq!(info.reset_ts, Some(1700000100));
     }
+
+    #[tokio::test]
+    async fn test_retry_on_body_match_retries_200_error_body() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"error":"rate_limited"}"#))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-Page", "2")
+                    .set_body_string(r#"[{"id":"1"}]"#),
+            )
+            .mount(&server)
+            .await;
+        let source: SourceConfig = serde_yaml_ng::from_str(&format!(
+            r#"
+url: "{}/logs"
+resilience:
+  retries:
+    max_attempts: 3
+    initial_backoff_secs: 0
+    retry_on_body_match:
+      path: error
+      equals: rate_limited
+"#,
+            server.uri()
+        ))
+        .unwrap();
+        let retry = source.resilience.as_ref().unwrap().retries.as_ref();
+        let url = format!("{}/logs", server.uri());
+        let client = Client::new();
+        let response = execute_with_retry(
+            &client, &source, "s1", &url, None, retry, None, None, None, None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(response.url().path(), "/logs");
+        assert_eq!(response.headers().get("X-Page").unwrap(), "2");
+        assert_eq!(response.text().await.unwrap(), r#"[{"id":"1"}]"#);
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+
+        let rule = RetryBodyMatchConfig {
+            path: None,
+            equals: None,
+            pattern: Some("(?i)too many requests".to_string()),
+        };
+        assert!(body_matches_retry_rule(
+            "rule",
+            b"Too Many Requests, slow down",
+            &rule
+        ));
+        assert!(!body_matches_retry_rule("rule", br#"[{"id":"1"}]"#, &rule));
+        let changed = RetryBodyMatchConfig {
+            pattern: Some("slow down".to_string()),
+            ..rule
+        };
+        assert!(body_matches_retry_rule(
+            "rule",
+            b"please slow down",
+            &changed
+        ));
+        assert!(!body_matches_retry_rule(
+            "rule",
+            b"Too Many Requests",
+            &changed
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_retry_on_body_match_respects_max_response_bytes() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("x".repeat(64)))
+            .mount(&server)
+            .await;
+        let source: SourceConfig = serde_yaml_ng::from_str(&format!(
+            r#"
+url: "{}/logs"
+max_response_bytes: 16
+resilience:
+  retries:
+    max_attempts: 2
+    initial_backoff_secs: 0
+    retry_on_body_match:
+      pattern: "error"
+"#,
+            server.uri()
+        ))
+        .unwrap();
+        let retry = source.resilience.as_ref().unwrap().retries.as_ref();
+        let url = format!("{}/logs", server.uri());
+        let err = execute_with_retry(
+            &Client::new(),
+            &source,
+            "s1",
+            &url,
+            None,
+            retry,
+            None,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("max_response_bytes"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_retry_on_timeout_false_does_not_retry_timed_out_request() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("[]")
+                    .set_delay(Duration::from_millis(500)),
+            )
+            .mount(&server)
+            .await;
+        let source: SourceConfig = serde_yaml_ng::from_str(&format!(
+            r#"
+url: "{}/logs"
+resilience:
+  retries:
+    max_attempts: 3
+    initial_backoff_secs: 0
+    retry_on:
+      timeout: false
+"#,
+            server.uri()
+        ))
+        .unwrap();
+        let retry = source.resilience.as_ref().unwrap().retries.as_ref();
+        assert!(retry.unwrap().retry_on.connect && retry.unwrap().retry_on.status);
+        let url = format!("{}/logs", server.uri());
+        let client = Client::builder()
+            .timeout(Duration::from_millis(100))
+            .build()
+            .unwrap();
+        let err = execute_with_retry(
+            &client, &source, "s1", &url, None, retry, None, None, None, None,
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            format!("{:#}", err).contains("timed out"),
+            "expected timeout, got {:#}",
+            err
+        );
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
 }
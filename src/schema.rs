@@ -0,0 +1,128 @@
+//! JSON Schema validation of emitted events (source `schema_file`).
+//! Requires the `schema` Cargo feature (jsonschema); without it, sources with `schema_file` are rejected at load.
+
+/// Check that the schema file reads, parses and compiles (config validation). Recompiles the file and replaces the
+/// cached validator, so a config reload picks up an edited schema.
+#[cfg(feature = "schema")]
+pub fn check(path: &str) -> anyhow::Result<()> {
+    let compiled = std::sync::Arc::new(compile(path)?);
+    VALIDATORS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(path.to_string(), compiled);
+    Ok(())
+}
+
+#[cfg(not(feature = "schema"))]
+pub fn check(_path: &str) -> anyhow::Result<()> {
+    anyhow::bail!("schema_file requires building with --features schema")
+}
+
+/// Validate `event` against the schema at `path`. The error lists each violation with its instance path.
+#[cfg(feature = "schema")]
+pub fn validate(path: &str, event: &serde_json::Value) -> anyhow::Result<()> {
+    let validator = validator(path)?;
+    let errors: Vec<String> = validator
+        .iter_errors(event)
+        .map(|e| format!("{}: {}", e.instance_path(), e))
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        anyhow::bail!("schema {:?}: {}", path, errors.join("; "))
+    }
+}
+
+#[cfg(not(feature = "schema"))]
+pub fn validate(_path: &str, _event: &serde_json::Value) -> anyhow::Result<()> {
+    anyhow::bail!("schema_file requires building with --features schema")
+}
+
+/// Compiled validators by schema path; refreshed by [`check`] on each config load.
+#[cfg(feature = "schema")]
+static VALIDATORS: std::sync::LazyLock<
+    std::sync::Mutex<std::collections::HashMap<String, std::sync::Arc<jsonschema::Validator>>>,
+> = std::sync::LazyLock::new(Default::default);
+
+#[cfg(feature = "schema")]
+fn validator(path: &str) -> anyhow::Result<std::sync::Arc<jsonschema::Validator>> {
+    if let Some(v) = VALIDATORS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(path)
+    {
+        return Ok(v.clone());
+    }
+    let compiled = std::sync::Arc::new(compile(path)?);
+    VALIDATORS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(path.to_string(), compiled.clone());
+    Ok(compiled)
+}
+
+#[cfg(feature = "schema")]
+fn compile(path: &str) -> anyhow::Result<jsonschema::Validator> {
+    use anyhow::Context;
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("read schema_file {:?}", path))?;
+    let schema: serde_json::Value =
+        serde_json::from_str(&text).with_context(|| format!("parse schema_file {:?}", path))?;
+    jsonschema::validator_for(&schema)
+        .map_err(|e| anyhow::anyhow!("compile schema_file {:?}: {}", path, e))
+}
+
+#[cfg(all(test, feature = "schema"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn schema_rejects_event_missing_required_field() {
+        let path = std::env::temp_dir().join("helr_schema_required_test.json");
+        std::fs::write(
+            &path,
+            json!({
+                "type": "object",
+                "required": ["id", "actor"],
+                "properties": {
+                    "id": {"type": "string"},
+                    "actor": {"type": "object", "required": ["id"]}
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+        check(path).unwrap();
+        validate(path, &json!({"id": "e1", "actor": {"id": "u1"}})).unwrap();
+        let err = validate(path, &json!({"id": "e2"}))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("\"actor\" is a required property"), "{}", err);
+        let err = validate(path, &json!({"id": "e3", "actor": {}}))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("/actor"), "{}", err);
+    }
+
+    #[test]
+    fn schema_check_picks_up_edited_schema() {
+        let path = std::env::temp_dir().join("helr_schema_reload_test.json");
+        std::fs::write(&path, json!({"required": ["id"]}).to_string()).unwrap();
+        let path_str = path.to_str().unwrap();
+        check(path_str).unwrap();
+        validate(path_str, &json!({"id": "e1"})).unwrap();
+        std::fs::write(&path, json!({"required": ["id", "actor"]}).to_string()).unwrap();
+        check(path_str).unwrap();
+        assert!(validate(path_str, &json!({"id": "e1"})).is_err());
+    }
+
+    #[test]
+    fn schema_check_fails_on_invalid_schema() {
+        let path = std::env::temp_dir().join("helr_schema_invalid_test.json");
+        std::fs::write(&path, r#"{"type": 12}"#).unwrap();
+        assert!(check(path.to_str().unwrap()).is_err());
+        assert!(check("/nonexistent/helr_schema.json").is_err());
+    }
+}
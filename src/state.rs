@@ -28,6 +28,11 @@ pub trait StateStore: Send + Sync {
 
     /// Remove all state for a source (e.g. for reset).
     async fn clear_source(&self, source_id: &str) -> anyhow::Result<()>;
+
+    /// Compact the backing storage. Returns (bytes before, bytes after), or None when the backend has nothing to compact.
+    async fn vacuum(&self) -> anyhow::Result<Option<(u64, u64)>> {
+        Ok(None)
+    }
 }
 
 /// In-memory state store for tests and default when no path is configured.
@@ -199,6 +204,27 @@ impl StateStore for SqliteStateStore {
         .await
         .map_err(|e| anyhow::anyhow!("spawn_blocking: {}", e))?
     }
+
+    /// VACUUM then PRAGMA optimize; size is page_count * page_size.
+    async fn vacuum(&self) -> anyhow::Result<Option<(u64, u64)>> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let c = conn
+                .lock()
+                .map_err(|_| anyhow::anyhow!("state store lock poisoned"))?;
+            let db_size = |c: &rusqlite::Connection| -> rusqlite::Result<u64> {
+                let pages: i64 = c.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+                let page_size: i64 = c.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+                Ok((pages * page_size) as u64)
+            };
+            let before = db_size(&c)?;
+            c.execute_batch("VACUUM; PRAGMA optimize;")?;
+            let after = db_size(&c)?;
+            Ok(Some((before, after)))
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("spawn_blocking: {}", e))?
+    }
 }
 
 /// Redis key prefix for state hashes: one hash per source, key = "helr:state:{source_id}".
@@ -437,4 +463,22 @@ mod tests {
         assert!(store.get("s1", "cursor").await.unwrap().is_none());
         let _ = std::fs::remove_file(&dir);
     }
+
+    #[tokio::test]
+    async fn sqlite_state_store_vacuum_reclaims_space() {
+        let path = std::env::temp_dir().join("helr_state_vacuum_test.db");
+        let _ = std::fs::remove_file(&path);
+        let store = SqliteStateStore::open(&path).unwrap();
+        let big = "x".repeat(4096);
+        for i in 0..64 {
+            store.set("s1", &format!("k{}", i), &big).await.unwrap();
+        }
+        store.clear_source("s1").await.unwrap();
+        store.set("s2", "cursor", "abc").await.unwrap();
+        let (before, after) = store.vacuum().await.unwrap().unwrap();
+        assert!(after < before, "before={} after={}", before, after);
+        assert_eq!(store.get("s2", "cursor").await.unwrap(), Some("abc".into()));
+        assert!(MemoryStateStore::new().vacuum().await.unwrap().is_none());
+        let _ = std::fs::remove_file(&path);
+    }
 }
@@ -735,6 +735,127 @@ sources:
     assert_eq!(obj["event"]["msg"], "replayed");
 }
 
+/// `helr bench` loops a tiny recording set into a null sink and reports non-zero throughput.
+#[test]
+fn integration_bench_reports_throughput() {
+    let config_dir = std::env::temp_dir().join("hel_integration_bench");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let replay_dir = config_dir.join("recordings");
+    let _ = std::fs::remove_dir_all(&replay_dir);
+    let source_dir = replay_dir.join("bench-source");
+    std::fs::create_dir_all(&source_dir).expect("create recording dir");
+    let body = json!([{"id": "b1"}, {"id": "b2"}]);
+    let rec = json!({
+        "url": "http://replay/replay/bench-source",
+        "status": 200,
+        "headers": {"Content-Type": "application/json"},
+        "body_base64": base64::engine::general_purpose::STANDARD.encode(serde_json::to_vec(&body).unwrap())
+    });
+    std::fs::write(source_dir.join("000.json"), rec.to_string()).expect("write recording");
+
+    let config_path = config_dir.join("helr.yaml");
+    std::fs::write(
+        &config_path,
+        r#"
+global:
+  log_level: error
+sources:
+  bench-source:
+    url: "http://placeholder/"
+"#,
+    )
+    .expect("write config");
+
+    let out = run_hel(
+        &[
+            "bench",
+            "--replay-dir",
+            replay_dir.to_str().unwrap(),
+            "--duration",
+            "1",
+        ],
+        config_path.to_str().unwrap(),
+    );
+    assert!(
+        out.status.success(),
+        "helr bench failed: stderr={}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let report: serde_json::Value =
+        serde_json::from_slice(&out.stdout).expect("bench report is JSON");
+    assert!(report["ticks"].as_u64().unwrap() > 1, "{}", report);
+    assert!(report["events"].as_u64().unwrap() > 2, "{}", report);
+    assert!(
+        report["events_per_sec"].as_f64().unwrap() > 0.0,
+        "{}",
+        report
+    );
+    assert!(
+        report["write_latency_p99_ns"].as_u64().unwrap()
+            >= report["write_latency_p50_ns"].as_u64().unwrap()
+    );
+}
+
+/// `--config -` reads YAML from stdin; env placeholders are still expanded.
+#[tokio::test]
+async fn integration_run_once_config_from_stdin() {
+    use std::io::Write;
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!([{"id": "s1", "msg": "from stdin"}])),
+        )
+        .mount(&server)
+        .await;
+
+    let yaml = r#"
+global:
+  log_level: error
+  state:
+    backend: memory
+sources:
+  stdin-source:
+    url: "${HELR_TEST_STDIN_URL}/"
+    resilience:
+      timeout_secs: 5
+"#;
+    let mut child = std::process::Command::new(hel_bin())
+        .args(["run", "--config", "-", "--once"])
+        .env("RUST_LOG", "error")
+        .env("HELR_LOG_LEVEL", "error")
+        .env("HELR_TEST_STDIN_URL", server.uri())
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("spawn helr");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(yaml.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().expect("wait helr");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        output.status.success(),
+        "helr run --config - failed: stdout={} stderr={}",
+        stdout,
+        stderr
+    );
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(|l| serde_json::from_str(l).expect("ndjson line"))
+        .collect();
+    assert_eq!(lines.len(), 1, "stdout={}", stdout);
+    assert_eq!(lines[0]["source"], "stdin-source");
+    assert_eq!(lines[0]["event"]["msg"], "from stdin");
+}
+
 fn hel_bin() -> String {
     std::env::var("CARGO_BIN_EXE_helr").unwrap_or_else(|_| {
         format!(
@@ -905,6 +1026,65 @@ sources:
     );
 }
 
+/// Circuit breaker with emit_circuit_events: opening the circuit writes a circuit envelope to stdout.
+#[tokio::test]
+async fn integration_circuit_open_emits_circuit_event() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_circuit_events");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  state:
+    backend: memory
+sources:
+  circuit-events-source:
+    url: "{}/"
+    resilience:
+      timeout_secs: 5
+      retries:
+        max_attempts: 1
+      circuit_breaker:
+        enabled: true
+        failure_threshold: 1
+        success_threshold: 1
+        half_open_timeout_secs: 60
+        emit_circuit_events: true
+"#,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let output = run_hel(&["run", "--once"], config_path.to_str().unwrap());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let circuit_lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+        .filter(|v| v["meta"]["circuit_state"].is_string())
+        .collect();
+    assert_eq!(
+        circuit_lines.len(),
+        1,
+        "expected one circuit envelope; stdout: {} stderr: {}",
+        stdout,
+        stderr
+    );
+    let obj = &circuit_lines[0];
+    assert_eq!(obj["source"], "circuit-events-source");
+    assert_eq!(obj["meta"]["circuit_state"], "open");
+    assert_eq!(obj["event"]["circuit_state"], "open");
+    assert_eq!(obj["event"]["previous"], "closed");
+}
+
 /// helr state set: set a single key, then show confirms it.
 #[tokio::test]
 async fn integration_state_set_then_show() {
@@ -983,118 +1163,891 @@ sources:
     );
 }
 
-/// Per-source state.watermark_field / watermark_param: after one poll tick, watermark is stored and state show displays it.
+/// state export <source>: only the named source appears in the exported JSON.
 #[tokio::test]
-async fn integration_watermark_state_stored_after_poll() {
-    let server = MockServer::start().await;
+async fn integration_state_export_single_source() {
+    let config_dir = std::env::temp_dir().join("hel_integration_state_export_source");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let state_path = config_dir.join("helr-state.db");
+    let _ = std::fs::remove_file(&state_path);
 
-    Mock::given(method("GET"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-            "items": [
-                {"id": {"time": "1000"}, "msg": "a"},
-                {"id": {"time": "2000"}, "msg": "b"}
-            ],
-            "nextPageToken": "p1"
-        })))
-        .up_to_n_times(1)
-        .mount(&server)
-        .await;
+    std::fs::write(
+        &config_path,
+        format!(
+            r#"
+global:
+  log_level: error
+  state:
+    backend: sqlite
+    path: "{}"
+sources:
+  source-a:
+    url: "https://example.com/a"
+  source-b:
+    url: "https://example.com/b"
+"#,
+            state_path.display()
+        ),
+    )
+    .expect("write config");
 
-    Mock::given(method("GET"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
-            "items": [{"id": {"time": "3000"}, "msg": "c"}]
-        })))
-        .mount(&server)
-        .await;
+    for (source, value) in [("source-a", "cursor-a"), ("source-b", "cursor-b")] {
+        let out_set = run_hel(
+            &["state", "set", source, "cursor", value],
+            config_path.to_str().unwrap(),
+        );
+        assert!(
+            out_set.status.success(),
+            "helr state set failed: stderr={}",
+            String::from_utf8_lossy(&out_set.stderr)
+        );
+    }
 
-    let config_dir = std::env::temp_dir().join("hel_integration_watermark");
+    let out_export = run_hel(
+        &["state", "export", "source-a"],
+        config_path.to_str().unwrap(),
+    );
+    assert!(
+        out_export.status.success(),
+        "helr state export failed: stderr={}",
+        String::from_utf8_lossy(&out_export.stderr)
+    );
+    let exported: serde_json::Value =
+        serde_json::from_slice(&out_export.stdout).expect("export is JSON");
+    assert_eq!(exported, json!({"source-a": {"cursor": "cursor-a"}}));
+}
+
+/// state vacuum on a sqlite store succeeds and reports the reclaimed size.
+#[test]
+fn integration_state_vacuum_sqlite() {
+    let config_dir = std::env::temp_dir().join("hel_integration_state_vacuum");
     let _ = std::fs::create_dir_all(&config_dir);
     let config_path = config_dir.join("helr.yaml");
-    let state_path = config_dir.join("helr-watermark-state.db");
+    let state_path = config_dir.join("helr-state.db");
     let _ = std::fs::remove_file(&state_path);
 
-    let yaml = format!(
-        r#"
+    std::fs::write(
+        &config_path,
+        format!(
+            r#"
 global:
   log_level: error
   state:
     backend: sqlite
     path: "{}"
 sources:
-  watermark-source:
-    url: "{}/"
-    pagination:
-      strategy: cursor
-      cursor_param: pageToken
-      cursor_path: nextPageToken
-    state:
-      watermark_field: "id.time"
-      watermark_param: "startTime"
-    resilience:
-      timeout_secs: 5
+  source-a:
+    url: "https://example.com/a"
 "#,
-        state_path.to_str().unwrap().replace('\\', "/"),
-        server.uri()
-    );
-    std::fs::write(&config_path, yaml).expect("write config");
+            state_path.display()
+        ),
+    )
+    .expect("write config");
 
-    let out_run = std::process::Command::new(hel_bin())
-        .args(["run", "--config", config_path.to_str().unwrap(), "--once"])
-        .env("RUST_LOG", "error")
-        .current_dir(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into()))
-        .output()
-        .expect("run helr");
+    let out_set = run_hel(
+        &["state", "set", "source-a", "cursor", "c1"],
+        config_path.to_str().unwrap(),
+    );
+    assert!(out_set.status.success());
 
+    let out = run_hel(&["state", "vacuum"], config_path.to_str().unwrap());
     assert!(
-        out_run.status.success(),
-        "helr run --once failed: stderr={}",
-        String::from_utf8_lossy(&out_run.stderr)
+        out.status.success(),
+        "helr state vacuum failed: stderr={}",
+        String::from_utf8_lossy(&out.stderr)
     );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("reclaimed"), "stdout: {}", stdout);
+}
 
-    let out_show = std::process::Command::new(hel_bin())
-        .args([
-            "state",
-            "--config",
-            config_path.to_str().unwrap(),
-            "show",
-            "watermark-source",
-        ])
-        .env("RUST_LOG", "error")
-        .current_dir(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into()))
-        .output()
-        .expect("run helr state show");
+/// `helr sources list` prints one row per configured source with redacted URL, pagination, interval and auth.
+#[test]
+fn integration_sources_list_prints_all_sources() {
+    let config_dir = std::env::temp_dir().join("hel_integration_sources_list");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
 
-    assert!(
-        out_show.status.success(),
-        "helr state show failed: stderr={}",
-        String::from_utf8_lossy(&out_show.stderr)
-    );
+    std::fs::write(
+        &config_path,
+        r#"
+global:
+  log_level: error
+sources:
+  okta-audit:
+    url: "https://example.okta.com/api/v1/logs?limit=100"
+    schedule:
+      interval_secs: 30
+    pagination:
+      strategy: link_header
+  vendor-feed:
+    url: "https://example.com/events?api_token=s3cr3t"
+"#,
+    )
+    .expect("write config");
 
-    let stdout = String::from_utf8_lossy(&out_show.stdout);
+    let out = run_hel(&["sources", "list"], config_path.to_str().unwrap());
     assert!(
-        stdout.contains("watermark") && stdout.contains("3000"),
-        "expected watermark=3000 (max of id.time) in state show output, got: {}",
-        stdout
+        out.status.success(),
+        "helr sources list failed: stderr={}",
+        String::from_utf8_lossy(&out.stderr)
     );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 3, "header + 2 sources: {}", stdout);
+    assert!(lines[0].starts_with("NAME"));
+    assert!(lines[1].starts_with("okta-audit"));
+    assert!(lines[1].contains("link_header"));
+    assert!(lines[1].contains("30s"));
+    assert!(lines[2].starts_with("vendor-feed"));
+    assert!(lines[2].contains("api_token=REDACTED"));
+    assert!(!stdout.contains("s3cr3t"));
 }
 
-/// State backend Redis: run one tick with redis state, then state show. Skips when REDIS_URL is not set.
+/// validate --strict: one request per source (no retries); reports each and exits non-zero when any is unreachable or not a success.
 #[tokio::test]
-async fn integration_state_backend_redis() {
-    let redis_url = match std::env::var("REDIS_URL") {
-        Ok(u) if !u.is_empty() => u,
-        _ => return, // skip when REDIS_URL not set (e.g. in CI without Redis)
-    };
+async fn integration_validate_strict_reports_unreachable_source() {
+    use wiremock::matchers::{header, path};
 
     let server = MockServer::start().await;
     Mock::given(method("GET"))
-        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
-            {"id": "r1", "published": "2024-01-15T12:00:00Z"}
-        ])))
+        .and(header("authorization", "Bearer strict-token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": "1"}])))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/broken"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(1)
         .mount(&server)
         .await;
 
-    let config_dir = std::env::temp_dir().join("hel_integration_redis");
+    let config_dir = std::env::temp_dir().join("hel_integration_validate_strict");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+sources:
+  good:
+    url: "{}/"
+    auth:
+      type: bearer
+      token_env: HELR_VALIDATE_STRICT_TOKEN
+  bad:
+    url: "http://127.0.0.1:1/"
+  broken:
+    url: "{}/broken"
+    resilience:
+      retries:
+        max_attempts: 3
+        initial_backoff_secs: 0
+"#,
+        server.uri(),
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let out = std::process::Command::new(hel_bin())
+        .args([
+            "validate",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--strict",
+        ])
+        .env("HELR_LOG_LEVEL", "error")
+        .env("HELR_VALIDATE_STRICT_TOKEN", "strict-token")
+        .output()
+        .expect("run helr");
+    assert!(
+        !out.status.success(),
+        "unreachable source must fail strict validation"
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("good: ok (http 200)"), "stdout: {}", stdout);
+    assert!(stdout.contains("bad: unreachable"), "stdout: {}", stdout);
+    assert!(
+        stdout.contains("broken: failed (http 500)"),
+        "stdout: {}",
+        stdout
+    );
+    assert!(!stdout.contains("\"id\""), "no events emitted: {}", stdout);
+}
+
+/// global.emit_tick_summary: one summary envelope (meta.summary) per source poll after the events.
+#[tokio::test]
+async fn integration_emit_tick_summary_single_page() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {"id": "1"},
+            {"id": "2"}
+        ])))
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_tick_summary");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  emit_tick_summary: true
+  state:
+    backend: memory
+sources:
+  summary-source:
+    url: "{}/events"
+    resilience:
+      timeout_secs: 5
+"#,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let output = run_hel(&["run", "--once"], config_path.to_str().unwrap());
+    assert!(
+        output.status.success(),
+        "helr run --once failed: stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let lines: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("NDJSON line"))
+        .collect();
+    assert_eq!(lines.len(), 3, "2 events + 1 summary: {:?}", lines);
+    let summaries: Vec<&serde_json::Value> = lines
+        .iter()
+        .filter(|v| v["meta"]["summary"] == json!(true))
+        .collect();
+    assert_eq!(summaries.len(), 1);
+    let summary = summaries[0];
+    assert_eq!(summary["source"], "summary-source");
+    assert_eq!(summary["event"]["pages"], 1);
+    assert_eq!(summary["event"]["events"], 2);
+    assert!(summary["event"]["bytes"].as_u64().unwrap() > 0);
+    assert!(summary["event"]["duration_ms"].is_u64());
+}
+
+/// subrequest: each listed incident ID is fetched from the detail endpoint and merged into the event.
+#[tokio::test]
+async fn integration_subrequest_enriches_events() {
+    use wiremock::matchers::path;
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/incidents"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "incidents": [{"id": "P1"}, {"id": "P2"}]
+        })))
+        .mount(&server)
+        .await;
+    for (id, title) in [("P1", "disk full"), ("P2", "api down")] {
+        Mock::given(method("GET"))
+            .and(path(format!("/incidents/{}", id)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "incident": {"id": id, "title": title}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+
+    let config_dir = std::env::temp_dir().join("hel_integration_subrequest");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  state:
+    backend: memory
+sources:
+  pagerduty:
+    url: "{uri}/incidents"
+    response_events_path: incidents
+    subrequest:
+      url: "{uri}/incidents/{{id}}"
+      response_path: incident
+      merge_key: detail
+    resilience:
+      timeout_secs: 5
+"#,
+        uri = server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let output = run_hel(&["run", "--once"], config_path.to_str().unwrap());
+    assert!(
+        output.status.success(),
+        "helr run --once failed: stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let lines: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("NDJSON line"))
+        .collect();
+    assert_eq!(lines.len(), 2, "{:?}", lines);
+    assert_eq!(lines[0]["event"]["id"], "P1");
+    assert_eq!(lines[0]["event"]["detail"]["title"], "disk full");
+    assert_eq!(lines[1]["event"]["id"], "P2");
+    assert_eq!(lines[1]["event"]["detail"]["title"], "api down");
+}
+
+/// global.include_instance_id: every envelope carries meta.instance_id (configured) and meta.collector_host.
+#[tokio::test]
+async fn integration_include_instance_id_in_meta() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": "1"}, {"id": "2"}])))
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_instance_id");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  instance_id: replica-7
+  include_instance_id: true
+  state:
+    backend: memory
+sources:
+  replica-source:
+    url: "{}/events"
+    resilience:
+      timeout_secs: 5
+"#,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let output = run_hel(&["run", "--once"], config_path.to_str().unwrap());
+    assert!(
+        output.status.success(),
+        "helr run --once failed: stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let lines: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("NDJSON line"))
+        .collect();
+    assert_eq!(lines.len(), 2);
+    for line in &lines {
+        assert_eq!(line["meta"]["instance_id"], "replica-7");
+        assert!(
+            line["meta"]["collector_host"]
+                .as_str()
+                .is_some_and(|h| !h.is_empty())
+        );
+    }
+}
+
+/// global.idle_shutdown_secs: with an always-empty source, `helr run` exits cleanly on its own.
+#[tokio::test]
+async fn integration_idle_shutdown_exits_when_no_events() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_idle_shutdown");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  idle_shutdown_secs: 2
+  state:
+    backend: memory
+sources:
+  empty-source:
+    url: "{}/events"
+    schedule:
+      interval_secs: 1
+    resilience:
+      timeout_secs: 5
+"#,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let mut child = std::process::Command::new(hel_bin())
+        .args(["run", "--config", config_path.to_str().unwrap()])
+        .env("HELR_LOG_LEVEL", "error")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .expect("spawn helr");
+
+    let wait_timeout = Duration::from_secs(20);
+    let start = std::time::Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(s)) => break s,
+            Ok(None) => {
+                if start.elapsed() > wait_timeout {
+                    let _ = child.kill();
+                    panic!("helr did not exit on idle within {:?}", wait_timeout);
+                }
+            }
+            Err(e) => panic!("try_wait failed: {}", e),
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    };
+    assert!(
+        status.success(),
+        "idle shutdown should exit 0: {:?}",
+        status
+    );
+    assert!(
+        start.elapsed() >= Duration::from_secs(2),
+        "must not exit before the idle threshold"
+    );
+    let polls = server.received_requests().await.unwrap_or_default().len();
+    assert!(
+        polls >= 2,
+        "source kept polling until idle: {} polls",
+        polls
+    );
+}
+
+/// test --golden: exit 0 when output matches the golden (ts ignored); non-zero with a unified diff otherwise.
+#[tokio::test]
+async fn integration_test_golden_match_and_mismatch() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {"id": "a", "action": "login"},
+            {"id": "b", "action": "logout"}
+        ])))
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_test_golden");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  state:
+    backend: memory
+sources:
+  golden-src:
+    url: "{}/events"
+    resilience:
+      timeout_secs: 5
+"#,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let good = config_dir.join("good.ndjson");
+    std::fs::write(
+        &good,
+        concat!(
+            r#"{"ts":"2000-01-01T00:00:00Z","source":"golden-src","endpoint":"/events","event":{"id":"a","action":"login"},"meta":{}}"#,
+            "\n",
+            r#"{"ts":"2000-01-01T00:00:00Z","source":"golden-src","endpoint":"/events","event":{"id":"b","action":"logout"},"meta":{}}"#,
+            "\n"
+        ),
+    )
+    .expect("write golden");
+    let out = run_hel(
+        &[
+            "test",
+            "--source",
+            "golden-src",
+            "--golden",
+            good.to_str().unwrap(),
+        ],
+        config_path.to_str().unwrap(),
+    );
+    assert!(
+        out.status.success(),
+        "golden should match: stdout={} stderr={}",
+        String::from_utf8_lossy(&out.stdout),
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(out.stdout.is_empty(), "no NDJSON printed with --golden");
+
+    let bad = config_dir.join("bad.ndjson");
+    std::fs::write(
+        &bad,
+        concat!(
+            r#"{"ts":"2000-01-01T00:00:00Z","source":"golden-src","endpoint":"/events","event":{"id":"a","action":"login"},"meta":{}}"#,
+            "\n",
+            r#"{"ts":"2000-01-01T00:00:00Z","source":"golden-src","endpoint":"/events","event":{"id":"b","action":"delete"},"meta":{}}"#,
+            "\n"
+        ),
+    )
+    .expect("write golden");
+    let out = run_hel(
+        &[
+            "test",
+            "--source",
+            "golden-src",
+            "--golden",
+            bad.to_str().unwrap(),
+        ],
+        config_path.to_str().unwrap(),
+    );
+    assert!(!out.status.success(), "golden mismatch must fail");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("--- golden"), "unified diff: {}", stdout);
+    assert!(stdout.contains(r#"-{"endpoint":"/events","event":{"action":"delete","id":"b"}"#));
+    assert!(stdout.contains(r#"+{"endpoint":"/events","event":{"action":"logout","id":"b"}"#));
+}
+
+/// body_content_type: form sends the POST body as application/x-www-form-urlencoded.
+#[tokio::test]
+async fn integration_post_form_encoded_body() {
+    use wiremock::matchers::{body_string, header};
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(header("content-type", "application/x-www-form-urlencoded"))
+        .and(body_string(
+            "filter=type+eq+%22login%22&limit=50&scope=a&scope=b",
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {"id": "1", "msg": "form ok"}
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_post_form");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  state:
+    backend: memory
+sources:
+  form-source:
+    url: "{}/events"
+    method: post
+    body_content_type: form
+    body:
+      filter: 'type eq "login"'
+      limit: 50
+      scope: ["a", "b"]
+    resilience:
+      timeout_secs: 5
+"#,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let output = run_hel(&["run", "--once"], config_path.to_str().unwrap());
+    assert!(
+        output.status.success(),
+        "helr run --once failed: stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("form ok"), "stdout: {}", stdout);
+}
+
+/// Per-source state.watermark_field / watermark_param: after one poll tick, watermark is stored and state show displays it.
+#[tokio::test]
+async fn integration_watermark_state_stored_after_poll() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "items": [
+                {"id": {"time": "1000"}, "msg": "a"},
+                {"id": {"time": "2000"}, "msg": "b"}
+            ],
+            "nextPageToken": "p1"
+        })))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "items": [{"id": {"time": "3000"}, "msg": "c"}]
+        })))
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_watermark");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let state_path = config_dir.join("helr-watermark-state.db");
+    let _ = std::fs::remove_file(&state_path);
+
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  state:
+    backend: sqlite
+    path: "{}"
+sources:
+  watermark-source:
+    url: "{}/"
+    pagination:
+      strategy: cursor
+      cursor_param: pageToken
+      cursor_path: nextPageToken
+    state:
+      watermark_field: "id.time"
+      watermark_param: "startTime"
+    resilience:
+      timeout_secs: 5
+"#,
+        state_path.to_str().unwrap().replace('\\', "/"),
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let out_run = std::process::Command::new(hel_bin())
+        .args(["run", "--config", config_path.to_str().unwrap(), "--once"])
+        .env("RUST_LOG", "error")
+        .current_dir(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into()))
+        .output()
+        .expect("run helr");
+
+    assert!(
+        out_run.status.success(),
+        "helr run --once failed: stderr={}",
+        String::from_utf8_lossy(&out_run.stderr)
+    );
+
+    let out_show = std::process::Command::new(hel_bin())
+        .args([
+            "state",
+            "--config",
+            config_path.to_str().unwrap(),
+            "show",
+            "watermark-source",
+        ])
+        .env("RUST_LOG", "error")
+        .current_dir(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into()))
+        .output()
+        .expect("run helr state show");
+
+    assert!(
+        out_show.status.success(),
+        "helr state show failed: stderr={}",
+        String::from_utf8_lossy(&out_show.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&out_show.stdout);
+    assert!(
+        stdout.contains("watermark") && stdout.contains("3000"),
+        "expected watermark=3000 (max of id.time) in state show output, got: {}",
+        stdout
+    );
+}
+
+/// start_mode latest: with no saved watermark, first request carries a near-now watermark param.
+#[tokio::test]
+async fn integration_start_mode_latest_seeds_watermark_to_now() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"items": []})))
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_start_mode_latest");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  state:
+    backend: memory
+sources:
+  latest-source:
+    url: "{}/"
+    start_mode: latest
+    pagination:
+      strategy: link_header
+      rel: next
+    state:
+      watermark_field: "published"
+      watermark_param: "since"
+    resilience:
+      timeout_secs: 5
+"#,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let before = chrono::Utc::now();
+    let output = run_hel(&["run", "--once"], config_path.to_str().unwrap());
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    let since = requests[0]
+        .url
+        .query_pairs()
+        .find(|(k, _)| k == "since")
+        .map(|(_, v)| v.into_owned())
+        .expect("first request should carry since param");
+    let since = chrono::DateTime::parse_from_rfc3339(&since).expect("since is RFC3339");
+    let delta = (since.with_timezone(&chrono::Utc) - before)
+        .num_seconds()
+        .abs();
+    assert!(delta <= 60, "since {} should be near now", since);
+}
+
+/// Audit log_http_requests: one poll emits an audit record with redacted URL and status.
+#[tokio::test]
+async fn integration_audit_logs_http_request() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": "1"}])))
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_audit_http");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: info
+  log_format: json
+  state:
+    backend: memory
+  audit:
+    enabled: true
+    log_http_requests: true
+sources:
+  audited-source:
+    url: "{}/logs?api_key=supersecret&limit=10"
+    resilience:
+      timeout_secs: 5
+"#,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let output = std::process::Command::new(hel_bin())
+        .args(["run", "--config", config_path.to_str().unwrap(), "--once"])
+        .current_dir(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into()))
+        .output()
+        .expect("run helr");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+
+    let audit_line = stderr
+        .lines()
+        .find(|l| l.contains("audit: http request"))
+        .unwrap_or_else(|| panic!("expected audit http request line, stderr: {}", stderr));
+    let expected_url = format!("{}/logs?api_key=REDACTED&limit=10", server.uri());
+    assert!(
+        audit_line.contains(&expected_url),
+        "audit line should contain redacted URL {}: {}",
+        expected_url,
+        audit_line
+    );
+    assert!(audit_line.contains("200"), "audit line: {}", audit_line);
+    assert!(!stderr.contains("supersecret"), "secret leaked: {}", stderr);
+}
+
+/// schedule.startup_jitter_secs: first poll is delayed by the logged jitter before events are emitted.
+#[tokio::test]
+async fn integration_startup_jitter_delays_first_poll() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": "j1"}])))
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_startup_jitter");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: debug
+  log_format: json
+  state:
+    backend: memory
+sources:
+  jitter-source:
+    url: "{}/"
+    schedule:
+      interval_secs: 60
+      startup_jitter_secs: 1
+    resilience:
+      timeout_secs: 5
+"#,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let started = std::time::Instant::now();
+    let output = std::process::Command::new(hel_bin())
+        .args(["run", "--config", config_path.to_str().unwrap(), "--once"])
+        .current_dir(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into()))
+        .output()
+        .expect("run helr");
+    let elapsed = started.elapsed();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 1, "stdout: {}", stdout);
+
+    let delay_ms = stderr
+        .lines()
+        .filter(|l| l.contains("startup jitter: first poll scheduled"))
+        .find_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+        .and_then(|v| v.pointer("/fields/delay_ms").and_then(|d| d.as_u64()))
+        .unwrap_or_else(|| panic!("expected startup jitter log line, stderr: {}", stderr));
+    assert!(delay_ms <= 1000, "delay {}ms exceeds bound", delay_ms);
+    assert!(
+        elapsed >= Duration::from_millis(delay_ms),
+        "run finished in {:?}, before startup jitter {}ms",
+        elapsed,
+        delay_ms
+    );
+}
+
+/// State backend Redis: run one tick with redis state, then state show. Skips when REDIS_URL is not set.
+#[tokio::test]
+async fn integration_state_backend_redis() {
+    let redis_url = match std::env::var("REDIS_URL") {
+        Ok(u) if !u.is_empty() => u,
+        _ => return, // skip when REDIS_URL not set (e.g. in CI without Redis)
+    };
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {"id": "r1", "published": "2024-01-15T12:00:00Z"}
+        ])))
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_redis");
     let _ = std::fs::create_dir_all(&config_dir);
     let config_path = config_dir.join("helr.yaml");
     let yaml = format!(
@@ -1243,6 +2196,193 @@ sources:
 "#);
 }
 
+/// Cursor pagination with has_more_path: a page with a non-empty cursor but has_more false stops pagination.
+#[tokio::test]
+async fn integration_cursor_pagination_has_more_false_stops() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "items": [{"id": "h1", "msg": "page1"}],
+            "cursor": "token2",
+            "has_more": true
+        })))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "items": [{"id": "h2", "msg": "page2"}],
+            "cursor": "token3",
+            "has_more": false
+        })))
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_cursor_has_more");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  state:
+    backend: memory
+sources:
+  has-more-source:
+    url: "{}/"
+    pagination:
+      strategy: cursor
+      cursor_param: cursor
+      cursor_path: cursor
+      has_more_path: has_more
+      max_pages: 10
+    resilience:
+      timeout_secs: 5
+"#,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let output = run_hel(&["run", "--once"], config_path.to_str().unwrap());
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 2, "stdout: {}", stdout);
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        requests.len(),
+        2,
+        "should stop after has_more false instead of following cursor"
+    );
+}
+
+/// Cursor pagination loop: page two returns the same cursor it was requested with; polling stops there
+/// instead of refetching the page until max_pages.
+#[tokio::test]
+async fn integration_cursor_pagination_repeated_cursor_stops() {
+    use wiremock::matchers::query_param;
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET"))
+        .and(query_param("after", "token2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "items": [{"id": "l2", "msg": "page2"}],
+            "next_cursor": "token2"
+        })))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "items": [{"id": "l1", "msg": "page1"}],
+            "next_cursor": "token2"
+        })))
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_cursor_loop");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  state:
+    backend: memory
+sources:
+  loop-source:
+    url: "{}/"
+    pagination:
+      strategy: cursor
+      cursor_param: after
+      cursor_path: next_cursor
+      max_pages: 10
+    resilience:
+      timeout_secs: 5
+"#,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let output = run_hel(&["run", "--once"], config_path.to_str().unwrap());
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 2, "stdout: {}", stdout);
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(
+        requests.len(),
+        2,
+        "should stop when the next cursor repeats instead of looping to max_pages"
+    );
+}
+
+/// initial_cursor_env: with empty state, the first cursor request carries the cursor from the env var.
+#[tokio::test]
+async fn integration_initial_cursor_from_env() {
+    use wiremock::matchers::query_param;
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(query_param("cursor", "dr-seed-42"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "items": [{"id": "s1"}],
+            "cursor": ""
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_initial_cursor_env");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  state:
+    backend: memory
+sources:
+  seeded-source:
+    url: "{}/"
+    initial_cursor_env: HELR_TEST_INITIAL_CURSOR
+    pagination:
+      strategy: cursor
+      cursor_param: cursor
+      cursor_path: cursor
+    resilience:
+      timeout_secs: 5
+"#,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let output = std::process::Command::new(hel_bin())
+        .args(["run", "--config", config_path.to_str().unwrap(), "--once"])
+        .env("HELR_LOG_LEVEL", "error")
+        .env("HELR_TEST_INITIAL_CURSOR", "dr-seed-42")
+        .output()
+        .expect("run helr");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].url.query(), Some("cursor=dr-seed-42"));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 1, "stdout: {}", stdout);
+}
+
 /// Page/offset pagination: two pages then empty.
 #[tokio::test]
 async fn integration_page_offset_pagination_two_pages() {
@@ -1361,20 +2501,24 @@ sources:
 }
 
 /// Link-header max_pages: stop after max_pages even if next link present.
-#[tokio::test]
-async fn integration_link_header_respects_max_pages() {
-    let server = MockServer::start().await;
-    let next_link = format!(r#"<{}/>; rel="next""#, server.uri());
-
-    Mock::given(method("GET"))
-        .respond_with(
-            ResponseTemplate::new(200)
-                .insert_header("Link", next_link.as_str())
-                .set_body_json(json!([{"id": "m1"}])),
-        )
-        .up_to_n_times(3)
-        .mount(&server)
-        .await;
+#[tokio::test]
+async fn integration_link_header_respects_max_pages() {
+    use wiremock::matchers::path;
+    let server = MockServer::start().await;
+
+    // Each page links to a distinct next page, so only max_pages stops pagination.
+    for (page, next) in [("/", "/p2"), ("/p2", "/p3"), ("/p3", "/p4")] {
+        let next_link = format!(r#"<{}{}>; rel="next""#, server.uri(), next);
+        Mock::given(method("GET"))
+            .and(path(page))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Link", next_link.as_str())
+                    .set_body_json(json!([{"id": "m1"}])),
+            )
+            .mount(&server)
+            .await;
+    }
 
     let config_dir = std::env::temp_dir().join("hel_integration_max_pages");
     let _ = std::fs::create_dir_all(&config_dir);
@@ -1733,19 +2877,306 @@ sources:
     }
 }
 
-/// Readyz with file output: returns 200, ready true, output_writable true.
+/// Readyz with file output: returns 200, ready true, output_writable true.
+#[tokio::test]
+async fn integration_health_readyz_file_output_200() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": "1"}])))
+        .mount(&server)
+        .await;
+
+    let health_port = 19285u16;
+    let config_dir = std::env::temp_dir().join("hel_integration_health_readyz");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let output_file = config_dir.join("out.ndjson");
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  state:
+    backend: memory
+  api:
+    enabled: true
+    address: "127.0.0.1"
+    port: {}
+sources:
+  readyz-source:
+    url: "{}/"
+    schedule:
+      interval_secs: 60
+    pagination:
+      strategy: link_header
+      rel: next
+"#,
+        health_port,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let mut child = std::process::Command::new(hel_bin())
+        .args([
+            "run",
+            "--config",
+            config_path.to_str().unwrap(),
+            "--output",
+            output_file.to_str().unwrap(),
+        ])
+        .env("RUST_LOG", "error")
+        .env("HELR_LOG_LEVEL", "error")
+        .current_dir(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into()))
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("spawn helr");
+
+    let base = format!("http://127.0.0.1:{}", health_port);
+    let client = reqwest::Client::new();
+    for _ in 0..30 {
+        std::thread::sleep(Duration::from_millis(100));
+        if client
+            .get(format!("{}/readyz", base))
+            .send()
+            .await
+            .map(|r| r.status().is_success())
+            .unwrap_or(false)
+        {
+            break;
+        }
+    }
+
+    let res = client
+        .get(format!("{}/readyz", base))
+        .send()
+        .await
+        .expect("get readyz");
+    let _ = child.kill();
+    let _ = child.wait();
+    assert!(
+        res.status().is_success(),
+        "readyz 200 when output file writable"
+    );
+    let body: serde_json::Value = res.json().await.expect("JSON");
+    assert_eq!(body["ready"], true);
+    assert_eq!(body["output_writable"], true);
+    assert_eq!(body["state_store_connected"], true);
+    assert_eq!(body["at_least_one_source_healthy"], true);
+}
+
+/// health.ready_requires_first_success: readyz is 503 until a source completes a successful poll, then 200.
+#[tokio::test]
+async fn integration_health_readyz_requires_first_success() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!([{"id": "1"}]))
+                .set_delay(Duration::from_secs(2)),
+        )
+        .mount(&server)
+        .await;
+
+    let health_port = 19289u16;
+    let config_dir = std::env::temp_dir().join("hel_integration_readyz_first_success");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  state:
+    backend: memory
+  api:
+    enabled: true
+    address: "127.0.0.1"
+    port: {}
+  health:
+    ready_requires_first_success: true
+sources:
+  warmup-source:
+    url: "{}/"
+    schedule:
+      interval_secs: 60
+    resilience:
+      timeout_secs: 10
+"#,
+        health_port,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let mut child = std::process::Command::new(hel_bin())
+        .args(["run", "--config", config_path.to_str().unwrap()])
+        .env("RUST_LOG", "error")
+        .env("HELR_LOG_LEVEL", "error")
+        .current_dir(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into()))
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("spawn helr");
+
+    let base = format!("http://127.0.0.1:{}", health_port);
+    let client = reqwest::Client::new();
+    let mut before = None;
+    for _ in 0..30 {
+        std::thread::sleep(Duration::from_millis(50));
+        if let Ok(r) = client.get(format!("{}/readyz", base)).send().await {
+            before = Some(r);
+            break;
+        }
+    }
+    let before = before.expect("readyz reachable");
+    let before_status = before.status();
+    let before_body: serde_json::Value = before.json().await.expect("readyz JSON");
+
+    let mut after_status = None;
+    for _ in 0..50 {
+        std::thread::sleep(Duration::from_millis(100));
+        let r = client
+            .get(format!("{}/readyz", base))
+            .send()
+            .await
+            .expect("get readyz");
+        if r.status().is_success() {
+            after_status = Some(r.status());
+            break;
+        }
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+
+    assert_eq!(
+        before_status,
+        reqwest::StatusCode::SERVICE_UNAVAILABLE,
+        "readyz before first success: {}",
+        before_body
+    );
+    assert_eq!(before_body["first_success"], json!(false));
+    assert_eq!(
+        after_status,
+        Some(reqwest::StatusCode::OK),
+        "readyz 200 after the first successful poll"
+    );
+}
+
+/// Graceful degradation: when SQLite state store fails to open and state_store_fallback is memory, health reports state_store_fallback_active.
+#[tokio::test]
+async fn integration_state_store_fallback_health_reports_fallback_active() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": "1"}])))
+        .mount(&server)
+        .await;
+
+    let health_port = 19286u16;
+    let config_dir = std::env::temp_dir().join("hel_integration_fallback");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    // SQLite path that will fail to open (parent dir does not exist), so we fall back to memory.
+    let state_path = config_dir.join("nonexistent_subdir").join("helr-state.db");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  state:
+    backend: sqlite
+    path: "{}"
+  degradation:
+    state_store_fallback: memory
+  api:
+    enabled: true
+    address: "127.0.0.1"
+    port: {}
+sources:
+  fallback-source:
+    url: "{}/"
+    schedule:
+      interval_secs: 60
+    pagination:
+      strategy: link_header
+      rel: next
+    resilience:
+      timeout_secs: 5
+"#,
+        state_path.to_str().unwrap().replace('\\', "/"),
+        health_port,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let mut child = std::process::Command::new(hel_bin())
+        .args(["run", "--config", config_path.to_str().unwrap()])
+        .env("RUST_LOG", "error")
+        .env("HELR_LOG_LEVEL", "error")
+        .current_dir(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into()))
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("spawn helr");
+
+    let base = format!("http://127.0.0.1:{}", health_port);
+    let client = reqwest::Client::new();
+    for _ in 0..30 {
+        std::thread::sleep(Duration::from_millis(100));
+        if client
+            .get(format!("{}/healthz", base))
+            .send()
+            .await
+            .map(|r| r.status().is_success())
+            .unwrap_or(false)
+        {
+            break;
+        }
+    }
+
+    let res_health = client
+        .get(format!("{}/healthz", base))
+        .send()
+        .await
+        .expect("get healthz");
+    assert!(
+        res_health.status().is_success(),
+        "GET /healthz: {}",
+        res_health.status()
+    );
+    let body: serde_json::Value = res_health.json().await.expect("healthz JSON");
+    assert_eq!(
+        body["state_store_fallback_active"].as_bool(),
+        Some(true),
+        "healthz should report state_store_fallback_active true when SQLite failed and fallback to memory is used: {}",
+        body
+    );
+
+    let res_ready = client
+        .get(format!("{}/readyz", base))
+        .send()
+        .await
+        .expect("get readyz");
+    assert!(res_ready.status().is_success());
+    let body_ready: serde_json::Value = res_ready.json().await.expect("readyz JSON");
+    assert_eq!(
+        body_ready["state_store_fallback_active"].as_bool(),
+        Some(true),
+        "readyz should report state_store_fallback_active true"
+    );
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// POST /drain pauses scheduled polling (no requests to the API while drained); POST /resume continues.
 #[tokio::test]
-async fn integration_health_readyz_file_output_200() {
+async fn integration_drain_pauses_and_resume_continues_polling() {
     let server = MockServer::start().await;
     Mock::given(method("GET"))
         .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": "1"}])))
         .mount(&server)
         .await;
 
-    let health_port = 19285u16;
-    let config_dir = std::env::temp_dir().join("hel_integration_health_readyz");
+    let health_port = 19287u16;
+    let config_dir = std::env::temp_dir().join("hel_integration_drain");
     let _ = std::fs::create_dir_all(&config_dir);
-    let output_file = config_dir.join("out.ndjson");
     let config_path = config_dir.join("helr.yaml");
     let yaml = format!(
         r#"
@@ -1758,10 +3189,10 @@ global:
     address: "127.0.0.1"
     port: {}
 sources:
-  readyz-source:
+  drain-source:
     url: "{}/"
     schedule:
-      interval_secs: 60
+      interval_secs: 1
     pagination:
       strategy: link_header
       rel: next
@@ -1772,18 +3203,12 @@ sources:
     std::fs::write(&config_path, yaml).expect("write config");
 
     let mut child = std::process::Command::new(hel_bin())
-        .args([
-            "run",
-            "--config",
-            config_path.to_str().unwrap(),
-            "--output",
-            output_file.to_str().unwrap(),
-        ])
+        .args(["run", "--config", config_path.to_str().unwrap()])
         .env("RUST_LOG", "error")
         .env("HELR_LOG_LEVEL", "error")
         .current_dir(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into()))
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
         .spawn()
         .expect("spawn helr");
 
@@ -1792,7 +3217,7 @@ sources:
     for _ in 0..30 {
         std::thread::sleep(Duration::from_millis(100));
         if client
-            .get(format!("{}/readyz", base))
+            .get(format!("{}/healthz", base))
             .send()
             .await
             .map(|r| r.status().is_success())
@@ -1802,65 +3227,85 @@ sources:
         }
     }
 
-    let res = client
-        .get(format!("{}/readyz", base))
+    let polls = || async { server.received_requests().await.unwrap_or_default().len() };
+    let res_drain = client
+        .post(format!("{}/drain", base))
         .send()
         .await
-        .expect("get readyz");
+        .expect("post drain");
+    let drain_body: serde_json::Value = res_drain.json().await.expect("drain JSON");
+    // Let any tick already in flight finish before taking the baseline.
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let drained = polls().await;
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    let after_drain = polls().await;
+
+    let res_resume = client
+        .post(format!("{}/resume", base))
+        .send()
+        .await
+        .expect("post resume");
+    let resume_body: serde_json::Value = res_resume.json().await.expect("resume JSON");
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    let after_resume = polls().await;
+
     let _ = child.kill();
     let _ = child.wait();
+
+    assert_eq!(drain_body["draining"], true);
+    assert_eq!(drain_body["ok"], true);
+    assert!(drained >= 1, "first tick polled before drain");
+    assert_eq!(after_drain, drained, "no polls while drained");
+    assert_eq!(resume_body["draining"], false);
     assert!(
-        res.status().is_success(),
-        "readyz 200 when output file writable"
+        after_resume > after_drain,
+        "polling continues after resume ({} -> {})",
+        after_drain,
+        after_resume
     );
-    let body: serde_json::Value = res.json().await.expect("JSON");
-    assert_eq!(body["ready"], true);
-    assert_eq!(body["output_writable"], true);
-    assert_eq!(body["state_store_connected"], true);
-    assert_eq!(body["at_least_one_source_healthy"], true);
 }
 
-/// Graceful degradation: when SQLite state store fails to open and state_store_fallback is memory, health reports state_store_fallback_active.
+/// POST /circuit/:id?state=open blocks the source's polls until state=closed resets the breaker.
 #[tokio::test]
-async fn integration_state_store_fallback_health_reports_fallback_active() {
+async fn integration_force_circuit_open_blocks_polls() {
     let server = MockServer::start().await;
     Mock::given(method("GET"))
         .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": "1"}])))
         .mount(&server)
         .await;
 
-    let health_port = 19286u16;
-    let config_dir = std::env::temp_dir().join("hel_integration_fallback");
+    let health_port = 19290u16;
+    let config_dir = std::env::temp_dir().join("hel_integration_force_circuit");
     let _ = std::fs::create_dir_all(&config_dir);
     let config_path = config_dir.join("helr.yaml");
-    // SQLite path that will fail to open (parent dir does not exist), so we fall back to memory.
-    let state_path = config_dir.join("nonexistent_subdir").join("helr-state.db");
     let yaml = format!(
         r#"
 global:
   log_level: error
   state:
-    backend: sqlite
-    path: "{}"
-  degradation:
-    state_store_fallback: memory
+    backend: memory
   api:
     enabled: true
     address: "127.0.0.1"
     port: {}
 sources:
-  fallback-source:
+  circuit-source:
     url: "{}/"
     schedule:
-      interval_secs: 60
-    pagination:
-      strategy: link_header
-      rel: next
+      interval_secs: 1
     resilience:
-      timeout_secs: 5
+      circuit_breaker:
+        enabled: true
+        failure_threshold: 5
+        success_threshold: 1
+        half_open_timeout_secs: 1
+  plain-source:
+    url: "{}/plain"
+    schedule:
+      interval_secs: 60
 "#,
-        state_path.to_str().unwrap().replace('\\', "/"),
         health_port,
+        server.uri(),
         server.uri()
     );
     std::fs::write(&config_path, yaml).expect("write config");
@@ -1870,8 +3315,8 @@ sources:
         .env("RUST_LOG", "error")
         .env("HELR_LOG_LEVEL", "error")
         .current_dir(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into()))
-        .stdout(std::process::Stdio::piped())
-        .stderr(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
         .spawn()
         .expect("spawn helr");
 
@@ -1890,39 +3335,200 @@ sources:
         }
     }
 
-    let res_health = client
+    let polls = || async {
+        server
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter(|r| r.url.path() == "/")
+            .count()
+    };
+    let res_open = client
+        .post(format!("{}/circuit/circuit-source?state=open", base))
+        .send()
+        .await
+        .expect("post circuit open");
+    let open_status = res_open.status();
+    let open_body: serde_json::Value = res_open.json().await.expect("circuit JSON");
+    // Let any request already in flight finish before taking the baseline.
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let opened = polls().await;
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    let after_open = polls().await;
+    let health: serde_json::Value = client
         .get(format!("{}/healthz", base))
         .send()
         .await
-        .expect("get healthz");
-    assert!(
-        res_health.status().is_success(),
-        "GET /healthz: {}",
-        res_health.status()
-    );
-    let body: serde_json::Value = res_health.json().await.expect("healthz JSON");
-    assert_eq!(
-        body["state_store_fallback_active"].as_bool(),
-        Some(true),
-        "healthz should report state_store_fallback_active true when SQLite failed and fallback to memory is used: {}",
-        body
-    );
+        .expect("get healthz")
+        .json()
+        .await
+        .expect("healthz JSON");
 
-    let res_ready = client
-        .get(format!("{}/readyz", base))
+    let res_close = client
+        .post(format!("{}/circuit/circuit-source?state=closed", base))
         .send()
         .await
-        .expect("get readyz");
-    assert!(res_ready.status().is_success());
-    let body_ready: serde_json::Value = res_ready.json().await.expect("readyz JSON");
+        .expect("post circuit closed");
+    let close_status = res_close.status();
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    let after_close = polls().await;
+
+    let bad_state = client
+        .post(format!("{}/circuit/circuit-source?state=half_open", base))
+        .send()
+        .await
+        .expect("post bad state")
+        .status();
+    let no_breaker = client
+        .post(format!("{}/circuit/plain-source?state=open", base))
+        .send()
+        .await
+        .expect("post no breaker")
+        .status();
+    let unknown = client
+        .post(format!("{}/circuit/nope?state=open", base))
+        .send()
+        .await
+        .expect("post unknown source")
+        .status();
+
+    let _ = child.kill();
+    let _ = child.wait();
+
+    assert_eq!(open_status, 200, "{}", open_body);
+    assert_eq!(open_body["ok"], true);
+    assert_eq!(open_body["state"], "open");
+    assert_eq!(after_open, opened, "no polls while circuit forced open");
     assert_eq!(
-        body_ready["state_store_fallback_active"].as_bool(),
-        Some(true),
-        "readyz should report state_store_fallback_active true"
+        health["sources"]["circuit-source"]["circuit_state"]["state"], "open",
+        "{}",
+        health
+    );
+    assert_eq!(close_status, 200);
+    assert!(
+        after_close > after_open,
+        "polling continues after reset ({} -> {})",
+        after_open,
+        after_close
+    );
+    assert_eq!(bad_state, 400);
+    assert_eq!(no_breaker, 400);
+    assert_eq!(unknown, 404);
+}
+
+/// Secret rotation: with restart_sources_on_sighup, rewriting the OAuth2 client_secret file and sending
+/// SIGHUP drops the cached token, so the next tick fetches a token with the new secret and uses it.
+#[cfg(unix)]
+#[tokio::test]
+async fn integration_sighup_picks_up_rotated_secret_file() {
+    use nix::sys::signal::{self, Signal};
+    use nix::unistd::Pid;
+    use std::io::BufRead;
+    use std::process::Stdio;
+    use wiremock::matchers::{body_string_contains, header, path};
+
+    let server = MockServer::start().await;
+    for (secret, token) in [("secret-old", "tok-old"), ("secret-new", "tok-new")] {
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .and(body_string_contains(format!("client_secret={}", secret)))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"access_token": token, "expires_in": 3600})),
+            )
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("GET"))
+        .and(path("/logs"))
+        .and(header("Authorization", "Bearer tok-new"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{"id": "rotated"}])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/logs"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([])))
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_sighup_rotation");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let secret_path = config_dir.join("client_secret");
+    std::fs::write(&secret_path, "secret-old").expect("write secret");
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  state:
+    backend: memory
+  reload:
+    restart_sources_on_sighup: true
+sources:
+  rotating-source:
+    url: "{0}/logs"
+    schedule:
+      interval_secs: 1
+    auth:
+      type: oauth2
+      token_url: "{0}/token"
+      client_id_env: HELR_TEST_ROTATION_CLIENT_ID
+      client_secret_file: "{1}"
+"#,
+        server.uri(),
+        secret_path.display()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let mut child = std::process::Command::new(hel_bin())
+        .args(["run", "--config", config_path.to_str().unwrap()])
+        .env("RUST_LOG", "error")
+        .env("HELR_LOG_LEVEL", "error")
+        .env("HELR_TEST_ROTATION_CLIENT_ID", "client-1")
+        .current_dir(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into()))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn helr");
+    let stdout = child.stdout.take().expect("stdout");
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::BufReader::new(stdout)
+            .lines()
+            .map_while(Result::ok)
+        {
+            let _ = tx.send(line);
+        }
+    });
+
+    std::thread::sleep(Duration::from_millis(1500));
+    std::fs::write(&secret_path, "secret-new").expect("rotate secret");
+    // Without SIGHUP the cached tok-old (expires_in 3600) would keep being used.
+    std::thread::sleep(Duration::from_millis(1200));
+    assert!(
+        rx.try_iter().all(|l| !l.contains("rotated")),
+        "rotated secret used before SIGHUP"
     );
+    let pid = Pid::from_raw(child.id() as i32);
+    signal::kill(pid, Signal::SIGHUP).expect("send SIGHUP");
 
+    let deadline = std::time::Instant::now() + Duration::from_secs(15);
+    let mut seen = false;
+    while std::time::Instant::now() < deadline {
+        if let Ok(line) = rx.recv_timeout(Duration::from_millis(200))
+            && line.contains("rotated")
+        {
+            seen = true;
+            break;
+        }
+    }
     let _ = child.kill();
     let _ = child.wait();
+    assert!(
+        seen,
+        "expected an event fetched with the token from the rotated secret"
+    );
 }
 
 /// SIGTERM mid-poll: send SIGTERM while helr is waiting on a slow response; process exits (graceful shutdown).
@@ -2081,3 +3687,145 @@ sources:
 {"endpoint":"http://mock-server//","event":{"action":"logout","id":"h2","published":"2024-06-01T10:00:01Z"},"meta":{"request_id":"helr-REDACTED"},"source":"hooks-inline-source","ts":"REDACTED_TS"}
 "#);
 }
+
+/// raw_passthrough: one envelope per response with the whole body as event and meta.raw true.
+#[tokio::test]
+async fn integration_raw_passthrough_emits_whole_response() {
+    let body = json!({
+        "report": {"generated": "2024-01-15T10:00:00Z", "rows": [{"a": 1}, {"a": 2}]},
+        "items": [{"id": "1"}, {"id": "2"}]
+    });
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body.clone()))
+        .mount(&server)
+        .await;
+
+    let config_dir = std::env::temp_dir().join("hel_integration_raw_passthrough");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  state:
+    backend: memory
+sources:
+  archive:
+    url: "{}/report"
+    raw_passthrough: true
+    resilience:
+      timeout_secs: 5
+"#,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let output = run_hel(&["run", "--once"], config_path.to_str().unwrap());
+    assert!(
+        output.status.success(),
+        "helr run --once failed: stderr={}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let lines: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| serde_json::from_str(l).expect("NDJSON line"))
+        .collect();
+    assert_eq!(lines.len(), 1, "{:?}", lines);
+    assert_eq!(lines[0]["event"], body);
+    assert_eq!(lines[0]["meta"]["raw"], true);
+    assert_eq!(lines[0]["source"], "archive");
+}
+
+/// GET /tail returns the most recent emitted events as NDJSON when global.debug.tail_capacity is set.
+#[tokio::test]
+async fn integration_tail_returns_recent_events() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+            {"id": "1"},
+            {"id": "2"},
+            {"id": "3"}
+        ])))
+        .mount(&server)
+        .await;
+
+    let health_port = 19288u16;
+    let config_dir = std::env::temp_dir().join("hel_integration_tail");
+    let _ = std::fs::create_dir_all(&config_dir);
+    let config_path = config_dir.join("helr.yaml");
+    let yaml = format!(
+        r#"
+global:
+  log_level: error
+  state:
+    backend: memory
+  api:
+    enabled: true
+    address: "127.0.0.1"
+    port: {}
+  debug:
+    tail_capacity: 2
+sources:
+  tail-source:
+    url: "{}/"
+    schedule:
+      interval_secs: 60
+"#,
+        health_port,
+        server.uri()
+    );
+    std::fs::write(&config_path, yaml).expect("write config");
+
+    let mut child = std::process::Command::new(hel_bin())
+        .args(["run", "--config", config_path.to_str().unwrap()])
+        .env("RUST_LOG", "error")
+        .env("HELR_LOG_LEVEL", "error")
+        .current_dir(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into()))
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .expect("spawn helr");
+
+    let base = format!("http://127.0.0.1:{}", health_port);
+    let client = reqwest::Client::new();
+    let mut body = String::new();
+    for _ in 0..50 {
+        std::thread::sleep(Duration::from_millis(100));
+        if let Ok(res) = client.get(format!("{}/tail", base)).send().await
+            && res.status().is_success()
+        {
+            body = res.text().await.unwrap_or_default();
+            if body.lines().count() == 2 {
+                break;
+            }
+        }
+    }
+    let last_one = client
+        .get(format!("{}/tail?n=1", base))
+        .send()
+        .await
+        .expect("get tail n=1")
+        .text()
+        .await
+        .unwrap_or_default();
+
+    let _ = child.kill();
+    let _ = child.wait();
+
+    let ids: Vec<String> = body
+        .lines()
+        .map(|l| {
+            let v: serde_json::Value = serde_json::from_str(l).expect("tail line is JSON");
+            v["event"]["id"].as_str().unwrap_or_default().to_string()
+        })
+        .collect();
+    assert_eq!(
+        ids,
+        vec!["2", "3"],
+        "tail keeps the last 2 events: {}",
+        body
+    );
+    assert_eq!(last_one.lines().count(), 1);
+    assert!(last_one.contains("\"id\":\"3\""), "{}", last_one);
+}